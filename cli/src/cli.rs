@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug, Clone)]
@@ -8,14 +8,24 @@ use std::path::PathBuf;
     about = "UltraWM - A next-generation, cross-platform tiling window manager",
 )]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     #[arg(
         short = 'c',
         long = "config",
         value_name = "FILE",
-        help = "Specify custom configuration file path"
+        help = "Specify custom configuration file path (.yaml or .toml)"
     )]
     pub config_path: Option<PathBuf>,
 
+    #[arg(
+        long = "layout-file",
+        value_name = "FILE",
+        help = "Load and save the window layout from a custom file"
+    )]
+    pub layout_file: Option<PathBuf>,
+
     #[arg(
         long = "validate",
         help = "Validate configuration and exit without starting"
@@ -47,10 +57,78 @@ pub struct Args {
     pub console: bool,
 }
 
+/// Talk to an already running instance, or inspect the configuration, instead of starting one
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Run a command in the running instance, e.g. `ultrawm cmd focus_next` or
+    /// `ultrawm cmd set_layout master-stack`
+    Cmd {
+        #[arg(value_name = "NAME")]
+        name: String,
+        #[arg(value_name = "ARGUMENT")]
+        argument: Option<String>,
+    },
+    /// Print the current layout of the running instance
+    Query {
+        #[arg(long = "json", help = "Print the layout as JSON")]
+        json: bool,
+    },
+    /// Switch the running instance to the named workspace
+    Workspace {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+    /// Save, load or list named layout presets
+    Preset {
+        #[command(subcommand)]
+        action: PresetAction,
+    },
+    /// Describe the configured layout
+    Layout {
+        #[command(subcommand)]
+        action: LayoutAction,
+    },
+    /// Try out the AI organizer
+    Ai {
+        #[command(subcommand)]
+        action: AiAction,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PresetAction {
+    /// Save the focused workspace's layout as a preset
+    Save {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+    /// Apply a preset to the focused workspace
+    Load {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+    /// List the saved presets
+    List,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum LayoutAction {
+    /// Print the placement targets and an example of the configured layout
+    Help,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum AiAction {
+    /// Print how the AI would organize the open windows, without moving any of them
+    Preview,
+}
+
 impl Default for Args {
     fn default() -> Self {
         Self {
+            command: None,
             config_path: None,
+            layout_file: None,
             validate: false,
             use_defaults: false,
             no_persistence: false,
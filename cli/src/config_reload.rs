@@ -0,0 +1,130 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Coalesces bursts of config file events into a single reload. Editors often write a file
+/// several times per save, so each event restarts the wait, and a reload is skipped when the
+/// contents are the same as last time.
+pub struct ConfigReloadDebouncer {
+    sender: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ConfigReloadDebouncer {
+    /// `read` returns the current file contents, or `None` if it can't be read right now.
+    /// `reload` is called once the events settle and the contents changed.
+    pub fn new(
+        debounce: Duration,
+        read: impl Fn() -> Option<Vec<u8>> + Send + 'static,
+        reload: impl Fn() + Send + 'static,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel::<()>();
+        let mut last_hash = read().map(|contents| hash(&contents));
+        let thread = thread::spawn(move || {
+            while receiver.recv().is_ok() {
+                let deadline = Instant::now() + debounce;
+                loop {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    match receiver.recv_timeout(timeout) {
+                        Ok(()) => {}
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+
+                // Half written or briefly missing while the editor swaps files in
+                let Some(contents) = read() else {
+                    continue;
+                };
+                let new_hash = hash(&contents);
+                if last_hash == Some(new_hash) {
+                    continue;
+                }
+                last_hash = Some(new_hash);
+                reload();
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+
+    /// Records a file event, the reload waits until they stop coming
+    pub fn notify(&self) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(());
+        }
+    }
+}
+
+impl Drop for ConfigReloadDebouncer {
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn hash(contents: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    fn debouncer(contents: &Arc<Mutex<Vec<u8>>>) -> (ConfigReloadDebouncer, Arc<AtomicUsize>) {
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let counter = reloads.clone();
+        let contents = contents.clone();
+        let debouncer = ConfigReloadDebouncer::new(
+            Duration::from_millis(50),
+            move || Some(contents.lock().unwrap().clone()),
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            },
+        );
+        (debouncer, reloads)
+    }
+
+    #[test]
+    fn test_rapid_events_reload_once() {
+        let contents = Arc::new(Mutex::new(b"window_gap: 10".to_vec()));
+        let (debouncer, reloads) = debouncer(&contents);
+
+        *contents.lock().unwrap() = b"window_gap: 20".to_vec();
+        for _ in 0..5 {
+            debouncer.notify();
+            thread::sleep(Duration::from_millis(5));
+        }
+        drop_after_settling(debouncer);
+
+        assert_eq!(reloads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_unchanged_contents_skip_reload() {
+        let contents = Arc::new(Mutex::new(b"window_gap: 10".to_vec()));
+        let (debouncer, reloads) = debouncer(&contents);
+
+        debouncer.notify();
+        debouncer.notify();
+        drop_after_settling(debouncer);
+
+        assert_eq!(reloads.load(Ordering::SeqCst), 0);
+    }
+
+    fn drop_after_settling(debouncer: ConfigReloadDebouncer) {
+        thread::sleep(Duration::from_millis(200));
+        drop(debouncer);
+    }
+}
@@ -3,14 +3,22 @@
 use log::{debug, error, info, trace, warn};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use ultrawm_core::ipc::{self, IpcError};
+use ultrawm_core::snapshot::LayoutSnapshot;
 use ultrawm_core::{config::Config, register_commands, UltraWMResult};
 
 mod cli;
+mod config_reload;
 mod error_dialog;
 mod logger;
 
 use cli::parse_args;
+use config_reload::ConfigReloadDebouncer;
+
+// How long config file events have to stop for before the config is reloaded
+const CONFIG_RELOAD_DEBOUNCE: Duration = Duration::from_millis(250);
 
 fn main() {
     // Parse args first to check for console flag
@@ -18,10 +26,22 @@ fn main() {
 
     // On Windows, allocate console if requested
     #[cfg(target_os = "windows")]
-    if args.console {
+    if args.console || args.command.is_some() {
         allocate_console();
     }
 
+    if let Some(cli::Command::Layout { action }) = &args.command {
+        std::process::exit(run_layout_command(action, &args));
+    }
+
+    if let Some(cli::Command::Ai { action }) = &args.command {
+        std::process::exit(run_ai_command(action, &args));
+    }
+
+    if let Some(command) = &args.command {
+        std::process::exit(run_ipc_command(command));
+    }
+
     // Initialize logger early (before error handling)
     if let Err(e) = logger::init_logger(args.quiet, args.verbose) {
         eprintln!("Failed to initialize logger: {}", e);
@@ -76,10 +96,21 @@ fn run_main(args: cli::Args) -> UltraWMResult<()> {
 
     // Handle dry-run mode
     if args.validate {
+        if let Err(e) = config.validate() {
+            return Err(format!("Config validation failed: {e}").into());
+        }
+        if let Err(e) = ultrawm_core::validate_displays(&config) {
+            return Err(format!("Config validation failed: {e:?}").into());
+        }
         info!("Config validation successful");
         return Ok(());
     }
 
+    if let Some(path) = &args.layout_file {
+        info!("Using layout file: {}", path.display());
+        ultrawm_core::set_layout_file(path.clone())?;
+    }
+
     if args.reset_layout {
         match ultrawm_core::reset_layout() {
             Ok(_) => info!("Successfully reset layout"),
@@ -91,10 +122,7 @@ fn run_main(args: cli::Args) -> UltraWMResult<()> {
         return Ok(());
     }
 
-    if args.no_persistence {
-        info!("Starting with no persistence");
-        config.persistence = false;
-    }
+    apply_cli_overrides(&mut config, &args);
 
     // Set up config file watching if we have a config path
     let _watcher = if let Some(path) = config.config_path.clone() {
@@ -126,23 +154,168 @@ fn run_main(args: cli::Args) -> UltraWMResult<()> {
     Ok(())
 }
 
+/// Applies settings given as flags, on top of the config file and environment overrides
+fn apply_cli_overrides(config: &mut Config, args: &cli::Args) {
+    if args.no_persistence {
+        info!("Starting with no persistence");
+        config.persistence = false;
+    }
+}
+
+/// Loads the config for subcommands that run without the WM, printing why if it can't
+fn load_command_config(args: &cli::Args) -> Option<Config> {
+    register_commands();
+
+    if args.use_defaults {
+        return Some(Config::default());
+    }
+
+    let config_path = args.config_path.as_ref().and_then(|p| p.to_str());
+    match Config::load(config_path, false) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            eprintln!("Failed to load config: {e}");
+            None
+        }
+    }
+}
+
+/// Runs a layout subcommand against the config without starting the WM. Returns the exit code.
+fn run_layout_command(action: &cli::LayoutAction, args: &cli::Args) -> i32 {
+    let Some(config) = load_command_config(args) else {
+        return 1;
+    };
+
+    match action {
+        cli::LayoutAction::Help => println!("{}", ultrawm_core::layout_help(&config)),
+    }
+    0
+}
+
+/// Runs an AI subcommand without starting the WM. Returns the exit code.
+fn run_ai_command(action: &cli::AiAction, args: &cli::Args) -> i32 {
+    let Some(config) = load_command_config(args) else {
+        return 1;
+    };
+
+    match action {
+        cli::AiAction::Preview => match ultrawm_core::ai_preview(config) {
+            Ok(yaml) => print!("{yaml}"),
+            Err(e) => {
+                match e.hint() {
+                    Some(hint) => eprintln!("AI preview failed: {e} ({hint})"),
+                    None => eprintln!("AI preview failed: {e}"),
+                }
+                return 1;
+            }
+        },
+    }
+    0
+}
+
+/// Sends a request to the running instance and prints the response. Returns the exit code.
+fn run_ipc_command(command: &cli::Command) -> i32 {
+    let (request, json) = match command {
+        cli::Command::Cmd { name, argument } => match argument {
+            Some(argument) => (format!("{name} {argument}"), false),
+            None => (name.clone(), false),
+        },
+        cli::Command::Query { json } => {
+            (format!("{} {}", ipc::QUERY_VERB, ipc::QUERY_LAYOUT), *json)
+        }
+        cli::Command::Workspace { name } => (format!("{} {}", ipc::WORKSPACE_VERB, name), false),
+        cli::Command::Preset { action } => {
+            let request = match action {
+                cli::PresetAction::Save { name } => format!("{} {}", ipc::PRESET_SAVE, name),
+                cli::PresetAction::Load { name } => format!("{} {}", ipc::PRESET_LOAD, name),
+                cli::PresetAction::List => ipc::PRESET_LIST.to_string(),
+            };
+            (format!("{} {}", ipc::PRESET_VERB, request), false)
+        }
+        cli::Command::Layout { .. } | cli::Command::Ai { .. } => {
+            unreachable!("layout and AI commands run without the WM")
+        }
+    };
+
+    match ipc::send_request(&request) {
+        Ok(response) => match response.strip_prefix(ipc::ERROR_PREFIX) {
+            Some(error) => {
+                eprintln!("{error}");
+                1
+            }
+            None if response == ipc::OK_RESPONSE => 0,
+            None if matches!(command, cli::Command::Preset { .. }) => {
+                match serde_json::from_str::<Vec<String>>(&response) {
+                    Ok(names) => names.iter().for_each(|name| println!("{name}")),
+                    Err(_) => println!("{response}"),
+                }
+                0
+            }
+            None if json || !matches!(command, cli::Command::Query { .. }) => {
+                println!("{response}");
+                0
+            }
+            None => match serde_json::from_str::<LayoutSnapshot>(&response) {
+                Ok(snapshot) => {
+                    print_layout(&snapshot);
+                    0
+                }
+                Err(e) => {
+                    eprintln!("Invalid response from UltraWM: {e}");
+                    1
+                }
+            },
+        },
+        Err(IpcError::NotRunning) => {
+            eprintln!("UltraWM is not running. Start it first, then try again.");
+            1
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
+}
+
+fn print_layout(snapshot: &LayoutSnapshot) {
+    for partition in &snapshot.partitions {
+        println!("{}", partition.name);
+        for workspace in snapshot
+            .workspaces
+            .iter()
+            .filter(|w| w.partition == Some(partition.id))
+        {
+            let current = partition.current_workspace == Some(workspace.id);
+            println!(
+                "  {}{}",
+                workspace.name,
+                if current { " (current)" } else { "" }
+            );
+            for window in &workspace.windows {
+                println!(
+                    "    {} {} - {}{}",
+                    if window.focused { "*" } else { " " },
+                    window.app_name,
+                    window.title,
+                    if window.floating { " [floating]" } else { "" }
+                );
+            }
+        }
+    }
+}
+
 fn setup_config_watcher(config_path: PathBuf) -> UltraWMResult<RecommendedWatcher> {
-    let config_path_clone = config_path.clone();
+    let read_path = config_path.clone();
+    let reload_path = config_path.clone();
+    let debouncer = ConfigReloadDebouncer::new(
+        CONFIG_RELOAD_DEBOUNCE,
+        move || std::fs::read(&read_path).ok(),
+        move || reload_config(&reload_path),
+    );
     let mut watcher: RecommendedWatcher =
         notify::recommended_watcher(move |res: Result<Event, notify::Error>| match res {
             Ok(event) => match event.kind {
-                EventKind::Modify(_) | EventKind::Create(_) => {
-                    match Config::load(Some(config_path_clone.to_str().unwrap()), false) {
-                        Ok(new_config) => {
-                            ultrawm_core::load_config(new_config)
-                                .unwrap_or_else(|e| warn!("Failed to load config: {:?}", e));
-                        }
-                        Err(e) => {
-                            error!("Failed to reload config: {}", e);
-                            warn!("Keeping previous configuration");
-                        }
-                    }
-                }
+                EventKind::Modify(_) | EventKind::Create(_) => debouncer.notify(),
                 EventKind::Remove(_) => {
                     warn!("Config file was removed, keeping current configuration");
                 }
@@ -159,6 +332,19 @@ fn setup_config_watcher(config_path: PathBuf) -> UltraWMResult<RecommendedWatche
     Ok(watcher)
 }
 
+fn reload_config(config_path: &Path) {
+    match Config::load(config_path.to_str(), false) {
+        Ok(new_config) => {
+            ultrawm_core::load_config(new_config)
+                .unwrap_or_else(|e| warn!("Failed to load config: {:?}", e));
+        }
+        Err(e) => {
+            error!("Failed to reload config: {}", e);
+            warn!("Keeping previous configuration");
+        }
+    }
+}
+
 #[cfg(target_os = "windows")]
 fn allocate_console() {
     use windows::Win32::System::Console::*;
@@ -183,3 +369,23 @@ fn allocate_console() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_flags_override_env_overrides() {
+        let mut config = Config::default();
+        config.persistence = false;
+        config.apply_env_overrides(|var| (var == "ULTRAWM_PERSISTENCE").then(|| "true".into()));
+        assert!(config.persistence);
+
+        let args = cli::Args {
+            no_persistence: true,
+            ..Default::default()
+        };
+        apply_cli_overrides(&mut config, &args);
+        assert!(!config.persistence);
+    }
+}
@@ -1,25 +1,66 @@
-use crate::config::Config;
+use crate::config::{AiConfig, AiProvider, Config};
 use log::{debug, error};
-use reqwest::Client;
+use reqwest::{Client, Request, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum AiClientError {
     #[error("AI features are not enabled in config")]
     NotEnabled,
-    #[error("AI API URL is not configured")]
-    NoApiUrl,
     #[error("AI API key is not configured")]
     NoApiKey,
-    #[error("HTTP request failed: {0}")]
-    RequestFailed(#[from] reqwest::Error),
+    #[error("Environment variable {0} with the AI API key is not set")]
+    ApiKeyEnvNotSet(String),
+    #[error("AI model is not configured")]
+    NoModel,
+    #[error("AI API rejected the request: {0}")]
+    Auth(String),
+    #[error("Could not reach the AI API: {0}")]
+    Network(reqwest::Error),
+    #[error("AI API did not respond in time")]
+    Timeout,
     #[error("API returned an error: {0}")]
     ApiError(String),
     #[error("Failed to parse response: {0}")]
     ParseError(String),
 }
 
+impl AiClientError {
+    fn from_reqwest(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::Network(e)
+        }
+    }
+
+    /// Whether no model could be reached at all, as opposed to one giving a bad answer
+    pub fn is_unavailable(&self) -> bool {
+        matches!(
+            self,
+            Self::NoApiKey | Self::ApiKeyEnvNotSet(_) | Self::Network(_)
+        )
+    }
+
+    /// What the user can change to fix the error, if anything
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::NotEnabled => Some("set ai.enabled to true in the config"),
+            Self::NoApiKey => Some("set ai.api_key or ai.api_key_env in the config"),
+            Self::ApiKeyEnvNotSet(_) => {
+                Some("export the variable before starting UltraWM or change ai.api_key_env")
+            }
+            Self::NoModel => Some("set ai.model in the config"),
+            Self::Auth(_) => Some("check that the API key is valid for the configured endpoint"),
+            Self::Network(_) => Some("check ai.base_url and that the server is running"),
+            Self::Timeout => Some("try again or raise ai.timeout_secs"),
+            Self::ApiError(_) | Self::ParseError(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: String,
@@ -43,12 +84,31 @@ struct ChatChoice {
     message: ChatMessage,
 }
 
+#[derive(Debug, Serialize)]
+struct OllamaChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    stream: bool,
+    options: OllamaOptions,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaOptions {
+    temperature: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaChatResponse {
+    message: ChatMessage,
+}
+
 /// Generic AI client for making chat completion requests.
 /// This is layout-agnostic and can be used for any AI functionality.
 pub struct AiClient {
     client: Client,
-    api_url: String,
-    api_key: String,
+    provider: AiProvider,
+    endpoint: String,
+    api_key: Option<String>,
     model: String,
     temperature: f32,
 }
@@ -61,64 +121,146 @@ impl AiClient {
             return Err(AiClientError::NotEnabled);
         }
 
-        if ai_config.api_url.is_empty() {
-            return Err(AiClientError::NoApiUrl);
-        }
+        Self::new(&ai_config, |name| std::env::var(name).ok())
+    }
 
-        if ai_config.api_key.is_empty() {
+    /// Creates a client for the given settings, looking up `api_key_env` with `env`
+    fn new(
+        ai_config: &AiConfig,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, AiClientError> {
+        let api_key = if !ai_config.api_key_env.is_empty() {
+            let key = env(&ai_config.api_key_env).filter(|key| !key.is_empty());
+            Some(key.ok_or_else(|| AiClientError::ApiKeyEnvNotSet(ai_config.api_key_env.clone()))?)
+        } else if !ai_config.api_key.is_empty() {
+            Some(ai_config.api_key.clone())
+        } else {
+            None
+        };
+
+        // Local Ollama servers don't need a key, but one is still sent if configured in case
+        // the server sits behind an authenticating proxy
+        if api_key.is_none() && ai_config.provider == AiProvider::OpenAi {
             return Err(AiClientError::NoApiKey);
         }
 
+        if ai_config.model.is_empty() {
+            return Err(AiClientError::NoModel);
+        }
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(ai_config.timeout_secs))
+            .build()
+            .map_err(AiClientError::from_reqwest)?;
+
         Ok(Self {
-            client: Client::new(),
-            api_url: ai_config.api_url,
-            api_key: ai_config.api_key,
-            model: ai_config.model,
+            client,
+            provider: ai_config.provider,
+            endpoint: chat_endpoint(ai_config),
+            api_key,
+            model: ai_config.model.clone(),
             temperature: ai_config.temperature,
         })
     }
 
+    /// Builds the HTTP request for a chat in the shape the provider expects
+    fn build_request(&self, messages: Vec<ChatMessage>) -> Result<Request, AiClientError> {
+        let mut request = self.client.post(&self.endpoint);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        request = match self.provider {
+            AiProvider::OpenAi => request.json(&ChatCompletionRequest {
+                model: self.model.clone(),
+                messages,
+                temperature: self.temperature,
+            }),
+            AiProvider::Ollama => request.json(&OllamaChatRequest {
+                model: self.model.clone(),
+                messages,
+                stream: false,
+                options: OllamaOptions {
+                    temperature: self.temperature,
+                },
+            }),
+        };
+
+        request.build().map_err(AiClientError::from_reqwest)
+    }
+
     /// Send a chat completion request to the AI API.
     pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String, AiClientError> {
-        let request = ChatCompletionRequest {
-            model: self.model.clone(),
-            messages,
-            temperature: self.temperature,
-        };
+        let request = self.build_request(messages)?;
 
-        debug!("Sending AI request to: {}", self.api_url);
+        debug!("Sending AI request to: {}", request.url());
 
         let response = self
             .client
-            .post(&self.api_url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+            .execute(request)
+            .await
+            .map_err(AiClientError::from_reqwest)?;
 
         let status = response.status();
         if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
             error!("AI API error ({}): {}", status, error_text);
-            return Err(AiClientError::ApiError(format!(
-                "Status {}: {}",
-                status, error_text
-            )));
+            let message = format!("Status {}: {}", status, error_text);
+            return Err(match status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => AiClientError::Auth(message),
+                _ => AiClientError::ApiError(message),
+            });
         }
 
-        let completion: ChatCompletionResponse = response.json().await.map_err(|e| {
-            AiClientError::ParseError(format!("Failed to parse chat completion response: {}", e))
-        })?;
+        let content = match self.provider {
+            AiProvider::OpenAi => {
+                let completion: ChatCompletionResponse = response.json().await.map_err(|e| {
+                    AiClientError::ParseError(format!(
+                        "Failed to parse chat completion response: {}",
+                        e
+                    ))
+                })?;
+                completion
+                    .choices
+                    .into_iter()
+                    .next()
+                    .map(|choice| choice.message.content)
+            }
+            AiProvider::Ollama => {
+                let chat: OllamaChatResponse = response.json().await.map_err(|e| {
+                    AiClientError::ParseError(format!(
+                        "Failed to parse Ollama chat response: {}",
+                        e
+                    ))
+                })?;
+                Some(chat.message.content)
+            }
+        };
 
-        completion
-            .choices
-            .first()
-            .map(|choice| choice.message.content.clone())
-            .ok_or_else(|| AiClientError::ParseError("No choices in response".to_string()))
+        content.ok_or_else(|| AiClientError::ParseError("No choices in response".to_string()))
     }
 }
 
+/// The URL chat requests are posted to. A full `api_url` wins over `base_url`, and an empty
+/// `base_url` falls back to the provider's default.
+fn chat_endpoint(ai_config: &AiConfig) -> String {
+    if !ai_config.api_url.is_empty() {
+        return ai_config.api_url.clone();
+    }
+
+    let (default_base_url, chat_path) = match ai_config.provider {
+        AiProvider::OpenAi => ("https://api.openai.com/v1", "chat/completions"),
+        AiProvider::Ollama => ("http://localhost:11434", "api/chat"),
+    };
+    let base_url = if ai_config.base_url.is_empty() {
+        default_base_url
+    } else {
+        ai_config.base_url.as_str()
+    };
+
+    format!("{}/{}", base_url.trim_end_matches('/'), chat_path)
+}
+
 /// Strip markdown code blocks if the AI included them despite instructions
 pub fn strip_markdown_code_block(text: &str) -> String {
     let trimmed = text.trim();
@@ -141,3 +283,97 @@ pub fn strip_markdown_code_block(text: &str) -> String {
 
     trimmed.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+    use serde_json::Value;
+
+    fn config(provider: AiProvider) -> AiConfig {
+        AiConfig {
+            enabled: true,
+            provider,
+            model: "test-model".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn env(name: &str) -> Option<String> {
+        (name == "TEST_AI_KEY").then(|| "sk-test".to_string())
+    }
+
+    fn build(client: &AiClient) -> (Request, Value) {
+        let request = client
+            .build_request(vec![ChatMessage {
+                role: "user".to_string(),
+                content: "Hello".to_string(),
+            }])
+            .unwrap();
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap();
+        let body = serde_json::from_slice(body).unwrap();
+        (request, body)
+    }
+
+    #[test]
+    fn test_openai_request() {
+        let config = AiConfig {
+            api_key_env: "TEST_AI_KEY".to_string(),
+            ..config(AiProvider::OpenAi)
+        };
+        let (request, body) = build(&AiClient::new(&config, env).unwrap());
+
+        assert_eq!(
+            request.url().as_str(),
+            "https://api.openai.com/v1/chat/completions"
+        );
+        assert_eq!(request.headers()[AUTHORIZATION], "Bearer sk-test");
+        assert_eq!(request.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(body["model"], "test-model");
+        assert_eq!(body["messages"][0]["content"], "Hello");
+    }
+
+    #[test]
+    fn test_openai_compatible_base_url() {
+        let config = AiConfig {
+            base_url: "http://localhost:8080/v1/".to_string(),
+            api_key: "key".to_string(),
+            ..config(AiProvider::OpenAi)
+        };
+        let (request, _) = build(&AiClient::new(&config, env).unwrap());
+
+        assert_eq!(
+            request.url().as_str(),
+            "http://localhost:8080/v1/chat/completions"
+        );
+        assert_eq!(request.headers()[AUTHORIZATION], "Bearer key");
+    }
+
+    #[test]
+    fn test_ollama_request() {
+        let (request, body) = build(&AiClient::new(&config(AiProvider::Ollama), env).unwrap());
+
+        assert_eq!(request.url().as_str(), "http://localhost:11434/api/chat");
+        assert!(request.headers().get(AUTHORIZATION).is_none());
+        assert_eq!(request.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(body["model"], "test-model");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["messages"][0]["role"], "user");
+    }
+
+    #[test]
+    fn test_missing_keys_are_reported() {
+        let missing_env = AiConfig {
+            api_key_env: "UNSET_AI_KEY".to_string(),
+            ..config(AiProvider::OpenAi)
+        };
+        assert!(matches!(
+            AiClient::new(&missing_env, env),
+            Err(AiClientError::ApiKeyEnvNotSet(name)) if name == "UNSET_AI_KEY"
+        ));
+        assert!(matches!(
+            AiClient::new(&config(AiProvider::OpenAi), env),
+            Err(AiClientError::NoApiKey)
+        ));
+    }
+}
@@ -1,12 +1,17 @@
 use crate::ai::client::{strip_markdown_code_block, AiClient, AiClientError, ChatMessage};
+use crate::ai::preview::OrganizePlan;
 use crate::config::Config;
 use crate::event_loop_wm::{WMOperationError, WMOperationResult};
-use crate::layouts::PlacementTarget;
+use crate::layouts::container_tree::serialization::{
+    SerializedContainer, SerializedContainerChild, SerializedContainerTree, SerializedWindow,
+};
+use crate::layouts::{ContainerId, Direction, LayoutType, PlacementTarget};
 use crate::partition::PartitionId;
-use crate::platform::WindowId;
+use crate::platform::{Bounds, Platform, PlatformImpl, PlatformWindowImpl, WindowId};
+use crate::window::WindowRef;
 use crate::wm::{WMError, WindowManager};
 use crate::workspace::WorkspaceId;
-use log::{debug, error};
+use log::{debug, error, warn};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
 use std::collections::HashMap;
@@ -18,6 +23,25 @@ pub enum AiLayoutError {
     Client(#[from] AiClientError),
     #[error("Failed to parse response: {0}")]
     ParseError(String),
+    #[error("Could not list windows: {0}")]
+    Platform(String),
+    #[error("Could not start the async runtime: {0}")]
+    Runtime(String),
+    #[error("Could not write the layouts as YAML: {0}")]
+    Yaml(String),
+}
+
+impl AiLayoutError {
+    /// What the user can change to fix the error, if anything
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            AiLayoutError::Client(client_error) => client_error.hint(),
+            AiLayoutError::ParseError(_)
+            | AiLayoutError::Platform(_)
+            | AiLayoutError::Runtime(_)
+            | AiLayoutError::Yaml(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -46,12 +70,12 @@ impl AiWindowInfo {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiLayoutResponse {
     pub partitions: Vec<AiPartitionLayout>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiPartitionLayout {
     pub id: PartitionId,
     pub layout: serde_yaml::Value,
@@ -74,10 +98,9 @@ pub enum WindowPlacement {
 }
 
 pub fn handle_organize_all_windows(wm: &WindowManager) -> WMOperationResult<()> {
-    let windows: Vec<AiWindowInfo> = wm
-        .get_all_windows()
+    let organizable = organizable_windows(wm);
+    let windows: Vec<AiWindowInfo> = organizable
         .iter()
-        .filter(|w| !w.title().is_empty())
         .map(|w| AiWindowInfo::new(w.id(), w.title()))
         .collect();
 
@@ -108,6 +131,9 @@ pub fn handle_organize_all_windows(wm: &WindowManager) -> WMOperationResult<()>
         .filter_map(|(pid, p)| p.current_workspace().map(|wid| (*pid, wid)))
         .collect();
 
+    // Worked out up front because windows can't be sent to the AI task
+    let fallback = heuristic_layout(&organizable, &heuristic_partitions(wm));
+
     tokio::spawn(async move {
         match organize_all_windows_async(
             windows,
@@ -118,27 +144,179 @@ pub fn handle_organize_all_windows(wm: &WindowManager) -> WMOperationResult<()>
         )
         .await
         {
-            Ok(response) => {
-                for partition_layout in &response.partitions {
-                    if let Some(workspace_id) = partition_to_workspace.get(&partition_layout.id) {
-                        crate::load_layout_to_workspace(
-                            *workspace_id,
-                            partition_layout.layout.clone(),
-                        );
-                    } else {
-                        error!("Partition {} not found", partition_layout.id);
-                    }
-                }
-            }
-            Err(e) => {
-                error!("AI error: {}", e);
+            Ok(response) => apply_layout_response(&response, &partition_to_workspace),
+            Err(AiLayoutError::Client(e)) if e.is_unavailable() => {
+                warn!(
+                    "AI is unavailable ({}), organizing windows by app instead",
+                    e
+                );
+                apply_layout_response(&fallback, &partition_to_workspace);
             }
+            Err(e) => log_ai_error(&e),
         }
     });
 
     Ok(())
 }
 
+/// Organizes all windows without a model by grouping them by app into balanced grids
+pub fn handle_organize_heuristic(wm: &mut WindowManager) -> WMOperationResult<()> {
+    let response = heuristic_layout(&organizable_windows(wm), &heuristic_partitions(wm));
+    for partition_layout in &response.partitions {
+        let workspace_id = wm
+            .partitions()
+            .get(&partition_layout.id)
+            .and_then(|p| p.current_workspace())
+            .ok_or(WMError::WorkspaceNotFound(0))?;
+        wm.load_layout_to_workspace(workspace_id, &partition_layout.layout)?;
+    }
+    Ok(())
+}
+
+/// Previews the response's layouts until they're confirmed, or loads them straight away if
+/// confirming is turned off
+fn apply_layout_response(
+    response: &AiLayoutResponse,
+    partition_to_workspace: &HashMap<PartitionId, WorkspaceId>,
+) {
+    let mut layouts = Vec::new();
+    for partition_layout in &response.partitions {
+        if let Some(workspace_id) = partition_to_workspace.get(&partition_layout.id) {
+            layouts.push((*workspace_id, partition_layout.layout.clone()));
+        } else {
+            error!("Partition {} not found", partition_layout.id);
+        }
+    }
+
+    if Config::ai().confirm_organize {
+        crate::preview_organize_plan(OrganizePlan::new(layouts));
+    } else {
+        for (workspace_id, layout) in layouts {
+            crate::load_layout_to_workspace(workspace_id, layout);
+        }
+    }
+}
+
+fn organizable_windows(wm: &WindowManager) -> Vec<WindowRef> {
+    wm.get_all_windows()
+        .into_iter()
+        .filter(|w| !w.title().is_empty())
+        .collect()
+}
+
+/// The partitions the heuristic can arrange, ordered left to right and then top to bottom.
+/// Only partitions showing a container tree are included since that's what it produces.
+fn heuristic_partitions(wm: &WindowManager) -> Vec<(PartitionId, Bounds)> {
+    let mut partitions: Vec<_> = wm
+        .partitions()
+        .values()
+        .filter(|p| {
+            p.current_workspace()
+                .and_then(|id| wm.workspaces().get(&id))
+                .is_some_and(|ws| ws.layout_type() == LayoutType::ContainerTree)
+        })
+        .map(|p| (p.id(), p.bounds().clone()))
+        .collect();
+    partitions.sort_by_key(|(_, b)| (b.position.x, b.position.y));
+    partitions
+}
+
+/// Arranges windows without asking a model. Windows are grouped by app, each group goes whole
+/// to the partition with the fewest windows so far, and every partition is laid out as a
+/// balanced grid with the windows of an app next to each other.
+pub fn heuristic_layout(
+    windows: &[WindowRef],
+    partitions: &[(PartitionId, Bounds)],
+) -> AiLayoutResponse {
+    let mut groups: HashMap<String, Vec<WindowId>> = HashMap::new();
+    for window in windows {
+        groups
+            .entry(window.app_name())
+            .or_default()
+            .push(window.id());
+    }
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    for (_, ids) in &mut groups {
+        ids.sort();
+    }
+    // Biggest groups first keeps the partitions balanced
+    groups.sort_by(|(a_app, a), (b_app, b)| b.len().cmp(&a.len()).then(a_app.cmp(b_app)));
+
+    let mut assigned: Vec<Vec<WindowId>> = vec![Vec::new(); partitions.len()];
+    for (_, ids) in groups {
+        if let Some(fewest) = assigned.iter_mut().min_by_key(|ids| ids.len()) {
+            fewest.extend(ids);
+        }
+    }
+
+    AiLayoutResponse {
+        partitions: partitions
+            .iter()
+            .zip(assigned)
+            .filter(|(_, ids)| !ids.is_empty())
+            .map(|((id, bounds), ids)| AiPartitionLayout {
+                id: *id,
+                layout: serde_yaml::to_value(grid_layout(&ids, bounds)).unwrap(),
+            })
+            .collect(),
+    }
+}
+
+/// Splits the windows into rows or columns along the longer side of the bounds, with as many
+/// lines as windows per line and every line within one window of the others.
+fn grid_layout(ids: &[WindowId], bounds: &Bounds) -> SerializedContainerTree {
+    let direction = if bounds.size.width >= bounds.size.height {
+        Direction::Horizontal
+    } else {
+        Direction::Vertical
+    };
+    let lines = ((ids.len() as f32).sqrt().ceil() as usize).max(1);
+    let per_line = ids.len() / lines;
+    let extra = ids.len() % lines;
+
+    let mut children = Vec::new();
+    let mut start = 0;
+    for line in 0..lines {
+        let len = per_line + usize::from(line < extra);
+        let line_ids = &ids[start..start + len];
+        start += len;
+
+        children.push(match line_ids {
+            [id] => SerializedContainerChild::Window(SerializedWindow { id: *id }),
+            _ => SerializedContainerChild::Container(SerializedContainer {
+                id: (line + 1) as ContainerId,
+                direction: direction.opposite(),
+                ratios: equal_ratios(line_ids.len()),
+                children: line_ids
+                    .iter()
+                    .map(|id| SerializedContainerChild::Window(SerializedWindow { id: *id }))
+                    .collect(),
+            }),
+        });
+    }
+
+    SerializedContainerTree {
+        root: SerializedContainer {
+            id: 0,
+            direction,
+            ratios: equal_ratios(lines),
+            children,
+        },
+    }
+}
+
+fn equal_ratios(count: usize) -> Vec<f32> {
+    vec![1.0 / count as f32; count]
+}
+
+/// Logs an AI failure along with what the user can do about it
+fn log_ai_error(e: &AiLayoutError) {
+    match e.hint() {
+        Some(hint) => error!("AI error: {} ({})", e, hint),
+        None => error!("AI error: {}", e),
+    }
+}
+
 /// Organize a single window using AI. Takes WindowManager and window_id directly.
 pub fn handle_organize_single_window(
     wm: &WindowManager,
@@ -214,9 +392,7 @@ pub fn handle_organize_single_window(
                     crate::place_window_relative(window_id, target, workspace_id);
                 }
             },
-            Err(e) => {
-                error!("AI error: {}", e);
-            }
+            Err(e) => log_ai_error(&e),
         }
     });
 
@@ -298,18 +474,66 @@ Windows:
     ];
 
     let response = client.chat(messages).await?;
-    let response = strip_markdown_code_block(&response);
+    parse_layout_response(&response)
+}
+
+fn parse_layout_response(response: &str) -> Result<AiLayoutResponse, AiLayoutError> {
+    let response = strip_markdown_code_block(response);
     debug!("AI response:\n{}", response);
 
-    // Parse the response as YAML
-    let layout_response: AiLayoutResponse = serde_yaml::from_str(&response).map_err(|e| {
+    serde_yaml::from_str(&response).map_err(|e| {
         AiLayoutError::ParseError(format!(
             "Failed to parse AI response as YAML: {}. Response was:\n{}",
             e, response
         ))
-    })?;
+    })
+}
+
+/// Asks the model how it would organize the windows open right now, without moving anything.
+/// Doesn't need the window manager to be running, partitions are worked out from the displays
+/// the same way it would.
+pub async fn preview_organize() -> Result<AiLayoutResponse, AiLayoutError> {
+    let windows: Vec<AiWindowInfo> = Platform::list_visible_windows()
+        .map_err(|e| AiLayoutError::Platform(e.to_string()))?
+        .iter()
+        .filter(|w| !w.title().is_empty())
+        .map(|w| AiWindowInfo::new(w.id(), w.title()))
+        .collect();
+
+    let partitions = Platform::list_all_displays()
+        .map_err(|e| AiLayoutError::Platform(e.to_string()))?
+        .iter()
+        .flat_map(WindowManager::partitions_for_display)
+        .map(|p| AiPartitionState {
+            id: p.id(),
+            name: p.name().to_string(),
+            x: p.bounds().position.x,
+            y: p.bounds().position.y,
+            width: p.bounds().size.width,
+            height: p.bounds().size.height,
+            workspace_id: None,
+            layout: None,
+        })
+        .collect();
+
+    let (example_layout, layout_description) = {
+        let layout = crate::layouts::configured_layout(&Config::current());
+        (layout.example_layout(), layout.layout_description())
+    };
+
+    organize_all_windows_async(
+        windows,
+        partitions,
+        example_layout,
+        layout_description,
+        Config::ai().organization_preferences,
+    )
+    .await
+}
 
-    Ok(layout_response)
+/// The layouts in a response as YAML, for printing
+pub fn preview_yaml(response: &AiLayoutResponse) -> Result<String, AiLayoutError> {
+    serde_yaml::to_string(response).map_err(|e| AiLayoutError::Yaml(e.to_string()))
 }
 
 async fn organize_single_window_async(
@@ -431,3 +655,147 @@ fn enrich_layout_with_titles(layout: &Value, window_titles: &HashMap<WindowId, S
         other => other.clone(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatformWindow;
+    use crate::platform::{Position, Size};
+    use crate::window::Window;
+    use std::rc::Rc;
+
+    fn mock_window(id: WindowId, app_name: &str) -> WindowRef {
+        let mut platform_window = MockPlatformWindow::new(
+            Position { x: 0, y: 0 },
+            Size {
+                width: 800,
+                height: 600,
+            },
+            format!("Window {id}"),
+        );
+        platform_window.id = id;
+        platform_window.app_name = app_name.to_string();
+        Rc::new(Window::new(platform_window))
+    }
+
+    fn window_order(container: &SerializedContainer, ids: &mut Vec<WindowId>) {
+        for child in &container.children {
+            match child {
+                SerializedContainerChild::Window(window) => ids.push(window.id),
+                SerializedContainerChild::Container(container) => window_order(container, ids),
+            }
+        }
+    }
+
+    fn layout_order(layout: &AiPartitionLayout) -> Vec<WindowId> {
+        let tree: SerializedContainerTree = serde_yaml::from_value(layout.layout.clone()).unwrap();
+        let mut ids = Vec::new();
+        window_order(&tree.root, &mut ids);
+        ids
+    }
+
+    #[test]
+    fn test_preview_yaml_matches_response() {
+        let canned = r#"```yaml
+partitions:
+  - id: 1
+    layout:
+      root:
+        children:
+          - window: 10
+          - window: 11
+        ratios: [0.5, 0.5]
+```"#;
+
+        let response = parse_layout_response(canned).unwrap();
+        let expected = "\
+partitions:
+- id: 1
+  layout:
+    root:
+      children:
+      - window: 10
+      - window: 11
+      ratios:
+      - 0.5
+      - 0.5
+";
+        assert_eq!(preview_yaml(&response).unwrap(), expected);
+
+        let error = parse_layout_response("not: [valid").unwrap_err();
+        assert!(matches!(error, AiLayoutError::ParseError(_)));
+        assert_eq!(error.hint(), None);
+    }
+
+    #[test]
+    fn test_heuristic_keeps_apps_adjacent() {
+        let apps = [
+            "Browser", "Terminal", "Browser", "Chat", "Terminal", "Browser", "Chat",
+        ];
+        let windows: Vec<_> = apps
+            .iter()
+            .enumerate()
+            .map(|(i, app)| mock_window(i as WindowId + 1, app))
+            .collect();
+
+        let response = heuristic_layout(&windows, &[(0, Bounds::new(0, 0, 1920, 1080))]);
+        assert_eq!(response.partitions.len(), 1);
+
+        let order = layout_order(&response.partitions[0]);
+        assert_eq!(order.len(), windows.len());
+        for app in ["Browser", "Terminal", "Chat"] {
+            let positions: Vec<_> = order
+                .iter()
+                .enumerate()
+                .filter(|(_, id)| apps[**id as usize - 1] == app)
+                .map(|(i, _)| i)
+                .collect();
+            assert_eq!(
+                positions.last().unwrap() - positions.first().unwrap() + 1,
+                positions.len(),
+                "{app} windows are not adjacent in {order:?}"
+            );
+        }
+
+        // 7 windows make 3 columns of 3, 2 and 2
+        let tree: SerializedContainerTree =
+            serde_yaml::from_value(response.partitions[0].layout.clone()).unwrap();
+        assert_eq!(tree.root.direction, Direction::Horizontal);
+        assert_eq!(tree.root.children.len(), 3);
+    }
+
+    #[test]
+    fn test_heuristic_keeps_apps_in_one_partition() {
+        let windows = vec![
+            mock_window(1, "Browser"),
+            mock_window(2, "Terminal"),
+            mock_window(3, "Browser"),
+            mock_window(4, "Chat"),
+            mock_window(5, "Browser"),
+            mock_window(6, "Terminal"),
+        ];
+        let partitions = [
+            (0, Bounds::new(0, 0, 1920, 1080)),
+            (1, Bounds::new(1920, 0, 1920, 1080)),
+        ];
+
+        let response = heuristic_layout(&windows, &partitions);
+
+        assert_eq!(layout_order(&response.partitions[0]), vec![1, 3, 5]);
+        assert_eq!(layout_order(&response.partitions[1]), vec![2, 6, 4]);
+    }
+
+    #[test]
+    fn test_heuristic_skips_partitions_without_windows() {
+        let windows = vec![mock_window(1, "Browser")];
+        let partitions = [
+            (0, Bounds::new(0, 0, 1920, 1080)),
+            (1, Bounds::new(1920, 0, 1920, 1080)),
+        ];
+
+        let response = heuristic_layout(&windows, &partitions);
+
+        assert_eq!(response.partitions.len(), 1);
+        assert_eq!(layout_order(&response.partitions[0]), vec![1]);
+    }
+}
@@ -1,6 +1,8 @@
 pub mod client;
 pub mod layout;
+pub mod preview;
 
 // Re-export commonly used types
 pub use client::{AiClient, AiClientError};
 pub use layout::{AiLayoutError, AiLayoutResponse, AiPartitionLayout, WindowPlacement};
+pub use preview::{OrganizePlan, OrganizePlanId, OrganizePreview};
@@ -0,0 +1,83 @@
+use crate::overlay;
+use crate::overlay::overlays::TilePreviewOverlay;
+use crate::platform::Bounds;
+use crate::workspace::WorkspaceId;
+use log::{error, info};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+pub type OrganizePlanId = u64;
+
+static PLAN_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Layouts proposed for workspaces, applied only once the user confirms them
+#[derive(Debug, Clone)]
+pub struct OrganizePlan {
+    id: OrganizePlanId,
+    layouts: Vec<(WorkspaceId, serde_yaml::Value)>,
+}
+
+impl OrganizePlan {
+    pub fn new(layouts: Vec<(WorkspaceId, serde_yaml::Value)>) -> Self {
+        Self {
+            id: PLAN_ID_COUNTER.fetch_add(1, Ordering::Relaxed),
+            layouts,
+        }
+    }
+
+    pub fn id(&self) -> OrganizePlanId {
+        self.id
+    }
+
+    pub fn layouts(&self) -> &[(WorkspaceId, serde_yaml::Value)] {
+        &self.layouts
+    }
+}
+
+/// Tile preview overlays showing where a plan would put each window. The overlays are removed
+/// when this is dropped, and the plan is expired if nobody confirms or cancels it in time.
+#[derive(Debug)]
+pub struct OrganizePreview {
+    _close: oneshot::Sender<()>,
+}
+
+impl OrganizePreview {
+    pub fn show(plan_id: OrganizePlanId, slots: Vec<Bounds>, timeout: Duration) -> Self {
+        let (close, closed) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let mut overlays = Vec::new();
+            for bounds in &slots {
+                match overlay::manager()
+                    .add(Box::new(TilePreviewOverlay::new()))
+                    .await
+                {
+                    Ok(overlay) => {
+                        overlay.move_to(bounds);
+                        overlay.show();
+                        overlays.push(overlay);
+                    }
+                    Err(e) => {
+                        error!("Failed to create organize preview overlay: {e}");
+                        break;
+                    }
+                }
+            }
+
+            tokio::select! {
+                _ = closed => {}
+                _ = tokio::time::sleep(timeout) => {
+                    info!("Organize preview timed out, discarding it");
+                    crate::expire_organize_plan(plan_id);
+                }
+            }
+
+            for overlay in &overlays {
+                overlay.hide();
+            }
+        });
+
+        Self { _close: close }
+    }
+}
@@ -32,6 +32,17 @@ impl Interpolatable for u8 {
     }
 }
 
+impl Interpolatable for skia_safe::Color {
+    fn interpolate(&self, target: &Self, t: f64) -> Self {
+        skia_safe::Color::from_argb(
+            self.a().interpolate(&target.a(), t),
+            self.r().interpolate(&target.r(), t),
+            self.g().interpolate(&target.g(), t),
+            self.b().interpolate(&target.b(), t),
+        )
+    }
+}
+
 // Bounds is expected to be imported from crate::platform
 use crate::platform::Bounds;
 impl Interpolatable for Bounds {
@@ -75,6 +86,61 @@ pub fn ease_in_out_cubic(t: f64) -> f64 {
     }
 }
 
+pub fn ease_in_out_quad(t: f64) -> f64 {
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+    }
+}
+
+pub fn ease_out_expo(t: f64) -> f64 {
+    if t >= 1.0 {
+        1.0
+    } else {
+        1.0 - 2f64.powf(-10.0 * t)
+    }
+}
+
+/// Slightly overshoots the target before settling on it.
+pub fn ease_out_back(t: f64) -> f64 {
+    const C1: f64 = 1.70158;
+    const C3: f64 = C1 + 1.0;
+    1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+}
+
+pub fn linear(t: f64) -> f64 {
+    t
+}
+
+/// Goes out to the target and back again, peaking halfway through.
+pub fn ease_pulse(t: f64) -> f64 {
+    (t.clamp(0.0, 1.0) * std::f64::consts::PI).sin()
+}
+
+/// Damping ratio of the spring curve. Below 1.0 the spring overshoots before settling.
+pub const SPRING_DAMPING_RATIO: f64 = 0.6;
+/// Natural angular frequency of the spring curve over the normalized animation time.
+pub const SPRING_ANGULAR_FREQUENCY: f64 = 12.0;
+/// The spring curve is within this distance of the target for the last fifth of the animation.
+pub const SPRING_SETTLE_EPSILON: f64 = 0.005;
+
+/// An underdamped spring that overshoots the target and settles back before the animation ends.
+pub fn ease_spring(t: f64) -> f64 {
+    if t <= 0.0 {
+        return 0.0;
+    }
+    if t >= 1.0 {
+        return 1.0;
+    }
+
+    let zeta = SPRING_DAMPING_RATIO;
+    let omega = SPRING_ANGULAR_FREQUENCY;
+    let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+    let envelope = (-zeta * omega * t).exp();
+    1.0 - envelope * ((omega_d * t).cos() + (zeta * omega / omega_d) * (omega_d * t).sin())
+}
+
 pub struct Animator<T>
 where
     T: Interpolatable,
@@ -88,6 +154,8 @@ where
     pub last_value: T,
     frame_times: VecDeque<Instant>,
     last_frame_time: Option<Instant>,
+    /// Average frame rate of the last animation that ran to the end
+    last_fps: Option<f64>,
 }
 
 impl<T> Animator<T>
@@ -105,6 +173,7 @@ where
             last_value: from,
             frame_times: VecDeque::with_capacity(60), // Store last 60 frames for FPS calculation
             last_frame_time: None,
+            last_fps: None,
         }
     }
 
@@ -126,7 +195,10 @@ where
 
     /// Returns Some(new_value) if animating, None if finished
     pub fn update(&mut self) -> Option<T> {
-        let now = Instant::now();
+        self.update_at(Instant::now())
+    }
+
+    fn update_at(&mut self, now: Instant) -> Option<T> {
         if !self.animating {
             return None;
         }
@@ -142,6 +214,7 @@ where
 
         if self.duration == 0 {
             self.animating = false;
+            self.last_fps = self.average_fps();
             self.last_value = self.to.clone();
             return Some(self.to.clone());
         }
@@ -152,8 +225,7 @@ where
         if t >= 1.0 {
             t = 1.0;
             self.animating = false;
-            // Can be uncommented for debugging
-            // self.print_fps();
+            self.last_fps = self.average_fps();
         }
         let eased_t = (self.ease_fn)(t);
         let value = self.from.interpolate(&self.to, eased_t);
@@ -161,14 +233,25 @@ where
         Some(value)
     }
 
-    pub fn print_fps(&self) {
-        if self.frame_times.len() < 2 {
-            return;
+    /// Frames per second over the recent frames, `None` without enough frames to tell
+    fn average_fps(&self) -> Option<f64> {
+        let (first, last) = (self.frame_times.front()?, self.frame_times.back()?);
+        let total_duration = (*last - *first).as_secs_f64();
+        if self.frame_times.len() < 2 || total_duration <= 0.0 {
+            return None;
         }
+        Some((self.frame_times.len() as f64 - 1.0) / total_duration)
+    }
+
+    /// Average frame rate of the last animation that finished, worked out once it finishes
+    pub fn last_fps(&self) -> Option<f64> {
+        self.last_fps
+    }
 
-        let total_duration = *self.frame_times.back().unwrap() - *self.frame_times.front().unwrap();
-        let fps = (self.frame_times.len() as f64 - 1.0) / total_duration.as_secs_f64();
-        debug!("Animation completed with average FPS: {fps:.1}");
+    pub fn print_fps(&self) {
+        if let Some(fps) = self.last_fps {
+            debug!("Animation completed with average FPS: {fps:.1}");
+        }
     }
 
     pub fn is_animating(&self) -> bool {
@@ -184,6 +267,7 @@ where
 mod tests {
     use super::*;
     use crate::platform::Bounds;
+    use skia_safe::Color;
     use std::time::Duration;
 
     // Helper function for linear easing (no easing)
@@ -252,6 +336,39 @@ mod tests {
         assert_eq!(result, 4);
     }
 
+    #[test]
+    fn test_color_interpolate_endpoints() {
+        let start = Color::from_argb(255, 0, 128, 255);
+        let end = Color::from_argb(0, 255, 128, 0);
+
+        assert_eq!(start.interpolate(&end, 0.0), start);
+        assert_eq!(start.interpolate(&end, 1.0), end);
+    }
+
+    #[test]
+    fn test_color_interpolate_midpoint() {
+        let start = Color::from_argb(255, 0, 128, 255);
+        let end = Color::from_argb(0, 255, 128, 0);
+        let result = start.interpolate(&end, 0.5);
+
+        assert_eq!(result, Color::from_argb(128, 128, 128, 128));
+    }
+
+    #[test]
+    fn test_color_interpolate_channels_independently() {
+        let start = Color::from_argb(255, 10, 20, 30);
+        let end = Color::from_argb(255, 110, 220, 30);
+
+        assert_eq!(
+            start.interpolate(&end, 0.5),
+            Color::from_argb(255, 60, 120, 30)
+        );
+        assert_eq!(
+            Color::BLACK.interpolate(&Color::WHITE, 0.5),
+            Color::from_argb(255, 128, 128, 128)
+        );
+    }
+
     #[test]
     fn test_bounds_interpolate_position() {
         let start = Bounds::new(0, 0, 100, 100);
@@ -340,6 +457,108 @@ mod tests {
         }
     }
 
+    fn assert_boundaries(ease_fn: fn(f64) -> f64) {
+        assert!((ease_fn(0.0) - 0.0).abs() < 1e-9);
+        assert!((ease_fn(1.0) - 1.0).abs() < 1e-9);
+    }
+
+    fn assert_monotonic(ease_fn: fn(f64) -> f64) {
+        let values: Vec<f64> = (0..=100).map(|i| ease_fn(i as f64 / 100.0)).collect();
+        for i in 1..values.len() {
+            assert!(
+                values[i] >= values[i - 1],
+                "Easing function should be monotonically increasing"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ease_in_out_quad() {
+        assert_boundaries(ease_in_out_quad);
+        assert_monotonic(ease_in_out_quad);
+        assert!((ease_in_out_quad(0.5) - 0.5).abs() < f64::EPSILON);
+        assert!((ease_in_out_quad(0.25) - 0.125).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_ease_out_expo() {
+        assert_boundaries(ease_out_expo);
+        assert_monotonic(ease_out_expo);
+        // Most of the motion happens early
+        assert!(ease_out_expo(0.25) > 0.8);
+    }
+
+    #[test]
+    fn test_ease_out_back() {
+        assert_boundaries(ease_out_back);
+        let peak = (0..=100)
+            .map(|i| ease_out_back(i as f64 / 100.0))
+            .fold(f64::MIN, f64::max);
+        assert!(peak > 1.0, "Back easing should overshoot the target");
+    }
+
+    #[test]
+    fn test_linear() {
+        assert_boundaries(super::linear);
+        assert_monotonic(super::linear);
+        assert_eq!(super::linear(0.3), 0.3);
+    }
+
+    #[test]
+    fn test_ease_spring_boundaries() {
+        assert_eq!(ease_spring(0.0), 0.0);
+        assert_eq!(ease_spring(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_ease_spring_overshoots() {
+        let peak = (0..100)
+            .map(|i| ease_spring(i as f64 / 100.0))
+            .fold(f64::MIN, f64::max);
+        assert!(
+            peak > 1.0,
+            "Spring should overshoot the target, peak was {peak}"
+        );
+    }
+
+    #[test]
+    fn test_ease_spring_settles() {
+        for i in 80..=100 {
+            let t = i as f64 / 100.0;
+            let distance = (ease_spring(t) - 1.0).abs();
+            assert!(
+                distance < SPRING_SETTLE_EPSILON,
+                "Spring should have settled at t={t}, distance was {distance}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ease_pulse_returns_to_start() {
+        assert!(ease_pulse(0.0).abs() < 1e-9);
+        assert!((ease_pulse(0.5) - 1.0).abs() < 1e-9);
+        assert!(ease_pulse(1.0).abs() < 1e-9);
+        assert!((ease_pulse(0.25) - ease_pulse(0.75)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_animator_with_spring_overshoots_then_settles() {
+        let mut animator = Animator::new(0.0f64, 100.0f64, ease_spring);
+        animator.start_from(0.0, 100.0, 100);
+
+        let mut peak = f64::MIN;
+        while let Some(value) = animator.update() {
+            peak = peak.max(value);
+            if !animator.is_animating() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(2));
+        }
+
+        assert!(peak > 100.0);
+        assert_eq!(*animator.current_value(), 100.0);
+    }
+
     // === Animator Tests ===
 
     #[test]
@@ -514,4 +733,23 @@ mod tests {
         assert_eq!(result.size.width, 100); // (50 + 150) / 2
         assert_eq!(result.size.height, 150); // (75 + 225) / 2
     }
+
+    #[test]
+    fn test_last_fps_after_steady_frames() {
+        let mut animator = Animator::new(0.0f32, 1.0f32, linear);
+        animator.start_from(0.0, 1.0, 500);
+        let start = animator.start_time.unwrap();
+
+        // 60 frames a second until the animation is over
+        let mut frame = 0;
+        while animator.is_animating() {
+            assert_eq!(animator.last_fps(), None);
+            frame += 1;
+            animator.update_at(start + Duration::from_micros(16_667 * frame));
+        }
+
+        assert_eq!(frame, 30);
+        let fps = animator.last_fps().unwrap();
+        assert!((fps - 60.0).abs() < 0.5, "{fps}");
+    }
 }
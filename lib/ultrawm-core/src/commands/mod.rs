@@ -1,13 +1,19 @@
 mod registry;
 
 pub use registry::{
-    build_commands, get_defaults, register, Command, CommandContext, CommandDef, CommandId,
+    build_commands, effective_keybinds, get_command, get_defaults, register, Command,
+    CommandContext, CommandDef, CommandFn, CommandId,
 };
 
-use crate::ai::layout::{handle_organize_all_windows, handle_organize_single_window};
+use crate::ai::layout::{
+    handle_organize_all_windows, handle_organize_heuristic, handle_organize_single_window,
+};
+use crate::config::Config;
 use crate::event_loop_wm::{WMOperationError, WMOperationResult};
+use crate::layouts::{LayoutType, Side};
 use crate::platform::WindowId;
-use crate::wm::WMError;
+use crate::wm::{WMError, WindowManager};
+use crate::workspace::WorkspaceId;
 use log::info;
 
 /// Helper to extract window_id from command context
@@ -18,6 +24,27 @@ fn get_window_id_from_context(ctx: Option<&CommandContext>) -> WMOperationResult
     })
 }
 
+/// Helper to resolve the workspace a command applies to. Prefers the target window's
+/// workspace, then the workspace at the context position, then the focused workspace.
+fn get_workspace_id_from_context(
+    wm: &WindowManager,
+    ctx: Option<&CommandContext>,
+) -> WMOperationResult<WorkspaceId> {
+    if let Some(window_id) = ctx.and_then(|c| c.target_window) {
+        let window = wm.get_window(window_id)?;
+        if let Some(workspace) = wm.get_workspace_with_window(&window) {
+            return Ok(workspace.id());
+        }
+    }
+
+    if let Some(position) = ctx.and_then(|c| c.position.as_ref()) {
+        return Ok(wm.get_workspace_at_position(position)?.id());
+    }
+
+    wm.focused_workspace_id()
+        .ok_or(WMOperationError::Error(WMError::WorkspaceNotFound(0)))
+}
+
 pub static AI_ORGANIZE_ALL_WINDOWS: CommandDef = CommandDef {
     display_name: "Auto Organize All Windows",
     id: "ai_organize_all_windows",
@@ -36,10 +63,43 @@ pub static AI_ORGANIZE_CURRENT_WINDOW: CommandDef = CommandDef {
     requires_window: true,
     handler: |wm, ctx| {
         let window_id = get_window_id_from_context(ctx)?;
+        wm.pulse_window(window_id)?;
         handle_organize_single_window(wm, window_id)
     },
 };
 
+pub static AI_ORGANIZE_CONFIRM: CommandDef = CommandDef {
+    display_name: "Confirm Auto Organize",
+    id: "ai_organize_confirm",
+    default_keybind: "cmd+alt+enter",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        if !wm.confirm_organize_plan()? {
+            info!("No organized layout is waiting to be confirmed");
+        }
+        Ok(())
+    },
+};
+
+pub static AI_ORGANIZE_CANCEL: CommandDef = CommandDef {
+    display_name: "Cancel Auto Organize",
+    id: "ai_organize_cancel",
+    default_keybind: "cmd+alt+backspace",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        wm.cancel_organize_plan();
+        Ok(())
+    },
+};
+
+pub static ORGANIZE_HEURISTIC: CommandDef = CommandDef {
+    display_name: "Organize Windows by App",
+    id: "organize_heuristic",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, _ctx| handle_organize_heuristic(wm),
+};
+
 pub static FLOAT_WINDOW: CommandDef = CommandDef {
     display_name: "Float Window",
     id: "float_window",
@@ -48,6 +108,33 @@ pub static FLOAT_WINDOW: CommandDef = CommandDef {
     handler: |wm, ctx| {
         let window_id = get_window_id_from_context(ctx)?;
         wm.float_window(window_id)?;
+        wm.pulse_window(window_id)?;
+        Ok(())
+    },
+};
+
+pub static FLOAT_ALL: CommandDef = CommandDef {
+    display_name: "Float All Windows",
+    id: "float_all",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, ctx| {
+        let workspace_id = get_workspace_id_from_context(wm, ctx)?;
+        let floated = wm.float_all(workspace_id)?;
+        info!("Floated {floated} windows on workspace {workspace_id}");
+        Ok(())
+    },
+};
+
+pub static TILE_ALL: CommandDef = CommandDef {
+    display_name: "Tile All Windows",
+    id: "tile_all",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, ctx| {
+        let workspace_id = get_workspace_id_from_context(wm, ctx)?;
+        let tiled = wm.tile_all(workspace_id)?;
+        info!("Tiled {tiled} windows on workspace {workspace_id}");
         Ok(())
     },
 };
@@ -66,6 +153,63 @@ pub static CLOSE_WINDOW: CommandDef = CommandDef {
     },
 };
 
+/// Asks to close every other window on the target window's workspace, closed once
+/// `CLOSE_CONFIRM` runs. Unbound by default, so it only runs when a keybind or menu item asks
+/// for it with a target window.
+pub static CLOSE_OTHERS: CommandDef = CommandDef {
+    display_name: "Close Other Windows",
+    id: "close_others",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| {
+        let window_id = get_window_id_from_context(ctx)?;
+        let workspace_id = get_workspace_id_from_context(wm, ctx)?;
+        let count = wm.request_close_windows(workspace_id, Some(window_id))?;
+        info!("Confirm to close {count} other windows");
+        Ok(())
+    },
+};
+
+/// Asks to close every window on the workspace. Unbound by default like `CLOSE_OTHERS`.
+pub static CLOSE_WORKSPACE_WINDOWS: CommandDef = CommandDef {
+    display_name: "Close All Windows in Workspace",
+    id: "close_workspace_windows",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, ctx| {
+        let workspace_id = get_workspace_id_from_context(wm, ctx)?;
+        let count = wm.request_close_windows(workspace_id, None)?;
+        info!("Confirm to close {count} windows");
+        Ok(())
+    },
+};
+
+pub static CLOSE_CONFIRM: CommandDef = CommandDef {
+    display_name: "Confirm Closing Windows",
+    id: "close_confirm",
+    default_keybind: "cmd+alt+x",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        match wm.confirm_close_windows()? {
+            Some(closed) => info!("Closed {} windows", closed.len()),
+            None => info!("No windows are waiting to be closed"),
+        }
+        Ok(())
+    },
+};
+
+/// Unbound by default, an unconfirmed close is dropped after a few seconds anyway
+pub static CLOSE_CANCEL: CommandDef = CommandDef {
+    display_name: "Cancel Closing Windows",
+    id: "close_cancel",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        wm.cancel_close_windows();
+        Ok(())
+    },
+};
+
 pub static MINIMIZE_WINDOW: CommandDef = CommandDef {
     display_name: "Minimize Window",
     id: "minimize_window",
@@ -79,10 +223,540 @@ pub static MINIMIZE_WINDOW: CommandDef = CommandDef {
     },
 };
 
+pub static RENAME_WORKSPACE: CommandDef = CommandDef {
+    display_name: "Rename Workspace",
+    id: "rename_workspace",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, ctx| {
+        let Some(name) = ctx.and_then(|c| c.argument.as_deref()) else {
+            info!("No workspace name provided for rename_workspace");
+            return Ok(());
+        };
+        let workspace_id = get_workspace_id_from_context(wm, ctx)?;
+        let name = wm.rename_workspace(workspace_id, name)?;
+        info!("Renamed workspace {workspace_id} to {name:?}");
+        Ok(())
+    },
+};
+
+/// Shows the workspace named by the argument on its partition
+pub static SWITCH_WORKSPACE: CommandDef = CommandDef {
+    display_name: "Switch Workspace",
+    id: "switch_workspace",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, ctx| {
+        let Some(name) = ctx.and_then(|c| c.argument.as_deref()) else {
+            info!("No workspace name provided for switch_workspace");
+            return Ok(());
+        };
+        let name = name.trim();
+        let workspace_id = wm
+            .find_workspace_by_name(name)
+            .ok_or_else(|| WMError::UnknownWorkspaceName(name.to_string()))?;
+        wm.switch_workspace(workspace_id)?;
+        Ok(())
+    },
+};
+
+pub static INCREASE_GAPS: CommandDef = CommandDef {
+    display_name: "Increase Gaps",
+    id: "gaps_increase",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        let gap = wm.adjust_window_gap(Config::window_gap_step() as i32)?;
+        info!("Window gap set to {gap}px");
+        Ok(())
+    },
+};
+
+pub static DECREASE_GAPS: CommandDef = CommandDef {
+    display_name: "Decrease Gaps",
+    id: "gaps_decrease",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        let gap = wm.adjust_window_gap(-(Config::window_gap_step() as i32))?;
+        info!("Window gap set to {gap}px");
+        Ok(())
+    },
+};
+
+pub static BALANCE_WEIGHTED: CommandDef = CommandDef {
+    display_name: "Balance Windows by App",
+    id: "balance_weighted",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, ctx| {
+        let workspace_id = get_workspace_id_from_context(wm, ctx)?;
+        wm.balance_weighted(workspace_id)?;
+        Ok(())
+    },
+};
+
+pub static GOLDEN_RATIO_LAYOUT: CommandDef = CommandDef {
+    display_name: "Golden Ratio Layout",
+    id: "golden_ratio_layout",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, ctx| {
+        let workspace_id = get_workspace_id_from_context(wm, ctx)?;
+        wm.apply_golden_ratio(workspace_id)?;
+        Ok(())
+    },
+};
+
+fn swap_window(
+    wm: &mut WindowManager,
+    ctx: Option<&CommandContext>,
+    side: Side,
+) -> WMOperationResult<()> {
+    let window_id = get_window_id_from_context(ctx)?;
+    wm.swap_direction(window_id, side)?;
+    Ok(())
+}
+
+pub static SWAP_LEFT: CommandDef = CommandDef {
+    display_name: "Swap Window Left",
+    id: "swap_left",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| swap_window(wm, ctx, Side::Left),
+};
+
+pub static SWAP_RIGHT: CommandDef = CommandDef {
+    display_name: "Swap Window Right",
+    id: "swap_right",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| swap_window(wm, ctx, Side::Right),
+};
+
+pub static SWAP_UP: CommandDef = CommandDef {
+    display_name: "Swap Window Up",
+    id: "swap_up",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| swap_window(wm, ctx, Side::Top),
+};
+
+pub static SWAP_DOWN: CommandDef = CommandDef {
+    display_name: "Swap Window Down",
+    id: "swap_down",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| swap_window(wm, ctx, Side::Bottom),
+};
+
+pub static SWAP_LARGEST: CommandDef = CommandDef {
+    display_name: "Swap With Largest Window",
+    id: "swap_largest",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| {
+        let window_id = get_window_id_from_context(ctx)?;
+        wm.swap_with_largest(window_id)?;
+        Ok(())
+    },
+};
+
+pub static SWAP_SMALLEST: CommandDef = CommandDef {
+    display_name: "Swap With Smallest Window",
+    id: "swap_smallest",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| {
+        let window_id = get_window_id_from_context(ctx)?;
+        wm.swap_with_smallest(window_id)?;
+        Ok(())
+    },
+};
+
+fn split_and_open(
+    wm: &mut WindowManager,
+    ctx: Option<&CommandContext>,
+    side: Side,
+) -> WMOperationResult<()> {
+    let window_id = get_window_id_from_context(ctx)?;
+    wm.split_next_window(window_id, side)?;
+    Ok(())
+}
+
+pub static SPLIT_AND_OPEN_LEFT: CommandDef = CommandDef {
+    display_name: "Open Next Window Left",
+    id: "split_and_open_left",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| split_and_open(wm, ctx, Side::Left),
+};
+
+pub static SPLIT_AND_OPEN_RIGHT: CommandDef = CommandDef {
+    display_name: "Open Next Window Right",
+    id: "split_and_open_right",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| split_and_open(wm, ctx, Side::Right),
+};
+
+pub static SPLIT_AND_OPEN_UP: CommandDef = CommandDef {
+    display_name: "Open Next Window Above",
+    id: "split_and_open_up",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| split_and_open(wm, ctx, Side::Top),
+};
+
+pub static SPLIT_AND_OPEN_DOWN: CommandDef = CommandDef {
+    display_name: "Open Next Window Below",
+    id: "split_and_open_down",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| split_and_open(wm, ctx, Side::Bottom),
+};
+
+pub static FOCUS_NEXT: CommandDef = CommandDef {
+    display_name: "Focus Next Window",
+    id: "focus_next",
+    default_keybind: "cmd+j",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        wm.focus_next()?;
+        Ok(())
+    },
+};
+
+pub static FOCUS_PREV: CommandDef = CommandDef {
+    display_name: "Focus Previous Window",
+    id: "focus_prev",
+    default_keybind: "cmd+k",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        wm.focus_prev()?;
+        Ok(())
+    },
+};
+
+pub static FOCUS_NEXT_IN_APP: CommandDef = CommandDef {
+    display_name: "Focus Next Window of App",
+    id: "focus_next_in_app",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        wm.focus_next_in_app()?;
+        Ok(())
+    },
+};
+
+pub static FOCUS_PREV_IN_APP: CommandDef = CommandDef {
+    display_name: "Focus Previous Window of App",
+    id: "focus_prev_in_app",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        wm.focus_prev_in_app()?;
+        Ok(())
+    },
+};
+
+fn move_to_display(
+    wm: &mut WindowManager,
+    ctx: Option<&CommandContext>,
+    next: bool,
+) -> WMOperationResult<()> {
+    let window_id = get_window_id_from_context(ctx)?;
+    let window = wm.get_window(window_id)?;
+    let Some(partition_id) = wm.get_partition_with_window(&window).map(|p| p.id()) else {
+        info!("Window {window_id} isn't on a display");
+        return Ok(());
+    };
+    let target = wm
+        .adjacent_partition(partition_id, next)
+        .filter(|target| *target != partition_id);
+    if let Some(target) = target {
+        wm.move_window_to_partition(window_id, target)?;
+    }
+    Ok(())
+}
+
+pub static MOVE_TO_DISPLAY_NEXT: CommandDef = CommandDef {
+    display_name: "Move Window to Next Display",
+    id: "move_to_display_next",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| move_to_display(wm, ctx, true),
+};
+
+pub static MOVE_TO_DISPLAY_PREV: CommandDef = CommandDef {
+    display_name: "Move Window to Previous Display",
+    id: "move_to_display_prev",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| move_to_display(wm, ctx, false),
+};
+
+fn resize_window_step(
+    wm: &mut WindowManager,
+    ctx: Option<&CommandContext>,
+    side: Side,
+    grow: bool,
+) -> WMOperationResult<()> {
+    let window_id = get_window_id_from_context(ctx)?;
+    let step = Config::keyboard_resize_step() as i32;
+    wm.resize_window_step(window_id, side, if grow { step } else { -step })
+        .map_err(WMOperationError::Resize)?;
+    Ok(())
+}
+
+pub static RESIZE_GROW_WIDTH: CommandDef = CommandDef {
+    display_name: "Grow Window Width",
+    id: "resize_grow_width",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| resize_window_step(wm, ctx, Side::Right, true),
+};
+
+pub static RESIZE_SHRINK_WIDTH: CommandDef = CommandDef {
+    display_name: "Shrink Window Width",
+    id: "resize_shrink_width",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| resize_window_step(wm, ctx, Side::Right, false),
+};
+
+pub static RESIZE_GROW_HEIGHT: CommandDef = CommandDef {
+    display_name: "Grow Window Height",
+    id: "resize_grow_height",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| resize_window_step(wm, ctx, Side::Bottom, true),
+};
+
+pub static RESIZE_SHRINK_HEIGHT: CommandDef = CommandDef {
+    display_name: "Shrink Window Height",
+    id: "resize_shrink_height",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| resize_window_step(wm, ctx, Side::Bottom, false),
+};
+
+pub static PROMOTE_WINDOW: CommandDef = CommandDef {
+    display_name: "Promote Window",
+    id: "promote_window",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| {
+        let window_id = get_window_id_from_context(ctx)?;
+        wm.promote_window(window_id)?;
+        Ok(())
+    },
+};
+
+fn current_layout_type(wm: &WindowManager, workspace_id: WorkspaceId) -> LayoutType {
+    wm.workspaces()
+        .get(&workspace_id)
+        .map(|w| w.layout_type())
+        .unwrap_or_default()
+}
+
+pub static SET_LAYOUT: CommandDef = CommandDef {
+    display_name: "Set Workspace Layout",
+    id: "set_layout",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, ctx| {
+        let Some(name) = ctx.and_then(|c| c.argument.as_deref()) else {
+            info!("No layout provided for set_layout");
+            return Ok(());
+        };
+        let name = name.trim();
+        let layout_type = serde_yaml::from_str::<LayoutType>(name)
+            .map_err(|_| WMError::UnknownLayout(name.to_string()))?;
+        let workspace_id = get_workspace_id_from_context(wm, ctx)?;
+        wm.set_workspace_layout(workspace_id, layout_type)?;
+        info!("Workspace {workspace_id} now uses the {layout_type:?} layout");
+        Ok(())
+    },
+};
+
+pub static CYCLE_LAYOUT: CommandDef = CommandDef {
+    display_name: "Cycle Workspace Layout",
+    id: "cycle_layout",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, ctx| {
+        let workspace_id = get_workspace_id_from_context(wm, ctx)?;
+        let layout_type = current_layout_type(wm, workspace_id).next();
+        wm.set_workspace_layout(workspace_id, layout_type)?;
+        info!("Workspace {workspace_id} now uses the {layout_type:?} layout");
+        Ok(())
+    },
+};
+
+pub static TOGGLE_STICKY: CommandDef = CommandDef {
+    display_name: "Toggle Sticky Window",
+    id: "toggle_sticky",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| {
+        let window_id = get_window_id_from_context(ctx)?;
+        let sticky = wm.toggle_sticky(window_id)?;
+        wm.pulse_window(window_id)?;
+        info!("Window {window_id} sticky: {sticky}");
+        Ok(())
+    },
+};
+
+pub static TOGGLE_FULLSCREEN: CommandDef = CommandDef {
+    display_name: "Toggle Full Screen",
+    id: "toggle_fullscreen",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| {
+        let window_id = get_window_id_from_context(ctx)?;
+        wm.toggle_fullscreen(window_id)?;
+        Ok(())
+    },
+};
+
+pub static TOGGLE_ALWAYS_ON_TOP: CommandDef = CommandDef {
+    display_name: "Toggle Always on Top",
+    id: "toggle_always_on_top",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| {
+        let window_id = get_window_id_from_context(ctx)?;
+        let on_top = wm.toggle_always_on_top(window_id)?;
+        info!("Window {window_id} always on top: {on_top}");
+        Ok(())
+    },
+};
+
+pub static TOGGLE_ASPECT_LOCK: CommandDef = CommandDef {
+    display_name: "Toggle Aspect Ratio Lock",
+    id: "toggle_aspect_lock",
+    default_keybind: "",
+    requires_window: true,
+    handler: |wm, ctx| {
+        let window_id = get_window_id_from_context(ctx)?;
+        let locked = wm.toggle_aspect_lock(window_id)?;
+        wm.pulse_window(window_id)?;
+        info!("Window {window_id} aspect ratio locked: {locked}");
+        Ok(())
+    },
+};
+
+pub static TOGGLE_TILING: CommandDef = CommandDef {
+    display_name: "Toggle Tiling",
+    id: "toggle_tiling",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        let paused = wm.toggle_paused()?;
+        info!("Tiling paused: {paused}");
+        Ok(())
+    },
+};
+
+pub static SCRATCHPAD_TOGGLE: CommandDef = CommandDef {
+    display_name: "Toggle Scratchpad",
+    id: "scratchpad_toggle",
+    default_keybind: "",
+    requires_window: false,
+    handler: |wm, ctx| {
+        // Hide the target if it came from the scratchpad, otherwise summon the last stashed
+        // window, and only stash the target when there's nothing to summon
+        let target = ctx.and_then(|c| c.target_window);
+        let window_id = match target.filter(|id| wm.in_scratchpad(*id)) {
+            Some(id) => id,
+            None => match wm.last_stashed_window() {
+                Some(id) => id,
+                None => get_window_id_from_context(ctx)?,
+            },
+        };
+        let showing = wm.scratchpad_toggle(window_id)?;
+        info!("Scratchpad window {window_id} showing: {showing}");
+        Ok(())
+    },
+};
+
+pub static UNDO: CommandDef = CommandDef {
+    display_name: "Undo Layout Change",
+    id: "undo",
+    default_keybind: "cmd+alt+z",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        if !wm.undo()? {
+            info!("Nothing to undo");
+        }
+        Ok(())
+    },
+};
+
+pub static REDO: CommandDef = CommandDef {
+    display_name: "Redo Layout Change",
+    id: "redo",
+    default_keybind: "cmd+alt+shift+z",
+    requires_window: false,
+    handler: |wm, _ctx| {
+        if !wm.redo()? {
+            info!("Nothing to redo");
+        }
+        Ok(())
+    },
+};
+
 pub fn register_commands() {
     register(&AI_ORGANIZE_ALL_WINDOWS);
     register(&AI_ORGANIZE_CURRENT_WINDOW);
+    register(&AI_ORGANIZE_CONFIRM);
+    register(&AI_ORGANIZE_CANCEL);
+    register(&ORGANIZE_HEURISTIC);
     register(&FLOAT_WINDOW);
+    register(&FLOAT_ALL);
+    register(&TILE_ALL);
     register(&CLOSE_WINDOW);
+    register(&CLOSE_OTHERS);
+    register(&CLOSE_WORKSPACE_WINDOWS);
+    register(&CLOSE_CONFIRM);
+    register(&CLOSE_CANCEL);
     register(&MINIMIZE_WINDOW);
+    register(&RENAME_WORKSPACE);
+    register(&SWITCH_WORKSPACE);
+    register(&INCREASE_GAPS);
+    register(&DECREASE_GAPS);
+    register(&BALANCE_WEIGHTED);
+    register(&GOLDEN_RATIO_LAYOUT);
+    register(&SWAP_LEFT);
+    register(&SWAP_RIGHT);
+    register(&SWAP_UP);
+    register(&SWAP_DOWN);
+    register(&SWAP_LARGEST);
+    register(&SWAP_SMALLEST);
+    register(&SPLIT_AND_OPEN_LEFT);
+    register(&SPLIT_AND_OPEN_RIGHT);
+    register(&SPLIT_AND_OPEN_UP);
+    register(&SPLIT_AND_OPEN_DOWN);
+    register(&FOCUS_NEXT);
+    register(&FOCUS_PREV);
+    register(&FOCUS_NEXT_IN_APP);
+    register(&FOCUS_PREV_IN_APP);
+    register(&MOVE_TO_DISPLAY_NEXT);
+    register(&MOVE_TO_DISPLAY_PREV);
+    register(&RESIZE_GROW_WIDTH);
+    register(&RESIZE_SHRINK_WIDTH);
+    register(&RESIZE_GROW_HEIGHT);
+    register(&RESIZE_SHRINK_HEIGHT);
+    register(&PROMOTE_WINDOW);
+    register(&SET_LAYOUT);
+    register(&CYCLE_LAYOUT);
+    register(&TOGGLE_STICKY);
+    register(&TOGGLE_FULLSCREEN);
+    register(&TOGGLE_ALWAYS_ON_TOP);
+    register(&TOGGLE_ASPECT_LOCK);
+    register(&TOGGLE_TILING);
+    register(&SCRATCHPAD_TOGGLE);
+    register(&UNDO);
+    register(&REDO);
 }
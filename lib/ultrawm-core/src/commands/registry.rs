@@ -13,6 +13,7 @@ pub type CommandId = String;
 pub struct CommandContext {
     pub target_window: Option<WindowId>,
     pub position: Option<Position>,
+    pub argument: Option<String>,
 }
 
 impl CommandContext {
@@ -20,6 +21,7 @@ impl CommandContext {
         Self {
             target_window: None,
             position: None,
+            argument: None,
         }
     }
 
@@ -27,6 +29,7 @@ impl CommandContext {
         Self {
             target_window: Some(window_id),
             position: None,
+            argument: None,
         }
     }
 
@@ -34,6 +37,7 @@ impl CommandContext {
         Self {
             target_window: None,
             position: Some(position),
+            argument: None,
         }
     }
 
@@ -41,6 +45,15 @@ impl CommandContext {
         Self {
             target_window: Some(window_id),
             position: Some(position),
+            argument: None,
+        }
+    }
+
+    pub fn with_argument(argument: impl Into<String>) -> Self {
+        Self {
+            target_window: None,
+            position: None,
+            argument: Some(argument.into()),
         }
     }
 }
@@ -58,10 +71,20 @@ static REGISTRY: LazyLock<RwLock<Vec<&'static CommandDef>>> =
 
 pub fn register(def: &'static CommandDef) {
     if let Ok(mut registry) = REGISTRY.write() {
-        registry.push(def);
+        if !registry.iter().any(|registered| registered.id == def.id) {
+            registry.push(def);
+        }
     }
 }
 
+/// Looks up a registered command by id.
+pub fn get_command(id: &str) -> Option<&'static CommandDef> {
+    REGISTRY
+        .read()
+        .ok()
+        .and_then(|registry| registry.iter().find(|def| def.id == id).copied())
+}
+
 pub fn get_defaults() -> HashMap<String, String> {
     REGISTRY
         .read()
@@ -74,37 +97,71 @@ pub fn get_defaults() -> HashMap<String, String> {
         .unwrap_or_default()
 }
 
-pub struct Command {
-    pub id: CommandId,
-    pub tracker: KeyboardKeybindTracker,
-    pub handler: CommandFn,
-}
-
-pub fn build_commands(keybinds: &HashMap<String, KeyboardKeybind>) -> Vec<Command> {
+/// The keybind each registered command ends up with, the configured one if there is one and
+/// its default otherwise
+pub fn effective_keybinds(
+    keybinds: &HashMap<String, KeyboardKeybind>,
+) -> Vec<(&'static CommandDef, KeyboardKeybind)> {
     REGISTRY
         .read()
         .map(|registry| {
             registry
                 .iter()
-                .filter_map(|def| {
+                .map(|def| {
                     let keybind = keybinds
                         .get(def.id)
                         .cloned()
                         .unwrap_or_else(|| vec![def.default_keybind].into());
-
-                    if keybind.combos().is_empty()
-                        || keybind.combos().iter().all(|combo| !combo.keys().any())
-                    {
-                        return None;
-                    }
-
-                    Some(Command {
-                        id: def.id.to_string(),
-                        tracker: KeyboardKeybindTracker::new(keybind),
-                        handler: def.handler,
-                    })
+                    (*def, keybind)
                 })
                 .collect()
         })
         .unwrap_or_default()
 }
+
+pub struct Command {
+    pub id: CommandId,
+    pub tracker: KeyboardKeybindTracker,
+    pub handler: CommandFn,
+}
+
+pub fn build_commands(keybinds: &HashMap<String, KeyboardKeybind>) -> Vec<Command> {
+    effective_keybinds(keybinds)
+        .into_iter()
+        .filter_map(|(def, keybind)| {
+            if keybind.combos().is_empty()
+                || keybind.combos().iter().all(|combo| !combo.keys().any())
+            {
+                return None;
+            }
+
+            Some(Command {
+                id: def.id.to_string(),
+                tracker: KeyboardKeybindTracker::new(keybind),
+                handler: def.handler,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_keybinds_override_defaults() {
+        crate::commands::register_commands();
+        let keybinds = HashMap::from([("undo".to_string(), vec!["ctrl+u"].into())]);
+
+        let effective = effective_keybinds(&keybinds);
+        let keybind = |id: &str| {
+            effective
+                .iter()
+                .find(|(def, _)| def.id == id)
+                .map(|(_, keybind)| keybind.combos()[0].to_string())
+        };
+
+        assert_eq!(keybind("undo").as_deref(), Some("ctrl+u"));
+        assert_eq!(keybind("redo").as_deref(), Some("shift+alt+cmd+z"));
+    }
+}
@@ -1,15 +1,23 @@
-use crate::config::config_serializer::serialize_config;
-use crate::config::{KeyboardKeybind, ModMouseKeybind, MouseKeybind};
+use crate::animation::{
+    ease_in_out_cubic, ease_in_out_quad, ease_out_back, ease_out_expo, ease_spring, linear,
+};
+use crate::config::config_serializer::{serialize_config, serialize_config_toml};
+use crate::config::{InputCombo, KeyboardKeybind, ModMouseKeybind, MouseKeybind};
+use crate::layouts::{LayoutType, Side};
+use crate::platform::Display;
 use crate::{commands, paths};
-use log::{info, warn};
-use once_cell::sync::Lazy;
+use log::{info, trace, warn};
+use once_cell::sync::{Lazy, OnceCell};
+use regex::Regex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::RwLock;
+use thiserror::Error;
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 #[serde(transparent)]
@@ -25,6 +33,78 @@ impl Commands {
                 .or_insert_with(|| vec![default.as_str()].into());
         }
     }
+
+    /// Key combos bound to more than one command, sorted by combo
+    pub fn conflicts(&self) -> Vec<KeybindConflict> {
+        let mut bound: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (def, keybind) in commands::effective_keybinds(&self.keybinds) {
+            for combo in keybind.combos() {
+                if !combo.keys().any() {
+                    continue;
+                }
+                let commands = bound.entry(canonical_combo(combo)).or_default();
+                if !commands.iter().any(|id| id == def.id) {
+                    commands.push(def.id.to_string());
+                }
+            }
+        }
+
+        bound
+            .into_iter()
+            .filter(|(_, commands)| commands.len() > 1)
+            .map(|(keybind, mut commands)| {
+                commands.sort();
+                KeybindConflict { keybind, commands }
+            })
+            .collect()
+    }
+}
+
+/// A combo written the same way no matter how it was typed in the config, modifiers first
+fn canonical_combo(combo: &InputCombo) -> String {
+    combo
+        .to_string()
+        .split_whitespace()
+        .map(|step| {
+            let (mut parts, mut keys): (Vec<&str>, Vec<&str>) = step
+                .split('+')
+                .partition(|part| matches!(*part, "ctrl" | "shift" | "alt" | "cmd"));
+            keys.sort();
+            parts.append(&mut keys);
+            parts.join("+")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A key combo that triggers more than one command
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeybindConflict {
+    pub keybind: String,
+    pub commands: Vec<String>,
+}
+
+impl fmt::Display for KeybindConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is bound to {}",
+            self.keybind,
+            self.commands.join(", ")
+        )
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Conflicting keybinds: {}", .0.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("; "))]
+    KeybindConflicts(Vec<KeybindConflict>),
+    #[error("Workspaces pinned to displays that aren't connected: {}", .0.join(", "))]
+    UnknownDisplays(Vec<String>),
+    #[error("Drag modifiers that aren't modifier keys, using the default bindings: {}", .0.join(", "))]
+    InvalidDragModifiers(Vec<String>),
+    #[error("Window rule patterns that aren't valid regexes, their rules never match: {}", .0.join(", "))]
+    InvalidRulePatterns(Vec<String>),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -36,34 +116,122 @@ pub struct Config {
 
     /// Save and restore your window layout when UltraWM starts
     pub persistence: bool,
+    /// How long layout saves are held back so a burst of changes, like a drag, is written to
+    /// disk once (0 saves after every change)
+    pub layout_save_debounce_ms: u64,
+    /// How many layout changes the undo command can step back through (0 disables undo)
+    pub undo_history_limit: u32,
+    /// Number of side-by-side partitions each display is split into
+    pub partitions_per_display: u32,
+    /// Relative widths of the partitions on a display, left to right. Leave empty to split
+    /// evenly. Ignored unless there is one value per partition.
+    pub partition_ratios: Vec<f32>,
     /// Space between windows in pixels (set to 0 for no gaps)
     pub window_gap: u32,
     /// Space between screen edges and windows in pixels
     pub partition_gap: u32,
+    /// Space between screen edges and windows in pixels, used instead of partition_gap when
+    /// set
+    pub outer_gap: Option<u32>,
+    /// Drop the window and partition gaps on workspaces with a single tiled window, so it
+    /// fills the partition
+    pub smart_gaps: bool,
+    /// How many pixels the increase/decrease gap commands change the window gap by
+    pub window_gap_step: u32,
+    /// How many pixels the keyboard resize commands grow or shrink a window by
+    pub keyboard_resize_step: u32,
+    /// Layout for new workspaces: "container-tree" or "master-stack"
+    pub default_layout: LayoutType,
+    /// Layout to use for specific workspaces by name, overrides default_layout
+    pub workspace_layouts: HashMap<String, LayoutType>,
+    /// Settings for specific workspaces by name, each one set overrides the global setting
+    pub workspaces: HashMap<String, WorkspaceConfig>,
+    /// Fraction of the width given to the master window in the master-stack layout
+    pub master_ratio: f32,
     /// New windows start as floating instead of automatically tiling
     pub float_new_windows: bool,
+    /// Put windows back where they were before they were tiled when they're floated again
+    pub remember_float_bounds: bool,
+    /// Center floated windows in the partition under them instead of keeping their bounds
+    pub float_window_centered: bool,
+    /// Fraction of the partition's width and height a centered floating window is sized to
+    pub float_window_size_ratio: f32,
+    /// New windows smaller than this (width, height) in pixels are floated instead of tiled
+    pub auto_float_min_tile_size: (u32, u32),
+    /// Only tile new windows that have a title, untitled windows are floated
+    pub require_title_to_tile: bool,
+    /// Whether new windows take focus: "focus", "no-focus", or "focus-if-same-app"
+    pub new_window_focus: NewWindowFocus,
+    /// Side of the focused window new windows are tiled on: "left", "right", "top" or
+    /// "bottom". When unset, new windows are tiled where they open
+    pub new_window_side: Option<Side>,
+    /// Opacity of windows that don't have focus (0.0 - 1.0, 1.0 disables dimming)
+    pub inactive_window_opacity: f32,
     /// Automatically focus windows when your mouse hovers over them
     pub focus_on_hover: bool,
+    /// How long in milliseconds the mouse has to rest on a window before focus on hover
+    /// focuses it. Passing over a window quicker than this leaves focus alone.
+    pub focus_hover_delay_ms: u32,
+    /// Move the mouse to the center of a window when it's focused from the keyboard
+    pub mouse_follows_focus: bool,
     /// Automatically focus windows when you start dragging them with a modifier key
     pub focus_on_drag: bool,
     /// The number of frames per second for overlay animations (tile preview, resize handles, etc.)
     pub overlay_animation_fps: u32,
+    /// The motion curve for animations (cubic, quad_in_out, expo_out, back_out, spring, or linear)
+    pub animation_easing: AnimationCurve,
     /// How long tile preview animations take in milliseconds
     pub tile_preview_animation_ms: u32,
     /// Enable fade in/out effects for tile previews
     pub tile_preview_fade_animate: bool,
     /// Enable movement animations for tile previews
     pub tile_preview_move_animate: bool,
+    /// Briefly show the active workspace name and index (e.g. 2/4) when the workspace changes
+    pub show_workspace_indicator: bool,
+    /// How long the workspace indicator stays visible in milliseconds
+    pub workspace_indicator_timeout_ms: u32,
     /// Enable animations when tiling windows
     pub window_tile_animate: bool,
-    /// How long window tiling animations take in milliseconds
+    /// How long window tiling animations take in milliseconds. The default for the per
+    /// operation durations below.
     pub window_tile_animation_ms: u32,
+    /// How long windows take to move into their tiled place, uses window_tile_animation_ms when
+    /// not set
+    pub tile_animation_ms: Option<u32>,
+    /// How long windows take to move when switching workspaces, uses window_tile_animation_ms
+    /// when not set
+    pub workspace_switch_animation_ms: Option<u32>,
+    /// How long a window takes to settle when it's floated, uses window_tile_animation_ms when
+    /// not set
+    pub float_snap_animation_ms: Option<u32>,
+    /// Where windows go while they're hidden, e.g. on another workspace or in the scratchpad:
+    /// "offscreen-right", "offscreen-bottom", or "minimize"
+    pub hidden_window_parking: HiddenWindowParking,
+    /// The motion curve for window tiling animations, uses animation_easing when not set
+    pub window_tile_animation_curve: Option<AnimationCurve>,
     /// The number of frames per second for window tiling animations
     pub window_tile_fps: u32,
+    /// Briefly dim a window when a command acts on it without visibly moving it
+    pub window_pulse: bool,
+    /// How far the window opacity dips during a pulse (0.0 - 1.0)
+    pub window_pulse_opacity: f32,
+    /// How long a window pulse takes in milliseconds
+    pub window_pulse_ms: u32,
     /// Show transparent resize handles between tiled windows for easy resizing
     pub resize_handles: bool,
-    /// Width of the transparent resize handles in pixels
-    pub resize_handle_width: u32,
+    /// Width of the area around a resize handle that can be grabbed, in pixels
+    pub resize_handle_hit_width: u32,
+    /// Width of the resize handle highlight that's drawn, in pixels
+    pub resize_handle_render_width: u32,
+    /// Replaced by resize_handle_hit_width and resize_handle_render_width, only read from
+    /// older config files
+    #[serde(skip_serializing)]
+    #[schemars(skip)]
+    pub resize_handle_width: Option<u32>,
+    /// Two clicks on a resize handle within this many milliseconds reset its split to equal sizes
+    pub resize_handle_double_click_ms: u32,
+    /// Fraction of the container that dragged splits snap to while the snap binding is held
+    pub resize_snap_increment: f32,
     /// Color of the resize handles (red, green, blue from 0-255)
     pub resize_handle_color: (u8, u8, u8),
     /// Opacity of drag handle highlight (0.0 - 1.0)
@@ -76,8 +244,29 @@ pub struct Config {
     pub resize_handle_bindings: ResizeHandleBindings,
     /// Mouse controls for moving and resizing windows with a modifier key
     pub mod_transform_bindings: ModTransformBindings,
-    /// Keyboard shortcuts for commands
+    /// Modifier that moves a window when held while dragging with the left mouse button, like
+    /// "alt". Replaces the tile binding when set, other keys are ignored
+    pub mod_drag_move_modifier: Option<String>,
+    /// Modifier that resizes a window when held while dragging with the right mouse button, like
+    /// "alt". Replaces the resize binding when set, other keys are ignored
+    pub mod_drag_resize_modifier: Option<String>,
+    /// Resize from the corner of whichever quadrant of the window the drag starts in, instead of
+    /// the nearest edge or sliding from the middle
+    pub resize_from_nearest_corner: bool,
+    /// Relative size of each app's windows when balancing by weight, apps not listed use 1.0
+    pub app_size_weights: HashMap<String, f32>,
+    /// Rules that change how matching windows are managed, checked in order (later rules win)
+    pub window_rules: Vec<WindowRule>,
+    /// Keyboard shortcuts for commands. A shortcut can be a sequence of combos separated by
+    /// spaces, like `cmd+w 1`
     pub commands: Commands,
+    /// How long to wait for the next combo of a keyboard sequence before starting over
+    pub key_sequence_timeout_ms: u64,
+    /// Modifier + mouse button shortcuts for commands, run on the window under the cursor when
+    /// clicked without dragging
+    pub mouse_commands: HashMap<String, ModMouseKeybind>,
+    /// Items in the right-click context menu, in order
+    pub context_menu: Vec<MenuItemDef>,
     /// AI-powered window organization settings
     pub ai: AiConfig,
 }
@@ -95,6 +284,8 @@ pub struct ResizeHandleBindings {
     pub resize_before_symmetric: MouseKeybind,
     /// Equally resize the sides of the right/bottom window
     pub resize_after_symmetric: MouseKeybind,
+    /// Hold while dragging a handle to snap the split to resize_snap_increment
+    pub snap: KeyboardKeybind,
 }
 
 impl Default for ResizeHandleBindings {
@@ -105,6 +296,7 @@ impl Default for ResizeHandleBindings {
             resize_evenly: vec!["mmb"].into(),
             resize_before_symmetric: vec!["lmb+mmb"].into(),
             resize_after_symmetric: vec!["rmb+mmb"].into(),
+            snap: vec!["shift"].into(),
         }
     }
 }
@@ -142,16 +334,170 @@ impl Default for ModTransformBindings {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum NewWindowFocus {
+    /// Leave new windows focused, as the OS does
+    Focus,
+    /// Keep focus on the previously focused window
+    NoFocus,
+    /// Only focus new windows from the same app as the focused window
+    FocusIfSameApp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum HiddenWindowParking {
+    /// Past the right edge of the rightmost display
+    OffscreenRight,
+    /// Below the bottom edge of the lowest display
+    OffscreenBottom,
+    /// Minimize the window
+    Minimize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationCurve {
+    Cubic,
+    Spring,
+    Linear,
+    QuadInOut,
+    ExpoOut,
+    BackOut,
+}
+
+impl AnimationCurve {
+    pub fn ease_fn(&self) -> fn(f64) -> f64 {
+        match self {
+            AnimationCurve::Cubic => ease_in_out_cubic,
+            AnimationCurve::Spring => ease_spring,
+            AnimationCurve::Linear => linear,
+            AnimationCurve::QuadInOut => ease_in_out_quad,
+            AnimationCurve::ExpoOut => ease_out_expo,
+            AnimationCurve::BackOut => ease_out_back,
+        }
+    }
+}
+
+/// An entry in the context menu. Runs a command, opens a submenu or draws a separator.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct MenuItemDef {
+    /// Text shown for the item, defaults to the command's name
+    pub label: Option<String>,
+    /// Id of the command to run on the clicked window
+    pub command: Option<String>,
+    /// Argument passed to the command (e.g., a layout or workspace name)
+    pub argument: Option<String>,
+    /// Items shown in a submenu under this one
+    pub items: Vec<MenuItemDef>,
+    /// Draw a separator line instead of an item
+    pub separator: bool,
+}
+
+impl MenuItemDef {
+    pub fn command(id: &str) -> Self {
+        Self {
+            command: Some(id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn separator() -> Self {
+        Self {
+            separator: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn default_context_menu() -> Vec<Self> {
+        vec![
+            Self::command("ai_organize_current_window"),
+            Self::command("ai_organize_all_windows"),
+            Self::separator(),
+            Self::command("float_window"),
+            Self::command("close_window"),
+            Self::command("minimize_window"),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct WorkspaceConfig {
+    /// Layout for new workspaces with this name, overrides workspace_layouts
+    pub layout: Option<LayoutType>,
+    /// Space between windows in pixels
+    pub window_gap: Option<u32>,
+    /// Space between screen edges and windows in pixels
+    pub partition_gap: Option<u32>,
+    /// Drop the gaps when the workspace has a single tiled window
+    pub smart_gaps: Option<bool>,
+    /// New windows on the workspace start as floating
+    pub float_new_windows: Option<bool>,
+    /// Name of the display the workspace is created on at startup (e.g., "DisplayPort-2").
+    /// The primary display is used while it isn't connected
+    pub display: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct WindowRule {
+    /// Regex matched against the application name
+    pub app_pattern: Option<String>,
+    /// Regex matched against the window title
+    pub title_pattern: Option<String>,
+    /// Always float (true) or always tile (false) matching windows
+    pub float: Option<bool>,
+    /// Never manage matching windows
+    pub ignore: bool,
+    /// Name of the workspace matching windows are placed in
+    pub workspace: Option<String>,
+    /// Opacity of matching windows (0.0 - 1.0)
+    pub opacity: Option<f32>,
+    /// Smallest share of its container a matching window can be resized to (0.0 - 1.0)
+    pub min_ratio: Option<f32>,
+    /// Largest share of its container a matching window can be resized to (0.0 - 1.0)
+    pub max_ratio: Option<f32>,
+
+    /// `app_pattern` compiled on first use, `None` inside if it isn't a valid regex
+    #[serde(skip)]
+    pub(crate) app_regex: OnceCell<Option<Regex>>,
+    /// `title_pattern` compiled on first use, `None` inside if it isn't a valid regex
+    #[serde(skip)]
+    pub(crate) title_regex: OnceCell<Option<Regex>>,
+}
+
+/// The kind of API the AI client talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AiProvider {
+    /// Any endpoint that speaks the OpenAI chat completions API
+    #[default]
+    OpenAi,
+    /// A local Ollama server
+    Ollama,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct AiConfig {
     /// Enable AI window organization features
     pub enabled: bool,
-    /// The API endpoint URL (e.g., "https://api.openai.com/v1/chat/completions")
+    /// The kind of API to talk to ("openai" or "ollama")
+    pub provider: AiProvider,
+    /// Base URL of the API. Leave empty to use the provider's default
+    /// ("https://api.openai.com/v1" for openai, "http://localhost:11434" for ollama)
+    pub base_url: String,
+    /// Full chat endpoint URL, overrides base_url
+    /// (e.g., "https://api.openai.com/v1/chat/completions")
     pub api_url: String,
     /// Your API key for authentication
     pub api_key: String,
-    /// The model to use (e.g., "gpt-4o", "claude-3-opus")
+    /// Name of an environment variable to read the API key from instead of api_key
+    pub api_key_env: String,
+    /// The model to use (e.g., "gpt-4o", "llama3.1")
     pub model: String,
     /// Custom instructions for how you'd like windows organized
     /// Example: "I prefer my browser on the left taking 60% of the screen,
@@ -160,17 +506,47 @@ pub struct AiConfig {
     /// Temperature for AI responses (0.0-2.0). Lower = more deterministic, higher = more creative.
     /// Default: 1.0
     pub temperature: f32,
+    /// Seconds to wait for the AI to respond before giving up
+    pub timeout_secs: u64,
+    /// Preview organized layouts and wait for the confirm command (cmd+alt+enter by default)
+    /// before moving any windows
+    pub confirm_organize: bool,
+    /// Seconds a previewed layout waits to be confirmed before it's discarded
+    pub preview_timeout_secs: u64,
 }
 
 impl Default for AiConfig {
     fn default() -> Self {
         Self {
             enabled: false,
+            provider: AiProvider::default(),
+            base_url: String::new(),
             api_url: String::new(),
             api_key: String::new(),
+            api_key_env: String::new(),
             model: String::new(),
             organization_preferences: String::new(),
             temperature: 1.0,
+            timeout_secs: 60,
+            confirm_organize: true,
+            preview_timeout_secs: 30,
+        }
+    }
+}
+
+/// The formats a config file can be written in, picked by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// TOML for `.toml` files, YAML for anything else
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Yaml,
         }
     }
 }
@@ -178,8 +554,35 @@ impl Default for AiConfig {
 static CURRENT_CONFIG: Lazy<Arc<RwLock<Config>>> =
     Lazy::new(|| Arc::new(RwLock::new(Config::default())));
 
+/// Held by every `ScopedConfig`, so tests that change the global config run one at a time
+#[cfg(test)]
+static SCOPED_CONFIG_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// The global config a test changed, put back when this is dropped
+#[cfg(test)]
+pub struct ScopedConfig {
+    previous: Config,
+    _lock: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+impl Drop for ScopedConfig {
+    fn drop(&mut self) {
+        Config::set_config(self.previous.clone());
+    }
+}
+
 impl Config {
     pub fn load(config_path: Option<&str>, save: bool) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::load_with_env(config_path, save, |var| std::env::var(var).ok())
+    }
+
+    /// Loads the config like `load`, reading environment overrides through `env`
+    fn load_with_env(
+        config_path: Option<&str>,
+        save: bool,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         let path = match config_path {
             Some(p) => PathBuf::from(p),
             None => paths::default_config_path()
@@ -194,7 +597,7 @@ impl Config {
         let contents = fs::read_to_string(&path)
             .map_err(|e| format!("Failed to read config file '{}': {}", path.display(), e))?;
 
-        let mut config: Config = serde_yaml::from_str(&contents)
+        let mut config = Self::parse(&contents, ConfigFormat::from_path(&path))
             .map_err(|e| format!("Failed to parse config file '{}': {}", path.display(), e))?;
 
         config.config_path = Some(path.clone());
@@ -209,9 +612,141 @@ impl Config {
             }
         }
 
+        // After saving so the overrides stay out of the file. CLI flags are applied on top.
+        config.apply_env_overrides(env);
+
+        if let Err(e) = config.validate() {
+            warn!("{e}");
+        }
+
+        Ok(config)
+    }
+
+    /// Overrides settings that hold a single value with `ULTRAWM_<SETTING>` environment
+    /// variables, e.g. `ULTRAWM_WINDOW_GAP=8`, looked up through `env`. Values are read as they
+    /// would be in a YAML config, variables that don't name a setting are ignored.
+    pub fn apply_env_overrides(&mut self, env: impl Fn(&str) -> Option<String>) {
+        let Ok(serde_yaml::Value::Mapping(mut fields)) = serde_yaml::to_value(&*self) else {
+            return;
+        };
+
+        let mut overridden = false;
+        for (key, value) in fields.iter_mut() {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            if value.is_mapping() || value.is_sequence() {
+                continue;
+            }
+            let var = format!("ULTRAWM_{}", key.to_uppercase());
+            let Some(raw) = env(&var) else {
+                continue;
+            };
+
+            // Strings are taken as is, so a name like "123" isn't read as a number
+            let parsed = if value.is_string() {
+                Ok(serde_yaml::Value::String(raw.clone()))
+            } else {
+                serde_yaml::from_str(&raw)
+            };
+            match parsed {
+                Ok(parsed) => {
+                    trace!("Config {key} overridden by {var}={raw}");
+                    *value = parsed;
+                    overridden = true;
+                }
+                Err(e) => warn!("Ignoring {var}, it isn't a valid value: {e}"),
+            }
+        }
+        if !overridden {
+            return;
+        }
+
+        match serde_yaml::from_value::<Config>(serde_yaml::Value::Mapping(fields)) {
+            Ok(mut config) => {
+                config.config_path = self.config_path.take();
+                *self = config;
+            }
+            Err(e) => warn!("Ignoring config overrides from the environment: {e}"),
+        }
+    }
+
+    /// Parses a config written in the given format, carrying over legacy fields
+    fn parse(contents: &str, format: ConfigFormat) -> Result<Self, Box<dyn std::error::Error>> {
+        let (mut config, raw): (Config, serde_yaml::Value) = match format {
+            ConfigFormat::Yaml => (
+                serde_yaml::from_str(contents)?,
+                serde_yaml::from_str(contents)?,
+            ),
+            ConfigFormat::Toml => (
+                toml::from_str(contents)?,
+                serde_yaml::to_value(toml::from_str::<toml::Value>(contents)?)?,
+            ),
+        };
+        config.apply_legacy_fields(&raw);
         Ok(config)
     }
 
+    /// Checks for settings that parse but can't all take effect, like two commands sharing a
+    /// keybind
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let conflicts = self.commands.conflicts();
+        if !conflicts.is_empty() {
+            return Err(ConfigError::KeybindConflicts(conflicts));
+        }
+
+        let invalid: Vec<String> = [&self.mod_drag_move_modifier, &self.mod_drag_resize_modifier]
+            .into_iter()
+            .flatten()
+            .filter(|modifier| !is_modifier_chord(modifier))
+            .map(|modifier| format!("{modifier:?}"))
+            .collect();
+        if !invalid.is_empty() {
+            return Err(ConfigError::InvalidDragModifiers(invalid));
+        }
+
+        let invalid: Vec<String> = self
+            .window_rules
+            .iter()
+            .flat_map(|rule| rule.invalid_patterns())
+            .map(|pattern| format!("{pattern:?}"))
+            .collect();
+        if !invalid.is_empty() {
+            return Err(ConfigError::InvalidRulePatterns(invalid));
+        }
+        Ok(())
+    }
+
+    /// Checks that the displays workspaces are pinned to are among the connected ones
+    pub fn validate_displays(&self, displays: &[Display]) -> Result<(), ConfigError> {
+        let mut unknown: Vec<String> = self
+            .workspaces
+            .iter()
+            .filter_map(|(name, workspace)| Some((name, workspace.display.as_ref()?)))
+            .filter(|(_, display)| !displays.iter().any(|d| &d.name == *display))
+            .map(|(name, display)| format!("{name:?} on {display:?}"))
+            .collect();
+        if unknown.is_empty() {
+            return Ok(());
+        }
+        unknown.sort();
+        Err(ConfigError::UnknownDisplays(unknown))
+    }
+
+    /// Carries settings from older config files over to the fields that replaced them. A
+    /// replacement field set in the file always wins.
+    fn apply_legacy_fields(&mut self, raw: &serde_yaml::Value) {
+        // resize_handle_width used to set both the hit area and the drawn handle
+        if let Some(width) = self.resize_handle_width.take() {
+            if raw.get("resize_handle_hit_width").is_none() {
+                self.resize_handle_hit_width = width;
+            }
+            if raw.get("resize_handle_render_width").is_none() {
+                self.resize_handle_render_width = width;
+            }
+        }
+    }
+
     fn create_default_config_file(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
@@ -247,26 +782,177 @@ impl Config {
         }
     }
 
+    /// Sets the global config to the defaults changed by `f` until the returned guard is
+    /// dropped. Waits for any other test holding one first, since they all share the config.
+    #[cfg(test)]
+    pub fn scoped<F>(f: F) -> ScopedConfig
+    where
+        F: FnOnce(&mut Config),
+    {
+        let lock = SCOPED_CONFIG_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let previous = Self::current().clone();
+        let mut config = Config::default();
+        f(&mut config);
+        Self::set_config(config);
+        ScopedConfig {
+            previous,
+            _lock: lock,
+        }
+    }
+
     pub fn persistence() -> bool {
         Self::current().persistence
     }
 
+    pub fn layout_save_debounce_ms() -> u64 {
+        Self::current().layout_save_debounce_ms
+    }
+
+    pub fn undo_history_limit() -> u32 {
+        Self::current().undo_history_limit
+    }
+
     pub fn window_gap() -> u32 {
         Self::current().window_gap
     }
 
+    pub fn partitions_per_display() -> u32 {
+        Self::current().partitions_per_display.max(1)
+    }
+
+    /// The fraction of a display's width each partition gets, left to right
+    pub fn partition_fractions() -> Vec<f32> {
+        let count = Self::partitions_per_display() as usize;
+        let ratios = Self::current().partition_ratios.clone();
+        let total: f32 = ratios.iter().sum();
+
+        if ratios.len() != count || ratios.iter().any(|r| *r <= 0.0) || total <= 0.0 {
+            return vec![1.0 / count as f32; count];
+        }
+        ratios.iter().map(|r| r / total).collect()
+    }
+
     pub fn partition_gap() -> u32 {
         Self::current().partition_gap
     }
 
+    pub fn outer_gap() -> Option<u32> {
+        Self::current().outer_gap
+    }
+
+    pub fn smart_gaps() -> bool {
+        Self::current().smart_gaps
+    }
+
+    pub fn window_gap_step() -> u32 {
+        Self::current().window_gap_step
+    }
+
+    pub fn keyboard_resize_step() -> u32 {
+        Self::current().keyboard_resize_step
+    }
+
+    pub fn default_layout() -> LayoutType {
+        Self::current().default_layout
+    }
+
+    /// The layout a workspace with this name should use
+    pub fn layout_for_workspace(name: &str) -> LayoutType {
+        let config = Self::current();
+        config
+            .workspaces
+            .get(name)
+            .and_then(|w| w.layout)
+            .or_else(|| config.workspace_layouts.get(name).copied())
+            .unwrap_or(config.default_layout)
+    }
+
+    /// The settings a workspace with this name overrides
+    pub fn workspace_config(name: &str) -> WorkspaceConfig {
+        Self::current()
+            .workspaces
+            .get(name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn master_ratio() -> f32 {
+        Self::current().master_ratio
+    }
+
     pub fn float_new_windows() -> bool {
         Self::current().float_new_windows
     }
 
+    pub fn remember_float_bounds() -> bool {
+        Self::current().remember_float_bounds
+    }
+
+    pub fn float_window_centered() -> bool {
+        Self::current().float_window_centered
+    }
+
+    pub fn float_window_size_ratio() -> f32 {
+        Self::current().float_window_size_ratio
+    }
+
+    /// The mod transform bindings with the move and resize modifiers applied. A modifier that
+    /// isn't one is skipped, `validate` reports it.
+    pub fn mod_transform_bindings() -> ModTransformBindings {
+        let config = Self::current();
+        let mut bindings = config.mod_transform_bindings.clone();
+        let valid = |modifier: &Option<String>| {
+            modifier
+                .clone()
+                .filter(|modifier| is_modifier_chord(modifier))
+        };
+        if let Some(modifier) = valid(&config.mod_drag_move_modifier) {
+            bindings.tile = vec![format!("{modifier}+lmb").as_str()].into();
+        }
+        if let Some(modifier) = valid(&config.mod_drag_resize_modifier) {
+            bindings.resize = vec![format!("{modifier}+rmb").as_str()].into();
+        }
+        bindings
+    }
+
+    pub fn resize_from_nearest_corner() -> bool {
+        Self::current().resize_from_nearest_corner
+    }
+
+    pub fn auto_float_min_tile_size() -> (u32, u32) {
+        Self::current().auto_float_min_tile_size
+    }
+
+    pub fn require_title_to_tile() -> bool {
+        Self::current().require_title_to_tile
+    }
+
+    pub fn new_window_focus() -> NewWindowFocus {
+        Self::current().new_window_focus
+    }
+
+    pub fn new_window_side() -> Option<Side> {
+        Self::current().new_window_side
+    }
+
+    pub fn inactive_window_opacity() -> f32 {
+        Self::current().inactive_window_opacity.clamp(0.0, 1.0)
+    }
+
     pub fn focus_on_hover() -> bool {
         Self::current().focus_on_hover
     }
 
+    pub fn focus_hover_delay_ms() -> u32 {
+        Self::current().focus_hover_delay_ms
+    }
+
+    pub fn mouse_follows_focus() -> bool {
+        Self::current().mouse_follows_focus
+    }
+
     pub fn focus_on_drag() -> bool {
         Self::current().focus_on_drag
     }
@@ -287,6 +973,14 @@ impl Config {
         Self::current().tile_preview_move_animate
     }
 
+    pub fn show_workspace_indicator() -> bool {
+        Self::current().show_workspace_indicator
+    }
+
+    pub fn workspace_indicator_timeout_ms() -> u32 {
+        Self::current().workspace_indicator_timeout_ms
+    }
+
     pub fn window_tile_animate() -> bool {
         Self::current().window_tile_animate
     }
@@ -295,16 +989,51 @@ impl Config {
         Self::current().window_tile_animation_ms
     }
 
+    pub fn hidden_window_parking() -> HiddenWindowParking {
+        Self::current().hidden_window_parking
+    }
+
+    pub fn animation_easing() -> AnimationCurve {
+        Self::current().animation_easing
+    }
+
+    pub fn window_tile_animation_curve() -> AnimationCurve {
+        let config = Self::current();
+        config
+            .window_tile_animation_curve
+            .unwrap_or(config.animation_easing)
+    }
+
     pub fn window_tile_fps() -> u32 {
         Self::current().window_tile_fps
     }
 
+    pub fn window_pulse() -> bool {
+        Self::current().window_pulse
+    }
+
+    pub fn window_pulse_opacity() -> f32 {
+        Self::current().window_pulse_opacity
+    }
+
+    pub fn window_pulse_ms() -> u32 {
+        Self::current().window_pulse_ms
+    }
+
     pub fn resize_handles() -> bool {
         Self::current().resize_handles
     }
 
-    pub fn resize_handle_width() -> u32 {
-        Self::current().resize_handle_width
+    pub fn resize_handle_hit_width() -> u32 {
+        Self::current().resize_handle_hit_width
+    }
+
+    pub fn resize_handle_render_width() -> u32 {
+        Self::current().resize_handle_render_width
+    }
+
+    pub fn resize_handle_double_click_ms() -> u32 {
+        Self::current().resize_handle_double_click_ms
     }
 
     pub fn resize_handle_color() -> (u8, u8, u8) {
@@ -323,10 +1052,26 @@ impl Config {
         Self::current().live_window_resize_fps
     }
 
+    pub fn key_sequence_timeout_ms() -> u64 {
+        Self::current().key_sequence_timeout_ms
+    }
+
+    pub fn app_size_weights() -> HashMap<String, f32> {
+        Self::current().app_size_weights.clone()
+    }
+
+    pub fn window_rules() -> Vec<WindowRule> {
+        Self::current().window_rules.clone()
+    }
+
     pub fn get_window_area_bindings(&self) -> &ModTransformBindings {
         &self.mod_transform_bindings
     }
 
+    pub fn context_menu() -> Vec<MenuItemDef> {
+        Self::current().context_menu.clone()
+    }
+
     pub fn ai() -> AiConfig {
         Self::current().ai.clone()
     }
@@ -337,7 +1082,10 @@ impl Config {
             fs::create_dir_all(parent)?;
         }
 
-        serialize_config(self, path.to_str().unwrap())?;
+        match ConfigFormat::from_path(path) {
+            ConfigFormat::Yaml => serialize_config(self, path.to_str().unwrap())?,
+            ConfigFormat::Toml => serialize_config_toml(self, path.to_str().unwrap())?,
+        }
         Ok(())
     }
 }
@@ -347,28 +1095,250 @@ impl Default for Config {
         Self {
             config_path: None,
             persistence: true,
+            layout_save_debounce_ms: 500,
+            undo_history_limit: 50,
+            partitions_per_display: 1,
+            partition_ratios: Vec::new(),
             window_gap: 20,
             partition_gap: 40,
+            outer_gap: None,
+            smart_gaps: false,
+            window_gap_step: 5,
+            keyboard_resize_step: 50,
+            default_layout: LayoutType::default(),
+            workspace_layouts: HashMap::new(),
+            workspaces: HashMap::new(),
+            master_ratio: 0.55,
             float_new_windows: true,
+            remember_float_bounds: true,
+            float_window_centered: false,
+            float_window_size_ratio: 0.6,
+            auto_float_min_tile_size: (200, 150),
+            require_title_to_tile: true,
+            new_window_focus: NewWindowFocus::Focus,
+            new_window_side: None,
+            inactive_window_opacity: 1.0,
             focus_on_hover: false,
+            focus_hover_delay_ms: 150,
+            mouse_follows_focus: false,
             focus_on_drag: false,
             overlay_animation_fps: 60,
+            animation_easing: AnimationCurve::Cubic,
             tile_preview_animation_ms: 150,
             tile_preview_fade_animate: true,
             tile_preview_move_animate: true,
+            show_workspace_indicator: true,
+            workspace_indicator_timeout_ms: 1500,
             window_tile_animate: true,
             window_tile_animation_ms: 150,
+            tile_animation_ms: None,
+            workspace_switch_animation_ms: None,
+            float_snap_animation_ms: None,
+            hidden_window_parking: HiddenWindowParking::OffscreenRight,
+            window_tile_animation_curve: None,
             window_tile_fps: 30,
+            window_pulse: true,
+            window_pulse_opacity: 0.5,
+            window_pulse_ms: 300,
             resize_handles: true,
-            resize_handle_width: 25,
+            resize_handle_hit_width: 25,
+            resize_handle_render_width: 25,
+            resize_handle_width: None,
+            resize_handle_double_click_ms: 400,
+            resize_snap_increment: 0.25,
             resize_handle_color: (40, 40, 40),
             resize_handle_opacity: 0.8,
             live_window_resize: true,
             live_window_resize_fps: 30,
             resize_handle_bindings: ResizeHandleBindings::default(),
             mod_transform_bindings: ModTransformBindings::default(),
+            mod_drag_move_modifier: None,
+            mod_drag_resize_modifier: None,
+            resize_from_nearest_corner: false,
+            app_size_weights: HashMap::new(),
+            window_rules: Vec::new(),
             commands: Commands::default(),
+            key_sequence_timeout_ms: 1000,
+            mouse_commands: HashMap::new(),
+            context_menu: MenuItemDef::default_context_menu(),
             ai: AiConfig::default(),
         }
     }
 }
+
+/// Whether `s` is only modifier keys, like "alt" or "ctrl+shift"
+fn is_modifier_chord(s: &str) -> bool {
+    s.split('+').all(|part| {
+        matches!(
+            part.trim().to_ascii_lowercase().as_str(),
+            "ctrl" | "shift" | "alt" | "super" | "win" | "cmd"
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_reports_keybind_conflicts() {
+        commands::register_commands();
+        let mut config = Config::default();
+        assert!(config.validate().is_ok());
+
+        config
+            .commands
+            .keybinds
+            .insert("float_window".to_string(), vec!["shift+alt+cmd+Z"].into());
+        config
+            .commands
+            .keybinds
+            .insert("close_window".to_string(), vec!["cmd+alt+shift+z"].into());
+
+        assert_eq!(
+            config.commands.conflicts(),
+            vec![KeybindConflict {
+                keybind: "shift+alt+cmd+z".to_string(),
+                commands: vec![
+                    "close_window".to_string(),
+                    "float_window".to_string(),
+                    "redo".to_string(),
+                ],
+            }]
+        );
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::KeybindConflicts(conflicts)) if conflicts.len() == 1
+        ));
+    }
+
+    #[test]
+    fn test_invalid_drag_modifiers_fall_back_to_the_defaults() {
+        let _config = Config::scoped(|c| {
+            c.mod_drag_move_modifier = Some("ctrl+alt".to_string());
+            c.mod_drag_resize_modifier = Some("hyper".to_string());
+        });
+        let defaults = Config::default().mod_transform_bindings;
+
+        let combos = |keybind: &ModMouseKeybind| -> Vec<String> {
+            keybind.combos().iter().map(|c| c.to_string()).collect()
+        };
+
+        let bindings = Config::mod_transform_bindings();
+        assert_eq!(combos(&bindings.tile), vec!["ctrl+alt+lmb"]);
+        assert_eq!(combos(&bindings.resize), combos(&defaults.resize));
+        assert!(matches!(
+            Config::current().validate(),
+            Err(ConfigError::InvalidDragModifiers(invalid)) if invalid == vec!["\"hyper\""]
+        ));
+    }
+
+    #[test]
+    fn test_validate_reports_invalid_rule_patterns() {
+        let mut config = Config::default();
+        config.window_rules.push(WindowRule {
+            app_pattern: Some("Slack".to_string()),
+            title_pattern: Some("(".to_string()),
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidRulePatterns(invalid)) if invalid == vec!["\"(\""]
+        ));
+    }
+
+    #[test]
+    fn test_yaml_and_toml_configs_load_the_same() {
+        let yaml = "\
+window_gap: 12
+focus_on_hover: true
+new_window_side: right
+partition_ratios: [0.6, 0.4]
+ai:
+  enabled: true
+  model: llama3.1
+";
+        let toml = r#"
+window_gap = 12
+focus_on_hover = true
+new_window_side = "right"
+partition_ratios = [0.6, 0.4]
+
+[ai]
+enabled = true
+model = "llama3.1"
+"#;
+
+        let dir = std::env::temp_dir().join(format!("ultrawm-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let yaml_path = dir.join("config.yaml");
+        let toml_path = dir.join("config.TOML");
+        fs::write(&yaml_path, yaml).unwrap();
+        fs::write(&toml_path, toml).unwrap();
+
+        let from_yaml = Config::load(yaml_path.to_str(), false).unwrap();
+        let from_toml = Config::load(toml_path.to_str(), false).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(from_toml.window_gap, 12);
+        assert_eq!(from_toml.new_window_side, Some(Side::Right));
+        assert_eq!(
+            serde_yaml::to_value(&from_yaml).unwrap(),
+            serde_yaml::to_value(&from_toml).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_toml_config_round_trips_with_docs() {
+        commands::register_commands();
+        let dir = std::env::temp_dir().join(format!("ultrawm-config-toml-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        let mut config = Config::default();
+        config.commands.fill_defaults();
+        config.window_gap = 7;
+        config.new_window_side = Some(Side::Left);
+
+        config.save_to_file(&path).unwrap();
+        let file = fs::read_to_string(&path).unwrap();
+        let loaded = Config::load_with_env(path.to_str(), false, |_| None);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(file.contains("# Space between windows in pixels"));
+        assert_eq!(
+            serde_yaml::to_value(&loaded.unwrap()).unwrap(),
+            serde_yaml::to_value(&config).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_env_vars_override_config_file() {
+        let dir = std::env::temp_dir().join(format!("ultrawm-config-env-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.yaml");
+        fs::write(
+            &path,
+            "focus_hover_delay_ms: 300\nmouse_follows_focus: false\n",
+        )
+        .unwrap();
+
+        let env = |var: &str| {
+            let value = match var {
+                "ULTRAWM_FOCUS_HOVER_DELAY_MS" => "80",
+                "ULTRAWM_MOUSE_FOLLOWS_FOCUS" => "true",
+                "ULTRAWM_NOT_A_SETTING" => "1",
+                _ => return None,
+            };
+            Some(value.to_string())
+        };
+        let config = Config::load_with_env(path.to_str(), true, env);
+        let file = fs::read_to_string(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let config = config.unwrap();
+        assert_eq!(config.focus_hover_delay_ms, 80);
+        assert!(config.mouse_follows_focus);
+        assert_eq!(config.config_path.as_deref(), Some(path.as_path()));
+        assert!(file.contains("300"));
+    }
+}
@@ -6,6 +6,34 @@ use std::io::{self, Write};
 
 pub fn serialize_config(config: &Config, path: &str) -> io::Result<()> {
     let yaml_string = serde_yaml::to_string(config).unwrap();
+    write_config(
+        path,
+        &add_comments_to_yaml(&yaml_string, &field_documentation()),
+    )
+}
+
+/// Like `serialize_config`, for configs kept in TOML
+pub fn serialize_config_toml(config: &Config, path: &str) -> io::Result<()> {
+    let toml_string = toml::to_string_pretty(config).map_err(io::Error::other)?;
+    write_config(
+        path,
+        &add_comments_to_toml(&toml_string, &field_documentation()),
+    )
+}
+
+fn write_config(path: &str, contents: &str) -> io::Result<()> {
+    let mut output = String::new();
+    output.push_str("# UltraWM Configuration File\n");
+    output.push_str("# Changes will take effect immediately\n\n\n");
+    output.push_str(contents);
+
+    let mut file = File::create(path)?;
+    file.write_all(output.as_bytes())?;
+    Ok(())
+}
+
+/// The doc comment of every config field, keyed by its dotted path
+fn field_documentation() -> HashMap<String, String> {
     let mut field_docs = HashMap::new();
     let config_schema = schema_for!(Config);
     extract_field_documentation(&config_schema, "", &mut field_docs);
@@ -23,15 +51,7 @@ pub fn serialize_config(config: &Config, path: &str) -> io::Result<()> {
         "mod_transform_bindings",
         &mut field_docs,
     );
-
-    let mut output = String::new();
-    output.push_str("# UltraWM Configuration File\n");
-    output.push_str("# Changes will take effect immediately\n\n\n");
-    output.push_str(&add_comments_to_yaml(&yaml_string, &field_docs));
-
-    let mut file = File::create(path)?;
-    file.write_all(output.as_bytes())?;
-    Ok(())
+    field_docs
 }
 
 fn extract_field_documentation(
@@ -112,3 +132,50 @@ fn add_comments_to_yaml(yaml: &str, field_docs: &HashMap<String, String>) -> Str
 
     result.join("\n")
 }
+
+fn add_comments_to_toml(toml: &str, field_docs: &HashMap<String, String>) -> String {
+    let mut result = Vec::new();
+    let mut table_path = String::new();
+
+    for line in toml.lines() {
+        let trimmed = line.trim();
+        let field_path = if let Some(header) = trimmed
+            .strip_prefix("[[")
+            .and_then(|h| h.strip_suffix("]]"))
+            .or_else(|| trimmed.strip_prefix('[').and_then(|h| h.strip_suffix(']')))
+        {
+            table_path = header.trim().to_string();
+            Some(table_path.clone())
+        } else {
+            // Array items and inline tables don't start with a bare key
+            trimmed
+                .split_once('=')
+                .map(|(key, _)| key.trim())
+                .filter(|key| {
+                    !key.is_empty()
+                        && key
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                })
+                .map(|key| match table_path.is_empty() {
+                    true => key.to_string(),
+                    false => format!("{table_path}.{key}"),
+                })
+        };
+
+        let doc = field_path.and_then(|path| {
+            let name = path.rsplit('.').next().unwrap_or(&path).to_string();
+            field_docs.get(&path).or_else(|| field_docs.get(&name))
+        });
+        if let Some(doc) = doc {
+            if !result.is_empty() {
+                result.push(String::new());
+            }
+            result.push(format!("# {doc}"));
+        }
+
+        result.push(line.to_string());
+    }
+
+    result.join("\n")
+}
@@ -10,10 +10,27 @@ use winit::keyboard::KeyCode;
 pub struct InputCombo {
     keys: Keys,
     buttons: MouseButtons,
+    /// Steps that must be pressed, in order, before this one, like `cmd+w` in `cmd+w 1`
+    prefix: Vec<InputCombo>,
 }
 
 impl InputCombo {
     pub fn parse(s: &str) -> Self {
+        // Steps are separated by spaces, but spaces around a `+` are still part of the step
+        let mut steps: Vec<String> = Vec::new();
+        for token in s.split_whitespace() {
+            match steps.last_mut() {
+                Some(step) if step.ends_with('+') || token.starts_with('+') => step.push_str(token),
+                _ => steps.push(token.to_string()),
+            }
+        }
+
+        let mut combo = Self::parse_step(steps.pop().as_deref().unwrap_or_default());
+        combo.prefix = steps.iter().map(|step| Self::parse_step(step)).collect();
+        combo
+    }
+
+    fn parse_step(s: &str) -> Self {
         let mut keybind = InputCombo::default();
         for part in s.split('+') {
             let part_lower = part.trim().to_ascii_lowercase();
@@ -111,6 +128,15 @@ impl InputCombo {
         &self.buttons
     }
 
+    pub fn prefix(&self) -> &[InputCombo] {
+        &self.prefix
+    }
+
+    /// Whether this combo is a sequence of steps rather than a single chord
+    pub fn is_sequence(&self) -> bool {
+        !self.prefix.is_empty()
+    }
+
     pub fn modifiers(&self) -> Vec<Modifier> {
         let mut modifiers = Vec::new();
         for key in self.keys.iter() {
@@ -212,6 +238,10 @@ impl Display for InputCombo {
         if self.buttons.contains(&MouseButton::Button5) {
             parts.push("forward");
         }
+
+        for step in &self.prefix {
+            write!(f, "{} ", step)?;
+        }
         write!(f, "{}", parts.join("+"))
     }
 }
@@ -71,6 +71,10 @@ impl KeybindVariant for MouseKeybindVariant {
             ));
         }
 
+        if combo.is_sequence() {
+            return Err(E::custom("This keybind must be a single combo"));
+        }
+
         Ok(())
     }
 }
@@ -87,6 +91,10 @@ impl KeybindVariant for ModifiedMouseKeybindVariant {
             return Err(E::custom("This keybind must contain at least one modifier"));
         }
 
+        if combo.is_sequence() {
+            return Err(E::custom("This keybind must be a single combo"));
+        }
+
         Ok(())
     }
 }
@@ -99,8 +107,14 @@ impl KeybindVariant for KeyboardKeybindVariant {
             return Err(E::custom("This keybind must contain at least one key"));
         }
 
-        if combo.buttons().any() {
-            return Err(E::custom("This keybind must not contain any mouse buttons"));
+        for step in combo.prefix().iter().chain([combo]) {
+            if !step.keys().any() {
+                return Err(E::custom("Each step of this keybind must contain a key"));
+            }
+
+            if step.buttons().any() {
+                return Err(E::custom("This keybind must not contain any mouse buttons"));
+            }
         }
 
         Ok(())
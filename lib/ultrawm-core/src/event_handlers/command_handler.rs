@@ -1,9 +1,12 @@
-use crate::commands::{build_commands, Command, CommandContext, CommandId};
-use crate::config::Config;
+use crate::commands::{build_commands, get_command, Command, CommandContext, CommandFn, CommandId};
+use crate::config::{Config, KeyboardKeybind};
 use crate::event_handlers::EventHandler;
 use crate::event_loop_wm::WMOperationResult;
-use crate::platform::WMEvent;
+use crate::platform::input_state::InputState;
+use crate::platform::{Keys, WMEvent};
 use crate::wm::WindowManager;
+use std::collections::HashMap;
+use std::time::Instant;
 
 pub struct CommandHandler {
     commands: Vec<Command>,
@@ -16,6 +19,31 @@ impl CommandHandler {
         }
     }
 
+    /// Rebuilds the keybinds after the config changed. Every tracker starts over, so a half
+    /// typed sequence is dropped, and keys held through the reload (like the editor's save
+    /// shortcut) don't fire until they're pressed again.
+    fn reload(&mut self, keybinds: &HashMap<String, KeyboardKeybind>, held: &Keys) {
+        self.commands = build_commands(keybinds);
+        for command in &mut self.commands {
+            command.tracker.hold(held);
+        }
+    }
+
+    /// The commands whose keybinds the keys just completed
+    fn just_pressed(&mut self, keys: &Keys, now: Instant) -> Vec<(CommandId, CommandFn)> {
+        // Every tracker has to see every key, otherwise a combo that fires one command would
+        // never count as the first step of another command's sequence
+        self.commands
+            .iter_mut()
+            .filter_map(|command| {
+                command
+                    .tracker
+                    .was_just_pressed(keys, now)
+                    .then(|| (command.id.clone(), command.handler))
+            })
+            .collect()
+    }
+
     pub fn execute_command(
         &self,
         command_id: &CommandId,
@@ -28,6 +56,12 @@ impl CommandHandler {
                 return Ok(true);
             }
         }
+
+        // Commands without a keybind can still be triggered, e.g. from IPC
+        if let Some(def) = get_command(command_id) {
+            (def.handler)(wm, context)?;
+            return Ok(true);
+        }
         Ok(false)
     }
 }
@@ -36,22 +70,78 @@ impl EventHandler for CommandHandler {
     fn handle_event(&mut self, event: &WMEvent, wm: &mut WindowManager) -> WMOperationResult<bool> {
         match event {
             WMEvent::KeyDown(_) | WMEvent::KeyUp(_) => {
-                for command in &mut self.commands {
-                    if command.tracker.was_just_pressed() {
-                        (command.handler)(wm, None)?;
-                        return Ok(true);
-                    }
+                let pressed = self.just_pressed(&InputState::pressed_keys(), Instant::now());
+                for (_, handler) in &pressed {
+                    handler(wm, None)?;
                 }
-                Ok(false)
+                Ok(!pressed.is_empty())
             }
             WMEvent::CommandTriggered(command_id, context) => {
                 self.execute_command(command_id, wm, context.as_ref())
             }
             WMEvent::ConfigChanged => {
-                self.commands = build_commands(&Config::current().commands.keybinds);
+                let keybinds = Config::current().commands.keybinds.clone();
+                self.reload(&keybinds, &InputState::pressed_keys());
                 Ok(false)
             }
             _ => Ok(false),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InputCombo;
+
+    fn keys(combo: &str) -> Keys {
+        InputCombo::parse(combo).keys().clone()
+    }
+
+    fn pressed_ids(handler: &mut CommandHandler, combo: &str) -> Vec<CommandId> {
+        handler
+            .just_pressed(&keys(combo), Instant::now())
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    #[test]
+    fn test_reload_remaps_command() {
+        crate::commands::register_commands();
+        let mut handler = CommandHandler {
+            commands: build_commands(&HashMap::from([(
+                "float_window".to_string(),
+                vec!["ctrl+alt+f"].into(),
+            )])),
+        };
+        assert_eq!(
+            pressed_ids(&mut handler, "ctrl+alt+f"),
+            vec!["float_window"]
+        );
+        pressed_ids(&mut handler, "");
+
+        // A sequence halfway typed when the config reloads starts over
+        let sequence = HashMap::from([("float_window".to_string(), vec!["ctrl+alt+g 1"].into())]);
+        handler.reload(&sequence, &Keys::new());
+        pressed_ids(&mut handler, "ctrl+alt+g");
+        pressed_ids(&mut handler, "");
+        handler.reload(&sequence, &Keys::new());
+        assert!(pressed_ids(&mut handler, "1").is_empty());
+
+        handler.reload(
+            &HashMap::from([("float_window".to_string(), vec!["ctrl+alt+g"].into())]),
+            &keys("ctrl+alt+g"),
+        );
+
+        // The held keys don't fire until they're pressed again, and the old key does nothing
+        assert!(pressed_ids(&mut handler, "ctrl+alt+g").is_empty());
+        assert!(pressed_ids(&mut handler, "").is_empty());
+        assert!(pressed_ids(&mut handler, "ctrl+alt+f").is_empty());
+        pressed_ids(&mut handler, "");
+        assert_eq!(
+            pressed_ids(&mut handler, "ctrl+alt+g"),
+            vec!["float_window"]
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use crate::config::Config;
+use crate::commands::get_command;
+use crate::config::{Config, MenuItemDef};
 use crate::event_handlers::mod_mouse_keybind_tracker::{KeybindEvent, ModMouseKeybindTracker};
 use crate::event_handlers::EventHandler;
 use crate::event_loop_main::run_on_main_thread;
@@ -6,10 +7,7 @@ use crate::event_loop_wm::WMOperationResult;
 use crate::menu::{show_menu_at_position, MenuBuilder};
 use crate::platform::{ContextMenuRequest, Position, WMEvent};
 use crate::wm::WindowManager;
-use crate::{
-    CommandContext, AI_ORGANIZE_ALL_WINDOWS, AI_ORGANIZE_CURRENT_WINDOW, CLOSE_WINDOW,
-    FLOAT_WINDOW, MINIMIZE_WINDOW,
-};
+use crate::CommandContext;
 use log::{debug, warn};
 
 pub struct ContextMenuHandler {
@@ -69,6 +67,90 @@ impl EventHandler for ContextMenuHandler {
     }
 }
 
+/// An entry of the context menu, with its command resolved and checked against the click
+#[derive(Debug, PartialEq)]
+enum ContextMenuEntry {
+    Command {
+        label: String,
+        command_id: &'static str,
+        argument: Option<String>,
+    },
+    Submenu {
+        label: String,
+        entries: Vec<ContextMenuEntry>,
+    },
+    Separator,
+}
+
+/// Works out what the context menu shows from the configured items. Unknown commands and
+/// commands that need a window when none was clicked are left out, along with any separators
+/// or submenus that end up with nothing to separate or show.
+fn context_menu_entries(items: &[MenuItemDef], has_window: bool) -> Vec<ContextMenuEntry> {
+    let mut entries = Vec::new();
+    for item in items {
+        if item.separator {
+            if !matches!(entries.last(), None | Some(ContextMenuEntry::Separator)) {
+                entries.push(ContextMenuEntry::Separator);
+            }
+        } else if !item.items.is_empty() {
+            let submenu = context_menu_entries(&item.items, has_window);
+            if !submenu.is_empty() {
+                entries.push(ContextMenuEntry::Submenu {
+                    label: item.label.clone().unwrap_or_default(),
+                    entries: submenu,
+                });
+            }
+        } else if let Some(id) = &item.command {
+            let Some(command) = get_command(id) else {
+                warn!("Unknown command in context menu: {id}");
+                continue;
+            };
+            if command.requires_window && !has_window {
+                continue;
+            }
+            entries.push(ContextMenuEntry::Command {
+                label: item
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| command.display_name.to_string()),
+                command_id: command.id,
+                argument: item.argument.clone(),
+            });
+        }
+    }
+
+    if matches!(entries.last(), Some(ContextMenuEntry::Separator)) {
+        entries.pop();
+    }
+    entries
+}
+
+fn add_entries(
+    menu_builder: &mut MenuBuilder,
+    entries: &[ContextMenuEntry],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for entry in entries {
+        match entry {
+            ContextMenuEntry::Command {
+                label,
+                command_id,
+                argument,
+            } => {
+                if let Some(command) = get_command(command_id) {
+                    menu_builder.add_command_with(command, label, argument.clone())?;
+                }
+            }
+            ContextMenuEntry::Submenu { label, entries } => {
+                menu_builder.begin_submenu(label);
+                add_entries(menu_builder, entries)?;
+                menu_builder.end_submenu()?;
+            }
+            ContextMenuEntry::Separator => menu_builder.add_separator()?,
+        }
+    }
+    Ok(())
+}
+
 fn show_context_menu(
     request: ContextMenuRequest,
     position: Position,
@@ -82,16 +164,11 @@ fn show_context_menu(
     let mut menu_builder = MenuBuilder::new().with_context(context);
 
     menu_builder.add_label(&format!("UltraWM {}", crate::version()))?;
-    menu_builder.add_separator()?;
 
-    menu_builder.add_command(&AI_ORGANIZE_CURRENT_WINDOW)?;
-    menu_builder.add_command(&AI_ORGANIZE_ALL_WINDOWS)?;
-
-    if request.target_window.is_some() {
+    let entries = context_menu_entries(&Config::context_menu(), request.target_window.is_some());
+    if !entries.is_empty() {
         menu_builder.add_separator()?;
-        menu_builder.add_command(&FLOAT_WINDOW)?;
-        menu_builder.add_command(&CLOSE_WINDOW)?;
-        menu_builder.add_command(&MINIMIZE_WINDOW)?;
+        add_entries(&mut menu_builder, &entries)?;
     }
 
     let menu = menu_builder.build();
@@ -99,3 +176,77 @@ fn show_context_menu(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(label: &str, command_id: &'static str, argument: Option<&str>) -> ContextMenuEntry {
+        ContextMenuEntry::Command {
+            label: label.to_string(),
+            command_id,
+            argument: argument.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_context_menu_entries_from_config() {
+        crate::commands::register_commands();
+        let items = vec![
+            MenuItemDef {
+                label: Some("Float".to_string()),
+                ..MenuItemDef::command("float_window")
+            },
+            MenuItemDef::command("close_window"),
+            MenuItemDef::separator(),
+            MenuItemDef {
+                label: Some("Layout".to_string()),
+                items: vec![
+                    MenuItemDef {
+                        label: Some("Master Stack".to_string()),
+                        argument: Some("master-stack".to_string()),
+                        ..MenuItemDef::command("set_layout")
+                    },
+                    MenuItemDef::command("cycle_layout"),
+                ],
+                ..Default::default()
+            },
+            MenuItemDef::command("not_a_command"),
+            MenuItemDef::separator(),
+        ];
+
+        let close = get_command("close_window").unwrap().display_name;
+        let cycle = get_command("cycle_layout").unwrap().display_name;
+        assert_eq!(
+            context_menu_entries(&items, true),
+            vec![
+                command("Float", "float_window", None),
+                command(close, "close_window", None),
+                ContextMenuEntry::Separator,
+                ContextMenuEntry::Submenu {
+                    label: "Layout".to_string(),
+                    entries: vec![
+                        command("Master Stack", "set_layout", Some("master-stack")),
+                        command(cycle, "cycle_layout", None),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_window_commands_are_left_out_without_a_window() {
+        crate::commands::register_commands();
+        let items = vec![
+            MenuItemDef::command("float_window"),
+            MenuItemDef::separator(),
+            MenuItemDef::command("ai_organize_all_windows"),
+        ];
+
+        let all = get_command("ai_organize_all_windows").unwrap().display_name;
+        assert_eq!(
+            context_menu_entries(&items, false),
+            vec![command(all, "ai_organize_all_windows", None)]
+        );
+    }
+}
@@ -1,12 +1,67 @@
 use crate::config::Config;
-use crate::event_handlers::EventHandler;
+use crate::event_handlers::{recheck_mouse_after, EventHandler};
 use crate::event_loop_wm::WMOperationResult;
 use crate::platform::{Position, WMEvent, WindowId};
 use crate::wm::WindowManager;
 use std::time::{Duration, Instant};
 
+#[derive(Debug, PartialEq, Eq)]
+enum HoverDwell {
+    /// The cursor isn't resting on a window that needs focus
+    Idle,
+    /// The cursor just moved onto a window and the delay started
+    Started,
+    /// The cursor is still on the window, but the delay hasn't passed
+    Waiting,
+    /// The cursor stayed on the window for the whole delay
+    Elapsed(WindowId),
+}
+
+/// Tracks how long the cursor has rested on a window, so focus only follows the mouse once
+/// it stops on a window rather than passing over it.
+struct HoverDwellTracker {
+    delay: Duration,
+    pending: Option<(WindowId, Instant)>,
+}
+
+impl HoverDwellTracker {
+    fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: None,
+        }
+    }
+
+    /// Updates the tracker with the window under the cursor at `now`
+    fn update(&mut self, window: Option<WindowId>, now: Instant) -> HoverDwell {
+        let Some(window) = window else {
+            self.pending = None;
+            return HoverDwell::Idle;
+        };
+
+        let since = match self.pending {
+            Some((pending, since)) if pending == window => since,
+            _ => {
+                self.pending = Some((window, now));
+                if !self.delay.is_zero() {
+                    return HoverDwell::Started;
+                }
+                now
+            }
+        };
+
+        if now.duration_since(since) >= self.delay {
+            self.pending = None;
+            HoverDwell::Elapsed(window)
+        } else {
+            HoverDwell::Waiting
+        }
+    }
+}
+
 pub struct FocusOnHoverHandler {
     enabled: bool,
+    dwell: HoverDwellTracker,
     last_focused_window: Option<WindowId>,
     last_check_time: Instant,
     last_check_position: Option<Position>,
@@ -19,6 +74,9 @@ impl FocusOnHoverHandler {
 
         Self {
             enabled: config.focus_on_hover,
+            dwell: HoverDwellTracker::new(Duration::from_millis(
+                config.focus_hover_delay_ms as u64,
+            )),
             last_focused_window: None,
             last_check_time: Instant::now(),
             last_check_position: None,
@@ -56,17 +114,19 @@ impl FocusOnHoverHandler {
         self.last_check_time = now;
         self.last_check_position = Some(pos.clone());
 
-        // Find the window at the current mouse position
-        let window_at_position = wm.find_window_at_position(pos);
+        // Find the window at the current mouse position, ignoring the one we already focused
+        let window_at_position = wm
+            .find_window_at_position(pos)
+            .map(|w| w.id())
+            .filter(|id| self.last_focused_window != Some(*id));
 
-        if let Some(window) = window_at_position {
-            let window_id = window.id();
-
-            // Only focus if it's a different window than the last focused one
-            if self.last_focused_window != Some(window_id) {
-                wm.focus_window(window_id)?;
+        match self.dwell.update(window_at_position, now) {
+            HoverDwell::Elapsed(window_id) => {
+                wm.focus_window_from_mouse(window_id)?;
                 self.last_focused_window = Some(window_id);
             }
+            HoverDwell::Started => recheck_mouse_after(self.dwell.delay.max(self.check_interval)),
+            HoverDwell::Idle | HoverDwell::Waiting => {}
         }
 
         Ok(())
@@ -75,6 +135,8 @@ impl FocusOnHoverHandler {
     pub fn update_config(&mut self) {
         let config = Config::current();
         self.enabled = config.focus_on_hover;
+        self.dwell =
+            HoverDwellTracker::new(Duration::from_millis(config.focus_hover_delay_ms as u64));
     }
 }
 
@@ -98,3 +160,66 @@ impl EventHandler for FocusOnHoverHandler {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAY: Duration = Duration::from_millis(200);
+
+    #[test]
+    fn test_focus_only_after_dwell() {
+        let mut dwell = HoverDwellTracker::new(DELAY);
+        let start = Instant::now();
+
+        assert_eq!(dwell.update(Some(1), start), HoverDwell::Started);
+        assert_eq!(
+            dwell.update(Some(1), start + Duration::from_millis(150)),
+            HoverDwell::Waiting
+        );
+        assert_eq!(
+            dwell.update(Some(1), start + Duration::from_millis(200)),
+            HoverDwell::Elapsed(1)
+        );
+    }
+
+    #[test]
+    fn test_moving_off_before_delay_cancels_focus() {
+        let mut dwell = HoverDwellTracker::new(DELAY);
+        let start = Instant::now();
+
+        assert_eq!(dwell.update(Some(1), start), HoverDwell::Started);
+        assert_eq!(
+            dwell.update(Some(2), start + Duration::from_millis(100)),
+            HoverDwell::Started
+        );
+        assert_eq!(
+            dwell.update(None, start + Duration::from_millis(150)),
+            HoverDwell::Idle
+        );
+
+        // Coming back starts the delay over
+        assert_eq!(
+            dwell.update(Some(1), start + Duration::from_millis(250)),
+            HoverDwell::Started
+        );
+        assert_eq!(
+            dwell.update(Some(1), start + Duration::from_millis(400)),
+            HoverDwell::Waiting
+        );
+        assert_eq!(
+            dwell.update(Some(1), start + Duration::from_millis(450)),
+            HoverDwell::Elapsed(1)
+        );
+    }
+
+    #[test]
+    fn test_zero_delay_focuses_immediately() {
+        let mut dwell = HoverDwellTracker::new(Duration::ZERO);
+
+        assert_eq!(
+            dwell.update(Some(1), Instant::now()),
+            HoverDwell::Elapsed(1)
+        );
+    }
+}
@@ -1,17 +1,35 @@
-use crate::config::KeyboardKeybind;
+use crate::config::{Config, InputCombo, KeyboardKeybind};
 use crate::platform::input_state::InputState;
+use crate::platform::Keys;
+use std::time::{Duration, Instant};
+use winit::keyboard::KeyCode;
 
 /// Tracks keyboard keybinds and detects when they are pressed
 pub struct KeyboardKeybindTracker {
     keybind: KeyboardKeybind,
     was_pressed: bool,
+    /// How many steps of each combo have been pressed so far, only used by sequences
+    progress: Vec<usize>,
+    /// When an unfinished sequence is given up on
+    sequence_deadline: Option<Instant>,
+    sequence_timeout: Duration,
+    last_keys: Keys,
 }
 
 impl KeyboardKeybindTracker {
     pub fn new(keybind: KeyboardKeybind) -> Self {
+        let timeout = Duration::from_millis(Config::key_sequence_timeout_ms());
+        Self::with_sequence_timeout(keybind, timeout)
+    }
+
+    pub fn with_sequence_timeout(keybind: KeyboardKeybind, sequence_timeout: Duration) -> Self {
         Self {
+            progress: vec![0; keybind.combos().len()],
             keybind,
             was_pressed: false,
+            sequence_deadline: None,
+            sequence_timeout,
+            last_keys: Keys::new(),
         }
     }
 
@@ -20,15 +38,195 @@ impl KeyboardKeybindTracker {
         InputState::binding_matches(&self.keybind)
     }
 
-    /// Check if the keybind was just pressed (transition from not pressed to pressed)
-    pub fn was_just_pressed(&mut self) -> bool {
-        let currently_pressed = self.is_pressed();
-        let just_pressed = currently_pressed && !self.was_pressed;
+    /// Check if the keybind was just pressed (transition from not pressed to pressed), or if the
+    /// last step of one of its sequences was
+    pub(crate) fn was_just_pressed(&mut self, keys: &Keys, now: Instant) -> bool {
+        if self
+            .sequence_deadline
+            .is_some_and(|deadline| now >= deadline)
+        {
+            self.reset_sequences();
+        }
+
+        let currently_pressed = self
+            .keybind
+            .combos()
+            .iter()
+            .any(|combo| !combo.is_sequence() && combo.keys().matches(keys));
+        let mut just_pressed = currently_pressed && !self.was_pressed;
         self.was_pressed = currently_pressed;
+
+        // Sequences only move on when a new key goes down, so releasing keys or holding a
+        // modifier for the next step doesn't reset them
+        let new_key = keys
+            .iter()
+            .any(|key| !is_modifier(key) && !self.last_keys.contains(key));
+        self.last_keys = keys.clone();
+        if !new_key {
+            return just_pressed;
+        }
+
+        let mut advanced = false;
+        for (combo, progress) in self.keybind.combos().iter().zip(&mut self.progress) {
+            if !combo.is_sequence() {
+                continue;
+            }
+
+            // A wrong key starts over, but it might be the start of the sequence again
+            let mut step = *progress;
+            if !step_keys(combo, step).matches(keys) {
+                step = 0;
+            }
+
+            *progress = if !step_keys(combo, step).matches(keys) {
+                0
+            } else if step == combo.prefix().len() {
+                just_pressed = true;
+                0
+            } else {
+                advanced = true;
+                step + 1
+            };
+        }
+
+        if advanced {
+            self.sequence_deadline = Some(now + self.sequence_timeout);
+        } else if self.progress.iter().all(|progress| *progress == 0) {
+            self.sequence_deadline = None;
+        }
+
         just_pressed
     }
 
+    fn reset_sequences(&mut self) {
+        self.progress.iter_mut().for_each(|progress| *progress = 0);
+        self.sequence_deadline = None;
+    }
+
+    /// Treats keys that are already down as seen, so they only fire once pressed again
+    pub fn hold(&mut self, keys: &Keys) {
+        self.was_pressed = self
+            .keybind
+            .combos()
+            .iter()
+            .any(|combo| !combo.is_sequence() && combo.keys().matches(keys));
+        self.last_keys = keys.clone();
+    }
+
     pub fn update(&mut self) {
         self.was_pressed = self.is_pressed();
     }
 }
+
+fn step_keys(combo: &InputCombo, step: usize) -> &Keys {
+    combo.prefix().get(step).unwrap_or(combo).keys()
+}
+
+fn is_modifier(key: &KeyCode) -> bool {
+    matches!(
+        key,
+        KeyCode::ControlLeft
+            | KeyCode::ControlRight
+            | KeyCode::ShiftLeft
+            | KeyCode::ShiftRight
+            | KeyCode::AltLeft
+            | KeyCode::AltRight
+            | KeyCode::SuperLeft
+            | KeyCode::SuperRight
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+
+    fn keys(combo: &str) -> Keys {
+        InputCombo::parse(combo).keys().clone()
+    }
+
+    /// Feeds key states to the tracker one at a time, returning which of them fired it
+    fn drive(
+        tracker: &mut KeyboardKeybindTracker,
+        start: Instant,
+        steps: &[(&str, u64)],
+    ) -> Vec<bool> {
+        steps
+            .iter()
+            .map(|(combo, at_ms)| {
+                tracker.was_just_pressed(&keys(combo), start + Duration::from_millis(*at_ms))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sequence_fires_on_last_step() {
+        let mut tracker =
+            KeyboardKeybindTracker::with_sequence_timeout(vec!["cmd+w 1"].into(), TIMEOUT);
+        let fired = drive(
+            &mut tracker,
+            Instant::now(),
+            &[
+                ("cmd", 0),
+                ("cmd+w", 10),
+                ("cmd", 20),
+                ("", 30),
+                ("1", 100),
+                ("", 120),
+            ],
+        );
+        assert_eq!(fired, vec![false, false, false, false, true, false]);
+    }
+
+    #[test]
+    fn test_sequence_resets_after_timeout() {
+        let mut tracker =
+            KeyboardKeybindTracker::with_sequence_timeout(vec!["cmd+w 1"].into(), TIMEOUT);
+        let fired = drive(
+            &mut tracker,
+            Instant::now(),
+            &[("cmd+w", 0), ("", 10), ("1", 600), ("", 610)],
+        );
+        assert_eq!(fired, vec![false, false, false, false]);
+    }
+
+    #[test]
+    fn test_sequence_resets_on_wrong_key() {
+        let mut tracker =
+            KeyboardKeybindTracker::with_sequence_timeout(vec!["cmd+w 1"].into(), TIMEOUT);
+        let fired = drive(
+            &mut tracker,
+            Instant::now(),
+            &[("cmd+w", 0), ("", 10), ("2", 20), ("", 30), ("1", 40)],
+        );
+        assert_eq!(fired, vec![false, false, false, false, false]);
+
+        // Pressing the prefix again starts the sequence over
+        let fired = drive(
+            &mut tracker,
+            Instant::now(),
+            &[("cmd+w", 0), ("", 10), ("cmd+w", 20), ("", 30), ("1", 40)],
+        );
+        assert_eq!(fired, vec![false, false, false, false, true]);
+    }
+
+    #[test]
+    fn test_prefix_still_fires_its_own_binding() {
+        let mut close =
+            KeyboardKeybindTracker::with_sequence_timeout(vec!["cmd+w"].into(), TIMEOUT);
+        let mut switch =
+            KeyboardKeybindTracker::with_sequence_timeout(vec!["cmd+w 1"].into(), TIMEOUT);
+        let start = Instant::now();
+        let steps = [("cmd", 0), ("cmd+w", 10), ("", 20), ("1", 30)];
+
+        assert_eq!(
+            drive(&mut close, start, &steps),
+            vec![false, true, false, false]
+        );
+        assert_eq!(
+            drive(&mut switch, start, &steps),
+            vec![false, false, false, true]
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::event_loop_wm::WMOperationResult;
-use crate::platform::WMEvent;
+use crate::platform::{Platform, PlatformImpl, WMEvent};
 use crate::wm::WindowManager;
+use crate::GLOBAL_EVENT_DISPATCHER;
+use std::time::Duration;
 
 pub mod native_transform_handler;
 mod native_transform_tracker;
@@ -18,6 +20,24 @@ pub mod command_handler;
 pub mod keyboard_keybind_tracker;
 
 pub mod context_menu_handler;
+pub mod mouse_command_handler;
+
+pub mod workspace_indicator_handler;
+
+/// The mouse may rest without sending more moves, so send a move at wherever it is once the
+/// delay is up
+pub(crate) fn recheck_mouse_after(delay: Duration) {
+    let Some(dispatcher) = GLOBAL_EVENT_DISPATCHER.get().cloned() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        if let Ok(position) = Platform::get_mouse_position() {
+            dispatcher.send(WMEvent::MouseMoved(position));
+        }
+    });
+}
 
 pub trait EventHandler {
     /// Returns true if events currently being handled
@@ -273,6 +273,7 @@ impl ModTransformHandler {
         ) {
             wm.flush()?;
             wm.try_save_layout();
+            wm.publish_snapshot();
         }
 
         Ok(())
@@ -319,6 +320,11 @@ impl ModTransformHandler {
 
 impl EventHandler for ModTransformHandler {
     fn handle_event(&mut self, event: &WMEvent, wm: &mut WindowManager) -> WMOperationResult<bool> {
+        // Leave manual moves alone while tiling is paused
+        if wm.paused() {
+            return Ok(false);
+        }
+
         let events = self.tracker.handle_event(event, wm);
 
         for drag_event in events {
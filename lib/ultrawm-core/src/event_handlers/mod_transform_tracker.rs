@@ -46,6 +46,7 @@ pub struct ModTransformTracker {
     toggle_binding: ModMouseKeybindTracker,
     resize_binding: ModMouseKeybindTracker,
     resize_symmetric_binding: ModMouseKeybindTracker,
+    resize_from_nearest_corner: bool,
     tile_drag: Option<DragContext>,
     float_drag: Option<DragContext>,
     shift_drag: Option<DragContext>,
@@ -71,6 +72,32 @@ impl ModTransformTracker {
         pos.x >= left_bound && pos.x <= right_bound && pos.y >= top_bound && pos.y <= bottom_bound
     }
 
+    /// The corner of the quadrant of the window the position is in
+    fn quadrant_corner(bounds: &Bounds, pos: &Position) -> ResizeDirection {
+        let center = bounds.center();
+        match (pos.x < center.x, pos.y < center.y) {
+            (true, true) => ResizeDirection::TopLeft,
+            (false, true) => ResizeDirection::TopRight,
+            (true, false) => ResizeDirection::BottomLeft,
+            (false, false) => ResizeDirection::BottomRight,
+        }
+    }
+
+    fn resize_type(
+        bounds: &Bounds,
+        pos: &Position,
+        nearest_corner: bool,
+        resize: fn(ResizeDirection) -> ModTransformType,
+    ) -> ModTransformType {
+        if nearest_corner {
+            resize(Self::quadrant_corner(bounds, pos))
+        } else if Self::is_position_in_middle(bounds, pos) {
+            ModTransformType::Slide
+        } else {
+            resize(Self::resize_mode(bounds, pos))
+        }
+    }
+
     fn resize_mode(bounds: &Bounds, pos: &Position) -> ResizeDirection {
         if let Some(dir) = Self::detect_corner_wedge(bounds, pos) {
             return dir;
@@ -138,21 +165,18 @@ impl ModTransformTracker {
     }
 
     pub fn new() -> Self {
-        let config = Config::current();
+        let bindings = Config::mod_transform_bindings();
         Self {
-            bindings: config.mod_transform_bindings.clone(),
-            tile_binding: ModMouseKeybindTracker::new(config.mod_transform_bindings.tile.clone()),
-            float_binding: ModMouseKeybindTracker::new(config.mod_transform_bindings.float.clone()),
-            shift_binding: ModMouseKeybindTracker::new(config.mod_transform_bindings.shift.clone()),
-            toggle_binding: ModMouseKeybindTracker::new(
-                config.mod_transform_bindings.toggle.clone(),
-            ),
-            resize_binding: ModMouseKeybindTracker::new(
-                config.mod_transform_bindings.resize.clone(),
-            ),
+            tile_binding: ModMouseKeybindTracker::new(bindings.tile.clone()),
+            float_binding: ModMouseKeybindTracker::new(bindings.float.clone()),
+            shift_binding: ModMouseKeybindTracker::new(bindings.shift.clone()),
+            toggle_binding: ModMouseKeybindTracker::new(bindings.toggle.clone()),
+            resize_binding: ModMouseKeybindTracker::new(bindings.resize.clone()),
             resize_symmetric_binding: ModMouseKeybindTracker::new(
-                config.mod_transform_bindings.resize_symmetric.clone(),
+                bindings.resize_symmetric.clone(),
             ),
+            resize_from_nearest_corner: Config::resize_from_nearest_corner(),
+            bindings,
             tile_drag: None,
             float_drag: None,
             shift_drag: None,
@@ -176,6 +200,8 @@ impl ModTransformTracker {
         event: &WMEvent,
         wm: &WindowManager,
     ) -> Vec<ModTransformDragEvent> {
+        let nearest_corner = self.resize_from_nearest_corner;
+
         // Call all bindings so they can track their state properly, collect and filter results
         let mut events: Vec<ModTransformDragEvent> = vec![
             Self::handle_binding(
@@ -210,12 +236,7 @@ impl ModTransformTracker {
                 event,
                 &mut self.resize_binding,
                 |bounds, pos| {
-                    if Self::is_position_in_middle(bounds, pos) {
-                        ModTransformType::Slide
-                    } else {
-                        let direction = Self::resize_mode(bounds, pos);
-                        ModTransformType::Resize(direction)
-                    }
+                    Self::resize_type(bounds, pos, nearest_corner, ModTransformType::Resize)
                 },
                 wm,
                 &mut self.resize_drag,
@@ -224,12 +245,12 @@ impl ModTransformTracker {
                 event,
                 &mut self.resize_symmetric_binding,
                 |bounds, pos| {
-                    if Self::is_position_in_middle(bounds, pos) {
-                        ModTransformType::Slide
-                    } else {
-                        let direction = Self::resize_mode(bounds, pos);
-                        ModTransformType::ResizeSymmetric(direction)
-                    }
+                    Self::resize_type(
+                        bounds,
+                        pos,
+                        nearest_corner,
+                        ModTransformType::ResizeSymmetric,
+                    )
                 },
                 wm,
                 &mut self.resize_symmetric_drag,
@@ -316,3 +337,56 @@ impl ModTransformTracker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quadrant_corner() {
+        let bounds = Bounds::new(100, 100, 400, 200);
+        let cases = [
+            (Position { x: 150, y: 120 }, ResizeDirection::TopLeft),
+            (Position { x: 450, y: 120 }, ResizeDirection::TopRight),
+            (Position { x: 150, y: 280 }, ResizeDirection::BottomLeft),
+            (Position { x: 450, y: 280 }, ResizeDirection::BottomRight),
+            // Close to the middle still picks a corner
+            (Position { x: 290, y: 190 }, ResizeDirection::TopLeft),
+            (Position { x: 310, y: 210 }, ResizeDirection::BottomRight),
+        ];
+
+        for (pos, expected) in cases {
+            assert_eq!(
+                ModTransformTracker::quadrant_corner(&bounds, &pos),
+                expected,
+                "{pos:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resize_type_from_nearest_corner() {
+        let bounds = Bounds::new(0, 0, 400, 400);
+        let middle = Position { x: 210, y: 190 };
+        let edge = Position { x: 5, y: 300 };
+
+        assert_eq!(
+            ModTransformTracker::resize_type(&bounds, &middle, true, ModTransformType::Resize),
+            ModTransformType::Resize(ResizeDirection::TopRight)
+        );
+        assert_eq!(
+            ModTransformTracker::resize_type(&bounds, &edge, true, ModTransformType::Resize),
+            ModTransformType::Resize(ResizeDirection::BottomLeft)
+        );
+
+        // Without it the middle slides and edges resize from the nearest edge
+        assert_eq!(
+            ModTransformTracker::resize_type(&bounds, &middle, false, ModTransformType::Resize),
+            ModTransformType::Slide
+        );
+        assert_eq!(
+            ModTransformTracker::resize_type(&bounds, &edge, false, ModTransformType::Resize),
+            ModTransformType::Resize(ResizeDirection::Left)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use crate::commands::{get_command, CommandContext, CommandDef};
+use crate::config::{Config, ModMouseKeybind};
+use crate::event_handlers::mod_mouse_keybind_tracker::{KeybindEvent, ModMouseKeybindTracker};
+use crate::event_handlers::EventHandler;
+use crate::event_loop_wm::WMOperationResult;
+use crate::platform::{Position, WMEvent, WindowId};
+use crate::wm::WindowManager;
+use log::{debug, warn};
+use std::collections::HashMap;
+
+/// Runs commands bound to a modifier + mouse button click. Only clicks trigger them, so the same
+/// combo can still be dragged to move or resize a window.
+pub struct MouseCommandHandler {
+    bindings: Vec<(&'static CommandDef, ModMouseKeybindTracker)>,
+}
+
+impl MouseCommandHandler {
+    pub fn new() -> Self {
+        Self {
+            bindings: build_bindings(&Config::current().mouse_commands),
+        }
+    }
+}
+
+impl EventHandler for MouseCommandHandler {
+    fn handle_event(&mut self, event: &WMEvent, wm: &mut WindowManager) -> WMOperationResult<bool> {
+        if matches!(event, WMEvent::ConfigChanged) {
+            self.bindings = build_bindings(&Config::current().mouse_commands);
+            return Ok(false);
+        }
+
+        let mut handled = false;
+        for (command, tracker) in &mut self.bindings {
+            let Some(KeybindEvent::Activate(pos)) = tracker.handle_event(event) else {
+                continue;
+            };
+
+            let target_window = wm.find_window_at_position(&pos).map(|w| w.id());
+            let Some(context) = click_context(command, pos, target_window) else {
+                continue;
+            };
+
+            debug!(
+                "Mouse command {} at {:?}, window: {:?}",
+                command.id, context.position, context.target_window
+            );
+            crate::trigger_command_with_context(command.id, Some(context));
+            handled = true;
+        }
+        Ok(handled)
+    }
+}
+
+/// One tracker per configured command, skipping unknown commands and empty bindings
+fn build_bindings(
+    mouse_commands: &HashMap<String, ModMouseKeybind>,
+) -> Vec<(&'static CommandDef, ModMouseKeybindTracker)> {
+    let mut bindings: Vec<_> = mouse_commands
+        .iter()
+        .filter(|(_, keybind)| !keybind.combos().is_empty())
+        .filter_map(|(id, keybind)| match get_command(id) {
+            Some(command) => Some((command, ModMouseKeybindTracker::new(keybind.clone()))),
+            None => {
+                warn!("Unknown command in mouse commands: {id}");
+                None
+            }
+        })
+        .collect();
+    bindings.sort_by_key(|(command, _)| command.id);
+    bindings
+}
+
+/// The context a click runs its command with, or None if the command needs a window and the
+/// click wasn't on one
+fn click_context(
+    command: &CommandDef,
+    position: Position,
+    target_window: Option<WindowId>,
+) -> Option<CommandContext> {
+    match target_window {
+        Some(window_id) => Some(CommandContext::with_window_and_position(
+            window_id, position,
+        )),
+        None if command.requires_window => None,
+        None => Some(CommandContext::with_position(position)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mouse_command_targets_clicked_window() {
+        crate::commands::register_commands();
+        let mouse_commands = HashMap::from([
+            ("close_window".to_string(), vec!["ctrl+mmb"].into()),
+            ("not_a_command".to_string(), vec!["ctrl+rmb"].into()),
+            ("float_window".to_string(), vec![].into()),
+        ]);
+
+        let bindings = build_bindings(&mouse_commands);
+        assert_eq!(bindings.len(), 1);
+        let (command, _) = &bindings[0];
+        assert_eq!(command.id, "close_window");
+
+        let position = Position { x: 300, y: 200 };
+        let context = click_context(command, position.clone(), Some(7)).unwrap();
+        assert_eq!(context.target_window, Some(7));
+        assert_eq!(context.position, Some(position.clone()));
+
+        // Window commands do nothing when the click misses every window
+        assert!(click_context(command, position, None).is_none());
+    }
+}
@@ -74,6 +74,11 @@ impl NativeTransformHandler {
 
 impl EventHandler for NativeTransformHandler {
     fn handle_event(&mut self, event: &WMEvent, wm: &mut WindowManager) -> WMOperationResult<bool> {
+        // Leave manual moves alone while tiling is paused
+        if wm.paused() {
+            return Ok(false);
+        }
+
         match self.tracker.handle_event(&event, &wm) {
             Some(WindowDragEvent::Drag(id, position, drag_type)) => {
                 self.drag(id, position, drag_type, wm)?;
@@ -138,25 +138,7 @@ impl NativeTransformTracker {
     }
 
     fn calculate_resize_direction(old: &Bounds, new: &Bounds) -> ResizeDirection {
-        let left_changed = new.position.x != old.position.x;
-        let right_changed =
-            (new.position.x + new.size.width as i32) != (old.position.x + old.size.width as i32);
-        let top_changed = new.position.y != old.position.y;
-        let bottom_changed =
-            (new.position.y + new.size.height as i32) != (old.position.y + old.size.height as i32);
-
-        match (left_changed, right_changed, top_changed, bottom_changed) {
-            (true, false, false, false) => ResizeDirection::Left,
-            (false, true, false, false) => ResizeDirection::Right,
-            (false, false, true, false) => ResizeDirection::Top,
-            (false, false, false, true) => ResizeDirection::Bottom,
-            (true, false, true, false) => ResizeDirection::TopLeft,
-            (false, true, true, false) => ResizeDirection::TopRight,
-            (true, false, false, true) => ResizeDirection::BottomLeft,
-            (false, true, false, true) => ResizeDirection::BottomRight,
-            // Default/fallback
-            _ => ResizeDirection::Right,
-        }
+        ResizeDirection::from_bounds_change(old, new)
     }
 }
 
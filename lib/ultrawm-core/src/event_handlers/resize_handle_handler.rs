@@ -1,19 +1,86 @@
+use crate::coalescing_channel::CoalescingAsyncChannel;
 use crate::config::Config;
 use crate::event_handlers::resize_handle_tracker::{ResizeHandleEvent, ResizeHandleTracker};
-use crate::event_handlers::EventHandler;
+use crate::event_handlers::{recheck_mouse_after, EventHandler};
 use crate::event_loop_wm::{WMOperationError, WMOperationResult};
 use crate::overlay;
 use crate::overlay::overlays::ResizeHandleOverlay;
 use crate::platform::input_state::InputState;
 use crate::platform::traits::PlatformImpl;
-use crate::platform::{CursorType, Platform, Position, WMEvent};
+use crate::platform::{CursorType, Platform, PlatformResult, Position, WMEvent};
 use crate::resize_handle::{ResizeHandle, ResizeMode};
 use crate::wm::WindowManager;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+#[derive(Debug, PartialEq)]
+enum DragFrame {
+    /// A frame has passed, resize to the latest position
+    Apply(Position),
+    /// The first position queued this frame, check again once the frame is up
+    Wait(Duration),
+    /// Waiting on a frame that already has a check coming
+    Queued,
+}
+
+/// Limits live resizes during a handle drag to one per frame. Positions go through a coalescing
+/// channel, so each frame only resizes to the latest one.
+struct DragFrameLimiter {
+    sender: mpsc::UnboundedSender<Position>,
+    positions: CoalescingAsyncChannel<Position>,
+    frame: Duration,
+    last_applied: Option<Instant>,
+    waiting: bool,
+}
+
+impl DragFrameLimiter {
+    fn new(fps: u32) -> Self {
+        let positions = CoalescingAsyncChannel::new();
+        Self {
+            sender: positions.sender(),
+            positions,
+            frame: Duration::from_millis(1000 / fps.max(1) as u64),
+            last_applied: None,
+            waiting: false,
+        }
+    }
+
+    fn push(&mut self, pos: Position, now: Instant) -> DragFrame {
+        let _ = self.sender.send(pos);
+
+        if let Some(last_applied) = self.last_applied {
+            let elapsed = now.duration_since(last_applied);
+            if elapsed < self.frame {
+                if self.waiting {
+                    return DragFrame::Queued;
+                }
+                self.waiting = true;
+                return DragFrame::Wait(self.frame - elapsed);
+            }
+        }
+
+        // Everything sent since the last frame collapses into the latest position
+        self.last_applied = Some(now);
+        self.waiting = false;
+        DragFrame::Apply(self.positions.try_coalesce(|_| true).unwrap_or(pos))
+    }
+
+    /// Drops anything still queued. The drop position is applied on its own so the drag always
+    /// lands exactly where the mouse was released.
+    fn finish(&mut self) {
+        let _ = self.positions.try_coalesce(|_| true);
+        self.last_applied = None;
+        self.waiting = false;
+    }
+}
 
 pub struct ResizeHandleHandler {
     overlay: overlay::Overlay,
     tracker: ResizeHandleTracker,
+    drag_frames: DragFrameLimiter,
     hover_resize_handle: Option<ResizeHandle>,
+    /// The cursor set while hovering, `None` when the platform's own cursor is showing
+    hover_cursor: Option<CursorType>,
     handles_enabled: bool,
     handle_width: u32,
 }
@@ -30,24 +97,23 @@ impl ResizeHandleHandler {
         Self {
             overlay,
             tracker: ResizeHandleTracker::new(),
+            drag_frames: DragFrameLimiter::new(config.window_tile_fps),
             hover_resize_handle: None,
+            hover_cursor: None,
             handles_enabled: config.resize_handles,
-            handle_width: config.resize_handle_width,
+            handle_width: config.resize_handle_render_width,
         }
     }
 
     fn mouse_moved(&mut self, pos: &Position, wm: &WindowManager) -> WMOperationResult<()> {
-        // Prevent normal window resizing
-        if let Some(_) = wm.find_window_at_resize_edge(pos) {
-            Platform::set_cursor(CursorType::Normal)
-                .map_err(|e| WMOperationError::Error(e.into()))?;
-            // PlatformEvents::set_intercept_clicks(true)?;
-        } else {
-            Platform::reset_cursor().map_err(|e| WMOperationError::Error(e.into()))?;
-            // PlatformEvents::set_intercept_clicks(false)?;
+        let handle_under_cursor = wm.resize_handle_at_position(pos);
+
+        // The cursor stays put through a drag, even when the mouse gets ahead of the handle.
+        // While a button is held some other drag owns the cursor.
+        if !self.tracker.active() && !InputState::pressed_mouse_buttons().any() {
+            self.update_hover_cursor(pos, wm)?;
         }
 
-        let handle_under_cursor = wm.resize_handle_at_position(pos);
         if handle_under_cursor.is_some() && self.hover_resize_handle.is_none() {
             self.hover_resize_handle = handle_under_cursor.clone();
 
@@ -68,7 +134,37 @@ impl ResizeHandleHandler {
         Ok(())
     }
 
+    /// Shows the cursor for what's under `pos`. Window edges without a handle show the normal
+    /// cursor to hide the native resize one.
+    fn update_hover_cursor(&mut self, pos: &Position, wm: &WindowManager) -> WMOperationResult<()> {
+        let cursor = match wm.resize_handle_at_position(pos) {
+            Some(handle) => Some(handle.orientation.cursor()),
+            None if wm.find_window_at_resize_edge(pos).is_some() => Some(CursorType::Normal),
+            None => None,
+        };
+        Self::update_cursor(&mut self.hover_cursor, cursor)
+            .map_err(|e| WMOperationError::Error(e.into()))
+    }
+
+    /// Shows the cursor, or the platform's own one for `None`, only calling into the platform
+    /// when it changes
+    fn update_cursor(
+        current: &mut Option<CursorType>,
+        cursor: Option<CursorType>,
+    ) -> PlatformResult<()> {
+        if *current == cursor {
+            return Ok(());
+        }
+        match cursor {
+            Some(cursor) => Platform::set_cursor(cursor)?,
+            None => Platform::reset_cursor()?,
+        }
+        *current = cursor;
+        Ok(())
+    }
+
     fn start(&mut self, handle: ResizeHandle, _pos: Position) -> WMOperationResult<()> {
+        self.drag_frames.finish();
         let preview_bounds = handle.preview_bounds(self.handle_width);
         self.overlay.move_to(&preview_bounds);
         self.overlay.show();
@@ -95,8 +191,15 @@ impl ResizeHandleHandler {
         self.overlay.show();
 
         if Config::current().live_window_resize {
-            if let Some(mode) = Self::get_mode() {
-                wm.resize_handle_moved(&handle, &pos, &mode)?;
+            match self.drag_frames.push(pos, Instant::now()) {
+                DragFrame::Apply(pos) => {
+                    if let Some(mode) = Self::get_mode() {
+                        wm.resize_handle_moved(&handle, &pos, &mode)?;
+                    }
+                }
+                // The mouse may stop before the frame is up, so move again at wherever it rests
+                DragFrame::Wait(delay) => recheck_mouse_after(delay),
+                DragFrame::Queued => {}
             }
         }
 
@@ -110,13 +213,18 @@ impl ResizeHandleHandler {
         wm: &mut WindowManager,
     ) -> WMOperationResult<()> {
         self.overlay.hide();
+        self.drag_frames.finish();
 
-        if let Some(mode) = Self::get_mode() {
-            wm.resize_handle_moved(&handle, &pos, &mode)?;
-            wm.flush()?;
-        }
+        let result = match Self::get_mode() {
+            Some(mode) => wm
+                .resize_handle_moved(&handle, &pos, &mode)
+                .and_then(|_| wm.flush()),
+            None => Ok(()),
+        };
 
-        wm.try_save_layout();
+        // Even when the last move failed, so the next drag starts its own undo step
+        wm.resize_handle_released();
+        result?;
 
         Ok(())
     }
@@ -124,25 +232,31 @@ impl ResizeHandleHandler {
     fn get_mode() -> Option<ResizeMode> {
         let config = Config::current();
         let binds = config.resize_handle_bindings.clone();
-        if InputState::binding_matches_mouse(&binds.resize_evenly) {
-            Some(ResizeMode::Evenly)
+        let mode = if InputState::binding_matches_mouse(&binds.resize_evenly) {
+            ResizeMode::Evenly
         } else if InputState::binding_matches_mouse(&binds.resize_before) {
-            Some(ResizeMode::Before)
+            ResizeMode::Before
         } else if InputState::binding_matches_mouse(&binds.resize_after) {
-            Some(ResizeMode::After)
+            ResizeMode::After
         } else if InputState::binding_matches_mouse(&binds.resize_before_symmetric) {
-            Some(ResizeMode::BeforeSymmetric)
+            ResizeMode::BeforeSymmetric
         } else if InputState::binding_matches_mouse(&binds.resize_after_symmetric) {
-            Some(ResizeMode::AfterSymmetric)
+            ResizeMode::AfterSymmetric
         } else {
-            None
+            return None;
+        };
+
+        // Any drag snaps while the snap keys are held
+        if InputState::binding_matches_key(&binds.snap) {
+            return Some(ResizeMode::Snap(config.resize_snap_increment));
         }
+        Some(mode)
     }
 }
 
 impl EventHandler for ResizeHandleHandler {
     fn handle_event(&mut self, event: &WMEvent, wm: &mut WindowManager) -> WMOperationResult<bool> {
-        if !self.handles_enabled {
+        if !self.handles_enabled || wm.paused() {
             return Ok(false);
         }
 
@@ -161,10 +275,173 @@ impl EventHandler for ResizeHandleHandler {
                 Ok(true)
             }
             Some(ResizeHandleEvent::End(handle, pos, _)) => {
-                self.drop(handle, pos, wm)?;
+                self.drop(handle, pos.clone(), wm)?;
+                // The handle moved with the drag, it may not be under the mouse anymore
+                self.update_hover_cursor(&pos, wm)?;
                 Ok(true)
             }
-            None => Ok(self.tracker.active()),
+            Some(ResizeHandleEvent::DoubleClick(handle, _)) => {
+                wm.reset_resize_handle(&handle)?;
+                Ok(true)
+            }
+            None => {
+                // A press away from the handles may start a mod drag, which sets its own cursor
+                // and resets it when done. Hand the cursor back now so the cache matches after.
+                if let WMEvent::MouseDown(..) = event {
+                    if !self.tracker.active() {
+                        Self::update_cursor(&mut self.hover_cursor, None)
+                            .map_err(|e| WMOperationError::Error(e.into()))?;
+                    }
+                }
+                Ok(self.tracker.active())
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::overlay::{Overlay, OverlayManager};
+    use crate::platform::mock::{MockPlatform, MockPlatformWindow};
+    use crate::platform::{Bounds, Display, MouseButton, Size};
+    use crate::resize_handle::HandleOrientation;
+    use crate::window::Window;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    fn pos(x: i32) -> Position {
+        Position { x, y: 500 }
+    }
+
+    #[test]
+    fn test_burst_only_applies_last_position_per_frame() {
+        let mut frames = DragFrameLimiter::new(10);
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        assert_eq!(frames.push(pos(100), at(0)), DragFrame::Apply(pos(100)));
+
+        // The rest of the burst lands inside the same 100ms frame
+        assert_eq!(
+            frames.push(pos(110), at(10)),
+            DragFrame::Wait(Duration::from_millis(90))
+        );
+        assert_eq!(frames.push(pos(120), at(20)), DragFrame::Queued);
+        assert_eq!(frames.push(pos(130), at(30)), DragFrame::Queued);
+
+        // The check at the end of the frame skips straight to the latest position
+        assert_eq!(frames.push(pos(140), at(100)), DragFrame::Apply(pos(140)));
+        assert_eq!(
+            frames.push(pos(150), at(150)),
+            DragFrame::Wait(Duration::from_millis(50))
+        );
+    }
+
+    #[test]
+    fn test_finish_drops_queued_positions() {
+        let mut frames = DragFrameLimiter::new(10);
+        let start = Instant::now();
+
+        frames.push(pos(100), start);
+        frames.push(pos(110), start + Duration::from_millis(10));
+        frames.finish();
+
+        // A new drag starts fresh and never sees the old drag's positions
+        assert_eq!(
+            frames.push(pos(300), start + Duration::from_millis(20)),
+            DragFrame::Apply(pos(300))
+        );
+    }
+
+    #[test]
+    fn test_hovering_vertical_handle_shows_horizontal_resize_cursor() {
+        let handle = ResizeHandle::new(pos(960), 1080, HandleOrientation::Vertical, 0, 1920, 1, 2);
+        let mut current = None;
+        MockPlatform::take_cursor_calls();
+
+        ResizeHandleHandler::update_cursor(&mut current, Some(handle.orientation.cursor()))
+            .unwrap();
+        // Moving along the same handle leaves the cursor alone
+        ResizeHandleHandler::update_cursor(&mut current, Some(handle.orientation.cursor()))
+            .unwrap();
+        ResizeHandleHandler::update_cursor(&mut current, None).unwrap();
+
+        assert_eq!(
+            MockPlatform::take_cursor_calls(),
+            vec![Some(CursorType::ResizeEast), None]
+        );
+        assert_eq!(current, None);
+    }
+
+    #[test]
+    fn test_hover_cursor_is_shown_again_after_a_mod_drag() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+        MockPlatform::set_displays(vec![Display {
+            id: 1,
+            name: "Main".to_string(),
+            bounds: Bounds::new(0, 0, 1920, 1080),
+            work_area: Bounds::new(0, 0, 1920, 1080),
+            scale_factor: 1.0,
+        }]);
+        let mut wm = WindowManager::new().unwrap();
+        for id in [1, 2] {
+            let mut window = MockPlatformWindow::new(
+                Position { x: 100, y: 100 },
+                Size {
+                    width: 800,
+                    height: 600,
+                },
+                format!("Window {id}"),
+            );
+            window.id = id;
+            window.visible = true;
+            wm.track_window(Rc::new(Window::new(window))).unwrap();
+        }
+        let center = Position { x: 960, y: 540 };
+        let handle = wm.resize_handles(&center)[0].clone();
+
+        let mut handler = ResizeHandleHandler {
+            overlay: Overlay::new(0, Arc::new(OverlayManager::new())),
+            tracker: ResizeHandleTracker::new(),
+            drag_frames: DragFrameLimiter::new(60),
+            hover_resize_handle: None,
+            hover_cursor: None,
+            handles_enabled: true,
+            handle_width: 4,
+        };
+        MockPlatform::take_cursor_calls();
+
+        handler
+            .handle_event(&WMEvent::MouseMoved(handle.center.clone()), &mut wm)
+            .unwrap();
+        assert_eq!(
+            MockPlatform::take_cursor_calls(),
+            vec![Some(handle.orientation.cursor())]
+        );
+
+        // A press inside a window starts a mod drag, which shows its own cursor and resets it
+        let inside = Position { x: 400, y: 540 };
+        handler
+            .handle_event(&WMEvent::MouseDown(inside, MouseButton::Left), &mut wm)
+            .unwrap();
+        Platform::set_cursor(CursorType::Move).unwrap();
+        Platform::reset_cursor().unwrap();
+        assert_eq!(
+            MockPlatform::take_cursor_calls(),
+            vec![None, Some(CursorType::Move), None]
+        );
+
+        handler
+            .handle_event(&WMEvent::MouseMoved(handle.center.clone()), &mut wm)
+            .unwrap();
+        assert_eq!(
+            MockPlatform::take_cursor_calls(),
+            vec![Some(handle.orientation.cursor())]
+        );
+    }
+}
@@ -1,12 +1,15 @@
+use crate::config::Config;
 use crate::platform::{input_state::InputState, MouseButtons, Position, WMEvent};
 use crate::resize_handle::ResizeHandle;
 use crate::wm::WindowManager;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
 pub enum ResizeHandleEvent {
     Start(ResizeHandle, Position, MouseButtons),
     Drag(ResizeHandle, Position, MouseButtons),
     End(ResizeHandle, Position, MouseButtons),
+    DoubleClick(ResizeHandle, Position),
 }
 
 #[derive(Debug)]
@@ -14,6 +17,8 @@ pub struct ResizeHandleTracker {
     active_handle: Option<ResizeHandle>,
     dragging: bool,
     drag_buttons: MouseButtons,
+    /// The handle that was last pressed, by its before and after ids, and when
+    last_click: Option<(u64, u64, Instant)>,
 }
 
 impl ResizeHandleTracker {
@@ -22,6 +27,7 @@ impl ResizeHandleTracker {
             active_handle: None,
             dragging: false,
             drag_buttons: MouseButtons::new(),
+            last_click: None,
         }
     }
 
@@ -47,6 +53,13 @@ impl ResizeHandleTracker {
             }
             WMEvent::MouseDown(pos, _) => {
                 if let Some(handle) = wm.resize_handle_at_position(pos) {
+                    let now = Instant::now();
+                    if self.is_double_click(&handle, now) {
+                        self.last_click = None;
+                        return Some(ResizeHandleEvent::DoubleClick(handle, pos.clone()));
+                    }
+                    self.last_click = Some((handle.before_id, handle.after_id, now));
+
                     self.dragging = true;
                     self.active_handle = Some(handle.clone());
                     return Some(ResizeHandleEvent::Start(
@@ -75,6 +88,15 @@ impl ResizeHandleTracker {
         None
     }
 
+    fn is_double_click(&self, handle: &ResizeHandle, now: Instant) -> bool {
+        let interval = Duration::from_millis(Config::resize_handle_double_click_ms() as u64);
+        self.last_click.is_some_and(|(before_id, after_id, at)| {
+            before_id == handle.before_id
+                && after_id == handle.after_id
+                && now.duration_since(at) <= interval
+        })
+    }
+
     pub fn get_drag_buttons(&self) -> &MouseButtons {
         &self.drag_buttons
     }
@@ -0,0 +1,158 @@
+use crate::config::Config;
+use crate::event_handlers::EventHandler;
+use crate::event_loop_wm::WMOperationResult;
+use crate::overlay;
+use crate::overlay::overlays::WorkspaceIndicatorOverlay;
+use crate::partition::{Partition, PartitionId};
+use crate::platform::{Bounds, WMEvent};
+use crate::wm::WindowManager;
+use crate::workspace::WorkspaceId;
+use log::error;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+const INDICATOR_WIDTH: u32 = 240;
+const INDICATOR_HEIGHT: u32 = 56;
+const INDICATOR_MARGIN: u32 = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+struct ActiveWorkspace {
+    id: WorkspaceId,
+    name: String,
+    index: usize,
+    total: usize,
+}
+
+struct PartitionIndicator {
+    overlay: overlay::Overlay,
+    bounds: Bounds,
+    workspace: Option<ActiveWorkspace>,
+    hide_task: Option<JoinHandle<()>>,
+}
+
+/// Shows a workspace indicator in the corner of a partition whenever its active workspace
+/// changes, and hides it again after a timeout.
+pub struct WorkspaceIndicatorHandler {
+    enabled: bool,
+    timeout: Duration,
+    indicators: HashMap<PartitionId, PartitionIndicator>,
+}
+
+impl WorkspaceIndicatorHandler {
+    pub async fn new(wm: &WindowManager) -> Self {
+        let enabled = Config::show_workspace_indicator();
+        let timeout = Duration::from_millis(Config::workspace_indicator_timeout_ms() as u64);
+
+        let mut indicators = HashMap::new();
+        if enabled {
+            for partition in wm.partitions().values() {
+                let overlay = match overlay::manager()
+                    .add(Box::new(WorkspaceIndicatorOverlay::new()))
+                    .await
+                {
+                    Ok(overlay) => overlay,
+                    Err(e) => {
+                        error!("Failed to create workspace indicator overlay: {e}");
+                        continue;
+                    }
+                };
+
+                indicators.insert(
+                    partition.id(),
+                    PartitionIndicator {
+                        overlay,
+                        bounds: Bounds::default(),
+                        // Start with the current workspace so the indicator doesn't flash on startup
+                        workspace: Self::active_workspace(wm, partition),
+                        hide_task: None,
+                    },
+                );
+            }
+        }
+
+        Self {
+            enabled,
+            timeout,
+            indicators,
+        }
+    }
+
+    fn update(&mut self, wm: &WindowManager) {
+        for (partition_id, indicator) in self.indicators.iter_mut() {
+            let Some(partition) = wm.partitions().get(partition_id) else {
+                continue;
+            };
+
+            let bounds = Self::indicator_bounds(partition.bounds());
+            if bounds != indicator.bounds {
+                indicator.overlay.move_to(&bounds);
+                indicator.bounds = bounds;
+            }
+
+            let workspace = Self::active_workspace(wm, partition);
+            if workspace != indicator.workspace {
+                if let Some(active) = &workspace {
+                    let (name, index, total) = (active.name.clone(), active.index, active.total);
+                    indicator
+                        .overlay
+                        .update(move |content: &mut WorkspaceIndicatorOverlay| {
+                            content.set_workspace(name, index, total);
+                        });
+                    indicator.overlay.show();
+                    // Restart the timeout when switching again while it is still shown
+                    if let Some(task) = indicator.hide_task.take() {
+                        task.abort();
+                    }
+                    indicator.hide_task = Some(indicator.overlay.hide_after(self.timeout));
+                }
+                indicator.workspace = workspace;
+            }
+        }
+    }
+
+    fn active_workspace(wm: &WindowManager, partition: &Partition) -> Option<ActiveWorkspace> {
+        let id = partition.current_workspace()?;
+        let workspace = wm.workspaces().get(&id)?;
+
+        let mut assigned: Vec<WorkspaceId> =
+            partition.assigned_workspaces().iter().copied().collect();
+        assigned.sort();
+        let index = assigned.iter().position(|w| *w == id)? + 1;
+
+        Some(ActiveWorkspace {
+            id,
+            name: workspace.name().to_string(),
+            index,
+            total: assigned.len(),
+        })
+    }
+
+    fn indicator_bounds(partition_bounds: &Bounds) -> Bounds {
+        let width = INDICATOR_WIDTH.min(partition_bounds.size.width);
+        Bounds::new(
+            partition_bounds.position.x + partition_bounds.size.width as i32
+                - (width + INDICATOR_MARGIN) as i32,
+            partition_bounds.position.y + INDICATOR_MARGIN as i32,
+            width,
+            INDICATOR_HEIGHT,
+        )
+    }
+}
+
+impl EventHandler for WorkspaceIndicatorHandler {
+    fn handle_event(
+        &mut self,
+        _event: &WMEvent,
+        wm: &mut WindowManager,
+    ) -> WMOperationResult<bool> {
+        if !self.enabled {
+            return Ok(false);
+        }
+
+        self.update(wm);
+
+        // Never consume events, the indicator only reacts to workspace changes
+        Ok(false)
+    }
+}
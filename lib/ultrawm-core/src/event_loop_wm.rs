@@ -1,13 +1,19 @@
+use crate::ai::OrganizePreview;
+use crate::commands::{get_command, CommandContext, SWITCH_WORKSPACE};
 use crate::config::Config;
 use crate::event_handlers::command_handler::CommandHandler;
 use crate::event_handlers::context_menu_handler::ContextMenuHandler;
 use crate::event_handlers::focus_on_hover_handler::FocusOnHoverHandler;
 use crate::event_handlers::mod_transform_handler::ModTransformHandler;
+use crate::event_handlers::mouse_command_handler::MouseCommandHandler;
 use crate::event_handlers::native_transform_handler::NativeTransformHandler;
 use crate::event_handlers::resize_handle_handler::ResizeHandleHandler;
+use crate::event_handlers::workspace_indicator_handler::WorkspaceIndicatorHandler;
 use crate::event_handlers::EventHandler;
+use crate::ipc::events;
 use crate::overlay;
 use crate::platform::PlatformWindowImpl;
+use crate::snapshot::{self, LayoutSnapshot};
 use crate::window::Window;
 use crate::wm::WMError;
 use crate::{
@@ -21,6 +27,7 @@ use crate::{
 };
 use log::{debug, error, info, warn};
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
 use tokio::time::Interval;
@@ -43,12 +50,14 @@ pub struct EventLoopWM {
     current_handler: Option<usize>,
     flush_interval: Interval,
     is_startup: bool,
+    /// The snapshot IPC events were last generated from
+    last_snapshot: Arc<LayoutSnapshot>,
 }
 
 impl EventLoopWM {
     pub async fn new() -> UltraWMResult<Self> {
         let wm = WindowManager::new()?;
-        let handlers = Self::create_handlers().await;
+        let handlers = Self::create_handlers(&wm).await;
 
         Ok(Self {
             wm,
@@ -56,6 +65,7 @@ impl EventLoopWM {
             current_handler: None,
             flush_interval: Self::create_flush_interval(),
             is_startup: true,
+            last_snapshot: snapshot::current(),
         })
     }
 
@@ -93,6 +103,7 @@ impl EventLoopWM {
                     event_loop.flush();
                 }
             }
+            event_loop.publish_ipc_events();
         }
 
         event_loop.wm.cleanup()?;
@@ -106,6 +117,7 @@ impl EventLoopWM {
         };
 
         if matches!(event, WMEvent::Shutdown) {
+            self.wm.final_save_layout();
             return LoopControl::Break;
         }
 
@@ -144,6 +156,12 @@ impl EventLoopWM {
             return LoopControl::Continue;
         }
 
+        if let WMEvent::PlaceWindowAt(window_id, bounds, floating, reply) = event {
+            let result = self.wm.place_window_at(window_id, bounds, floating);
+            let _ = reply.send(result.map_err(|e| e.to_string()));
+            return LoopControl::Continue;
+        }
+
         if let WMEvent::FloatWindow(window_id) = event {
             self.wm.float_window(window_id).unwrap_or_else(|e| {
                 error!("Failed to float window: {e}");
@@ -151,12 +169,73 @@ impl EventLoopWM {
             return LoopControl::Continue;
         }
 
+        if let WMEvent::PreviewOrganizePlan(plan) = event {
+            let slots = self.wm.organize_preview_slots(&plan);
+            let timeout = Duration::from_secs(Config::ai().preview_timeout_secs);
+            let preview = OrganizePreview::show(plan.id(), slots, timeout);
+            self.wm.set_organize_plan(plan, Some(preview));
+            return LoopControl::Continue;
+        }
+
+        if let WMEvent::OrganizePlanExpired(plan_id) = event {
+            self.wm.expire_organize_plan(plan_id);
+            return LoopControl::Continue;
+        }
+
+        if let WMEvent::DisplaysChanged = event {
+            self.wm.displays_changed().unwrap_or_else(|e| {
+                error!("Failed to update partitions for the new displays: {e}");
+            });
+            return LoopControl::Continue;
+        }
+
+        if let WMEvent::Relayout = event {
+            self.wm.relayout_all().unwrap_or_else(|e| {
+                error!("Failed to relayout: {e}");
+            });
+            return LoopControl::Continue;
+        }
+
+        if let WMEvent::Snapshot(reply) = event {
+            let _ = reply.send(self.wm.snapshot());
+            return LoopControl::Continue;
+        }
+
+        if let WMEvent::SaveLayoutPreset(name, reply) = event {
+            let _ = reply.send(self.wm.save_layout_preset(&name).map_err(|e| e.to_string()));
+            return LoopControl::Continue;
+        }
+
+        if let WMEvent::LoadLayoutPreset(name, reply) = event {
+            let _ = reply.send(self.wm.load_layout_preset(&name).map_err(|e| e.to_string()));
+            return LoopControl::Continue;
+        }
+
+        if let WMEvent::RunCommand(command_id, argument, reply) = event {
+            let result = match get_command(&command_id) {
+                Some(def) => {
+                    let context = argument.map(CommandContext::with_argument);
+                    (def.handler)(&mut self.wm, context.as_ref()).map_err(|e| e.to_string())
+                }
+                None => Err(format!("Unknown command {command_id:?}")),
+            };
+            let _ = reply.send(result);
+            return LoopControl::Continue;
+        }
+
+        if let WMEvent::SwitchWorkspace(name, reply) = event {
+            let context = CommandContext::with_argument(name);
+            let result = (SWITCH_WORKSPACE.handler)(&mut self.wm, Some(&context));
+            let _ = reply.send(result.map_err(|e| e.to_string()));
+            return LoopControl::Continue;
+        }
+
         LoopControl::Continue
     }
 
     async fn reload_config(&mut self) {
         info!("Reloading config...");
-        self.handlers = Self::create_handlers().await;
+        self.handlers = Self::create_handlers(&self.wm).await;
         self.current_handler = None;
         self.wm.config_changed().unwrap_or_else(|e| {
             error!("Could not reload config: {e}");
@@ -176,7 +255,22 @@ impl EventLoopWM {
                 let _ = self.wm.remove_window(*id);
             }
             WMEvent::WindowFocused(id) => {
-                self.wm.move_to_top(*id);
+                self.wm.window_focused(*id);
+                self.wm
+                    .reevaluate_transient_window(*id)
+                    .unwrap_or_else(|e| {
+                        warn!("Could not re-evaluate transient window: {e}");
+                    });
+            }
+            WMEvent::WindowTitleChanged(id, title) => {
+                self.wm.window_title_changed(*id, title.clone());
+            }
+            WMEvent::WindowFullscreenChanged(id, fullscreen) => {
+                self.wm
+                    .window_fullscreen_changed(*id, *fullscreen)
+                    .unwrap_or_else(|e| {
+                        warn!("Could not update full screen window: {e}");
+                    });
             }
             _ => {}
         }
@@ -210,15 +304,30 @@ impl EventLoopWM {
         }
     }
 
+    /// Sends IPC events for whatever changed since the last published snapshot.
+    fn publish_ipc_events(&mut self) {
+        let current = snapshot::current();
+        if Arc::ptr_eq(&current, &self.last_snapshot) {
+            return;
+        }
+
+        for event in events::diff_snapshots(&self.last_snapshot, &current) {
+            events::publish(event);
+        }
+        self.last_snapshot = current;
+    }
+
     fn flush(&mut self) {
         self.wm.flush().unwrap_or_else(|e| {
             error!("Flush error: {e}");
         });
     }
 
-    async fn create_handlers() -> Vec<Box<dyn EventHandler>> {
+    async fn create_handlers(wm: &WindowManager) -> Vec<Box<dyn EventHandler>> {
         let handlers: Vec<Box<dyn EventHandler>> = vec![
+            Box::new(WorkspaceIndicatorHandler::new(wm).await),
             Box::new(ContextMenuHandler::new()),
+            Box::new(MouseCommandHandler::new()),
             Box::new(NativeTransformHandler::new().await),
             Box::new(ResizeHandleHandler::new().await),
             Box::new(ModTransformHandler::new().await),
@@ -0,0 +1,232 @@
+//! Events streamed to `subscribe` clients. Each event is sent as one line of JSON, tagged by
+//! its `event` field, e.g. `{"event":"WindowFocused","window":42}`.
+
+use crate::partition::PartitionId;
+use crate::platform::{Bounds, WindowId};
+use crate::snapshot::LayoutSnapshot;
+use crate::workspace::WorkspaceId;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tokio::sync::broadcast;
+
+/// How many events a subscriber may fall behind before it is dropped
+const EVENT_BUFFER_SIZE: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event")]
+pub enum IpcEvent {
+    /// A partition now shows a different workspace
+    WorkspaceSwitched {
+        partition: PartitionId,
+        workspace: WorkspaceId,
+        name: String,
+    },
+    WindowFocused {
+        window: WindowId,
+    },
+    WindowOpened {
+        window: WindowId,
+        title: String,
+        app_name: String,
+    },
+    WindowClosed {
+        window: WindowId,
+    },
+    /// Windows moved, resized, floated or changed workspace. Query the layout for details.
+    LayoutChanged,
+}
+
+static EVENTS: Lazy<broadcast::Sender<IpcEvent>> =
+    Lazy::new(|| broadcast::channel(EVENT_BUFFER_SIZE).0);
+
+/// Sends an event to every subscriber. Never blocks, subscribers that can't keep up miss
+/// events instead.
+pub fn publish(event: IpcEvent) {
+    let _ = EVENTS.send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<IpcEvent> {
+    EVENTS.subscribe()
+}
+
+/// Works out which events happened between two snapshots.
+pub fn diff_snapshots(old: &LayoutSnapshot, new: &LayoutSnapshot) -> Vec<IpcEvent> {
+    let mut events = Vec::new();
+
+    for partition in &new.partitions {
+        let previous = old
+            .partitions
+            .iter()
+            .find(|p| p.id == partition.id)
+            .and_then(|p| p.current_workspace);
+        let Some(workspace_id) = partition.current_workspace else {
+            continue;
+        };
+        if previous == Some(workspace_id) {
+            continue;
+        }
+        if let Some(workspace) = new.workspaces.iter().find(|w| w.id == workspace_id) {
+            events.push(IpcEvent::WorkspaceSwitched {
+                partition: partition.id,
+                workspace: workspace_id,
+                name: workspace.name.clone(),
+            });
+        }
+    }
+
+    let old_windows: HashSet<WindowId> = window_ids(old).collect();
+    let new_windows: HashSet<WindowId> = window_ids(new).collect();
+    for window in new.workspaces.iter().flat_map(|w| &w.windows) {
+        if !old_windows.contains(&window.id) {
+            events.push(IpcEvent::WindowOpened {
+                window: window.id,
+                title: window.title.clone(),
+                app_name: window.app_name.clone(),
+            });
+        }
+    }
+    for id in window_ids(old).filter(|id| !new_windows.contains(id)) {
+        events.push(IpcEvent::WindowClosed { window: id });
+    }
+
+    if let Some(window) = new.focused_window {
+        if old.focused_window != Some(window) {
+            events.push(IpcEvent::WindowFocused { window });
+        }
+    }
+
+    if layout_of(old) != layout_of(new) {
+        events.push(IpcEvent::LayoutChanged);
+    }
+
+    events
+}
+
+fn window_ids(snapshot: &LayoutSnapshot) -> impl Iterator<Item = WindowId> + '_ {
+    snapshot
+        .workspaces
+        .iter()
+        .flat_map(|w| w.windows.iter().map(|window| window.id))
+}
+
+/// The parts of a snapshot that describe where windows are, ignoring focus and flush state.
+fn layout_of(snapshot: &LayoutSnapshot) -> Vec<(WorkspaceId, WindowId, Bounds, bool)> {
+    snapshot
+        .workspaces
+        .iter()
+        .flat_map(|w| {
+            w.windows
+                .iter()
+                .map(|window| (w.id, window.id, window.bounds.clone(), window.floating))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{PartitionSnapshot, WindowSnapshot, WorkspaceSnapshot};
+
+    fn window(id: WindowId, x: i32) -> WindowSnapshot {
+        WindowSnapshot {
+            id,
+            title: format!("Window {id}"),
+            app_name: "Terminal".to_string(),
+            bounds: Bounds::new(x, 0, 100, 100),
+            floating: false,
+            focused: false,
+            dirty: false,
+        }
+    }
+
+    fn snapshot(
+        current_workspace: WorkspaceId,
+        windows: Vec<WindowSnapshot>,
+        focused_window: Option<WindowId>,
+    ) -> LayoutSnapshot {
+        LayoutSnapshot {
+            partitions: vec![PartitionSnapshot {
+                id: 0,
+                name: "Main".to_string(),
+                bounds: Bounds::new(0, 0, 1920, 1080),
+                current_workspace: Some(current_workspace),
+            }],
+            workspaces: vec![
+                WorkspaceSnapshot {
+                    id: 1,
+                    name: "One".to_string(),
+                    partition: Some(0),
+                    windows,
+                },
+                WorkspaceSnapshot {
+                    id: 2,
+                    name: "Two".to_string(),
+                    partition: Some(0),
+                    windows: Vec::new(),
+                },
+            ],
+            focused_window,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_identical_snapshots_have_no_events() {
+        let old = snapshot(1, vec![window(1, 0)], Some(1));
+        assert!(diff_snapshots(&old, &old.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_dirty_state_is_not_a_layout_change() {
+        let old = snapshot(1, vec![window(1, 0)], Some(1));
+        let mut new = old.clone();
+        new.workspaces[0].windows[0].dirty = true;
+
+        assert!(diff_snapshots(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn test_window_opened_and_focused() {
+        let old = snapshot(1, vec![window(1, 0)], Some(1));
+        let new = snapshot(1, vec![window(1, 0), window(2, 100)], Some(2));
+
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            vec![
+                IpcEvent::WindowOpened {
+                    window: 2,
+                    title: "Window 2".to_string(),
+                    app_name: "Terminal".to_string(),
+                },
+                IpcEvent::WindowFocused { window: 2 },
+                IpcEvent::LayoutChanged,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_window_closed_and_workspace_switched() {
+        let old = snapshot(1, vec![window(1, 0)], None);
+        let new = snapshot(2, Vec::new(), None);
+
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            vec![
+                IpcEvent::WorkspaceSwitched {
+                    partition: 0,
+                    workspace: 2,
+                    name: "Two".to_string(),
+                },
+                IpcEvent::WindowClosed { window: 1 },
+                IpcEvent::LayoutChanged,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_json_is_tagged() {
+        let json = serde_json::to_string(&IpcEvent::WindowFocused { window: 42 }).unwrap();
+        assert_eq!(json, r#"{"event":"WindowFocused","window":42}"#);
+    }
+}
@@ -0,0 +1,448 @@
+//! Line based IPC for controlling a running instance. Each request is a single line holding a
+//! command id with an optional argument, or a `query`, and gets a single newline terminated
+//! response. Commands reply once they ran, with their error if they failed. Query responses are
+//! JSON without any newlines, so a client can always read exactly one line. A `subscribe`
+//! request keeps the connection open and streams an `IpcEvent` per line instead. `preset`
+//! requests save, load and list named layout presets, and `workspace` requests switch to a
+//! workspace by name.
+
+pub mod events;
+
+use crate::commands::get_command;
+use crate::ipc::events::IpcEvent;
+use crate::paths;
+use log::{info, warn};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::thread;
+use thiserror::Error;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Verb for requests that return data instead of running a command
+pub const QUERY_VERB: &str = "query";
+
+/// Query that returns the current `LayoutSnapshot` as JSON. A bare `query` does the same.
+pub const QUERY_LAYOUT: &str = "layout";
+
+/// Verb for requests that manage layout presets, followed by one of the preset actions
+pub const PRESET_VERB: &str = "preset";
+
+/// Saves the focused workspace as the named preset
+pub const PRESET_SAVE: &str = "save";
+
+/// Applies the named preset to the focused workspace
+pub const PRESET_LOAD: &str = "load";
+
+/// Returns the saved preset names as a JSON array
+pub const PRESET_LIST: &str = "list";
+
+/// Verb for requests that show the workspace named after it
+pub const WORKSPACE_VERB: &str = "workspace";
+
+/// Request that streams events until the connection closes
+pub const SUBSCRIBE_VERB: &str = "subscribe";
+
+/// Response sent after a command was dispatched
+pub const OK_RESPONSE: &str = "ok";
+
+/// Prefix of responses for requests that failed
+pub const ERROR_PREFIX: &str = "error: ";
+
+#[derive(Debug, Error)]
+pub enum IpcError {
+    #[error("UltraWM is not running")]
+    NotRunning,
+    #[error("Another instance of UltraWM is already running")]
+    AlreadyRunning,
+    #[error("Could not determine the IPC socket path")]
+    NoSocketPath,
+    #[error("IPC error: {0}")]
+    Io(#[from] io::Error),
+}
+
+pub type IpcResult<T> = Result<T, IpcError>;
+
+fn handle_request(request: &str) -> String {
+    let request = request.trim();
+
+    let mut parts = request.split_whitespace();
+    match parts.next() {
+        Some(QUERY_VERB) => {
+            return match parts.next().unwrap_or(QUERY_LAYOUT) {
+                QUERY_LAYOUT => query_layout(),
+                other => format!("{ERROR_PREFIX}unknown query {other:?}"),
+            };
+        }
+        Some(PRESET_VERB) => {
+            let action = parts.next().unwrap_or_default();
+            let name = parts.collect::<Vec<_>>().join(" ");
+            return handle_preset_request(action, &name);
+        }
+        Some(WORKSPACE_VERB) => {
+            let name = parts.collect::<Vec<_>>().join(" ");
+            if name.is_empty() {
+                return format!("{ERROR_PREFIX}missing workspace name");
+            }
+            return match crate::switch_workspace(&name) {
+                Ok(()) => OK_RESPONSE.to_string(),
+                Err(e) => format!("{ERROR_PREFIX}{e}"),
+            };
+        }
+        Some(command) if get_command(command).is_some() => {
+            let argument = parts.collect::<Vec<_>>().join(" ");
+            let argument = (!argument.is_empty()).then_some(argument.as_str());
+            return match crate::run_command(command, argument) {
+                Ok(()) => OK_RESPONSE.to_string(),
+                Err(e) => format!("{ERROR_PREFIX}{e}"),
+            };
+        }
+        _ => {}
+    }
+
+    format!("{ERROR_PREFIX}unknown command {request:?}")
+}
+
+fn handle_preset_request(action: &str, name: &str) -> String {
+    let result = match action {
+        PRESET_LIST => {
+            return serde_json::to_string(&crate::list_layout_presets())
+                .unwrap_or_else(|e| format!("{ERROR_PREFIX}{e}"));
+        }
+        PRESET_SAVE | PRESET_LOAD if name.is_empty() => {
+            return format!("{ERROR_PREFIX}missing preset name");
+        }
+        PRESET_SAVE => crate::save_layout_preset(name),
+        PRESET_LOAD => crate::load_layout_preset(name),
+        other => return format!("{ERROR_PREFIX}unknown preset action {other:?}"),
+    };
+
+    match result {
+        Ok(()) => OK_RESPONSE.to_string(),
+        Err(e) => format!("{ERROR_PREFIX}{e}"),
+    }
+}
+
+/// Serves the shared copy of the layout, so queries never wait on the WM thread
+fn query_layout() -> String {
+    if !crate::is_running() {
+        return format!("{ERROR_PREFIX}window manager is not running");
+    }
+    serde_json::to_string(crate::get_layout_snapshot().as_ref())
+        .unwrap_or_else(|e| format!("{ERROR_PREFIX}{e}"))
+}
+
+fn serve_connection(reader: impl Read, mut writer: impl Write) {
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if line.trim() == SUBSCRIBE_VERB {
+            stream_events(writer);
+            return;
+        }
+
+        let response = handle_request(&line);
+        if writeln!(writer, "{response}")
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+/// Writes events to a subscriber until it disconnects. Subscribers that fall too far behind
+/// are dropped, publishing never waits on them.
+fn stream_events(mut writer: impl Write) {
+    let mut events = events::subscribe();
+    loop {
+        let event: IpcEvent = match events.blocking_recv() {
+            Ok(event) => event,
+            Err(RecvError::Lagged(missed)) => {
+                warn!("Dropping IPC subscriber that missed {missed} events");
+                let _ = writeln!(writer, "{ERROR_PREFIX}missed {missed} events");
+                return;
+            }
+            Err(RecvError::Closed) => return,
+        };
+
+        let Ok(json) = serde_json::to_string(&event) else {
+            continue;
+        };
+        if writeln!(writer, "{json}")
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+fn not_running(error: io::Error) -> IpcError {
+    match error.kind() {
+        io::ErrorKind::ConnectionRefused | io::ErrorKind::NotFound => IpcError::NotRunning,
+        _ => IpcError::Io(error),
+    }
+}
+
+/// Sends a request to the running instance and returns its response.
+pub fn send_request(request: &str) -> IpcResult<String> {
+    let path = paths::ipc_socket_path().ok_or(IpcError::NoSocketPath)?;
+
+    #[cfg(unix)]
+    let stream = std::os::unix::net::UnixStream::connect(&path).map_err(not_running)?;
+    #[cfg(windows)]
+    let stream = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&path)
+        .map_err(not_running)?;
+
+    let mut writer = stream.try_clone()?;
+    writeln!(writer, "{}", request.trim())?;
+    writer.flush()?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+    Ok(response.trim_end().to_string())
+}
+
+/// Starts listening for requests on a background thread.
+#[cfg(unix)]
+pub fn start_server() -> IpcResult<()> {
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    let path = paths::ipc_socket_path().ok_or(IpcError::NoSocketPath)?;
+    if path.exists() {
+        if UnixStream::connect(&path).is_ok() {
+            return Err(IpcError::AlreadyRunning);
+        }
+        // Left behind by an instance that didn't shut down cleanly
+        std::fs::remove_file(&path)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let listener = UnixListener::bind(&path)?;
+    info!("Listening for commands on {}", path.display());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("IPC connection failed: {e}");
+                    continue;
+                }
+            };
+
+            thread::spawn(move || match stream.try_clone() {
+                Ok(reader) => serve_connection(reader, stream),
+                Err(e) => warn!("IPC connection failed: {e}"),
+            });
+        }
+    });
+
+    Ok(())
+}
+
+/// Starts listening for requests on a background thread.
+#[cfg(windows)]
+pub fn start_server() -> IpcResult<()> {
+    use std::fs::File;
+    use std::os::windows::io::FromRawHandle;
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::{CloseHandle, ERROR_PIPE_CONNECTED};
+    use windows::Win32::Storage::FileSystem::PIPE_ACCESS_DUPLEX;
+    use windows::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_TYPE_BYTE,
+        PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    const BUFFER_SIZE: u32 = 4096;
+
+    let path = paths::ipc_socket_path().ok_or(IpcError::NoSocketPath)?;
+    if File::options().read(true).write(true).open(&path).is_ok() {
+        return Err(IpcError::AlreadyRunning);
+    }
+
+    let name = HSTRING::from(path.as_os_str());
+    info!("Listening for commands on {}", path.display());
+
+    thread::spawn(move || loop {
+        // Each client gets its own pipe instance
+        let pipe = unsafe {
+            CreateNamedPipeW(
+                &name,
+                PIPE_ACCESS_DUPLEX,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                None,
+            )
+        };
+        if pipe.is_invalid() {
+            warn!("Could not create IPC pipe: {}", io::Error::last_os_error());
+            return;
+        }
+
+        let connected = match unsafe { ConnectNamedPipe(pipe, None) } {
+            Ok(()) => true,
+            Err(e) => e.code() == ERROR_PIPE_CONNECTED.to_hresult(),
+        };
+        if !connected {
+            let _ = unsafe { CloseHandle(pipe) };
+            continue;
+        }
+
+        let stream = unsafe { File::from_raw_handle(pipe.0 as _) };
+        thread::spawn(move || match stream.try_clone() {
+            Ok(reader) => serve_connection(reader, stream),
+            Err(e) => warn!("IPC connection failed: {e}"),
+        });
+    });
+
+    Ok(())
+}
+
+/// Removes the socket so clients don't try to talk to a stopped instance.
+pub fn stop_server() {
+    #[cfg(unix)]
+    if let Some(path) = paths::ipc_socket_path() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    const NOT_RUNNING: &str = "error: Window manager is not running";
+
+    /// Sends requests over one connection and returns a response per line
+    fn round_trip(requests: &[&str]) -> Vec<String> {
+        let input = requests.join("\n");
+        let mut output = Vec::new();
+        serve_connection(input.as_bytes(), &mut output);
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Forwards everything written to it, failing once the receiver is gone
+    struct ChannelWriter(mpsc::Sender<Vec<u8>>);
+
+    impl Write for ChannelWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0
+                .send(buf.to_vec())
+                .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_each_request_gets_one_response_line() {
+        let responses = round_trip(&["query bogus", "", "  ", "frobnicate"]);
+        assert_eq!(
+            responses,
+            vec![
+                "error: unknown query \"bogus\"",
+                "error: unknown command \"frobnicate\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_without_a_running_wm() {
+        let responses = round_trip(&["query", "query layout"]);
+        assert_eq!(
+            responses,
+            vec![
+                "error: window manager is not running",
+                "error: window manager is not running",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preset_requests() {
+        let responses = round_trip(&[
+            "preset save",
+            "preset load",
+            "preset save Coding",
+            "preset load Coding",
+            "preset rename Coding",
+            "preset list",
+        ]);
+        assert_eq!(
+            responses[..5],
+            [
+                "error: missing preset name",
+                "error: missing preset name",
+                NOT_RUNNING,
+                NOT_RUNNING,
+                "error: unknown preset action \"rename\"",
+            ]
+        );
+        assert!(serde_json::from_str::<Vec<String>>(&responses[5]).is_ok());
+    }
+
+    #[test]
+    fn test_workspace_requests() {
+        let responses = round_trip(&["workspace", "workspace Dev Tools"]);
+        assert_eq!(
+            responses,
+            vec!["error: missing workspace name", NOT_RUNNING]
+        );
+    }
+
+    #[test]
+    fn test_command_requests() {
+        commands::register_commands();
+        let responses = round_trip(&["undo", "set_layout master-stack"]);
+        assert_eq!(responses, vec![NOT_RUNNING, NOT_RUNNING]);
+    }
+
+    #[test]
+    fn test_subscribe_streams_events_until_the_client_leaves() {
+        let (sender, receiver) = mpsc::channel();
+        let server = thread::spawn(move || {
+            serve_connection("subscribe\n".as_bytes(), ChannelWriter(sender))
+        });
+
+        // The server subscribes on its own thread, so publish until it is listening
+        let mut line = Vec::new();
+        while !line.ends_with(b"\n") {
+            events::publish(IpcEvent::LayoutChanged);
+            while let Ok(bytes) = receiver.recv_timeout(Duration::from_millis(10)) {
+                line.extend(bytes);
+            }
+        }
+        let first = String::from_utf8(line).unwrap();
+        let first = first.lines().next().unwrap();
+        assert!(serde_json::from_str::<IpcEvent>(first).is_ok());
+
+        drop(receiver);
+        while !server.is_finished() {
+            events::publish(IpcEvent::LayoutChanged);
+            thread::sleep(Duration::from_millis(10));
+        }
+        server.join().unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 use crate::layouts::container_tree::container::container_window::ContainerWindow;
 use crate::layouts::container_tree::container::{Container, ParentContainerRef};
-use crate::platform::Bounds;
+use crate::layouts::RatioLimits;
+use crate::platform::{Bounds, Size};
 use std::rc::Rc;
 
 pub type ContainerRef = Rc<Container>;
@@ -27,6 +28,21 @@ impl ContainerChildRef {
         }
     }
 
+    pub fn min_size(&self) -> Size {
+        match self {
+            ContainerChildRef::Container(container) => container.min_size(),
+            ContainerChildRef::Window(window) => window.min_size(),
+        }
+    }
+
+    /// Limits on the share of the parent this child can be resized to
+    pub fn ratio_limits(&self) -> RatioLimits {
+        match self {
+            ContainerChildRef::Container(container) => container.ratio_limits(),
+            ContainerChildRef::Window(window) => window.window().ratio_limits(),
+        }
+    }
+
     pub(super) fn set_bounds(&self, bounds: Bounds) {
         match self {
             ContainerChildRef::Container(container) => container.set_bounds(bounds),
@@ -1,3 +1,4 @@
+use crate::config::Config;
 use crate::layouts::container_tree::container::{
     Container, ContainerRef, ContainerWindowRef, ParentContainerRef,
 };
@@ -63,6 +64,19 @@ impl ContainerWindow {
         self.window.set_bounds(bounds);
     }
 
+    /// The smallest slot the window fits in, including the window gap. Zero on an axis the
+    /// window has no minimum for.
+    pub fn min_size(&self) -> Size {
+        let Some(min) = self.window.min_size() else {
+            return Size::default();
+        };
+        let gap = Config::window_gap();
+        Size {
+            width: if min.width > 0 { min.width + gap } else { 0 },
+            height: if min.height > 0 { min.height + gap } else { 0 },
+        }
+    }
+
     pub fn parent(&self) -> ContainerRef {
         self.parent.borrow().upgrade().unwrap()
     }
@@ -3,9 +3,9 @@ pub use container_window::*;
 
 use super::Side;
 use crate::layouts::container_tree::ContainerId;
-use crate::layouts::{next_tree_node_id, Direction};
-use crate::platform::Bounds;
-use std::cell::{Ref, RefCell, RefMut};
+use crate::layouts::{next_tree_node_id, Direction, RatioLimits};
+use crate::platform::{Bounds, Size};
+use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::rc::{Rc, Weak};
 
 pub mod container_ref;
@@ -13,6 +13,76 @@ mod container_window;
 
 pub type ParentContainerRef = Weak<Container>;
 
+// Smallest weight a child can get when balancing, so no window collapses to nothing
+const MIN_WEIGHT: f32 = 0.05;
+
+// φ, the ratio each golden ratio split divides its space by
+const GOLDEN_RATIO: f32 = 1.618_034;
+
+// Smallest share each side of a dragged divider keeps, unless its children set their own
+const MIN_SPLIT_RATIO: f32 = 0.1;
+
+/// Splits `total` between children by weight without giving any child less than its minimum.
+/// When the minimums don't all fit, sizes are split by weight alone, but never below 1px.
+pub(super) fn distribute_sizes(total: u32, weights: &[f32], mins: &[u32]) -> Vec<u32> {
+    let count = weights.len();
+    let min = |i: usize| mins.get(i).copied().unwrap_or(0);
+    let fits = (0..count).map(min).sum::<u32>() <= total;
+
+    let mut sizes = vec![0; count];
+    let mut pinned = vec![false; count];
+    loop {
+        let pinned_total: u32 = (0..count).filter(|&i| pinned[i]).map(min).sum();
+        let free_weight: f32 = (0..count).filter(|&i| !pinned[i]).map(|i| weights[i]).sum();
+        // Until a child is pinned, ratios that don't add up to 1 leave the rest to the last child
+        let divisor = if pinned.contains(&true) {
+            free_weight.max(f32::EPSILON)
+        } else {
+            free_weight.max(1.0)
+        };
+        let available = total as i32 - pinned_total as i32;
+        let last_free = (0..count).rev().find(|&i| !pinned[i]);
+
+        let mut remaining = available;
+        for i in 0..count {
+            sizes[i] = if pinned[i] {
+                min(i)
+            } else if Some(i) == last_free {
+                remaining.max(0) as u32
+            } else {
+                let size = ((available as f32 * weights[i]) / divisor).round() as u32;
+                remaining -= size as i32;
+                size
+            };
+        }
+
+        if !fits {
+            for size in sizes.iter_mut() {
+                *size = (*size).max(1);
+            }
+            return sizes;
+        }
+
+        if last_free.is_none() {
+            // Every child is at its minimum, the last one takes what's left
+            if let Some(last) = sizes.last_mut() {
+                *last += total - pinned_total;
+            }
+            return sizes;
+        }
+
+        let below: Vec<usize> = (0..count)
+            .filter(|&i| !pinned[i] && sizes[i] < min(i))
+            .collect();
+        if below.is_empty() {
+            return sizes;
+        }
+        for i in below {
+            pinned[i] = true;
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum InsertOrder {
     Before,
@@ -43,6 +113,8 @@ pub struct Container {
     children: RefCell<Vec<ContainerChildRef>>,
     ratios: RefCell<Vec<f32>>,
     self_ref: RefCell<ParentContainerRef>,
+    // Children need to be laid out again, see `recalculate_dirty`
+    dirty: Cell<bool>,
 }
 
 impl PartialEq for Container {
@@ -70,6 +142,7 @@ impl Container {
             children: RefCell::new(Vec::new()),
             ratios: RefCell::new(Vec::new()),
             self_ref: RefCell::new(Weak::new()),
+            dirty: Cell::new(true),
         });
 
         self_rc.self_ref.replace(Rc::downgrade(&self_rc));
@@ -85,7 +158,24 @@ impl Container {
     }
 
     pub fn set_bounds(&self, bounds: Bounds) {
-        self.bounds.replace(bounds);
+        // Only this container's children move, so ancestors don't need to know
+        if self.bounds.replace(bounds.clone()) != bounds {
+            self.dirty.set(true);
+        }
+    }
+
+    pub fn dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// Marks this container as needing its children laid out again. Ancestors are marked too,
+    /// since a change in children can change how much room this container needs.
+    pub fn mark_dirty(&self) {
+        self.dirty.set(true);
+        let parent = self.parent.borrow().as_ref().and_then(|p| p.upgrade());
+        if let Some(parent) = parent {
+            parent.mark_dirty();
+        }
     }
 
     pub fn direction(&self) -> Direction {
@@ -97,7 +187,7 @@ impl Container {
     }
 
     pub fn set_ratios(&self, ratios: Vec<f32>) {
-        self.ratios.replace(ratios);
+        *self.ratios_mut() = ratios;
         self.normalize_ratios();
     }
 
@@ -117,9 +207,15 @@ impl Container {
     }
 
     fn children_mut(&self) -> RefMut<'_, Vec<ContainerChildRef>> {
+        self.mark_dirty();
         self.children.borrow_mut()
     }
 
+    fn ratios_mut(&self) -> RefMut<'_, Vec<f32>> {
+        self.mark_dirty();
+        self.ratios.borrow_mut()
+    }
+
     pub fn self_ref(&self) -> ParentContainerRef {
         self.self_ref.borrow().clone()
     }
@@ -163,7 +259,7 @@ impl Container {
             }
 
             self.children_mut().remove(current_index);
-            self.ratios.borrow_mut().remove(current_index);
+            self.ratios_mut().remove(current_index);
         }
 
         // Insert the window into this container
@@ -195,18 +291,18 @@ impl Container {
 
         if children_count == 1 {
             // First child gets ratio of 1.0
-            self.ratios.replace(vec![1.0]);
+            *self.ratios_mut() = vec![1.0];
             return;
         }
 
         let new_ratio = 1.0 / (children_count - 1) as f32;
-        self.ratios.borrow_mut().insert(index, new_ratio);
+        self.ratios_mut().insert(index, new_ratio);
         self.normalize_ratios();
     }
 
     /// Normalize ratios so they sum to the target total (usually 1.0)
     fn normalize_ratios(&self) {
-        let mut ratios = self.ratios.borrow_mut();
+        let mut ratios = self.ratios_mut();
         let current_total: f32 = ratios.iter().sum();
 
         if current_total > 0.0 && (current_total - 1.0).abs() > f32::EPSILON {
@@ -263,7 +359,7 @@ impl Container {
         }
 
         self.children_mut().clear();
-        self.ratios.borrow_mut().clear();
+        self.ratios_mut().clear();
         let split_container = self.add_container(split_container);
 
         match order {
@@ -303,6 +399,22 @@ impl Container {
         b.set_parent(a_parent.self_ref());
     }
 
+    /// Moves a window out of its container to sit right after that container in the
+    /// grandparent. The old container collapses if only one child is left. Returns false when
+    /// the window is already a child of the root.
+    pub fn promote_window(window: &ContainerWindowRef) -> bool {
+        let parent = window.parent();
+        let Some(grandparent) = parent.parent() else {
+            return false;
+        };
+
+        let Some(index) = grandparent.index_of_child(&ContainerChildRef::Container(parent)) else {
+            return false;
+        };
+        grandparent.insert_window(index + 1, window.clone());
+        true
+    }
+
     /// Collapses this container if it has only one child and is not the root.
     pub fn collapse(&self) {
         if self.children().len() != 1 || self.parent().is_none() {
@@ -312,7 +424,7 @@ impl Container {
         let parent = self.parent().unwrap();
         let self_ref = self.self_ref().upgrade().unwrap();
         let child = self.children_mut().pop().unwrap();
-        self.ratios.borrow_mut().pop();
+        self.ratios_mut().pop();
         let self_index = parent
             .index_of_child(&ContainerChildRef::Container(self_ref.clone()))
             .unwrap();
@@ -368,7 +480,7 @@ impl Container {
         if let Some(index) = self.index_of_child(child) {
             self.children_mut().remove(index);
             if index < self.ratios.borrow().len() {
-                self.ratios.borrow_mut().remove(index);
+                self.ratios_mut().remove(index);
                 self.normalize_ratios();
             }
         }
@@ -384,19 +496,153 @@ impl Container {
         }
 
         let ratio = 1.0 / children.len() as f32;
-        self.ratios.replace(vec![ratio; children.len()]);
+        *self.ratios_mut() = vec![ratio; children.len()];
     }
 
+    /// Sets the ratios of this container and all containers below it from the weight of each
+    /// window. Nested containers weigh as much as the average of their children. Returns the
+    /// weight of this container.
+    pub fn balance_weighted(&self, weight_of: &dyn Fn(&ContainerWindowRef) -> f32) -> f32 {
+        let weights: Vec<f32> = self
+            .children()
+            .iter()
+            .map(|child| match child {
+                ContainerChildRef::Window(w) => weight_of(w),
+                ContainerChildRef::Container(c) => c.balance_weighted(weight_of),
+            })
+            .map(|weight| weight.max(MIN_WEIGHT))
+            .collect();
+
+        if weights.is_empty() {
+            return 1.0;
+        }
+
+        let average = weights.iter().sum::<f32>() / weights.len() as f32;
+        self.set_ratios(weights);
+        average
+    }
+
+    /// Sets the ratios of this container and all containers below it so every child takes
+    /// 1/φ of the space left by the children before it, and the last child takes the rest.
+    /// Nested containers run the other way, so a tree that keeps splitting its last window
+    /// becomes a spiral.
+    pub fn apply_golden_ratio(&self) {
+        let children = self.children();
+        if children.is_empty() {
+            return;
+        }
+
+        let mut remaining = 1.0;
+        let mut ratios = Vec::with_capacity(children.len());
+        for _ in 1..children.len() {
+            let ratio = remaining / GOLDEN_RATIO;
+            ratios.push(ratio);
+            remaining -= ratio;
+        }
+        ratios.push(remaining);
+
+        for child in children.iter() {
+            if let ContainerChildRef::Container(c) = child {
+                c.apply_golden_ratio();
+            }
+        }
+        self.set_ratios(ratios);
+    }
+
+    /// The smallest bounds this container can be without shrinking any window below its
+    /// minimum size
+    pub fn min_size(&self) -> Size {
+        let children = self.children();
+        let mins = children.iter().map(|child| child.min_size());
+        match self.direction {
+            Direction::Horizontal => mins.fold(Size::default(), |acc, min| Size {
+                width: acc.width + min.width,
+                height: acc.height.max(min.height),
+            }),
+            Direction::Vertical => mins.fold(Size::default(), |acc, min| Size {
+                width: acc.width.max(min.width),
+                height: acc.height + min.height,
+            }),
+        }
+    }
+
+    /// The tightest limits of the windows inside, at any depth, so a column holding a window
+    /// with limits keeps to them as a whole
+    pub fn ratio_limits(&self) -> RatioLimits {
+        self.children()
+            .iter()
+            .map(|child| child.ratio_limits())
+            .fold(RatioLimits::default(), |acc, limits| RatioLimits {
+                min_ratio: match (acc.min_ratio, limits.min_ratio) {
+                    (Some(a), Some(b)) => Some(a.max(b)),
+                    (a, b) => a.or(b),
+                },
+                max_ratio: match (acc.max_ratio, limits.max_ratio) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                },
+            })
+    }
+
+    fn size_along(&self, size: &Size) -> u32 {
+        match self.direction {
+            Direction::Horizontal => size.width,
+            Direction::Vertical => size.height,
+        }
+    }
+
+    /// Moves the ratios to match the sizes children actually get once minimum sizes are
+    /// applied, so a drag can't push a window below its minimum
+    fn apply_min_sizes_to_ratios(&self) {
+        let mins: Vec<u32> = self
+            .children()
+            .iter()
+            .map(|child| self.size_along(&child.min_size()))
+            .collect();
+        let container_size = self.size_along(&self.bounds().size);
+        if container_size == 0 || mins.iter().all(|m| *m == 0) {
+            return;
+        }
+
+        let sizes = distribute_sizes(container_size, &self.ratios.borrow(), &mins);
+        *self.ratios_mut() = sizes
+            .iter()
+            .map(|size| *size as f32 / container_size as f32)
+            .collect();
+    }
+
+    /// Lays out every container and window below this one
     pub fn recalculate(&self) {
-        // Early exit if no children
+        self.layout_children(true);
+    }
+
+    /// Lays out only the containers that changed since they were last laid out, and anything
+    /// below them that moved as a result. Gives the same bounds as `recalculate`, as long as
+    /// window minimum sizes haven't changed in the meantime.
+    pub fn recalculate_dirty(&self) {
+        self.layout_children(false);
+    }
+
+    fn layout_children(&self, force: bool) {
         let children = self.children();
+        if !force && !self.dirty.get() {
+            // Nothing here changed, but something further down might have
+            for child in children.iter() {
+                if let ContainerChildRef::Container(c) = child {
+                    c.layout_children(false);
+                }
+            }
+            return;
+        }
+        self.dirty.set(false);
+
+        // Early exit if no children
         if children.is_empty() {
             return;
         }
 
         // Get all data we need upfront to minimize borrows
         let ratios = self.ratios.borrow();
-        let total_weight: f32 = ratios.iter().sum::<f32>().max(1.0);
         let container_size: u32 = match self.direction {
             Direction::Horizontal => self.bounds().size.width,
             Direction::Vertical => self.bounds().size.height,
@@ -407,20 +653,11 @@ impl Container {
         };
 
         // Pre-calculate all sizes to avoid floating point errors accumulating
-        let mut sizes: Vec<u32> = Vec::with_capacity(children.len());
-        let mut remaining_size = container_size as i32;
-
-        for (idx, weight) in ratios.iter().enumerate() {
-            let is_last = idx == children.len() - 1;
-            let size = if is_last {
-                remaining_size.max(0) as u32
-            } else {
-                let size = ((container_size as f32 * *weight) / total_weight).round() as u32;
-                remaining_size -= size as i32;
-                size
-            };
-            sizes.push(size);
-        }
+        let mins: Vec<u32> = children
+            .iter()
+            .map(|child| self.size_along(&child.min_size()))
+            .collect();
+        let sizes = distribute_sizes(container_size, &ratios, &mins);
 
         // Apply all sizes in a single pass
         let mut current_position = start_position;
@@ -442,9 +679,9 @@ impl Container {
             child.set_bounds(new_bounds);
             current_position += size as i32;
 
-            // Only recurse if it's a container
+            // Only recurse if it's a container. Setting its bounds marks it dirty if it moved.
             if let ContainerChildRef::Container(c) = child {
-                c.recalculate();
+                c.layout_children(force);
             }
         }
     }
@@ -462,7 +699,7 @@ impl Container {
             return;
         }
 
-        let mut ratios = self.ratios.borrow_mut();
+        let mut ratios = self.ratios_mut();
         const MIN_WEIGHT: f32 = 0.05_f32;
 
         let top_offset = new_bounds.position.y - old_bounds.position.y;
@@ -546,6 +783,8 @@ impl Container {
 
         // Normalize
         self.normalize_ratios();
+        self.apply_ratio_limits();
+        self.apply_min_sizes_to_ratios();
 
         // Update our bounds if needed
         if let Some(parent) = self.parent() {
@@ -626,6 +865,25 @@ impl Container {
     /// Resize the split between children at the given index based on a new position
     /// The index represents the first child of the right group (children at index and after)
     pub fn resize_between(&self, split_index: usize, new_position: i32) -> bool {
+        self.resize_between_with_snap(split_index, new_position, None)
+    }
+
+    /// Like `resize_between`, but rounds the split to the nearest multiple of `increment`
+    pub fn resize_between_snapped(
+        &self,
+        split_index: usize,
+        new_position: i32,
+        increment: f32,
+    ) -> bool {
+        self.resize_between_with_snap(split_index, new_position, Some(increment))
+    }
+
+    fn resize_between_with_snap(
+        &self,
+        split_index: usize,
+        new_position: i32,
+        snap: Option<f32>,
+    ) -> bool {
         let children = self.children();
         if children.len() <= 1 || split_index >= children.len() || split_index == 0 {
             return false;
@@ -648,22 +906,29 @@ impl Container {
         };
 
         // Calculate the ratio for the left side based on the new split position
-        let left_ratio = (new_position - container_start) as f32 / container_size;
+        let mut left_ratio = (new_position - container_start) as f32 / container_size;
+        if let Some(increment) = snap.filter(|i| *i > 0.0 && *i < 1.0) {
+            left_ratio = (left_ratio / increment).round() * increment;
+        }
         let right_ratio = 1.0 - left_ratio;
 
         // Ensure minimum ratios
-        let min_ratio = 0.1;
-        let (left_ratio, right_ratio) = if left_ratio < min_ratio {
-            (min_ratio, 1.0 - min_ratio)
-        } else if right_ratio < min_ratio {
-            (1.0 - min_ratio, min_ratio)
-        } else {
-            (left_ratio, right_ratio)
+        let limits: Vec<RatioLimits> = children.iter().map(|c| c.ratio_limits()).collect();
+        let side_min = |limits: &[RatioLimits]| {
+            if limits.iter().all(|l| l.min_ratio.is_some()) {
+                limits.iter().filter_map(|l| l.min_ratio).sum()
+            } else {
+                MIN_SPLIT_RATIO
+            }
         };
+        let left_min = side_min(&limits[..split_index]);
+        let right_min = side_min(&limits[split_index..]);
+        let left_ratio = left_ratio.clamp(left_min, (1.0 - right_min).max(left_min));
+        let right_ratio = 1.0 - left_ratio;
 
         // Get current ratios and calculate scaling factors
         {
-            let mut ratios = self.ratios.borrow_mut();
+            let mut ratios = self.ratios_mut();
             let total_weight: f32 = ratios.iter().sum();
 
             // Calculate current left and right weights
@@ -692,9 +957,50 @@ impl Container {
         }
 
         self.normalize_ratios();
+        self.apply_ratio_limits();
 
         true
     }
+
+    /// Moves ratios outside their child's limits back inside. The difference goes to the
+    /// other children in proportion to their ratios.
+    fn apply_ratio_limits(&self) {
+        let limits: Vec<RatioLimits> = self.children().iter().map(|c| c.ratio_limits()).collect();
+        if limits.iter().all(RatioLimits::is_empty) || limits.len() != self.ratios.borrow().len() {
+            return;
+        }
+
+        let mut ratios = self.ratios_mut();
+        let mut pinned = vec![false; ratios.len()];
+        loop {
+            let mut changed = false;
+            for (i, limit) in limits.iter().enumerate() {
+                let clamped = limit.clamp(ratios[i]);
+                if !pinned[i] && clamped != ratios[i] {
+                    ratios[i] = clamped;
+                    pinned[i] = true;
+                    changed = true;
+                }
+            }
+
+            let free_total: f32 = (0..ratios.len())
+                .filter(|&i| !pinned[i])
+                .map(|i| ratios[i])
+                .sum();
+            if !changed || free_total <= 0.0 {
+                return;
+            }
+
+            let pinned_total: f32 = (0..ratios.len())
+                .filter(|&i| pinned[i])
+                .map(|i| ratios[i])
+                .sum();
+            let scale = (1.0 - pinned_total).max(0.0) / free_total;
+            for i in (0..ratios.len()).filter(|&i| !pinned[i]) {
+                ratios[i] *= scale;
+            }
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -704,6 +1010,8 @@ mod tests {
     use crate::layouts::container_tree::tests::{
         assert_is_container, assert_is_window, assert_window, new_bounds, new_container, new_window,
     };
+    use crate::platform::mock::MockPlatformWindow;
+    use crate::window::Window;
 
     pub(super) fn new_container_with_bounds(bounds: Bounds) -> ContainerRef {
         Container::new(bounds.clone(), Direction::Horizontal, None)
@@ -1131,6 +1439,94 @@ mod tests {
         }
     }
 
+    fn new_app_window(app_name: &str) -> ContainerWindowRef {
+        let bounds = new_bounds();
+        let mut platform_window =
+            MockPlatformWindow::new(bounds.position, bounds.size, "Mock Window".to_owned());
+        platform_window.app_name = app_name.to_owned();
+        ContainerWindow::new(Rc::new(Window::new(platform_window)))
+    }
+
+    fn app_weight(window: &ContainerWindowRef) -> f32 {
+        match window.window().app_name().as_str() {
+            "Terminal" => 3.0,
+            "Slack" => 1.0,
+            _ => 2.0,
+        }
+    }
+
+    #[test]
+    fn test_balance_weighted_two_apps() {
+        let root = new_container();
+        root.add_window(new_app_window("Terminal"));
+        root.add_window(new_app_window("Slack"));
+
+        root.balance_weighted(&app_weight);
+        root.recalculate();
+
+        assert!((root.ratios()[0] - 0.75).abs() < f32::EPSILON);
+        assert!((root.ratios()[1] - 0.25).abs() < f32::EPSILON);
+        assert_eq!(root.children()[0].bounds().size.width, 375);
+        assert_eq!(root.children()[1].bounds().size.width, 125);
+    }
+
+    #[test]
+    fn test_balance_weighted_nested_container_uses_average() {
+        let root = new_container();
+        let slack = root.add_window(new_app_window("Slack"));
+        root.add_window(new_app_window("Terminal"));
+        let nested = root.split_window(&slack, new_app_window("Terminal"), InsertOrder::After);
+
+        root.balance_weighted(&app_weight);
+
+        // The nested container holds Slack (1.0) and Terminal (3.0), so it weighs 2.0
+        assert!((nested.ratios()[0] - 0.25).abs() < f32::EPSILON);
+        assert!((nested.ratios()[1] - 0.75).abs() < f32::EPSILON);
+        assert!((root.ratios()[0] - 0.4).abs() < 1e-6);
+        assert!((root.ratios()[1] - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_golden_ratio_spiral() {
+        // A spiral of four windows: each split holds a window and the rest of the spiral
+        let root = new_container();
+        root.add_window(new_window());
+        let second = root.add_window(new_window());
+        let inner = root.split_window(&second, new_window(), InsertOrder::After);
+        let third = assert_is_window(&inner.children()[1]);
+        let innermost = inner.split_window(&third, new_window(), InsertOrder::After);
+
+        root.apply_golden_ratio();
+        root.recalculate();
+
+        let phi = (1.0 + 5.0_f32.sqrt()) / 2.0;
+        for container in [&root, &inner, &innermost] {
+            let ratios = container.ratios();
+            assert_eq!(ratios.len(), 2);
+            assert!((ratios[0] - 1.0 / phi).abs() < 1e-4);
+            assert!((ratios[1] - 1.0 / (phi * phi)).abs() < 1e-4);
+        }
+        assert_ne!(inner.direction(), root.direction());
+        assert_ne!(innermost.direction(), inner.direction());
+        // 500 / φ
+        assert!(root.children()[0].bounds().size.width.abs_diff(309) <= 1);
+    }
+
+    #[test]
+    fn test_golden_ratio_three_children() {
+        let root = new_container();
+        for _ in 0..3 {
+            root.add_window(new_window());
+        }
+
+        root.apply_golden_ratio();
+
+        let ratios = root.ratios();
+        assert!((ratios[0] - 0.618).abs() < 1e-3);
+        assert!((ratios[1] - 0.236).abs() < 1e-3);
+        assert!((ratios[2] - 0.146).abs() < 1e-3);
+    }
+
     #[test]
     fn test_set_ratios_normalization() {
         let root = new_container();
@@ -1308,6 +1704,74 @@ mod tests {
         assert!((ratios[1] - 0.4).abs() < 0.01);
     }
 
+    #[test]
+    fn test_resize_between_clamps_to_ratio_limits() {
+        let root = new_container_with_bounds(Bounds::new(0, 0, 1000, 500));
+        let sidebar = root.add_window(new_window());
+        root.add_window(new_window());
+        sidebar.window().set_ratio_limits(RatioLimits {
+            min_ratio: Some(0.05),
+            max_ratio: Some(0.3),
+        });
+
+        // Dragging the divider past the sidebar's max stops it there
+        assert!(root.resize_between(1, 600));
+        assert!((root.ratios()[0] - 0.3).abs() < 0.01);
+        assert!((root.ratios()[1] - 0.7).abs() < 0.01);
+
+        // Its own min replaces the global minimum
+        assert!(root.resize_between(1, 20));
+        assert!((root.ratios()[0] - 0.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_resize_between_clamps_a_column_to_its_windows_limits() {
+        let root = new_container_with_bounds(Bounds::new(0, 0, 1000, 500));
+        let column = root.add_container(Container::new(
+            new_bounds(),
+            Direction::Vertical,
+            Some(root.self_ref()),
+        ));
+        let sidebar = column.add_window(new_window());
+        column.add_window(new_window());
+        root.add_window(new_window());
+        sidebar.window().set_ratio_limits(RatioLimits {
+            min_ratio: None,
+            max_ratio: Some(0.3),
+        });
+
+        assert_eq!(column.ratio_limits().max_ratio, Some(0.3));
+        assert!(root.resize_between(1, 600));
+        assert!((root.ratios()[0] - 0.3).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_resize_between_clamps_to_limits_of_nested_windows() {
+        let root = new_container_with_bounds(Bounds::new(0, 0, 1000, 500));
+        let column = root.add_container(Container::new(
+            new_bounds(),
+            Direction::Vertical,
+            Some(root.self_ref()),
+        ));
+        let row = column.add_container(Container::new(
+            new_bounds(),
+            Direction::Horizontal,
+            Some(column.self_ref()),
+        ));
+        let sidebar = row.add_window(new_window());
+        row.add_window(new_window());
+        column.add_window(new_window());
+        root.add_window(new_window());
+        sidebar.window().set_ratio_limits(RatioLimits {
+            min_ratio: None,
+            max_ratio: Some(0.3),
+        });
+
+        assert_eq!(column.ratio_limits().max_ratio, Some(0.3));
+        assert!(root.resize_between(1, 600));
+        assert!((root.ratios()[0] - 0.3).abs() < 0.01);
+    }
+
     #[test]
     fn test_resize_between_invalid_index() {
         let root = new_container();
@@ -1347,6 +1811,47 @@ mod tests {
         assert!((ratios[1] - 0.9).abs() < 0.01);
     }
 
+    #[test]
+    fn test_resize_between_snapped_rounds_to_increment() {
+        for (position, expected) in [
+            (230, 0.25),
+            (270, 0.25),
+            (480, 0.5),
+            (540, 0.5),
+            (760, 0.75),
+        ] {
+            let root = new_container_with_bounds(Bounds::new(0, 0, 1000, 500));
+            root.add_window(new_window());
+            root.add_window(new_window());
+
+            assert!(root.resize_between_snapped(1, position, 0.25));
+
+            let ratios = root.ratios();
+            assert!(
+                (ratios[0] - expected).abs() < 1e-6,
+                "position {position} gave {}",
+                ratios[0]
+            );
+            assert!((ratios[1] - (1.0 - expected)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_resize_between_snapped_splits_right_group() {
+        let root = new_container_with_bounds(Bounds::new(0, 0, 1000, 500));
+        root.add_window(new_window());
+        root.add_window(new_window());
+        root.add_window(new_window());
+
+        // Splitting before the last child snaps the combined left side
+        assert!(root.resize_between_snapped(2, 730, 0.25));
+
+        let ratios = root.ratios();
+        let left_ratio: f32 = ratios[..2].iter().sum();
+        assert!((left_ratio - 0.75).abs() < 1e-6);
+        assert!((ratios[2] - 0.25).abs() < 1e-6);
+    }
+
     // === Calculate Bounds Tests ===
 
     #[test]
@@ -1684,4 +2189,88 @@ mod tests {
         assert_eq!(window_b1.parent(), root_b);
         assert_eq!(window_b2.parent(), root_a);
     }
+
+    fn new_window_with_min_width(min_width: u32) -> ContainerWindowRef {
+        let bounds = new_bounds();
+        let mut platform_window =
+            MockPlatformWindow::new(bounds.position, bounds.size, "Mock Window".to_owned());
+        platform_window.min_size = Some(Size {
+            width: min_width,
+            height: 0,
+        });
+        ContainerWindow::new(Rc::new(Window::new(platform_window)))
+    }
+
+    #[test]
+    fn test_recalculate_respects_min_size() {
+        let root = new_container_with_bounds(Bounds::new(0, 0, 1000, 500));
+        let window_a = root.add_window(new_window_with_min_width(500));
+        let window_b = root.add_window(new_window());
+        let window_c = root.add_window(new_window());
+        root.equalize_ratios();
+        root.recalculate();
+
+        let min = window_a.min_size().width;
+        assert!(window_a.bounds().size.width >= min);
+
+        // The rest is split evenly between the windows without a minimum
+        let rest = 1000 - window_a.bounds().size.width;
+        assert_eq!(
+            window_b.bounds().size.width + window_c.bounds().size.width,
+            rest
+        );
+        assert!(
+            window_b
+                .bounds()
+                .size
+                .width
+                .abs_diff(window_c.bounds().size.width)
+                <= 1
+        );
+        assert_eq!(
+            window_b.bounds().position.x,
+            window_a.bounds().size.width as i32
+        );
+    }
+
+    #[test]
+    fn test_resize_does_not_shrink_below_min_size() {
+        let root = new_container_with_bounds(Bounds::new(0, 0, 1000, 500));
+        let window_a = root.add_window(new_window_with_min_width(400));
+        let window_b = root.add_window(new_window());
+        root.recalculate();
+
+        // Drag the shared edge far into window A
+        root.resize_edge(
+            &ContainerChildRef::Window(window_b.clone()),
+            100,
+            Side::Left,
+            false,
+        );
+        root.recalculate();
+
+        assert!(window_a.bounds().size.width >= window_a.min_size().width);
+        assert_eq!(
+            window_a.bounds().size.width + window_b.bounds().size.width,
+            1000
+        );
+    }
+
+    #[test]
+    fn test_min_sizes_that_do_not_fit_shrink_proportionally() {
+        let root = new_container_with_bounds(Bounds::new(0, 0, 300, 500));
+        let window_a = root.add_window(new_window_with_min_width(400));
+        let window_b = root.add_window(new_window_with_min_width(400));
+        root.equalize_ratios();
+        root.recalculate();
+
+        assert_eq!(window_a.bounds().size.width, 150);
+        assert_eq!(window_b.bounds().size.width, 150);
+    }
+
+    #[test]
+    fn test_distribute_sizes_never_below_one_pixel() {
+        let sizes = distribute_sizes(2, &[0.98, 0.01, 0.01], &[10, 10, 10]);
+        assert!(sizes.iter().all(|size| *size >= 1));
+    }
 }
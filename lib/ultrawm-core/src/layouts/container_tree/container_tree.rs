@@ -1,5 +1,4 @@
 use super::ContainerTreePlacementTargetType;
-use crate::config::Config;
 use crate::layouts::container_tree::container::{
     Container, ContainerChildRef, ContainerRef, ContainerWindow, ContainerWindowRef,
 };
@@ -14,7 +13,10 @@ use crate::layouts::container_tree::{
 use crate::layouts::serialization::{
     SerializedContainer, SerializedContainerChild, SerializedWindow,
 };
-use crate::layouts::{ContainerId, LayoutError, LayoutResult, PlacementTarget, Side, WindowLayout};
+use crate::layouts::{
+    tiling_area, tiling_area_with_gaps, ContainerId, Gaps, LayoutError, LayoutResult, LayoutType,
+    PlacementTarget, Side, WindowLayout,
+};
 use crate::platform::{Bounds, PlatformWindowImpl, Position, WindowId};
 use crate::resize_handle::{HandleOrientation, ResizeHandle, ResizeMode};
 use crate::tile_result::InsertResult;
@@ -28,6 +30,8 @@ pub struct ContainerTree {
     bounds: Bounds,
     root: ContainerRef,
     windows: HashMap<WindowId, ContainerWindowRef>,
+    /// Gaps used instead of the global ones
+    gaps: Option<Gaps>,
 }
 
 impl ContainerTree {
@@ -35,6 +39,12 @@ impl ContainerTree {
         self.bounds.clone()
     }
 
+    /// The area the root container fills
+    fn root_bounds(&self) -> Bounds {
+        let gaps = self.gaps.unwrap_or_else(Gaps::from_config);
+        tiling_area_with_gaps(&self.bounds, &gaps)
+    }
+
     pub fn root(&self) -> ContainerRef {
         self.root.clone()
     }
@@ -47,6 +57,8 @@ impl ContainerTree {
         serde_yaml::to_value(serialized).unwrap()
     }
 
+    /// Rebuilds a saved tree from the windows that are still open. Slots of windows that are
+    /// gone collapse.
     fn deserialize(
         bounds: Bounds,
         available_windows: &Vec<WindowRef>,
@@ -67,22 +79,22 @@ impl ContainerTree {
         };
 
         // Create a map of available windows by ID
-        let available_windows: HashMap<WindowId, WindowRef> = available_windows
+        let available_by_id: HashMap<WindowId, WindowRef> = available_windows
             .iter()
             .map(|w| (w.id(), w.clone()))
             .collect();
 
         debug!(
             "Deserializing layout with {} available windows: {:?}",
-            available_windows.len(),
-            available_windows.keys().collect::<Vec<_>>()
+            available_by_id.len(),
+            available_by_id.keys().collect::<Vec<_>>()
         );
 
         let mut windows_map = HashMap::new();
         let root = match deserialize_container(
             &serialized.root,
-            Self::get_root_bounds(&bounds),
-            &available_windows,
+            tiling_area(&bounds),
+            &available_by_id,
             &mut windows_map,
             None,
         ) {
@@ -109,6 +121,7 @@ impl ContainerTree {
             bounds,
             root,
             windows: windows_map,
+            gaps: None,
         })
     }
 
@@ -158,7 +171,7 @@ impl ContainerTree {
                         child_prefix,
                         connector,
                         window.id(),
-                        window.platform_window().title(),
+                        window.title(),
                         bounds.size.width,
                         bounds.size.height,
                         bounds.position.x,
@@ -361,11 +374,156 @@ impl ContainerTree {
         preview_bounds
     }
 
+    /// Walks up from a child to the first container that splits along `side` and has a
+    /// sibling on that side of the child's branch. Returns that container and the branch.
+    fn find_resizable_edge(
+        child: &ContainerChildRef,
+        side: Side,
+    ) -> Option<(ContainerRef, ContainerChildRef)> {
+        let mut child = child.clone();
+        while let Some(parent) = child.parent() {
+            if parent.direction() == side.direction() {
+                let index = parent.index_of_child(&child)?;
+                let has_neighbor = if side.is_before() {
+                    index > 0
+                } else {
+                    index + 1 < parent.children().len()
+                };
+                if has_neighbor {
+                    return Some((parent, child));
+                }
+            }
+            child = ContainerChildRef::Container(parent);
+        }
+        None
+    }
+
     /// Finds a child (container or window) by its tree node ID.
     pub fn find_child(&self, id: u64) -> Option<ContainerChildRef> {
         self.find_child_recursive(&self.root, id)
     }
 
+    /// Finds the container a resize handle splits, along with the children on either side of
+    /// it and the index of the one after it.
+    fn find_container_for_handle(
+        &self,
+        handle: &ResizeHandle,
+    ) -> Option<(ContainerRef, ContainerChildRef, ContainerChildRef, usize)> {
+        let before_child = self.find_child(handle.before_id)?;
+        let parent = before_child.parent()?;
+
+        let (after_index, after_child) = parent
+            .children()
+            .iter()
+            .enumerate()
+            .find(|(_, child)| child.id() == handle.after_id)
+            .map(|(index, child)| (index, child.clone()))?;
+
+        Some((parent, before_child, after_child, after_index))
+    }
+
+    /// Inserts a window into a container's children on the given side, or at the end when no
+    /// side is given. With a ratio the window gets that much of the container, and the other
+    /// children share the rest in proportion.
+    fn insert_into_container(
+        container: &ContainerRef,
+        container_window: &ContainerWindowRef,
+        side: Option<Side>,
+        ratio: Option<f32>,
+    ) {
+        if container.children().is_empty() {
+            container.add_window(container_window.clone());
+            return;
+        }
+
+        let index = match side {
+            Some(Side::Left | Side::Top) => 0,
+            _ => container.children().len(),
+        };
+        container.insert_window(index, container_window.clone());
+
+        if let Some(ratio) = ratio {
+            let placed = ContainerChildRef::Window(container_window.clone());
+            let Some(index) = container.index_of_child(&placed) else {
+                return;
+            };
+
+            let others: f32 = container
+                .ratios()
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != index)
+                .map(|(_, r)| r)
+                .sum();
+            let ratios = container
+                .ratios()
+                .iter()
+                .enumerate()
+                .map(|(i, r)| {
+                    if i == index {
+                        ratio
+                    } else if others > 0.0 {
+                        r / others * (1.0 - ratio)
+                    } else {
+                        *r
+                    }
+                })
+                .collect();
+            container.set_ratios(ratios);
+        }
+    }
+
+    /// Inserts a window next to `target` in the target's own container. The placed window takes
+    /// `ratio` of the target's space (half by default) and the other children keep theirs.
+    fn insert_beside(
+        parent: &ContainerRef,
+        target: &ContainerWindowRef,
+        container_window: &ContainerWindowRef,
+        side: Side,
+        ratio: Option<f32>,
+    ) {
+        let ratio = ratio.unwrap_or(0.5);
+        let target_child = ContainerChildRef::Window(target.clone());
+        let placed_child = ContainerChildRef::Window(container_window.clone());
+        let old_shares: Vec<(ContainerChildRef, f32)> = parent
+            .children()
+            .iter()
+            .cloned()
+            .zip(parent.ratios().iter().copied())
+            .collect();
+        let share_of = |child: &ContainerChildRef| {
+            old_shares
+                .iter()
+                .find(|(c, _)| c == child)
+                .map(|(_, r)| *r)
+                .unwrap_or(0.0)
+        };
+
+        let target_index = parent.index_of_child(&target_child).unwrap_or(0);
+        let index = if side.is_before() {
+            target_index
+        } else {
+            target_index + 1
+        };
+        parent.insert_window(index, container_window.clone());
+
+        let target_share = share_of(&target_child);
+        let ratios = parent
+            .children()
+            .iter()
+            .map(|child| {
+                if *child == placed_child {
+                    target_share * ratio
+                } else if *child == target_child {
+                    target_share * (1.0 - ratio)
+                } else {
+                    share_of(child)
+                }
+            })
+            .collect();
+        parent.set_ratios(ratios);
+    }
+
     fn find_child_recursive(&self, container: &ContainerRef, id: u64) -> Option<ContainerChildRef> {
         for child in container.children().iter() {
             if child.id() == id {
@@ -448,21 +606,13 @@ impl ContainerTree {
             }
         }
     }
-
-    fn get_root_bounds(bounds: &Bounds) -> Bounds {
-        let config = Config::current();
-
-        // Apply partition gap and invert the window gap so that the outer gap is 0
-        Bounds::new(
-            bounds.position.x + config.partition_gap as i32 - config.window_gap as i32 / 2,
-            bounds.position.y + config.partition_gap as i32 - config.window_gap as i32 / 2,
-            bounds.size.width - config.partition_gap * 2 + config.window_gap,
-            bounds.size.height - config.partition_gap * 2 + config.window_gap,
-        )
-    }
 }
 
 impl WindowLayout for ContainerTree {
+    fn layout_type(&self) -> LayoutType {
+        LayoutType::ContainerTree
+    }
+
     fn layout_description(&self) -> String {
         "ContainerTree is a layout that uses a tree of containers to arrange windows. It supports splitting and merging containers, and adding windows to containers. Each container can hold any number of windows or containers as children.".to_string()
     }
@@ -471,8 +621,12 @@ impl WindowLayout for ContainerTree {
         r#"Placement targets:
   - {type: window, id: <window_id>} - Place relative to a window
   - {type: container, id: <container_id>} - Place relative to a container
+  - {type: root} - Add to the root container, filling the layout when it's empty
+  - {relative_to: <window_id>, side: ..., ratio: ...} - Shorthand for {type: window, id: ...}
+  - {fill_root: true} - Shorthand for {type: root}
 
-When placing relative to a window, the new window will be split from the target window.
+When placing relative to a window, the new window will be placed beside the target window,
+splitting it if the side runs across the target's container.
 When placing relative to a container, the new window will be added to that container.
 
 Placement options:
@@ -489,14 +643,14 @@ Placement options:
                 direction: Direction::Horizontal,
                 ratios: vec![0.6, 0.4],
                 children: vec![
-                    SerializedContainerChild::Window(SerializedWindow { id: 12345 }),
+                    SerializedContainerChild::Window(SerializedWindow::new(12345)),
                     SerializedContainerChild::Container(SerializedContainer {
                         id: 1,
                         direction: Direction::Vertical,
                         ratios: vec![0.5, 0.5],
                         children: vec![
-                            SerializedContainerChild::Window(SerializedWindow { id: 67890 }),
-                            SerializedContainerChild::Window(SerializedWindow { id: 11111 }),
+                            SerializedContainerChild::Window(SerializedWindow::new(67890)),
+                            SerializedContainerChild::Window(SerializedWindow::new(11111)),
                         ],
                     }),
                 ],
@@ -509,7 +663,7 @@ Placement options:
     where
         Self: Sized,
     {
-        let root_bounds = Self::get_root_bounds(&bounds);
+        let root_bounds = tiling_area(&bounds);
         let root = Container::new_root(root_bounds);
         root.equalize_ratios();
         root.recalculate();
@@ -517,6 +671,7 @@ Placement options:
             bounds,
             root,
             windows: HashMap::new(),
+            gaps: None,
         }
     }
 
@@ -642,7 +797,7 @@ Placement options:
             }
         }
 
-        self.root.recalculate();
+        self.root.recalculate_dirty();
 
         Ok(InsertResult::None)
     }
@@ -652,14 +807,7 @@ Placement options:
         window: &WindowRef,
         target: PlacementTarget,
     ) -> LayoutResult<InsertResult> {
-        let placement_target: ContainerTreePlacementTarget = serde_yaml::from_value(target.clone())
-            .map_err(|e| {
-                LayoutError::PlacementTargetNotFound(format!(
-                    "Failed to deserialize placement target: {}. Target was: {}",
-                    e,
-                    serde_yaml::to_string(&target).unwrap_or_default()
-                ))
-            })?;
+        let placement_target = ContainerTreePlacementTarget::parse(&target)?;
 
         // Extract side and ratio from placement target, with defaults
         let side = placement_target.side;
@@ -673,8 +821,19 @@ Placement options:
 
         match placement_target.target {
             ContainerTreePlacementTargetType::Window { id: window_id } => {
+                if window_id == window.id() {
+                    return Err(LayoutError::PlacementTargetNotFound(format!(
+                        "Window {window_id} can't be placed relative to itself"
+                    )));
+                }
+
                 // Find the target window
-                let target_window = self.get_window(&window_id)?;
+                let target_window = self.get_window(&window_id).map_err(|_| {
+                    LayoutError::PlacementTargetNotFound(format!(
+                        "Window {window_id} is not tiled in this layout, available windows: {:?}",
+                        self.windows.keys().collect::<Vec<_>>()
+                    ))
+                })?;
                 let parent = target_window.parent();
 
                 // Determine side - use provided side, or infer from parent direction
@@ -685,22 +844,25 @@ Placement options:
                     }
                 });
 
-                // Split the window on the specified side
-                let new_container =
-                    parent.split_window(&target_window, container_window.clone(), side.into());
+                if side.direction() == parent.direction() {
+                    // The parent already runs the right way, so sit beside the target in it
+                    Self::insert_beside(&parent, &target_window, &container_window, side, ratio);
+                } else {
+                    // Split the window on the specified side
+                    let new_container =
+                        parent.split_window(&target_window, container_window.clone(), side.into());
 
-                // Apply ratio if provided
-                if let Some(ratio) = ratio {
-                    let len = new_container.ratios().len();
-                    if len == 2 {
-                        new_container.set_ratios(vec![ratio, 1.0 - ratio]);
+                    // Apply ratio if provided, the ratio is the size of the placed window
+                    if let Some(ratio) = ratio {
+                        if new_container.ratios().len() == 2 {
+                            if side.is_before() {
+                                new_container.set_ratios(vec![ratio, 1.0 - ratio]);
+                            } else {
+                                new_container.set_ratios(vec![1.0 - ratio, ratio]);
+                            }
+                        }
                     }
                 }
-
-                // Update windows map if this is a new window
-                if is_new_window {
-                    self.windows.insert(window.id(), container_window);
-                }
             }
             ContainerTreePlacementTargetType::Container { id: container_id } => {
                 // Find the target container
@@ -710,50 +872,20 @@ Placement options:
                         container_id
                     )),
                 )?;
-
-                if target_container.children().is_empty() {
-                    // Empty container: just add the window
-                    target_container.add_window(container_window.clone());
-                } else {
-                    // Container has children: insert at the appropriate edge based on side
-                    let index = if let Some(side) = side {
-                        match side {
-                            Side::Left | Side::Top => 0,
-                            Side::Right | Side::Bottom => target_container.children().len(),
-                        }
-                    } else {
-                        // Default to end
-                        target_container.children().len()
-                    };
-                    target_container.insert_window(index, container_window.clone());
-
-                    // Apply ratio if provided
-                    if let Some(ratio) = ratio {
-                        let mut ratios = target_container.ratios().clone();
-                        if index < ratios.len() {
-                            // Adjust ratios: new window gets 'ratio', redistribute the rest
-                            let remaining = 1.0 - ratio;
-                            let old_ratio = ratios[index];
-                            ratios[index] = ratio;
-                            // Redistribute remaining ratio proportionally
-                            for r in &mut ratios {
-                                if *r != ratio {
-                                    *r = (*r / (1.0 - old_ratio)) * remaining;
-                                }
-                            }
-                            target_container.set_ratios(ratios);
-                        }
-                    }
-                }
-
-                // Update windows map if this is a new window
-                if is_new_window {
-                    self.windows.insert(window.id(), container_window);
-                }
+                Self::insert_into_container(&target_container, &container_window, side, ratio);
+            }
+            ContainerTreePlacementTargetType::Root => {
+                let root = self.root.clone();
+                Self::insert_into_container(&root, &container_window, side, ratio);
             }
         }
 
-        self.root.recalculate();
+        // Update windows map if this is a new window
+        if is_new_window {
+            self.windows.insert(window.id(), container_window);
+        }
+
+        self.root.recalculate_dirty();
         Ok(InsertResult::None)
     }
 
@@ -781,6 +913,26 @@ Placement options:
         Ok(())
     }
 
+    fn swap_windows(&mut self, a: &WindowRef, b: &WindowRef) -> LayoutResult<()> {
+        let a = self.get_window(&a.id())?;
+        let b = self.get_window(&b.id())?;
+
+        Container::swap(&ContainerChildRef::Window(a), &ContainerChildRef::Window(b));
+        self.root.recalculate();
+        Ok(())
+    }
+
+    fn promote_window(&mut self, window: &WindowRef) -> LayoutResult<()> {
+        let container_window = self.get_window(&window.id())?;
+        if !Container::promote_window(&container_window) {
+            debug!("Window {} is already at the top level", window.id());
+            return Ok(());
+        }
+
+        self.root.recalculate();
+        Ok(())
+    }
+
     fn remove_window(&mut self, window: &WindowRef) -> LayoutResult<()> {
         let window_id = window.id();
         let container_window = self.get_window(&window_id)?.clone();
@@ -789,7 +941,7 @@ Placement options:
 
         // Remove from windows map
         self.windows.remove(&window_id);
-        self.root.recalculate();
+        self.root.recalculate_dirty();
 
         Ok(())
     }
@@ -801,8 +953,42 @@ Placement options:
             return Ok(()); // Not managed by this layout
         };
         let parent = container_window.parent();
+        // The window may have been moved by hand, so put it back even if no ratios change
+        parent.mark_dirty();
         parent.resize_window(&ContainerChildRef::Window(container_window), bounds);
-        self.root.recalculate();
+        self.root.recalculate_dirty();
+        Ok(())
+    }
+
+    fn resize_window_step(
+        &mut self,
+        window: &WindowRef,
+        side: Side,
+        delta_px: i32,
+    ) -> LayoutResult<()> {
+        let child = ContainerChildRef::Window(self.get_window(&window.id())?);
+
+        // Windows against the edge of the layout resize from their other edge instead
+        let Some((parent, child, side)) = Self::find_resizable_edge(&child, side)
+            .map(|(p, c)| (p, c, side))
+            .or_else(|| {
+                Self::find_resizable_edge(&child, side.opposite())
+                    .map(|(p, c)| (p, c, side.opposite()))
+            })
+        else {
+            return Ok(());
+        };
+
+        let bounds = child.bounds();
+        let edge_pos = match side {
+            Side::Left => bounds.position.x - delta_px,
+            Side::Right => bounds.position.x + bounds.size.width as i32 + delta_px,
+            Side::Top => bounds.position.y - delta_px,
+            Side::Bottom => bounds.position.y + bounds.size.height as i32 + delta_px,
+        };
+
+        parent.resize_edge(&child, edge_pos, side, false);
+        self.root.recalculate_dirty();
         Ok(())
     }
 
@@ -812,35 +998,28 @@ Placement options:
         handles
     }
 
+    fn reset_resize_handle(&mut self, handle: &ResizeHandle) -> bool {
+        let Some((parent, ..)) = self.find_container_for_handle(handle) else {
+            return false;
+        };
+
+        // Only the container that owns the handle is equalized, nested splits keep their sizes
+        parent.equalize_ratios();
+        self.root.recalculate_dirty();
+        true
+    }
+
     fn resize_handle_moved(
         &mut self,
         handle: &ResizeHandle,
         position: &Position,
         mode: &ResizeMode,
     ) -> bool {
-        // Find before_child and its parent
-        let Some(before_child) = self.find_child(handle.before_id) else {
+        let Some((parent, before_child, after_child, after_index)) =
+            self.find_container_for_handle(handle)
+        else {
             return false;
         };
-        let Some(parent) = before_child.parent() else {
-            return false;
-        };
-
-        // Find after_child and its index in the parent
-        let (after_child, after_index) = {
-            let children = parent.children();
-            let mut result = None;
-            for (idx, child) in children.iter().enumerate() {
-                if child.id() == handle.after_id {
-                    result = Some((child.clone(), idx));
-                    break;
-                }
-            }
-            match result {
-                Some(r) => r,
-                None => return false,
-            }
-        };
 
         // Determine the new position based on the handle orientation
         let new_position = match handle.orientation {
@@ -850,6 +1029,9 @@ Placement options:
 
         let success = match mode {
             ResizeMode::Evenly => parent.resize_between(after_index, new_position),
+            ResizeMode::Snap(increment) => {
+                parent.resize_between_snapped(after_index, new_position, *increment)
+            }
             ResizeMode::Before => {
                 let side = match handle.orientation {
                     HandleOrientation::Vertical => Side::Right,
@@ -885,7 +1067,7 @@ Placement options:
         };
 
         if success {
-            self.root.recalculate();
+            self.root.recalculate_dirty();
         }
 
         success
@@ -910,16 +1092,30 @@ Placement options:
         result
     }
 
+    fn balance_weighted(&mut self, weight_of: &dyn Fn(&WindowRef) -> f32) {
+        self.root.balance_weighted(&|w| weight_of(&w.window()));
+        self.root.recalculate();
+    }
+
+    fn apply_golden_ratio(&mut self) {
+        self.root.apply_golden_ratio();
+        self.root.recalculate();
+    }
+
     fn config_changed(&mut self) {
-        let bounds = Self::get_root_bounds(&self.bounds);
-        self.root.set_bounds(bounds);
+        self.root.set_bounds(self.root_bounds());
         self.root.recalculate();
     }
 
     fn set_bounds(&mut self, bounds: Bounds) {
         self.bounds = bounds;
-        let root_bounds = Self::get_root_bounds(&self.bounds);
-        self.root.set_bounds(root_bounds);
+        self.root.set_bounds(self.root_bounds());
+        self.root.recalculate();
+    }
+
+    fn set_gaps(&mut self, gaps: Option<Gaps>) {
+        self.gaps = gaps;
+        self.root.set_bounds(self.root_bounds());
         self.root.recalculate();
     }
 }
@@ -927,6 +1123,7 @@ Placement options:
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::layouts::Direction;
     use crate::platform::mock::MockPlatformWindow;
     use crate::platform::{Bounds, Position, Size};
     use crate::window::Window;
@@ -1091,4 +1288,345 @@ mod tests {
         assert_eq!(tree.windows.len(), 1,);
         assert!(tree.windows.contains_key(&1),);
     }
+
+    #[test]
+    fn test_swap_windows_across_nested_container() {
+        let mut tree = ContainerTree::new(create_test_bounds());
+        let (a, b, c) = (
+            create_mock_window(1),
+            create_mock_window(2),
+            create_mock_window(3),
+        );
+
+        // Root: [A, [B / C]]
+        let a_ref = tree.root.add_window(ContainerWindow::new(a.clone()));
+        let nested = tree.root.add_container(Container::new(
+            create_test_bounds(),
+            Direction::Vertical,
+            None,
+        ));
+        let b_ref = nested.add_window(ContainerWindow::new(b.clone()));
+        let c_ref = nested.add_window(ContainerWindow::new(c.clone()));
+        for window in [&a_ref, &b_ref, &c_ref] {
+            tree.windows.insert(window.window_id(), window.clone());
+        }
+        tree.root.recalculate();
+
+        let a_bounds = a.bounds();
+        let c_bounds = c.bounds();
+
+        tree.swap_windows(&a, &c).unwrap();
+
+        assert_eq!(a_ref.parent().id(), nested.id());
+        assert_eq!(c_ref.parent().id(), tree.root.id());
+        assert_eq!(b_ref.parent().id(), nested.id());
+        assert_eq!(a.bounds(), c_bounds);
+        assert_eq!(c.bounds(), a_bounds);
+    }
+
+    /// Creates a tree with the windows side by side in the root, keeping the windows map in
+    /// sync with the containers.
+    fn create_tree_with_connected_windows(windows: &[WindowRef]) -> ContainerTree {
+        let mut tree = ContainerTree::new(create_test_bounds());
+        for window in windows {
+            let container_window = tree.root.add_window(ContainerWindow::new(window.clone()));
+            tree.windows.insert(window.id(), container_window);
+        }
+        tree.root.recalculate();
+        tree
+    }
+
+    #[test]
+    fn test_resize_window_step_grows_right_edge() {
+        let windows = vec![create_mock_window(1), create_mock_window(2)];
+        let mut tree = create_tree_with_connected_windows(&windows);
+        let before = windows[0].bounds();
+
+        tree.resize_window_step(&windows[0], Side::Right, 50)
+            .unwrap();
+
+        let after = windows[0].bounds();
+        assert_eq!(after.position.x, before.position.x);
+        assert!((after.size.width as i32 - before.size.width as i32 - 50).abs() <= 1);
+    }
+
+    #[test]
+    fn test_resize_window_step_uses_opposite_edge_at_layout_edge() {
+        let windows = vec![create_mock_window(1), create_mock_window(2)];
+        let mut tree = create_tree_with_connected_windows(&windows);
+        let before = windows[1].bounds();
+
+        tree.resize_window_step(&windows[1], Side::Right, 50)
+            .unwrap();
+
+        let after = windows[1].bounds();
+        assert!(after.position.x < before.position.x);
+        assert!(after.size.width > before.size.width);
+    }
+
+    #[test]
+    fn test_resize_window_step_stops_at_min_ratio() {
+        let windows = vec![create_mock_window(1), create_mock_window(2)];
+        let mut tree = create_tree_with_connected_windows(&windows);
+
+        for _ in 0..100 {
+            tree.resize_window_step(&windows[0], Side::Right, 50)
+                .unwrap();
+        }
+        let clamped = windows[0].bounds();
+        let ratios = tree.root.ratios().clone();
+
+        tree.resize_window_step(&windows[0], Side::Right, 50)
+            .unwrap();
+
+        assert_eq!(windows[0].bounds(), clamped);
+        assert!(windows[1].bounds().size.width > 0);
+        assert!((ratios[0] - 0.95).abs() < 0.001);
+        assert!((ratios[1] - 0.05).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_promote_window_moves_up_to_root() {
+        let windows: Vec<_> = (1..=4).map(create_mock_window).collect();
+        let mut tree = ContainerTree::new(create_test_bounds());
+
+        // Root: [A, [B / [C, D]]]
+        let a = tree
+            .root
+            .add_window(ContainerWindow::new(windows[0].clone()));
+        let outer = tree.root.add_container(Container::new(
+            create_test_bounds(),
+            Direction::Vertical,
+            None,
+        ));
+        let b = outer.add_window(ContainerWindow::new(windows[1].clone()));
+        let inner = outer.add_container(Container::new(
+            create_test_bounds(),
+            Direction::Horizontal,
+            None,
+        ));
+        let c = inner.add_window(ContainerWindow::new(windows[2].clone()));
+        let d = inner.add_window(ContainerWindow::new(windows[3].clone()));
+        for window in [&a, &b, &c, &d] {
+            tree.windows.insert(window.window_id(), window.clone());
+        }
+        tree.root.recalculate();
+
+        // The inner container collapses once C leaves it
+        tree.promote_window(&windows[2]).unwrap();
+        assert_eq!(c.parent().id(), outer.id());
+        assert_eq!(d.parent().id(), outer.id());
+
+        tree.promote_window(&windows[2]).unwrap();
+        assert_eq!(c.parent().id(), tree.root.id());
+        assert_eq!(tree.root.children().len(), 3);
+        assert_eq!(
+            tree.root
+                .index_of_child(&ContainerChildRef::Window(c.clone())),
+            Some(2)
+        );
+
+        // Already a child of the root
+        tree.promote_window(&windows[2]).unwrap();
+        assert_eq!(c.parent().id(), tree.root.id());
+        assert_eq!(tree.root.children().len(), 3);
+    }
+
+    #[test]
+    fn test_reset_resize_handle_equalizes_only_owning_container() {
+        let windows: Vec<_> = (1..=3).map(create_mock_window).collect();
+        let mut tree = ContainerTree::new(create_test_bounds());
+
+        // Root: [A, [B / C]]
+        let a = tree
+            .root
+            .add_window(ContainerWindow::new(windows[0].clone()));
+        let nested = tree.root.add_container(Container::new(
+            create_test_bounds(),
+            Direction::Vertical,
+            None,
+        ));
+        let b = nested.add_window(ContainerWindow::new(windows[1].clone()));
+        let c = nested.add_window(ContainerWindow::new(windows[2].clone()));
+        for window in [&a, &b, &c] {
+            tree.windows.insert(window.window_id(), window.clone());
+        }
+        tree.root.set_ratios(vec![0.7, 0.3]);
+        nested.set_ratios(vec![0.2, 0.8]);
+        tree.root.recalculate();
+
+        let handle = tree
+            .resize_handles()
+            .into_iter()
+            .find(|h| h.before_id == b.id() && h.after_id == c.id())
+            .unwrap();
+        assert!(tree.reset_resize_handle(&handle));
+
+        assert_eq!(*nested.ratios(), vec![0.5, 0.5]);
+        assert_eq!(*tree.root.ratios(), vec![0.7, 0.3]);
+        assert!(
+            (windows[1].bounds().size.height as i32 - windows[2].bounds().size.height as i32).abs()
+                <= 1
+        );
+    }
+
+    fn placement(yaml: &str) -> PlacementTarget {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_insert_relative_to_window_with_side_and_ratio() {
+        let windows: Vec<_> = (1..=2).map(create_mock_window).collect();
+        let mut tree = create_tree_with_connected_windows(&windows[..1]);
+
+        tree.insert_relative(
+            &windows[1],
+            placement("relative_to: 1\nside: right\nratio: 0.3"),
+        )
+        .unwrap();
+
+        let children: Vec<_> = tree.root.children().iter().map(|c| c.id()).collect();
+        let first = tree.windows[&1].id();
+        let second = tree.windows[&2].id();
+        assert_eq!(children, vec![first, second]);
+        assert!((tree.root.ratios()[1] - 0.3).abs() < 0.001);
+        assert!(windows[1].bounds().position.x > windows[0].bounds().position.x);
+    }
+
+    #[test]
+    fn test_insert_relative_tagged_window_splits_across_parent() {
+        let windows: Vec<_> = (1..=2).map(create_mock_window).collect();
+        let mut tree = create_tree_with_connected_windows(&windows[..1]);
+
+        tree.insert_relative(
+            &windows[1],
+            placement("type: window\nid: 1\nside: top\nratio: 0.25"),
+        )
+        .unwrap();
+
+        let parent = tree.windows[&2].parent();
+        assert_eq!(parent.direction(), Direction::Vertical);
+        assert!((parent.ratios()[0] - 0.25).abs() < 0.001);
+        assert!(windows[1].bounds().position.y < windows[0].bounds().position.y);
+    }
+
+    #[test]
+    fn test_insert_relative_fill_root() {
+        let windows: Vec<_> = (1..=2).map(create_mock_window).collect();
+        let mut tree = ContainerTree::new(create_test_bounds());
+
+        tree.insert_relative(&windows[0], placement("fill_root: true"))
+            .unwrap();
+        assert_eq!(tree.root.children().len(), 1);
+
+        tree.insert_relative(&windows[1], placement("fill_root: true"))
+            .unwrap();
+        let children: Vec<_> = tree.root.children().iter().map(|c| c.id()).collect();
+        assert_eq!(children, vec![tree.windows[&1].id(), tree.windows[&2].id()]);
+    }
+
+    #[test]
+    fn test_insert_relative_to_missing_window_names_it() {
+        let windows: Vec<_> = (1..=2).map(create_mock_window).collect();
+        let mut tree = create_tree_with_connected_windows(&windows[..1]);
+
+        let result = tree.insert_relative(&windows[1], placement("relative_to: 42\nside: left"));
+        match result {
+            Err(LayoutError::PlacementTargetNotFound(message)) => assert!(message.contains("42")),
+            other => panic!("expected PlacementTargetNotFound, got {other:?}"),
+        }
+        assert!(!tree.windows.contains_key(&2));
+    }
+
+    #[test]
+    fn test_insert_relative_rejects_invalid_targets() {
+        let windows: Vec<_> = (1..=2).map(create_mock_window).collect();
+        let mut tree = create_tree_with_connected_windows(&windows[..1]);
+
+        for yaml in [
+            "fill_root: false",
+            "relative_to: 1\nratio: 1.5",
+            "somewhere: else",
+        ] {
+            assert!(matches!(
+                tree.insert_relative(&windows[1], placement(yaml)),
+                Err(LayoutError::PlacementTargetNotFound(_))
+            ));
+        }
+    }
+
+    fn window_bounds(tree: &ContainerTree) -> Vec<(WindowId, Bounds)> {
+        let mut bounds: Vec<_> = tree
+            .windows
+            .iter()
+            .map(|(id, window)| (*id, window.bounds()))
+            .collect();
+        bounds.sort_by_key(|(id, _)| *id);
+        bounds
+    }
+
+    #[test]
+    fn test_recalculate_dirty_matches_full_recalculate() {
+        // xorshift, so every run goes through the same operations
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut random = |n: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % n
+        };
+
+        let mut tree = ContainerTree::new(create_test_bounds());
+        let mut next_id = 1;
+        for _ in 0..500 {
+            let ids: Vec<WindowId> = tree.windows.keys().copied().collect();
+            let op = if ids.len() < 2 { 0 } else { random(4) };
+            match op {
+                0 => {
+                    let position = Position {
+                        x: random(1920) as i32,
+                        y: random(1080) as i32,
+                    };
+                    let _ = tree.insert_window(&create_mock_window(next_id), &position);
+                    next_id += 1;
+                }
+                1 => {
+                    let id = ids[random(ids.len() as u64) as usize];
+                    let window = tree.windows[&id].window();
+                    tree.remove_window(&window).unwrap();
+                }
+                2 => {
+                    let id = ids[random(ids.len() as u64) as usize];
+                    let window = tree.windows[&id].window();
+                    let side =
+                        [Side::Left, Side::Right, Side::Top, Side::Bottom][random(4) as usize];
+                    let delta = random(200) as i32 - 100;
+                    tree.resize_window_step(&window, side, delta).unwrap();
+                }
+                _ => {
+                    let handles = tree.resize_handles();
+                    if handles.is_empty() {
+                        continue;
+                    }
+                    let handle = &handles[random(handles.len() as u64) as usize];
+                    let along = handle.min + random((handle.max - handle.min).max(1) as u64) as i32;
+                    let position = match handle.orientation {
+                        HandleOrientation::Vertical => Position {
+                            x: along,
+                            y: handle.center.y,
+                        },
+                        HandleOrientation::Horizontal => Position {
+                            x: handle.center.x,
+                            y: along,
+                        },
+                    };
+                    tree.resize_handle_moved(handle, &position, &ResizeMode::Evenly);
+                }
+            }
+
+            let incremental = window_bounds(&tree);
+            tree.root.recalculate();
+            assert_eq!(incremental, window_bounds(&tree), "after op {op}");
+        }
+    }
 }
@@ -2,6 +2,8 @@ pub use container_tree::*;
 
 use crate::{
     layouts::container_tree::container::{ContainerChildRef, ContainerWindowRef},
+    layouts::{LayoutError, LayoutResult, PlacementTarget},
+    platform::Bounds,
     WindowId,
 };
 use serde::{Deserialize, Serialize};
@@ -72,6 +74,29 @@ impl ResizeDirection {
             _ => false,
         }
     }
+
+    /// Works out which edges moved between two bounds of the same window
+    pub fn from_bounds_change(old: &Bounds, new: &Bounds) -> Self {
+        let left_changed = new.position.x != old.position.x;
+        let right_changed =
+            (new.position.x + new.size.width as i32) != (old.position.x + old.size.width as i32);
+        let top_changed = new.position.y != old.position.y;
+        let bottom_changed =
+            (new.position.y + new.size.height as i32) != (old.position.y + old.size.height as i32);
+
+        match (left_changed, right_changed, top_changed, bottom_changed) {
+            (true, false, false, false) => ResizeDirection::Left,
+            (false, true, false, false) => ResizeDirection::Right,
+            (false, false, true, false) => ResizeDirection::Top,
+            (false, false, false, true) => ResizeDirection::Bottom,
+            (true, false, true, false) => ResizeDirection::TopLeft,
+            (false, true, true, false) => ResizeDirection::TopRight,
+            (true, false, false, true) => ResizeDirection::BottomLeft,
+            (false, true, false, true) => ResizeDirection::BottomRight,
+            // Default/fallback
+            _ => ResizeDirection::Right,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -95,8 +120,79 @@ pub struct ContainerTreePlacementTarget {
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum ContainerTreePlacementTargetType {
-    Window { id: WindowId },
-    Container { id: ContainerId },
+    Window {
+        id: WindowId,
+    },
+    Container {
+        id: ContainerId,
+    },
+    /// The root container, filling the whole layout when it's empty
+    Root,
+}
+
+/// Shorter ways to write a placement target for scripts
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PlacementTargetShorthand {
+    RelativeTo {
+        relative_to: WindowId,
+        #[serde(default)]
+        side: Option<Side>,
+        #[serde(default)]
+        ratio: Option<f32>,
+    },
+    FillRoot {
+        fill_root: bool,
+    },
+}
+
+impl ContainerTreePlacementTarget {
+    /// Parses a placement target from either the tagged form (`{type: window, id: 1}`) or one
+    /// of the shorthands, `{relative_to: 1, side: right, ratio: 0.3}` and `{fill_root: true}`.
+    pub fn parse(value: &PlacementTarget) -> LayoutResult<Self> {
+        let target = match serde_yaml::from_value::<Self>(value.clone()) {
+            Ok(target) => target,
+            Err(tagged_error) => match serde_yaml::from_value(value.clone()) {
+                Ok(PlacementTargetShorthand::RelativeTo {
+                    relative_to,
+                    side,
+                    ratio,
+                }) => Self {
+                    target: ContainerTreePlacementTargetType::Window { id: relative_to },
+                    side,
+                    ratio,
+                },
+                Ok(PlacementTargetShorthand::FillRoot { fill_root: true }) => Self {
+                    target: ContainerTreePlacementTargetType::Root,
+                    side: None,
+                    ratio: None,
+                },
+                Ok(PlacementTargetShorthand::FillRoot { fill_root: false }) => {
+                    return Err(LayoutError::PlacementTargetNotFound(
+                        "fill_root must be true, leave it out to place the window elsewhere"
+                            .to_string(),
+                    ));
+                }
+                Err(_) => {
+                    return Err(LayoutError::PlacementTargetNotFound(format!(
+                        "Failed to deserialize placement target: {}. Target was: {}",
+                        tagged_error,
+                        serde_yaml::to_string(value).unwrap_or_default()
+                    )));
+                }
+            },
+        };
+
+        if let Some(ratio) = target.ratio {
+            if !(ratio > 0.0 && ratio < 1.0) {
+                return Err(LayoutError::PlacementTargetNotFound(format!(
+                    "Placement ratio must be between 0 and 1, got {ratio}"
+                )));
+            }
+        }
+
+        Ok(target)
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,8 @@
 use crate::layouts::container_tree::container::{
-    Container, ContainerChildRef, ContainerRef, ContainerWindow, ContainerWindowRef,
+    distribute_sizes, Container, ContainerChildRef, ContainerRef, ContainerWindow,
+    ContainerWindowRef,
 };
-use crate::layouts::{ContainerId, Direction};
+use crate::layouts::{tiling_area, ContainerId, Direction, RatioLimits};
 use crate::platform::{Bounds, PlatformWindowImpl, WindowId};
 use crate::window::WindowRef;
 use serde::{Deserialize, Serialize};
@@ -31,6 +32,20 @@ pub enum SerializedContainerChild {
 #[derive(Serialize, Deserialize)]
 pub struct SerializedWindow {
     pub id: WindowId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_ratio: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ratio: Option<f32>,
+}
+
+impl SerializedWindow {
+    pub fn new(id: WindowId) -> Self {
+        Self {
+            id,
+            min_ratio: None,
+            max_ratio: None,
+        }
+    }
 }
 
 pub fn serialize_container(container: &ContainerRef) -> SerializedContainer {
@@ -54,8 +69,65 @@ pub fn serialize_container(container: &ContainerRef) -> SerializedContainer {
 }
 
 fn serialize_window(window: &ContainerWindowRef) -> SerializedWindow {
+    let limits = window.window().ratio_limits();
     SerializedWindow {
         id: window.window().platform_window().id(),
+        min_ratio: limits.min_ratio,
+        max_ratio: limits.max_ratio,
+    }
+}
+
+/// The slot each window of a saved tree would get in `bounds`, in layout order. Nothing is
+/// known about the windows here, so slots are split by ratio alone, ignoring minimum sizes.
+pub fn preview_container_tree(
+    bounds: &Bounds,
+    saved_layout: &serde_yaml::Value,
+) -> Vec<(WindowId, Bounds)> {
+    let Ok(serialized) = serde_yaml::from_value::<SerializedContainerTree>(saved_layout.clone())
+    else {
+        return Vec::new();
+    };
+
+    let mut slots = Vec::new();
+    preview_container(&serialized.root, tiling_area(bounds), &mut slots);
+    slots
+}
+
+fn preview_container(
+    container: &SerializedContainer,
+    bounds: Bounds,
+    slots: &mut Vec<(WindowId, Bounds)>,
+) {
+    let count = container.children.len();
+    let ratios = if container.ratios.len() == count {
+        container.ratios.clone()
+    } else {
+        vec![1.0 / count as f32; count]
+    };
+    let (total, mut current) = match container.direction {
+        Direction::Horizontal => (bounds.size.width, bounds.position.x),
+        Direction::Vertical => (bounds.size.height, bounds.position.y),
+    };
+
+    for (child, size) in container
+        .children
+        .iter()
+        .zip(distribute_sizes(total, &ratios, &[]))
+    {
+        let child_bounds = match container.direction {
+            Direction::Horizontal => {
+                Bounds::new(current, bounds.position.y, size, bounds.size.height)
+            }
+            Direction::Vertical => Bounds::new(bounds.position.x, current, bounds.size.width, size),
+        };
+        current += size as i32;
+
+        match child {
+            SerializedContainerChild::Window(window) => slots.push((window.id, child_bounds)),
+            SerializedContainerChild::Container(container) => {
+                preview_container(container, child_bounds, slots)
+            }
+        }
     }
 }
 
@@ -100,6 +172,10 @@ pub(crate) fn deserialize_container(
             }
             SerializedContainerChild::Window(window_data) => {
                 if let Some(window_ref) = available_windows.get(&window_data.id) {
+                    window_ref.set_ratio_limits(RatioLimits {
+                        min_ratio: window_data.min_ratio,
+                        max_ratio: window_data.max_ratio,
+                    });
                     let container_window = ContainerWindow::new(window_ref.clone());
                     let window_ref = container.add_window(container_window);
                     windows_map.insert(window_data.id, window_ref);
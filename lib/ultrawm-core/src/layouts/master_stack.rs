@@ -0,0 +1,597 @@
+use crate::config::Config;
+use crate::layouts::{
+    tiling_area_with_gaps, Direction, Gaps, LayoutError, LayoutResult, LayoutType, PlacementTarget,
+    Side, WindowLayout,
+};
+use crate::platform::{Bounds, Position, WindowId};
+use crate::resize_handle::{HandleOrientation, ResizeHandle, ResizeMode};
+use crate::tile_result::InsertResult;
+use crate::window::WindowRef;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+const MIN_MASTER_RATIO: f32 = 0.1;
+const MAX_MASTER_RATIO: f32 = 0.9;
+
+/// A layout with one master window on the left and the remaining windows stacked evenly on the
+/// right.
+#[derive(Debug)]
+pub struct MasterStack {
+    bounds: Bounds,
+    /// The first window is the master, the rest make up the stack from top to bottom
+    windows: Vec<WindowRef>,
+    /// Fraction of the width given to the master when the stack isn't empty
+    master_ratio: f32,
+    /// Gaps used instead of the global ones
+    gaps: Option<Gaps>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializedMasterStack {
+    pub master_ratio: f32,
+    pub windows: Vec<SerializedStackWindow>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializedStackWindow {
+    pub id: WindowId,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MasterStackPlacementTarget {
+    Master,
+    Stack {
+        #[serde(default)]
+        index: Option<usize>,
+    },
+}
+
+impl MasterStack {
+    pub fn master_ratio(&self) -> f32 {
+        self.master_ratio
+    }
+
+    /// Creates a layout holding the windows, the first one as the master
+    pub fn with_windows(bounds: Bounds, windows: &[WindowRef]) -> Self {
+        let mut layout = Self::new(bounds);
+        layout.windows = windows.to_vec();
+        layout.recalculate();
+        layout
+    }
+
+    /// The area the windows are tiled in
+    fn area(&self) -> Bounds {
+        let gaps = self.gaps.unwrap_or_else(Gaps::from_config);
+        tiling_area_with_gaps(&self.bounds, &gaps)
+    }
+
+    fn index_of(&self, window: &WindowRef) -> Option<usize> {
+        self.windows.iter().position(|w| w.id() == window.id())
+    }
+
+    /// The x coordinate the master and the stack meet at
+    fn split_x(&self) -> i32 {
+        let area = self.area();
+        area.position.x + (area.size.width as f32 * self.master_ratio).round() as i32
+    }
+
+    /// The slot bounds for a layout holding `count` windows, master first
+    fn slots(&self, count: usize) -> Vec<Bounds> {
+        let area = self.area();
+        if count == 0 {
+            return Vec::new();
+        }
+        if count == 1 {
+            return vec![area];
+        }
+
+        let split_x = self.split_x();
+        let master_width = (split_x - area.position.x) as u32;
+        let stack_width = area.size.width - master_width;
+        let mut slots = vec![Bounds::new(
+            area.position.x,
+            area.position.y,
+            master_width,
+            area.size.height,
+        )];
+
+        let stack_count = (count - 1) as u32;
+        let stack_height = area.size.height / stack_count;
+        for i in 0..stack_count {
+            // The last window takes whatever is left over from rounding
+            let height = if i == stack_count - 1 {
+                area.size.height - stack_height * i
+            } else {
+                stack_height
+            };
+            slots.push(Bounds::new(
+                split_x,
+                area.position.y + (stack_height * i) as i32,
+                stack_width,
+                height,
+            ));
+        }
+        slots
+    }
+
+    fn recalculate(&mut self) {
+        for (window, bounds) in self.windows.iter().zip(self.slots(self.windows.len())) {
+            window.set_bounds(bounds);
+        }
+    }
+
+    /// The index a window dropped at `position` would be inserted at, ignoring `ignore`
+    fn insert_index(&self, position: &Position, ignore: &WindowRef) -> usize {
+        let others: Vec<&WindowRef> = self
+            .windows
+            .iter()
+            .filter(|w| w.id() != ignore.id())
+            .collect();
+
+        if others.is_empty() || position.x < self.split_x() {
+            return 0;
+        }
+
+        let above = others
+            .iter()
+            .skip(1)
+            .filter(|w| w.bounds().center().y < position.y)
+            .count();
+        1 + above
+    }
+
+    /// The slot each window of a saved layout would get in `bounds`, master first
+    pub fn preview(bounds: &Bounds, saved_layout: &serde_yaml::Value) -> Vec<(WindowId, Bounds)> {
+        let Ok(serialized) = serde_yaml::from_value::<SerializedMasterStack>(saved_layout.clone())
+        else {
+            return Vec::new();
+        };
+
+        let mut layout = Self::new(bounds.clone());
+        layout.master_ratio = serialized
+            .master_ratio
+            .clamp(MIN_MASTER_RATIO, MAX_MASTER_RATIO);
+        let slots = layout.slots(serialized.windows.len());
+        serialized.windows.iter().map(|w| w.id).zip(slots).collect()
+    }
+
+    fn set_master_ratio(&mut self, ratio: f32) {
+        self.master_ratio = ratio.clamp(MIN_MASTER_RATIO, MAX_MASTER_RATIO);
+        self.recalculate();
+    }
+}
+
+impl WindowLayout for MasterStack {
+    fn new(bounds: Bounds) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            bounds,
+            windows: Vec::new(),
+            master_ratio: Config::master_ratio().clamp(MIN_MASTER_RATIO, MAX_MASTER_RATIO),
+            gaps: None,
+        }
+    }
+
+    fn layout_type(&self) -> LayoutType {
+        LayoutType::MasterStack
+    }
+
+    fn layout_description(&self) -> String {
+        "MasterStack is a layout with one large master window on the left and every other window stacked evenly on the right. The split between the master and the stack is set by master_ratio.".to_string()
+    }
+
+    fn placement_help(&self) -> String {
+        r#"Placement targets:
+  - {type: master} - Make the window the master, the current master moves to the top of the stack
+  - {type: stack} - Add the window to the bottom of the stack
+  - {type: stack, index: <n>} - Add the window to the stack at position n (0 is the top)"#
+            .to_string()
+    }
+
+    fn example_layout(&self) -> serde_yaml::Value {
+        let example = SerializedMasterStack {
+            master_ratio: 0.6,
+            windows: vec![
+                SerializedStackWindow { id: 1 },
+                SerializedStackWindow { id: 2 },
+                SerializedStackWindow { id: 3 },
+            ],
+        };
+        serde_yaml::to_value(example).unwrap()
+    }
+
+    fn deserialize(
+        bounds: Bounds,
+        available_windows: &Vec<WindowRef>,
+        saved_layout: &serde_yaml::Value,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        let serialized: SerializedMasterStack = match serde_yaml::from_value(saved_layout.clone()) {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Failed to parse master stack layout: {e}, starting from scratch");
+                return Self::new(bounds);
+            }
+        };
+
+        let windows: Vec<WindowRef> = serialized
+            .windows
+            .iter()
+            .filter_map(|saved| available_windows.iter().find(|w| w.id() == saved.id))
+            .cloned()
+            .collect();
+
+        let mut layout = Self::with_windows(bounds, &windows);
+        layout.set_master_ratio(serialized.master_ratio);
+        layout
+    }
+
+    fn serialize(&self) -> serde_yaml::Value {
+        let serialized = SerializedMasterStack {
+            master_ratio: self.master_ratio,
+            windows: self
+                .windows
+                .iter()
+                .map(|w| SerializedStackWindow { id: w.id() })
+                .collect(),
+        };
+        serde_yaml::to_value(serialized).unwrap()
+    }
+
+    fn get_preview_bounds(&self, window: &WindowRef, position: &Position) -> Option<Bounds> {
+        let count = self
+            .windows
+            .iter()
+            .filter(|w| w.id() != window.id())
+            .count()
+            + 1;
+        let index = self.insert_index(position, window);
+        self.slots(count).get(index).cloned()
+    }
+
+    fn windows(&self) -> Vec<WindowRef> {
+        self.windows.clone()
+    }
+
+    fn insert_window(
+        &mut self,
+        window: &WindowRef,
+        position: &Position,
+    ) -> LayoutResult<InsertResult> {
+        let index = self.insert_index(position, window);
+        self.windows.retain(|w| w.id() != window.id());
+        self.windows
+            .insert(index.min(self.windows.len()), window.clone());
+        self.recalculate();
+        Ok(InsertResult::None)
+    }
+
+    fn insert_relative(
+        &mut self,
+        window: &WindowRef,
+        target: PlacementTarget,
+    ) -> LayoutResult<InsertResult> {
+        let target: MasterStackPlacementTarget = serde_yaml::from_value(target.clone())
+            .map_err(|e| LayoutError::PlacementTargetNotFound(format!("{target:?}: {e}")))?;
+
+        self.windows.retain(|w| w.id() != window.id());
+        let index = match target {
+            MasterStackPlacementTarget::Master => 0,
+            MasterStackPlacementTarget::Stack { index: None } => self.windows.len(),
+            MasterStackPlacementTarget::Stack { index: Some(i) } => (i + 1).min(self.windows.len()),
+        };
+        self.windows.insert(index, window.clone());
+        self.recalculate();
+        Ok(InsertResult::None)
+    }
+
+    fn replace_window(
+        &mut self,
+        old_window: &WindowRef,
+        new_window: &WindowRef,
+    ) -> LayoutResult<()> {
+        let index = self
+            .index_of(old_window)
+            .ok_or(LayoutError::WindowNotFound(old_window.id()))?;
+        self.windows[index] = new_window.clone();
+        self.recalculate();
+        Ok(())
+    }
+
+    fn swap_windows(&mut self, a: &WindowRef, b: &WindowRef) -> LayoutResult<()> {
+        let a_index = self
+            .index_of(a)
+            .ok_or(LayoutError::WindowNotFound(a.id()))?;
+        let b_index = self
+            .index_of(b)
+            .ok_or(LayoutError::WindowNotFound(b.id()))?;
+        self.windows.swap(a_index, b_index);
+        self.recalculate();
+        Ok(())
+    }
+
+    /// Promoting a stack window makes it the master, the old master moves to the top of the
+    /// stack.
+    fn promote_window(&mut self, window: &WindowRef) -> LayoutResult<()> {
+        let index = self
+            .index_of(window)
+            .ok_or(LayoutError::WindowNotFound(window.id()))?;
+        let window = self.windows.remove(index);
+        self.windows.insert(0, window);
+        self.recalculate();
+        Ok(())
+    }
+
+    fn remove_window(&mut self, window: &WindowRef) -> LayoutResult<()> {
+        let index = self
+            .index_of(window)
+            .ok_or(LayoutError::WindowNotFound(window.id()))?;
+        self.windows.remove(index);
+        self.recalculate();
+        Ok(())
+    }
+
+    fn resize_window(&mut self, window: &WindowRef, bounds: &Bounds) -> LayoutResult<()> {
+        let index = self
+            .index_of(window)
+            .ok_or(LayoutError::WindowNotFound(window.id()))?;
+
+        if self.windows.len() > 1 {
+            let area = self.area();
+            // The master's right edge and the stack's left edge are the only ones that move
+            let split_x = if index == 0 {
+                bounds.position.x + bounds.size.width as i32
+            } else {
+                bounds.position.x
+            };
+            self.set_master_ratio((split_x - area.position.x) as f32 / area.size.width as f32);
+        } else {
+            self.recalculate();
+        }
+        Ok(())
+    }
+
+    fn resize_window_step(
+        &mut self,
+        window: &WindowRef,
+        side: Side,
+        delta_px: i32,
+    ) -> LayoutResult<()> {
+        let index = self
+            .index_of(window)
+            .ok_or(LayoutError::WindowNotFound(window.id()))?;
+
+        // Stack windows always share the height evenly
+        if self.windows.len() < 2 || side.direction() == Direction::Vertical {
+            return Ok(());
+        }
+
+        let area = self.area();
+        let delta = delta_px as f32 / area.size.width as f32;
+        if index == 0 {
+            self.set_master_ratio(self.master_ratio + delta);
+        } else {
+            self.set_master_ratio(self.master_ratio - delta);
+        }
+        Ok(())
+    }
+
+    fn resize_handles(&self) -> Vec<ResizeHandle> {
+        if self.windows.len() < 2 {
+            return Vec::new();
+        }
+
+        let area = self.area();
+        vec![ResizeHandle::new(
+            Position {
+                x: self.split_x(),
+                y: area.center().y,
+            },
+            area.size.height,
+            HandleOrientation::Vertical,
+            area.position.x + (area.size.width as f32 * MIN_MASTER_RATIO) as i32,
+            area.position.x + (area.size.width as f32 * MAX_MASTER_RATIO) as i32,
+            self.windows[0].id(),
+            self.windows[1].id(),
+        )]
+    }
+
+    fn resize_handle_moved(
+        &mut self,
+        handle: &ResizeHandle,
+        position: &Position,
+        mode: &ResizeMode,
+    ) -> bool {
+        if self.windows.len() < 2 || handle.before_id != self.windows[0].id() {
+            return false;
+        }
+
+        let area = self.area();
+        let x = handle.clamp_coordinate(position.x);
+        let mut ratio = (x - area.position.x) as f32 / area.size.width as f32;
+        if let ResizeMode::Snap(increment) = mode {
+            if *increment > 0.0 && *increment < 1.0 {
+                ratio = (ratio / increment).round() * increment;
+            }
+        }
+        self.set_master_ratio(ratio);
+        true
+    }
+
+    fn reset_resize_handle(&mut self, handle: &ResizeHandle) -> bool {
+        if self.windows.len() < 2 || handle.before_id != self.windows[0].id() {
+            return false;
+        }
+
+        self.set_master_ratio(Config::master_ratio());
+        true
+    }
+
+    fn balance_weighted(&mut self, weight_of: &dyn Fn(&WindowRef) -> f32) {
+        if self.windows.len() < 2 {
+            return;
+        }
+
+        let master = weight_of(&self.windows[0]);
+        let stack =
+            self.windows[1..].iter().map(weight_of).sum::<f32>() / (self.windows.len() - 1) as f32;
+        if master + stack > 0.0 {
+            self.set_master_ratio(master / (master + stack));
+        }
+    }
+
+    fn debug_layout(&self) -> String {
+        let mut result = format!(
+            "MasterStack Layout ({}x{} at {},{}, ratio {:.2}):\n",
+            self.bounds.size.width,
+            self.bounds.size.height,
+            self.bounds.position.x,
+            self.bounds.position.y,
+            self.master_ratio
+        );
+
+        if self.windows.is_empty() {
+            result.push_str("└─ (empty)\n");
+        }
+        for (i, window) in self.windows.iter().enumerate() {
+            let connector = if i == self.windows.len() - 1 {
+                "└─"
+            } else {
+                "├─"
+            };
+            let role = if i == 0 { "Master" } else { "Stack" };
+            result.push_str(&format!(
+                "{connector} {role} Window {} \"{}\"\n",
+                window.id(),
+                window.title()
+            ));
+        }
+        result
+    }
+
+    fn config_changed(&mut self) {
+        self.recalculate();
+    }
+
+    fn set_bounds(&mut self, bounds: Bounds) {
+        self.bounds = bounds;
+        self.recalculate();
+    }
+
+    fn set_gaps(&mut self, gaps: Option<Gaps>) {
+        self.gaps = gaps;
+        self.recalculate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layouts::tiling_area;
+    use crate::platform::mock::MockPlatformWindow;
+    use crate::platform::Size;
+    use crate::window::Window;
+    use std::rc::Rc;
+
+    fn create_mock_window(id: u64) -> WindowRef {
+        let mut platform_window = MockPlatformWindow::new(
+            Position { x: 0, y: 0 },
+            Size {
+                width: 800,
+                height: 600,
+            },
+            format!("Test Window {}", id),
+        );
+        platform_window.id = id;
+        Rc::new(Window::new(platform_window))
+    }
+
+    fn create_test_bounds() -> Bounds {
+        Bounds::new(0, 0, 1920, 1080)
+    }
+
+    #[test]
+    fn test_first_window_fills_area() {
+        let mut layout = MasterStack::new(create_test_bounds());
+        let window = create_mock_window(1);
+
+        layout
+            .insert_window(&window, &Position { x: 500, y: 500 })
+            .unwrap();
+
+        assert_eq!(window.bounds(), tiling_area(&create_test_bounds()));
+    }
+
+    #[test]
+    fn test_insert_on_left_becomes_master() {
+        let a = create_mock_window(1);
+        let b = create_mock_window(2);
+        let mut layout = MasterStack::with_windows(create_test_bounds(), &[a.clone()]);
+
+        layout
+            .insert_window(&b, &Position { x: 1800, y: 500 })
+            .unwrap();
+        assert_eq!(layout.windows()[0].id(), 1);
+        assert!(b.bounds().position.x > a.bounds().position.x);
+
+        let c = create_mock_window(3);
+        layout
+            .insert_window(&c, &Position { x: 10, y: 500 })
+            .unwrap();
+        let ids: Vec<WindowId> = layout.windows().iter().map(|w| w.id()).collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+        assert!(a.bounds().position.y < b.bounds().position.y);
+        assert_eq!(a.bounds().position.x, b.bounds().position.x);
+    }
+
+    #[test]
+    fn test_resize_handle_moves_split() {
+        let a = create_mock_window(1);
+        let b = create_mock_window(2);
+        let mut layout = MasterStack::with_windows(create_test_bounds(), &[a.clone(), b.clone()]);
+
+        let handles = layout.resize_handles();
+        assert_eq!(handles.len(), 1);
+
+        let area = tiling_area(&create_test_bounds());
+        let target_x = area.position.x + area.size.width as i32 / 4;
+        assert!(layout.resize_handle_moved(
+            &handles[0],
+            &Position {
+                x: target_x,
+                y: 500
+            },
+            &ResizeMode::Evenly
+        ));
+
+        assert!((layout.master_ratio() - 0.25).abs() < 0.01);
+        assert_eq!(b.bounds().position.x, layout.split_x());
+        assert_eq!(
+            a.bounds().position.x + a.bounds().size.width as i32,
+            layout.split_x()
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let windows = vec![
+            create_mock_window(1),
+            create_mock_window(2),
+            create_mock_window(3),
+        ];
+        let mut layout = MasterStack::with_windows(create_test_bounds(), &windows);
+        layout.set_master_ratio(0.7);
+
+        let serialized = layout.serialize();
+        let restored = MasterStack::deserialize(create_test_bounds(), &windows, &serialized);
+
+        let ids: Vec<WindowId> = restored.windows().iter().map(|w| w.id()).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert!((restored.master_ratio() - 0.7).abs() < f32::EPSILON);
+    }
+}
@@ -1,13 +1,162 @@
+use crate::config::Config;
 use crate::platform::{Bounds, Position, WindowId};
 use crate::resize_handle::{ResizeHandle, ResizeMode};
 use crate::tile_result::InsertResult;
 use crate::window::WindowRef;
 pub use container_tree::*;
+pub use master_stack::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use thiserror::Error;
 
 pub mod container_tree;
+pub mod master_stack;
+
+/// The layouts a workspace can use to arrange its tiled windows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum LayoutType {
+    /// Nested containers split horizontally or vertically
+    #[default]
+    ContainerTree,
+    /// One large master window with the rest stacked beside it
+    MasterStack,
+}
+
+impl LayoutType {
+    pub const ALL: [LayoutType; 2] = [LayoutType::ContainerTree, LayoutType::MasterStack];
+
+    /// The layout after this one in `ALL`, wrapping around
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|t| t == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// Creates an empty layout of the given type
+pub fn new_layout(layout_type: LayoutType, bounds: Bounds) -> Box<dyn WindowLayout> {
+    match layout_type {
+        LayoutType::ContainerTree => Box::new(ContainerTree::new(bounds)),
+        LayoutType::MasterStack => Box::new(MasterStack::new(bounds)),
+    }
+}
+
+/// Rebuilds a layout of the given type from its serialized form
+pub fn deserialize_layout(
+    layout_type: LayoutType,
+    bounds: Bounds,
+    available_windows: &Vec<WindowRef>,
+    saved_layout: &serde_yaml::Value,
+) -> Box<dyn WindowLayout> {
+    match layout_type {
+        LayoutType::ContainerTree => Box::new(<ContainerTree as WindowLayout>::deserialize(
+            bounds,
+            available_windows,
+            saved_layout,
+        )),
+        LayoutType::MasterStack => Box::new(MasterStack::deserialize(
+            bounds,
+            available_windows,
+            saved_layout,
+        )),
+    }
+}
+
+/// The slot each window of a saved layout would get in `bounds`, without touching the windows
+/// or any live layout
+pub fn preview_layout(
+    layout_type: LayoutType,
+    bounds: &Bounds,
+    saved_layout: &serde_yaml::Value,
+) -> Vec<(WindowId, Bounds)> {
+    match layout_type {
+        LayoutType::ContainerTree => {
+            container_tree::serialization::preview_container_tree(bounds, saved_layout)
+        }
+        LayoutType::MasterStack => MasterStack::preview(bounds, saved_layout),
+    }
+}
+
+/// Creates an empty layout of the configured default type. This lets a layout describe itself
+/// without a running window manager, so the bounds are only nominal.
+pub fn configured_layout(config: &Config) -> Box<dyn WindowLayout> {
+    new_layout(config.default_layout, Bounds::new(0, 0, 1920, 1080))
+}
+
+/// Describes a layout and how to place windows in it, followed by an example of its saved
+/// form. The output only depends on the layout type.
+pub fn layout_help(layout: &dyn WindowLayout) -> String {
+    let example = serde_yaml::to_string(&layout.example_layout()).unwrap_or_default();
+    format!(
+        "{}\n\n{}\n\nExample layout:\n{}",
+        layout.layout_description().trim_end(),
+        layout.placement_help().trim_end(),
+        example.trim_end()
+    )
+}
+
+/// How small or large a share of its container a window can be resized to, from 0 to 1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RatioLimits {
+    pub min_ratio: Option<f32>,
+    pub max_ratio: Option<f32>,
+}
+
+impl RatioLimits {
+    pub fn is_empty(&self) -> bool {
+        self.min_ratio.is_none() && self.max_ratio.is_none()
+    }
+
+    pub fn clamp(&self, ratio: f32) -> f32 {
+        let ratio = self.max_ratio.map_or(ratio, |max| ratio.min(max));
+        self.min_ratio.map_or(ratio, |min| ratio.max(min))
+    }
+}
+
+/// The gaps a layout tiles with, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gaps {
+    /// Space between windows
+    pub window: u32,
+    /// Space between the partition edges and windows
+    pub partition: u32,
+    /// Space between the partition edges and windows instead of `partition`, when set
+    pub outer: Option<u32>,
+}
+
+impl Gaps {
+    /// The gaps from the global config
+    pub fn from_config() -> Self {
+        let config = Config::current();
+        Self {
+            window: config.window_gap,
+            partition: config.partition_gap,
+            outer: config.outer_gap,
+        }
+    }
+
+    /// Space left between the partition edges and the windows along them
+    pub fn edge(&self) -> u32 {
+        self.outer.unwrap_or(self.partition)
+    }
+}
+
+/// The area a layout tiles windows in. Applies the outer gap and inverts the window gap so
+/// that windows along the edges are exactly the outer gap away from them.
+pub fn tiling_area(bounds: &Bounds) -> Bounds {
+    tiling_area_with_gaps(bounds, &Gaps::from_config())
+}
+
+/// Like `tiling_area`, with gaps other than the global ones
+pub fn tiling_area_with_gaps(bounds: &Bounds, gaps: &Gaps) -> Bounds {
+    Bounds::new(
+        bounds.position.x + gaps.edge() as i32 - gaps.window as i32 / 2,
+        bounds.position.y + gaps.edge() as i32 - gaps.window as i32 / 2,
+        bounds.size.width - gaps.edge() * 2 + gaps.window,
+        bounds.size.height - gaps.edge() * 2 + gaps.window,
+    )
+}
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -25,7 +174,7 @@ impl Direction {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Side {
     Left,
@@ -49,6 +198,15 @@ impl Side {
     pub fn is_after(&self) -> bool {
         matches!(self, Side::Right | Side::Bottom)
     }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+            Side::Top => Side::Bottom,
+            Side::Bottom => Side::Top,
+        }
+    }
 }
 
 pub type PlacementTarget = serde_yaml::Value;
@@ -75,6 +233,8 @@ pub trait WindowLayout: Debug {
     where
         Self: Sized;
 
+    fn layout_type(&self) -> LayoutType;
+
     fn layout_description(&self) -> String;
 
     fn placement_help(&self) -> String;
@@ -113,10 +273,25 @@ pub trait WindowLayout: Debug {
         new_window: &WindowRef,
     ) -> LayoutResult<()>;
 
+    /// Swaps the places of two windows in the layout
+    fn swap_windows(&mut self, a: &WindowRef, b: &WindowRef) -> LayoutResult<()>;
+
+    /// Moves a window up one level in the layout, out of its current group
+    fn promote_window(&mut self, window: &WindowRef) -> LayoutResult<()>;
+
     fn remove_window(&mut self, window: &WindowRef) -> LayoutResult<()>;
 
     fn resize_window(&mut self, window: &WindowRef, bounds: &Bounds) -> LayoutResult<()>;
 
+    /// Moves the edge of a window on the given side outward by `delta_px`, or inward when
+    /// negative.
+    fn resize_window_step(
+        &mut self,
+        window: &WindowRef,
+        side: Side,
+        delta_px: i32,
+    ) -> LayoutResult<()>;
+
     fn resize_handles(&self) -> Vec<ResizeHandle> {
         Vec::new()
     }
@@ -130,9 +305,76 @@ pub trait WindowLayout: Debug {
         false
     }
 
+    /// Resets the split a resize handle controls back to its default. Returns whether anything
+    /// changed.
+    fn reset_resize_handle(&mut self, _handle: &ResizeHandle) -> bool {
+        false
+    }
+
+    /// Sizes windows relative to each other by a weight per window. Layouts without adjustable
+    /// sizes ignore this.
+    fn balance_weighted(&mut self, _weight_of: &dyn Fn(&WindowRef) -> f32) {}
+
+    /// Sizes each split by the golden ratio. Layouts without adjustable sizes ignore this.
+    fn apply_golden_ratio(&mut self) {}
+
     fn debug_layout(&self) -> String;
 
     fn config_changed(&mut self) {}
 
     fn set_bounds(&mut self, bounds: Bounds);
+
+    /// Tiles with these gaps instead of the global ones, or the global ones again when `None`
+    fn set_gaps(&mut self, gaps: Option<Gaps>);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::window::gapped_bounds;
+
+    #[test]
+    fn test_layout_help_is_deterministic() {
+        for layout_type in LayoutType::ALL {
+            let config = Config {
+                default_layout: layout_type,
+                ..Default::default()
+            };
+            let help = layout_help(configured_layout(&config).as_ref());
+
+            assert_eq!(help, layout_help(configured_layout(&config).as_ref()));
+            assert!(help.contains("Placement targets:"));
+            assert!(help.contains("Example layout:\n"));
+            assert!(!help.ends_with('\n'));
+        }
+    }
+
+    #[test]
+    fn test_outer_gap_is_the_edge_inset() {
+        let partition = Bounds::new(100, 50, 1600, 900);
+        let mut gaps = Gaps {
+            window: 20,
+            partition: 40,
+            outer: Some(12),
+        };
+
+        // What a window filling the tiling area ends up with on screen
+        let inset = |gaps: &Gaps| {
+            let window = gapped_bounds(&tiling_area_with_gaps(&partition, gaps), gaps.window, 1.0);
+            let right = |b: &Bounds| b.position.x + b.size.width as i32;
+            let bottom = |b: &Bounds| b.position.y + b.size.height as i32;
+            (
+                window.position.x - partition.position.x,
+                window.position.y - partition.position.y,
+                right(&partition) - right(&window),
+                bottom(&partition) - bottom(&window),
+            )
+        };
+        assert_eq!(inset(&gaps), (12, 12, 12, 12));
+
+        // Unset falls back to the partition gap
+        gaps.outer = None;
+        let root = tiling_area_with_gaps(&partition, &gaps);
+        assert_eq!(root, Bounds::new(130, 80, 1540, 840));
+    }
 }
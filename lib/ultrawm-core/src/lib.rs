@@ -4,11 +4,12 @@
 use crate::event_loop_main::EventLoopMain;
 use crate::event_loop_wm::EventLoopWM;
 use crate::platform::{
-    EventBridge, EventDispatcher, PlatformError, PlatformEvents, PlatformEventsImpl, WMEvent,
+    EventBridge, EventDispatcher, PlatformError, PlatformEvents, PlatformEventsImpl, PlatformImpl,
+    WMEvent,
 };
 use crate::tray::UltraWMTray;
 use crate::workspace::WorkspaceId;
-use log::error;
+use log::{error, warn};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex, OnceLock};
 use std::{process, thread};
@@ -21,6 +22,7 @@ pub mod config;
 pub(crate) mod event_handlers;
 mod event_loop_main;
 pub mod event_loop_wm;
+pub mod ipc;
 mod layouts;
 pub mod menu;
 pub mod overlay;
@@ -29,11 +31,13 @@ pub mod paths;
 pub mod platform;
 mod resize_handle;
 mod serialization;
+pub mod snapshot;
 mod thread_lock;
 pub mod tile_preview_handler;
 mod tile_result;
 pub mod tray;
 mod window;
+mod window_rules;
 mod wm;
 mod workspace;
 mod workspace_animator;
@@ -43,12 +47,22 @@ use crate::platform::input_state::InputState;
 use crate::wm::WMError;
 pub use commands::{
     register_commands, CommandContext, CommandDef, CommandId, AI_ORGANIZE_ALL_WINDOWS,
-    AI_ORGANIZE_CURRENT_WINDOW, CLOSE_WINDOW, FLOAT_WINDOW, MINIMIZE_WINDOW,
+    AI_ORGANIZE_CANCEL, AI_ORGANIZE_CONFIRM, AI_ORGANIZE_CURRENT_WINDOW, BALANCE_WEIGHTED,
+    CLOSE_CANCEL, CLOSE_CONFIRM, CLOSE_OTHERS, CLOSE_WINDOW, CLOSE_WORKSPACE_WINDOWS, CYCLE_LAYOUT,
+    DECREASE_GAPS, FLOAT_ALL, FLOAT_WINDOW, FOCUS_NEXT, FOCUS_NEXT_IN_APP, FOCUS_PREV,
+    FOCUS_PREV_IN_APP, GOLDEN_RATIO_LAYOUT, INCREASE_GAPS, MINIMIZE_WINDOW, MOVE_TO_DISPLAY_NEXT,
+    MOVE_TO_DISPLAY_PREV, ORGANIZE_HEURISTIC, PROMOTE_WINDOW, REDO, RENAME_WORKSPACE,
+    RESIZE_GROW_HEIGHT, RESIZE_GROW_WIDTH, RESIZE_SHRINK_HEIGHT, RESIZE_SHRINK_WIDTH,
+    SCRATCHPAD_TOGGLE, SET_LAYOUT, SPLIT_AND_OPEN_DOWN, SPLIT_AND_OPEN_LEFT, SPLIT_AND_OPEN_RIGHT,
+    SPLIT_AND_OPEN_UP, SWAP_DOWN, SWAP_LARGEST, SWAP_LEFT, SWAP_RIGHT, SWAP_SMALLEST, SWAP_UP,
+    SWITCH_WORKSPACE, TILE_ALL, TOGGLE_ALWAYS_ON_TOP, TOGGLE_ASPECT_LOCK, TOGGLE_FULLSCREEN,
+    TOGGLE_STICKY, TOGGLE_TILING, UNDO,
 };
 pub use config::Config;
 pub use event_loop_main::run_on_main_thread_blocking;
 pub use platform::inteceptor::Interceptor;
-pub use platform::{ContextMenuRequest, Platform, Position, WindowId};
+pub use platform::{Bounds, ContextMenuRequest, CursorType, Platform, Position, WindowId};
+pub use workspace_animator::{AnimationKind, AnimationKindStats, AnimationStats};
 
 static GLOBAL_EVENT_DISPATCHER: OnceLock<EventDispatcher> = OnceLock::new();
 
@@ -108,6 +122,12 @@ pub fn reset_layout() -> UltraWMResult<()> {
     serialization::reset_layout().map_err(|_| "Failed to reset layout".into())
 }
 
+/// Load and save the layout from the given file instead of the default layout file.
+/// Must be called before the window manager is started.
+pub fn set_layout_file(path: std::path::PathBuf) -> UltraWMResult<()> {
+    serialization::set_layout_file_path(path).map_err(|e| e.to_string().into())
+}
+
 pub fn start_with_config(config: Config) -> UltraWMResult<()> {
     Config::set_config(config);
     start()
@@ -185,12 +205,158 @@ pub fn place_window_relative(
     }
 }
 
+/// Moves a window to the given bounds. Floating windows are put right there, tiled windows are
+/// tiled where the center of the bounds is. Must not be called from an async context.
+pub fn place_window_at(window_id: WindowId, bounds: Bounds, floating: bool) -> Result<(), String> {
+    let dispatcher = GLOBAL_EVENT_DISPATCHER
+        .get()
+        .cloned()
+        .ok_or("Window manager is not running")?;
+    let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+    dispatcher.send(WMEvent::PlaceWindowAt(
+        window_id, bounds, floating, reply_tx,
+    ));
+    reply_rx
+        .blocking_recv()
+        .map_err(|_| "Window manager stopped".to_string())?
+}
+
 pub fn float_window(window_id: WindowId) {
     if let Some(dispatcher) = GLOBAL_EVENT_DISPATCHER.get().cloned() {
         dispatcher.send(WMEvent::FloatWindow(window_id));
     }
 }
 
+/// Previews a proposed arrangement, applying it only once `AI_ORGANIZE_CONFIRM` is triggered
+pub fn preview_organize_plan(plan: ai::OrganizePlan) {
+    if let Some(dispatcher) = GLOBAL_EVENT_DISPATCHER.get().cloned() {
+        dispatcher.send(WMEvent::PreviewOrganizePlan(plan));
+    }
+}
+
+pub fn expire_organize_plan(plan_id: ai::OrganizePlanId) {
+    if let Some(dispatcher) = GLOBAL_EVENT_DISPATCHER.get().cloned() {
+        dispatcher.send(WMEvent::OrganizePlanExpired(plan_id));
+    }
+}
+
+/// Recalculates every workspace and moves all tiled windows back to their tiled position,
+/// e.g. after an external tool moved windows around.
+pub fn relayout_all() {
+    if let Some(dispatcher) = GLOBAL_EVENT_DISPATCHER.get().cloned() {
+        dispatcher.send(WMEvent::Relayout);
+    }
+}
+
+/// Asks the window manager for a snapshot of the current layout and waits for the reply.
+/// Returns None if the window manager isn't running. Must not be called from an async context.
+pub fn snapshot() -> Option<snapshot::LayoutSnapshot> {
+    let dispatcher = GLOBAL_EVENT_DISPATCHER.get().cloned()?;
+    let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+    dispatcher.send(WMEvent::Snapshot(reply_tx));
+    reply_rx.blocking_recv().ok()
+}
+
+/// Saves the focused workspace's layout as a named preset. Must not be called from an async
+/// context.
+pub fn save_layout_preset(name: &str) -> Result<(), String> {
+    let dispatcher = GLOBAL_EVENT_DISPATCHER
+        .get()
+        .cloned()
+        .ok_or("Window manager is not running")?;
+    let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+    dispatcher.send(WMEvent::SaveLayoutPreset(name.to_string(), reply_tx));
+    reply_rx
+        .blocking_recv()
+        .map_err(|_| "Window manager stopped".to_string())?
+}
+
+/// Applies a named preset to the focused workspace. Must not be called from an async context.
+pub fn load_layout_preset(name: &str) -> Result<(), String> {
+    let dispatcher = GLOBAL_EVENT_DISPATCHER
+        .get()
+        .cloned()
+        .ok_or("Window manager is not running")?;
+    let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+    dispatcher.send(WMEvent::LoadLayoutPreset(name.to_string(), reply_tx));
+    reply_rx
+        .blocking_recv()
+        .map_err(|_| "Window manager stopped".to_string())?
+}
+
+/// Runs a command with an optional argument and waits for its result, unlike
+/// `trigger_command`. Must not be called from an async context.
+pub fn run_command(command_name: &str, argument: Option<&str>) -> Result<(), String> {
+    let dispatcher = GLOBAL_EVENT_DISPATCHER
+        .get()
+        .cloned()
+        .ok_or("Window manager is not running")?;
+    let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+    dispatcher.send(WMEvent::RunCommand(
+        command_name.to_string(),
+        argument.map(str::to_string),
+        reply_tx,
+    ));
+    reply_rx
+        .blocking_recv()
+        .map_err(|_| "Window manager stopped".to_string())?
+}
+
+/// Shows the named workspace on its partition. Must not be called from an async context.
+pub fn switch_workspace(name: &str) -> Result<(), String> {
+    let dispatcher = GLOBAL_EVENT_DISPATCHER
+        .get()
+        .cloned()
+        .ok_or("Window manager is not running")?;
+    let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+    dispatcher.send(WMEvent::SwitchWorkspace(name.to_string(), reply_tx));
+    reply_rx
+        .blocking_recv()
+        .map_err(|_| "Window manager stopped".to_string())?
+}
+
+/// The names of all saved layout presets
+pub fn list_layout_presets() -> Vec<String> {
+    serialization::list_layout_presets()
+}
+
+/// Checks the displays workspaces are pinned to in the config are connected. Doesn't need the
+/// window manager to be running.
+pub fn validate_displays(config: &Config) -> UltraWMResult<()> {
+    let displays = Platform::list_all_displays()?;
+    config
+        .validate_displays(&displays)
+        .map_err(|e| e.to_string().into())
+}
+
+/// Describes the configured default layout and its placement targets, for scripts that place
+/// windows. Doesn't need the window manager to be running.
+pub fn layout_help(config: &Config) -> String {
+    let layout = layouts::configured_layout(config);
+    layouts::layout_help(layout.as_ref())
+}
+
+/// Asks the AI how it would organize the open windows and returns its layouts as YAML, without
+/// moving anything. Doesn't need the window manager to be running.
+pub fn ai_preview(config: Config) -> Result<String, ai::AiLayoutError> {
+    Config::set_config(config);
+    let runtime =
+        tokio::runtime::Runtime::new().map_err(|e| ai::AiLayoutError::Runtime(e.to_string()))?;
+    let response = runtime.block_on(ai::layout::preview_organize())?;
+    ai::layout::preview_yaml(&response)
+}
+
+/// Whether the window manager was started in this process
+pub fn is_running() -> bool {
+    GLOBAL_EVENT_DISPATCHER.get().is_some()
+}
+
+/// Returns the most recent layout published by the window manager. This reads a shared copy
+/// and never waits on the WM thread, so it is safe to poll, e.g. from a status bar.
+pub fn get_layout_snapshot() -> Arc<snapshot::LayoutSnapshot> {
+    snapshot::current()
+}
+
 pub fn start() -> UltraWMResult<()> {
     let bridge = EventBridge::new();
     let dispatcher = bridge.dispatcher();
@@ -198,6 +364,10 @@ pub fn start() -> UltraWMResult<()> {
     // Store the dispatcher globally for later use
     GLOBAL_EVENT_DISPATCHER.set(dispatcher.clone()).unwrap();
 
+    if let Err(e) = ipc::start_server() {
+        warn!("Could not start IPC server: {e}");
+    }
+
     unsafe {
         PlatformEvents::initialize(dispatcher)?;
     }
@@ -242,6 +412,7 @@ pub fn start() -> UltraWMResult<()> {
         PlatformEvents::finalize()?;
     }
 
+    ipc::stop_server();
     Ok(())
 }
 
@@ -2,7 +2,7 @@ use crate::menu::accelerator::keybind_to_accelerator;
 use crate::{CommandDef, Config};
 use log::{debug, warn};
 use muda::accelerator::Accelerator;
-use muda::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use muda::{CheckMenuItem, IsMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tray_icon::menu::MenuEvent as TrayMenuEvent;
@@ -73,6 +73,8 @@ impl MenuSystem {
 
 pub struct MenuBuilder {
     menu: Menu,
+    /// Submenus being filled in, innermost last. Items are appended to the innermost one.
+    submenus: Vec<Submenu>,
     context: Option<crate::CommandContext>,
     check_items: Arc<Mutex<HashMap<String, (CheckMenuItem, ConfigGetterFnArc)>>>,
 }
@@ -81,6 +83,7 @@ impl MenuBuilder {
     pub fn new() -> Self {
         Self {
             menu: Menu::new(),
+            submenus: Vec::new(),
             context: None,
             check_items: Arc::new(Mutex::new(HashMap::new())),
         }
@@ -91,33 +94,70 @@ impl MenuBuilder {
         self
     }
 
+    fn append(&mut self, item: &dyn IsMenuItem) -> Result<(), Box<dyn std::error::Error>> {
+        match self.submenus.last() {
+            Some(submenu) => submenu.append(item)?,
+            None => self.menu.append(item)?,
+        }
+        Ok(())
+    }
+
     pub fn add_label(&mut self, text: &str) -> Result<(), Box<dyn std::error::Error>> {
         let item = MenuItem::new(text, false, None);
-        self.menu.append(&item)?;
+        self.append(&item)?;
         Ok(())
     }
 
     pub fn add_separator(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let separator = PredefinedMenuItem::separator();
-        self.menu.append(&separator)?;
+        self.append(&separator)?;
+        Ok(())
+    }
+
+    /// Starts a submenu. Items added until `end_submenu` go in it.
+    pub fn begin_submenu(&mut self, text: &str) {
+        self.submenus.push(Submenu::new(text, true));
+    }
+
+    pub fn end_submenu(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(submenu) = self.submenus.pop() {
+            self.append(&submenu)?;
+        }
         Ok(())
     }
 
     pub fn add_command(
         &mut self,
         cmd: &'static crate::CommandDef,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.add_command_with(cmd, cmd.display_name, None)
+    }
+
+    /// Adds a command under its own label, passing `argument` to it when clicked
+    pub fn add_command_with(
+        &mut self,
+        cmd: &'static crate::CommandDef,
+        text: &str,
+        argument: Option<String>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         if cmd.requires_window && self.context.is_none() {
             return Ok(());
         }
 
         let accelerator = get_command_accelerator(cmd);
-        let item_id = format!("cmd:{}", cmd.id);
-        let item = MenuItem::with_id(item_id.clone(), cmd.display_name, true, accelerator);
-        self.menu.append(&item)?;
+        let item_id = match &argument {
+            Some(argument) => format!("cmd:{}:{}", cmd.id, argument),
+            None => format!("cmd:{}", cmd.id),
+        };
+        let item = MenuItem::with_id(item_id.clone(), text, true, accelerator);
+        self.append(&item)?;
 
         let cmd_id = cmd.id.to_string();
-        let context = self.context.clone();
+        let mut context = self.context.clone();
+        if argument.is_some() {
+            let context = context.get_or_insert_with(crate::CommandContext::new);
+            context.argument = argument;
+        }
         MenuSystem::register_callback(
             item_id,
             Box::new(move || {
@@ -135,7 +175,7 @@ impl MenuBuilder {
         let item = MenuItem::new(text, true, None);
         let id = item.id().clone();
         let id_str = id.0.as_str().to_string();
-        self.menu.append(&item)?;
+        self.append(&item)?;
 
         MenuSystem::register_callback(id_str, Box::new(callback));
 
@@ -156,7 +196,7 @@ impl MenuBuilder {
         let item = CheckMenuItem::new(text, true, initial_value, None);
         let id = item.id().clone();
         let id_str = id.0.as_str().to_string();
-        self.menu.append(&item)?;
+        self.append(&item)?;
 
         let getter_arc = Arc::new(Box::new(config_getter.clone()) as ConfigGetterFn);
         if self.context.is_none() {
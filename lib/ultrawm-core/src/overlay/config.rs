@@ -28,5 +28,6 @@ pub enum OverlayWindowCommand {
     Show,
     Hide,
     MoveTo(Bounds),
+    SetBackgroundColor(Color),
     Exit,
 }
@@ -1,6 +1,7 @@
 use crate::overlay::OverlayWindowConfig;
 use crate::platform::{Bounds, PlatformResult};
 use skia_safe::Canvas;
+use std::any::Any;
 
 pub trait OverlayContent: Send + 'static {
     fn config(&self) -> OverlayWindowConfig;
@@ -12,4 +13,13 @@ pub trait OverlayContent: Send + 'static {
     fn on_hide(&mut self) {}
 
     fn on_bounds_changed(&mut self, _bounds: &Bounds) {}
+
+    /// Frames per second this overlay animates and redraws at, `None` uses
+    /// `overlay_animation_fps`
+    fn preferred_fps(&self) -> Option<u32> {
+        None
+    }
+
+    /// Allows `Overlay::update` to reach the concrete content type
+    fn as_any_mut(&mut self) -> &mut dyn Any;
 }
@@ -3,7 +3,10 @@ use crate::overlay::manager::OverlayManager;
 use crate::overlay::OverlayId;
 use crate::overlay::OverlayWindowCommand;
 use crate::platform::Bounds;
+use skia_safe::Color;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
 
 /// Handle to an overlay window - provides ergonomic API
 pub struct Overlay {
@@ -29,12 +32,28 @@ impl Overlay {
             .send_command(self.id, OverlayWindowCommand::Hide);
     }
 
+    /// Hide the overlay after a delay. Abort the returned task to keep it shown.
+    pub fn hide_after(&self, delay: Duration) -> JoinHandle<()> {
+        let manager = self.manager.clone();
+        let id = self.id;
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            manager.send_command(id, OverlayWindowCommand::Hide);
+        })
+    }
+
     /// Move the overlay to new bounds
     pub fn move_to(&self, bounds: &Bounds) {
         self.manager
             .send_command(self.id, OverlayWindowCommand::MoveTo(bounds.clone()));
     }
 
+    /// Animate the overlay background to a new color
+    pub fn set_background_color(&self, color: Color) {
+        self.manager
+            .send_command(self.id, OverlayWindowCommand::SetBackgroundColor(color));
+    }
+
     /// Update the overlay content
     pub fn update_content<F>(&self, f: F)
     where
@@ -43,6 +62,19 @@ impl Overlay {
         self.manager.update_content(self.id, f);
     }
 
+    /// Update the overlay content if it is a `T`
+    pub fn update<T, F>(&self, f: F)
+    where
+        T: OverlayContent,
+        F: FnOnce(&mut T) + Send + 'static,
+    {
+        self.update_content(move |content| {
+            if let Some(content) = content.as_any_mut().downcast_mut::<T>() {
+                f(content);
+            }
+        });
+    }
+
     /// Get the overlay ID
     pub fn id(&self) -> OverlayId {
         self.id
@@ -1,9 +1,9 @@
-use crate::animation::{ease_in_out_cubic, Animator};
+use crate::animation::Animator;
 use crate::event_loop_main::get_event_loop_blocking;
 use crate::overlay::content::OverlayContent;
 use crate::overlay::handle::Overlay;
 use crate::overlay::OverlayId;
-use crate::overlay::{OverlayWindowCommand, OverlayWindowConfig};
+use crate::overlay::{OverlayWindowBackgroundStyle, OverlayWindowCommand, OverlayWindowConfig};
 use crate::platform::{Bounds, PlatformOverlay, PlatformOverlayImpl, PlatformResult, WindowId};
 use log::{debug, error};
 use skia_safe::{surfaces, Color, Surface};
@@ -62,10 +62,9 @@ impl OverlayManager {
         let mut next_id: OverlayId = 1;
         let mut running = true;
 
-        let mut frame_timer = Instant::now();
-        // Use global overlay animation FPS from config
+        // How often to check for commands while nothing needs drawing
         let overlay_fps = crate::config::Config::overlay_animation_fps().max(1);
-        let target_frame_duration = Duration::from_secs_f64(1.0 / overlay_fps as f64);
+        let idle_frame_duration = Duration::from_secs_f64(1.0 / overlay_fps as f64);
 
         while running {
             // Process all pending commands
@@ -122,36 +121,41 @@ impl OverlayManager {
                 break;
             }
 
-            // Update and render all overlays
+            // Update and render the overlays whose next frame is due, each at its own rate
             let now = Instant::now();
-            let elapsed = now.duration_since(frame_timer);
+            let mut to_remove = Vec::new();
+            let mut next_frame: Option<Duration> = None;
 
-            if elapsed >= target_frame_duration {
-                let mut to_remove = Vec::new();
+            for (id, state) in overlays.iter_mut() {
+                if !state.needs_render() {
+                    continue;
+                }
 
-                for (id, state) in overlays.iter_mut() {
+                if state.frame_timer.tick(now) {
                     if state.is_animating() {
                         state.animate_frame();
                     }
-
-                    if state.needs_render() {
-                        if let Err(e) = state.render() {
-                            error!("Failed to render overlay {}: {}", id, e);
-                            to_remove.push(*id);
-                        }
+                    if let Err(e) = state.render() {
+                        error!("Failed to render overlay {}: {}", id, e);
+                        to_remove.push(*id);
+                        continue;
+                    }
+                    if !state.needs_render() {
+                        continue;
                     }
                 }
 
-                // Remove failed overlays
-                for id in to_remove {
-                    overlays.remove(&id);
-                }
+                let wait = state.frame_timer.until_next(now);
+                next_frame = Some(next_frame.map_or(wait, |next| next.min(wait)));
+            }
 
-                frame_timer = now;
-            } else {
-                // Sleep until next frame
-                tokio::time::sleep(target_frame_duration - elapsed).await;
+            // Remove failed overlays
+            for id in to_remove {
+                overlays.remove(&id);
             }
+
+            // Sleep until the next frame is due
+            tokio::time::sleep(next_frame.unwrap_or(idle_frame_duration)).await;
         }
     }
 
@@ -200,6 +204,10 @@ impl OverlayManager {
         });
 
         let (window, handle) = window.ok_or("Failed to create window on main thread")?;
+        let ease_fn = crate::config::Config::animation_easing().ease_fn();
+        let fps = content
+            .preferred_fps()
+            .unwrap_or_else(crate::config::Config::overlay_animation_fps);
 
         Ok(OverlayState {
             id,
@@ -209,13 +217,15 @@ impl OverlayManager {
             last_size: (0, 0),
             config,
             content,
-            fade_animator: Animator::new(0.0, 0.0, ease_in_out_cubic),
-            move_animator: Animator::new(Bounds::default(), Bounds::default(), ease_in_out_cubic),
+            fade_animator: Animator::new(0.0, 0.0, ease_fn),
+            move_animator: Animator::new(Bounds::default(), Bounds::default(), ease_fn),
+            color_animator: None,
             native_fade_animation: false,
             native_move_animation: false,
             visible: false,
             needs_render: false,
             current_bounds: Bounds::default(),
+            frame_timer: FrameTimer::new(fps),
         })
     }
 
@@ -286,11 +296,48 @@ struct OverlayState {
     content: Box<dyn OverlayContent>,
     fade_animator: Animator<f32>,
     move_animator: Animator<Bounds>,
+    color_animator: Option<Animator<Color>>,
     native_fade_animation: bool,
     native_move_animation: bool,
     visible: bool,
     needs_render: bool,
     current_bounds: Bounds,
+    frame_timer: FrameTimer,
+}
+
+/// Paces one overlay's frames at its own rate
+struct FrameTimer {
+    frame: Duration,
+    last_frame: Option<Instant>,
+}
+
+impl FrameTimer {
+    fn new(fps: u32) -> Self {
+        Self {
+            frame: Duration::from_secs_f64(1.0 / fps.max(1) as f64),
+            last_frame: None,
+        }
+    }
+
+    /// Whether a frame is due, counting it as drawn if it is. The first frame after a quiet
+    /// spell is always due, so a change shows straight away.
+    fn tick(&mut self, now: Instant) -> bool {
+        if self
+            .last_frame
+            .is_some_and(|last| now.duration_since(last) < self.frame)
+        {
+            return false;
+        }
+        self.last_frame = Some(now);
+        true
+    }
+
+    /// How long until the next frame is due
+    fn until_next(&self, now: Instant) -> Duration {
+        self.last_frame.map_or(Duration::ZERO, |last| {
+            (last + self.frame).saturating_duration_since(now)
+        })
+    }
 }
 
 impl OverlayState {
@@ -328,12 +375,21 @@ impl OverlayState {
                 }
                 self.needs_render = true;
             }
+            OverlayWindowCommand::SetBackgroundColor(color) => {
+                self.start_color(color, self.config.move_animation_ms);
+                self.needs_render = true;
+            }
             OverlayWindowCommand::Exit => {}
         }
     }
 
     fn is_animating(&self) -> bool {
-        self.fade_animator.is_animating() || self.move_animator.is_animating()
+        self.fade_animator.is_animating()
+            || self.move_animator.is_animating()
+            || self
+                .color_animator
+                .as_ref()
+                .is_some_and(|a| a.is_animating())
     }
 
     fn needs_render(&self) -> bool {
@@ -376,6 +432,14 @@ impl OverlayState {
             self.set_bounds(target_bounds);
         }
 
+        if let Some(color_animator) = &mut self.color_animator {
+            if let Some(color) = color_animator.update() {
+                if let Some(background) = &mut self.config.background {
+                    background.color = color;
+                }
+            }
+        }
+
         self.needs_render = true;
     }
 
@@ -418,6 +482,33 @@ impl OverlayState {
         }
     }
 
+    fn start_color(&mut self, color: Color, duration_ms: u32) {
+        let Some(background) = &mut self.config.background else {
+            self.config.background = Some(OverlayWindowBackgroundStyle {
+                opacity: 1.0,
+                color,
+            });
+            return;
+        };
+
+        if duration_ms == 0 || !self.visible {
+            background.color = color;
+            self.color_animator = None;
+            return;
+        }
+
+        let from = background.color;
+        self.color_animator
+            .get_or_insert_with(|| {
+                Animator::new(
+                    from,
+                    color,
+                    crate::config::Config::animation_easing().ease_fn(),
+                )
+            })
+            .start_from(from, color, duration_ms);
+    }
+
     fn set_bounds(&mut self, bounds: Bounds) {
         // Update current_bounds immediately so surface size is correct
         if bounds != self.current_bounds {
@@ -488,3 +579,33 @@ impl OverlayState {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_overlay_renders_less_often() {
+        let mut static_timer = FrameTimer::new(1);
+        let mut animating_timer = FrameTimer::new(60);
+        let start = Instant::now();
+
+        // Both want a frame every millisecond for two seconds
+        let (mut static_frames, mut animating_frames) = (0, 0);
+        for ms in 0..2000 {
+            let now = start + Duration::from_millis(ms);
+            static_frames += static_timer.tick(now) as u32;
+            animating_frames += animating_timer.tick(now) as u32;
+        }
+
+        assert_eq!(static_frames, 2);
+        assert!(
+            (110..=120).contains(&animating_frames),
+            "{animating_frames}"
+        );
+        assert_eq!(
+            static_timer.until_next(start + Duration::from_millis(1500)),
+            Duration::from_millis(500)
+        );
+    }
+}
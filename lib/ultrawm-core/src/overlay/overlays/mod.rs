@@ -1,5 +1,7 @@
 pub mod resize_handle_overlay;
 pub mod tile_preview_overlay;
+pub mod workspace_indicator_overlay;
 
 pub use resize_handle_overlay::ResizeHandleOverlay;
 pub use tile_preview_overlay::TilePreviewOverlay;
+pub use workspace_indicator_overlay::WorkspaceIndicatorOverlay;
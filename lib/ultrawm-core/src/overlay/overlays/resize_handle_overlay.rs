@@ -3,6 +3,7 @@ use crate::overlay::OverlayContent;
 use crate::overlay::{OverlayWindowBackgroundStyle, OverlayWindowBorderStyle, OverlayWindowConfig};
 use crate::platform::{Bounds, PlatformResult};
 use skia_safe::{Canvas, Color};
+use std::any::Any;
 
 pub struct ResizeHandleOverlay;
 
@@ -39,6 +40,10 @@ impl OverlayContent for ResizeHandleOverlay {
         // Resize handle is just a border/background overlay, no custom drawing needed
         Ok(())
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 impl Default for ResizeHandleOverlay {
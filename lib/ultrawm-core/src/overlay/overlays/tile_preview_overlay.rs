@@ -3,6 +3,7 @@ use crate::overlay::OverlayContent;
 use crate::overlay::{OverlayWindowBackgroundStyle, OverlayWindowConfig};
 use crate::platform::{Bounds, PlatformResult};
 use skia_safe::{Canvas, Color};
+use std::any::Any;
 
 pub struct TilePreviewOverlay;
 
@@ -40,6 +41,10 @@ impl OverlayContent for TilePreviewOverlay {
         // Tile preview is just a background overlay, no custom drawing needed
         Ok(())
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 impl Default for TilePreviewOverlay {
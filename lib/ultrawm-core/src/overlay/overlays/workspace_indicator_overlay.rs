@@ -0,0 +1,97 @@
+use crate::config::Config;
+use crate::overlay::OverlayContent;
+use crate::overlay::{OverlayWindowBackgroundStyle, OverlayWindowConfig};
+use crate::platform::{Bounds, PlatformResult};
+use skia_safe::{Canvas, Color, Font, FontMgr, FontStyle, Paint, Typeface};
+use std::any::Any;
+
+const FONT_SIZE: f32 = 20.0;
+const PADDING: f32 = 16.0;
+
+/// Shows the name of the active workspace and its position, e.g. "Dev  2/4".
+pub struct WorkspaceIndicatorOverlay {
+    name: String,
+    index: usize,
+    total: usize,
+    /// Loaded on the first draw, looking the font up is too slow to do every frame
+    typeface: Option<Typeface>,
+}
+
+impl WorkspaceIndicatorOverlay {
+    pub fn new() -> Self {
+        Self {
+            name: String::new(),
+            index: 0,
+            total: 0,
+            typeface: None,
+        }
+    }
+
+    pub fn set_workspace(&mut self, name: String, index: usize, total: usize) {
+        self.name = name;
+        self.index = index;
+        self.total = total;
+    }
+}
+
+impl OverlayContent for WorkspaceIndicatorOverlay {
+    fn config(&self) -> OverlayWindowConfig {
+        let config = Config::current();
+        OverlayWindowConfig {
+            fade_animation_ms: config.tile_preview_animation_ms,
+            move_animation_ms: 0,
+            border_radius: 12.0,
+            blur: true,
+            background: Some(OverlayWindowBackgroundStyle {
+                color: Color::from_rgb(35, 35, 35),
+                opacity: 0.75,
+            }),
+            border: None,
+        }
+    }
+
+    /// Only ever fades in and out, so it doesn't need the full overlay frame rate
+    fn preferred_fps(&self) -> Option<u32> {
+        Some(30)
+    }
+
+    fn draw(&mut self, canvas: &Canvas, bounds: &Bounds) -> PlatformResult<()> {
+        if self.typeface.is_none() {
+            self.typeface = FontMgr::default().legacy_make_typeface(None, FontStyle::bold());
+        }
+        let typeface = self
+            .typeface
+            .clone()
+            .ok_or("Could not load font for workspace indicator")?;
+        let font = Font::from_typeface(typeface, FONT_SIZE);
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(Color::WHITE);
+
+        let baseline = (bounds.size.height as f32 + FONT_SIZE * 0.7) / 2.0;
+        canvas.draw_str(&self.name, (PADDING, baseline), &font, &paint);
+
+        let index = format!("{}/{}", self.index, self.total);
+        let (index_width, _) = font.measure_str(&index, Some(&paint));
+        paint.set_color(Color::from_rgb(170, 170, 170));
+        canvas.draw_str(
+            &index,
+            (bounds.size.width as f32 - PADDING - index_width, baseline),
+            &font,
+            &paint,
+        );
+
+        Ok(())
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Default for WorkspaceIndicatorOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
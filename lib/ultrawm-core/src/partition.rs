@@ -10,6 +10,8 @@ pub struct Partition {
     id: PartitionId,
     name: String,
     bounds: Bounds,
+    /// Scale factor of the display the partition is on
+    scale_factor: f32,
     current_workspace: Option<WorkspaceId>,
     assigned_workspaces: HashSet<WorkspaceId>,
 }
@@ -23,6 +25,7 @@ impl Partition {
             id,
             name,
             bounds,
+            scale_factor: 1.0,
             current_workspace: None,
             assigned_workspaces: HashSet::new(),
         }
@@ -40,6 +43,18 @@ impl Partition {
         &self.bounds
     }
 
+    pub fn set_bounds(&mut self, bounds: Bounds) {
+        self.bounds = bounds;
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
     pub fn current_workspace(&self) -> Option<WorkspaceId> {
         self.current_workspace
     }
@@ -48,6 +63,15 @@ impl Partition {
         &self.assigned_workspaces
     }
 
+    /// Makes an assigned workspace the active one. Returns false if it isn't assigned here.
+    pub fn set_current_workspace(&mut self, workspace_id: WorkspaceId) -> bool {
+        if !self.assigned_workspaces.contains(&workspace_id) {
+            return false;
+        }
+        self.current_workspace = Some(workspace_id);
+        true
+    }
+
     pub fn assign_workspace(&mut self, workspace_id: WorkspaceId) {
         self.current_workspace.get_or_insert(workspace_id);
         self.assigned_workspaces.insert(workspace_id);
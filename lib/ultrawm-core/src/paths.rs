@@ -15,9 +15,17 @@ pub fn log_file_path() -> Option<PathBuf> {
     data_dir().map(|dir| dir.join("ultrawm.log"))
 }
 
-/// Get the path to the default config file
+/// Get the path to the default config file, config.toml if there is one, otherwise
+/// config.yaml
 pub fn default_config_path() -> Option<PathBuf> {
-    config_dir().map(|dir| dir.join("config.yaml"))
+    config_dir().map(|dir| {
+        let toml = dir.join("config.toml");
+        if toml.exists() {
+            toml
+        } else {
+            dir.join("config.yaml")
+        }
+    })
 }
 
 /// Get the path to the layout file
@@ -25,6 +33,25 @@ pub fn layout_file_path() -> Option<PathBuf> {
     data_dir().map(|dir| dir.join("layout.yaml"))
 }
 
+/// Get the directory named layout presets are stored in
+pub fn presets_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("presets"))
+}
+
+/// Get the path of the socket used to send commands to a running instance
+#[cfg(unix)]
+pub fn ipc_socket_path() -> Option<PathBuf> {
+    dirs::runtime_dir()
+        .or_else(data_dir)
+        .map(|dir| dir.join("ultrawm.sock"))
+}
+
+/// Get the name of the pipe used to send commands to a running instance
+#[cfg(windows)]
+pub fn ipc_socket_path() -> Option<PathBuf> {
+    Some(PathBuf::from(r"\\.\pipe\ultrawm"))
+}
+
 /// Ensure the data directory exists
 pub fn ensure_data_dir() -> Option<PathBuf> {
     data_dir().and_then(|dir| {
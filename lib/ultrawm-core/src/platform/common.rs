@@ -1,9 +1,12 @@
+use crate::ai::{OrganizePlan, OrganizePlanId};
 use crate::platform::PlatformWindow;
+use crate::snapshot::LayoutSnapshot;
 use crate::workspace::WorkspaceId;
 use crate::{commands::CommandContext, layouts::PlacementTarget};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use thiserror::Error;
+use tokio::sync::oneshot;
 use winit::keyboard::KeyCode;
 
 pub const DEFAULT_MOVEMENT_THRESHOLD: i32 = 5;
@@ -43,6 +46,10 @@ pub enum WMEvent {
     WindowOpened(PlatformWindow),
     WindowClosed(WindowId),
     WindowFocused(WindowId),
+    /// The window's title changed, e.g. when switching tabs in a browser
+    WindowTitleChanged(WindowId, String),
+    /// The window entered or left full screen. Full screen windows aren't tiled.
+    WindowFullscreenChanged(WindowId, bool),
     /// The window has begun to be moved or resized. Preferably only sent once per window
     /// transformation, but may be sent multiple times. Extra events will be ignored.
     WindowTransformStarted(WindowId),
@@ -56,10 +63,30 @@ pub enum WMEvent {
     Shutdown,
     ConfigChanged,
     CommandTriggered(String, Option<CommandContext>),
+    /// Run a command with an optional argument and reply with the result
+    RunCommand(String, Option<String>, oneshot::Sender<Result<(), String>>),
     ShowContextMenu(ContextMenuRequest),
     LoadLayoutToWorkspace(WorkspaceId, serde_yaml::Value),
     PlaceWindowRelative(WindowId, PlacementTarget, WorkspaceId),
+    /// Move a window to the given bounds, floating or tiled there, and reply with the result
+    PlaceWindowAt(WindowId, Bounds, bool, oneshot::Sender<Result<(), String>>),
     FloatWindow(WindowId),
+    /// Show a proposed arrangement and hold it until it's confirmed or cancelled
+    PreviewOrganizePlan(OrganizePlan),
+    /// Nobody confirmed or cancelled the plan in time
+    OrganizePlanExpired(OrganizePlanId),
+    /// Recalculate every workspace and move all tiled windows back into place
+    Relayout,
+    /// A display was plugged in, unplugged or rearranged
+    DisplaysChanged,
+    /// Reply with a snapshot of the current layout
+    Snapshot(oneshot::Sender<LayoutSnapshot>),
+    /// Save the focused workspace as a named preset and reply with the result
+    SaveLayoutPreset(String, oneshot::Sender<Result<(), String>>),
+    /// Apply a named preset to the focused workspace and reply with the result
+    LoadLayoutPreset(String, oneshot::Sender<Result<(), String>>),
+    /// Show the named workspace on its partition and reply with the result
+    SwitchWorkspace(String, oneshot::Sender<Result<(), String>>),
 }
 
 /// Request to show a context menu
@@ -232,6 +259,55 @@ impl Bounds {
             && self.position.y + self.size.height as i32 > other.position.y
     }
 
+    /// The overlapping area of both bounds, or None if they don't overlap. Bounds that only
+    /// touch along an edge don't overlap.
+    pub fn intersection(&self, other: &Bounds) -> Option<Bounds> {
+        let left = self.position.x.max(other.position.x);
+        let top = self.position.y.max(other.position.y);
+        let right = (self.position.x + self.size.width as i32)
+            .min(other.position.x + other.size.width as i32);
+        let bottom = (self.position.y + self.size.height as i32)
+            .min(other.position.y + other.size.height as i32);
+
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        Some(Bounds::new(
+            left,
+            top,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        ))
+    }
+
+    pub fn intersection_area(&self, other: &Bounds) -> u64 {
+        self.intersection(other).map_or(0, |bounds| bounds.area())
+    }
+
+    /// How much of `other` lies inside these bounds, from 0 to 1
+    pub fn contains_ratio(&self, other: &Bounds) -> f32 {
+        let area = other.area();
+        if area == 0 {
+            return 0.0;
+        }
+        self.intersection_area(other) as f32 / area as f32
+    }
+
+    pub fn area(&self) -> u64 {
+        self.size.width as u64 * self.size.height as u64
+    }
+
+    /// The same bounds moved by `dx` and `dy`
+    pub fn translated(&self, dx: i32, dy: i32) -> Bounds {
+        Bounds::new(
+            self.position.x + dx,
+            self.position.y + dy,
+            self.size.width,
+            self.size.height,
+        )
+    }
+
     pub fn offset_top(&mut self, offset: i32) {
         self.position.y += offset;
         self.size.height = (self.size.height as i32 - offset) as u32;
@@ -286,12 +362,14 @@ impl Position {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Display {
     pub id: DisplayId,
     pub name: String,
     pub bounds: Bounds,
     pub work_area: Bounds,
+    /// How many coordinate units one logical pixel takes up on this display
+    pub scale_factor: f32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -310,3 +388,45 @@ pub enum CursorType {
     Wait,
     NotAllowed,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intersection_of_overlapping_bounds() {
+        let a = Bounds::new(0, 0, 100, 100);
+        let b = Bounds::new(50, 25, 100, 100);
+
+        assert_eq!(a.intersection(&b), Some(Bounds::new(50, 25, 50, 75)));
+        assert_eq!(a.intersection(&b), b.intersection(&a));
+        assert_eq!(a.intersection_area(&b), 3750);
+        assert_eq!(a.contains_ratio(&b), 0.375);
+        assert_eq!(a.contains_ratio(&Bounds::new(10, 10, 20, 20)), 1.0);
+    }
+
+    #[test]
+    fn test_touching_and_disjoint_bounds_do_not_intersect() {
+        let a = Bounds::new(0, 0, 100, 100);
+        let touching = Bounds::new(100, 0, 100, 100);
+        let disjoint = Bounds::new(300, 300, 10, 10);
+
+        for other in [&touching, &disjoint] {
+            assert_eq!(a.intersection(other), None);
+            assert_eq!(a.intersection_area(other), 0);
+            assert_eq!(a.contains_ratio(other), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_intersection_with_negative_coordinates() {
+        // A display to the left of and above the main one
+        let a = Bounds::new(-1920, -200, 1920, 1080);
+        let b = Bounds::new(-100, 800, 200, 200);
+
+        assert_eq!(a.intersection(&b), Some(Bounds::new(-100, 800, 100, 80)));
+        assert_eq!(a.intersection_area(&b), 8000);
+        assert_eq!(a.contains_ratio(&b), 0.2);
+        assert_eq!(a.intersection(&Bounds::new(0, -200, 10, 10)), None);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::platform::PlatformResult;
+use log::warn;
+use std::thread;
+
+/// Lists the windows for each key, usually a process id, concurrently and returns them in the
+/// order of `keys`. `list` runs on worker threads, so it must only make calls that are safe off
+/// the main thread. If a worker fails or panics, everything is listed again serially on the
+/// calling thread, so a platform that turns out not to like it still gets a complete list.
+pub fn enumerate_concurrently<K, T, F>(keys: Vec<K>, list: F) -> PlatformResult<Vec<T>>
+where
+    K: Copy + Send + Sync,
+    T: Send,
+    F: Fn(K) -> PlatformResult<Vec<T>> + Sync,
+{
+    let workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(keys.len());
+    if workers <= 1 {
+        return enumerate_serially(keys, list);
+    }
+
+    let chunk_size = keys.len().div_ceil(workers);
+    let results = thread::scope(|scope| {
+        let handles: Vec<_> = keys
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| enumerate_serially(chunk.to_vec(), &list)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .map_err(|_| "Window enumeration thread panicked")
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let mut items = Vec::new();
+    for result in results {
+        match result {
+            Ok(Ok(chunk)) => items.extend(chunk),
+            Ok(Err(e)) => {
+                warn!("Concurrent window enumeration failed, retrying serially: {e}");
+                return enumerate_serially(keys, list);
+            }
+            Err(e) => {
+                warn!("{e}, retrying serially");
+                return enumerate_serially(keys, list);
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Lists the windows for each key one after another on the calling thread
+pub fn enumerate_serially<K, T, F>(keys: Vec<K>, list: F) -> PlatformResult<Vec<T>>
+where
+    F: Fn(K) -> PlatformResult<Vec<T>>,
+{
+    let mut items = Vec::new();
+    for key in keys {
+        items.extend(list(key)?);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatform;
+    use crate::platform::{PlatformWindowImpl, ProcessId, WindowId};
+    use std::collections::HashSet;
+
+    fn window_ids(windows: &[crate::platform::PlatformWindow]) -> Vec<WindowId> {
+        windows.iter().map(|w| w.id()).collect()
+    }
+
+    #[test]
+    fn test_concurrent_matches_serial() {
+        let pids: Vec<ProcessId> = (1..=50).collect();
+
+        let serial = enumerate_serially(pids.clone(), MockPlatform::list_windows_for_pid).unwrap();
+        let concurrent = enumerate_concurrently(pids, MockPlatform::list_windows_for_pid).unwrap();
+
+        assert!(!serial.is_empty());
+        assert_eq!(window_ids(&concurrent), window_ids(&serial));
+        let unique: HashSet<_> = window_ids(&concurrent).into_iter().collect();
+        assert_eq!(unique.len(), serial.len());
+    }
+
+    #[test]
+    fn test_falls_back_to_serial_when_a_worker_fails() {
+        let caller = thread::current().id();
+        let pids: Vec<ProcessId> = (1..=50).collect();
+
+        let windows = enumerate_concurrently(pids.clone(), |pid| {
+            if thread::current().id() != caller {
+                panic!("not allowed off the calling thread");
+            }
+            MockPlatform::list_windows_for_pid(pid)
+        })
+        .unwrap();
+
+        let serial = enumerate_serially(pids, MockPlatform::list_windows_for_pid).unwrap();
+        assert_eq!(window_ids(&windows), window_ids(&serial));
+    }
+
+    #[test]
+    fn test_errors_are_returned_when_serial_fails_too() {
+        let result = enumerate_concurrently((1..=50).collect(), |pid: ProcessId| {
+            if pid == 25 {
+                Err("No windows".into())
+            } else {
+                MockPlatform::list_windows_for_pid(pid)
+            }
+        });
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "bench-tests")]
+    #[test]
+    fn bench_concurrent_enumeration() {
+        use std::time::{Duration, Instant};
+
+        // Stands in for an app that is slow to answer accessibility requests
+        let slow_list = |pid: ProcessId| {
+            thread::sleep(Duration::from_millis(5));
+            MockPlatform::list_windows_for_pid(pid)
+        };
+        let pids: Vec<ProcessId> = (1..=100).collect();
+
+        let start = Instant::now();
+        enumerate_serially(pids.clone(), slow_list).unwrap();
+        let serial = start.elapsed();
+
+        let start = Instant::now();
+        enumerate_concurrently(pids, slow_list).unwrap();
+        let concurrent = start.elapsed();
+
+        println!("serial: {serial:?}, concurrent: {concurrent:?}");
+        if thread::available_parallelism().map_or(1, |n| n.get()) > 1 {
+            assert!(concurrent < serial);
+        }
+    }
+}
@@ -185,9 +185,16 @@ impl EventListenerAX {
         } else if notification == notification::window_moved() {
             WMEvent::WindowTransformStarted(window.id())
         } else if notification == notification::window_resized() {
+            // There's no notification for full screen, but entering or leaving it resizes
+            self.dispatcher.send(WMEvent::WindowFullscreenChanged(
+                window.id(),
+                window.is_fullscreen(),
+            ));
             WMEvent::WindowTransformStarted(window.id())
         } else if notification == notification::element_destroyed() {
             WMEvent::WindowClosed(window.id())
+        } else if notification == notification::title_changed() {
+            WMEvent::WindowTitleChanged(window.id(), window.title())
         } else {
             warn!("Unknown notification: {:?}", notification);
             return Ok(());
@@ -222,12 +229,10 @@ impl EventListenerAX {
         window: &AXUIElementExt,
     ) -> PlatformResult<Vec<EventNotification>> {
         let d = &Some(MacOSPlatformWindow::new(window.clone())?);
-        Ok(vec![self.notify(
-            observer,
-            window,
-            notification::element_destroyed(),
-            d,
-        )?])
+        Ok(vec![
+            self.notify(observer, window, notification::element_destroyed(), d)?,
+            self.notify(observer, window, notification::title_changed(), d)?,
+        ])
     }
 
     fn notify(
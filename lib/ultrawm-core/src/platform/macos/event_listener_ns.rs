@@ -1,9 +1,11 @@
 use crate::platform::macos::event_listener_ax::EventListenerAX;
-use crate::platform::{PlatformResult, ProcessId};
+use crate::platform::macos::platform::MacOSPlatform;
+use crate::platform::{EventDispatcher, PlatformResult, ProcessId, WMEvent};
 use block2::{Block, StackBlock};
 use log::{error, warn};
 use objc2::rc::Retained;
 use objc2::runtime::{NSObjectProtocol, ProtocolObject};
+use objc2_app_kit::NSApplicationDidChangeScreenParametersNotification;
 use objc2_app_kit::NSRunningApplication;
 use objc2_app_kit::NSWorkspace;
 use objc2_app_kit::NSWorkspaceApplicationKey;
@@ -11,7 +13,7 @@ use objc2_app_kit::{
     NSWorkspaceDidLaunchApplicationNotification, NSWorkspaceDidTerminateApplicationNotification,
 };
 use objc2_foundation::NSOperationQueue;
-use objc2_foundation::{NSNotification, NSNotificationName};
+use objc2_foundation::{NSNotification, NSNotificationCenter, NSNotificationName};
 use std::cell::RefCell;
 use std::ptr::NonNull;
 use std::rc::Rc;
@@ -20,13 +22,18 @@ type EventHandlerBlock = Block<dyn Fn(NonNull<NSNotification>) -> ()>;
 
 pub struct EventListenerNS {
     listener_ax: Rc<RefCell<EventListenerAX>>,
+    dispatcher: EventDispatcher,
     observers: Vec<Retained<ProtocolObject<dyn NSObjectProtocol>>>,
 }
 
 impl EventListenerNS {
-    pub fn run(listener_ax: Rc<RefCell<EventListenerAX>>) -> PlatformResult<Rc<RefCell<Self>>> {
+    pub fn run(
+        listener_ax: Rc<RefCell<EventListenerAX>>,
+        dispatcher: EventDispatcher,
+    ) -> PlatformResult<Rc<RefCell<Self>>> {
         let listener = Rc::new(RefCell::new(Self {
             listener_ax,
+            dispatcher,
             observers: Vec::new(),
         }));
 
@@ -50,6 +57,17 @@ impl EventListenerNS {
 
     fn handle_event(&self, notification: NonNull<NSNotification>) -> PlatformResult<()> {
         unsafe {
+            if notification
+                .as_ref()
+                .name()
+                .isEqualToString(NSApplicationDidChangeScreenParametersNotification)
+            {
+                // Refresh before telling the WM so it reads the new screens
+                MacOSPlatform::refresh_screens()?;
+                self.dispatcher.send(WMEvent::DisplaysChanged);
+                return Ok(());
+            }
+
             let user_info = notification
                 .as_ref()
                 .userInfo()
@@ -84,6 +102,16 @@ impl EventListenerNS {
         unsafe {
             self.add_observer(NSWorkspaceDidLaunchApplicationNotification, block)?;
             self.add_observer(NSWorkspaceDidTerminateApplicationNotification, block)?;
+
+            // Screen changes are posted by the app, not the workspace
+            let observer = NSNotificationCenter::defaultCenter()
+                .addObserverForName_object_queue_usingBlock(
+                    Some(NSApplicationDidChangeScreenParametersNotification),
+                    None,
+                    Some(&NSOperationQueue::mainQueue()),
+                    &block,
+                );
+            self.observers.push(observer);
         }
 
         Ok(())
@@ -24,7 +24,7 @@ unsafe impl PlatformEventsImpl for MacOSPlatformEvents {
         MacOSPlatform::initialize_screens()?;
 
         let listener_ax = EventListenerAX::run(dispatcher.clone())?;
-        let listener_ns = EventListenerNS::run(listener_ax.clone())?;
+        let listener_ns = EventListenerNS::run(listener_ax.clone(), dispatcher.clone())?;
         let listener_cg = EventListenerCG::run(dispatcher.clone())?;
 
         // Intentionally leak the listeners so they live for the program duration
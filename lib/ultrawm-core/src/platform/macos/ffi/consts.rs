@@ -14,9 +14,11 @@ pub mod accessibility_attribute {
     cf_str!(subrole, "AXSubrole");
     cf_str!(position, "AXPosition");
     cf_str!(size, "AXSize");
+    cf_str!(minimum_size, "AXMinimumSize");
     cf_str!(windows, "AXWindows");
     cf_str!(focused_window, "AXFocusedWindow");
     cf_str!(minimized, "AXMinimized");
+    cf_str!(full_screen, "AXFullScreen");
 }
 
 pub mod window_info {
@@ -38,6 +40,7 @@ pub mod notification {
     cf_str!(window_moved, "AXWindowMoved");
     cf_str!(window_resized, "AXWindowResized");
     cf_str!(element_destroyed, "AXUIElementDestroyed");
+    cf_str!(title_changed, "AXTitleChanged");
 }
 
 pub mod run_loop_mode {
@@ -247,11 +247,21 @@ impl AXUIElementExt {
             .map(|v| v.into_size().ok_or(kAXErrorFailure).unwrap())
     }
 
+    pub fn minimum_size(&self) -> AXResult<CGSize> {
+        self.copy_attribute_value::<AXValueExt>(accessibility_attribute::minimum_size())
+            .and_then(|v| v.into_size().ok_or(kAXErrorFailure))
+    }
+
     pub fn minimized(&self) -> AXResult<bool> {
         self.copy_attribute_value::<CFBoolean>(accessibility_attribute::minimized())
             .map(|b| bool::from(b))
     }
 
+    pub fn full_screen(&self) -> AXResult<bool> {
+        self.copy_attribute_value::<CFBoolean>(accessibility_attribute::full_screen())
+            .map(|b| bool::from(b))
+    }
+
     pub fn pid(&self) -> AXResult<pid_t> {
         self.element.get_pid()
     }
@@ -281,6 +291,18 @@ impl AXUIElementExt {
             .map_err(|e| e)
     }
 
+    pub fn set_full_screen(&self, full_screen: bool) -> AXResult<()> {
+        let value = if full_screen {
+            CFBoolean::true_value()
+        } else {
+            CFBoolean::false_value()
+        };
+        self.element.set_attribute_value(
+            accessibility_attribute::full_screen().as_concrete_TypeRef(),
+            value.as_CFTypeRef(),
+        )
+    }
+
     pub fn get_close_button(&self) -> AXResult<AXUIElementExt> {
         self.copy_attribute_value::<AXUIElementExt>(CFString::from_static_string("AXCloseButton"))
     }
@@ -1,3 +1,4 @@
+use crate::platform::enumerate::enumerate_concurrently;
 use crate::platform::inteceptor::Interceptor;
 use crate::platform::macos::ffi::{window_info, AXUIElementExt, CFArrayExt, CFDictionaryExt};
 use crate::platform::macos::ObserveError::NotManageable;
@@ -8,6 +9,7 @@ use crate::platform::{
 };
 use application_services::accessibility_ui::AXUIElement;
 use application_services::pid_t;
+use core_graphics::display::CGDisplay;
 use core_graphics::event::{CGEvent, CGEventTapLocation, CGEventType, CGMouseButton};
 use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 use core_graphics::window::{copy_window_info, kCGNullWindowID, kCGWindowListOptionAll};
@@ -18,14 +20,21 @@ use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 use objc2_foundation::{NSNumber, NSRect};
 use std::collections::HashSet;
 use std::sync::atomic::AtomicI32;
-use std::sync::OnceLock;
+use std::sync::RwLock;
 
 pub struct MacOSPlatform;
 
 static CURRENT_CURSOR_TYPE: AtomicI32 = AtomicI32::new(-1);
-static CACHED_SCREENS: OnceLock<Vec<CachedScreen>> = OnceLock::new();
-static MAX_SCREEN_TOP: OnceLock<i32> = OnceLock::new();
-static CGEVENT_Y_OFFSET: OnceLock<i32> = OnceLock::new();
+/// Screens as they were when last read from AppKit. Refreshed when the display configuration
+/// changes, which also moves the coordinate offsets below.
+static CACHED_SCREENS: RwLock<Option<ScreenCache>> = RwLock::new(None);
+
+#[derive(Debug, Clone)]
+struct ScreenCache {
+    screens: Vec<CachedScreen>,
+    max_screen_top: i32,
+    cgevent_y_offset: i32,
+}
 
 // TODO: Improve screens
 #[derive(Debug, Clone)]
@@ -55,10 +64,14 @@ impl MacOSPlatform {
     }
 
     pub fn initialize_screens() -> PlatformResult<()> {
-        if CACHED_SCREENS.get().is_some() {
+        if Self::screen_cache().is_some() {
             return Ok(());
         }
+        Self::refresh_screens()
+    }
 
+    /// Throws away the cached screens and reads them again. Has to run on the main thread.
+    pub fn refresh_screens() -> PlatformResult<()> {
         unsafe {
             let mtm = MainThreadMarker::new().unwrap();
             let displays = NSScreen::screens(mtm);
@@ -71,11 +84,6 @@ impl MacOSPlatform {
                 .map(|screen| screen.frame().origin.y as f64 + screen.frame().size.height as f64)
                 .fold(0.0, f64::max) as i32;
 
-            // Cache the max screen top for use in coordinate conversions
-            MAX_SCREEN_TOP
-                .set(max_screen_top)
-                .map_err(|_| PlatformError::Error("Failed to cache max screen top".to_string()))?;
-
             // Find the primary display (origin.y = 0 in macOS coordinates) and calculate
             // the offset needed to convert CGEvent coordinates to our coordinate system.
             // CGEvent uses Y=0 at the top of the primary display, but our system uses
@@ -89,9 +97,6 @@ impl MacOSPlatform {
             } else {
                 0
             };
-            CGEVENT_Y_OFFSET.set(cgevent_y_offset).map_err(|_| {
-                PlatformError::Error("Failed to cache CGEvent Y offset".to_string())
-            })?;
 
             for screen in displays {
                 let desc = screen.deviceDescription();
@@ -136,19 +141,31 @@ impl MacOSPlatform {
                 });
             }
 
-            CACHED_SCREENS
-                .set(result)
+            // Cache the max screen top and CGEvent offset for use in coordinate conversions
+            let mut cache = CACHED_SCREENS
+                .write()
                 .map_err(|_| PlatformError::Error("Failed to cache screens".to_string()))?;
+            *cache = Some(ScreenCache {
+                screens: result,
+                max_screen_top,
+                cgevent_y_offset,
+            });
         }
         Ok(())
     }
 
-    fn get_cached_screens() -> PlatformResult<&'static [CachedScreen]> {
-        if let Some(screens) = CACHED_SCREENS.get() {
-            Ok(screens)
-        } else {
-            Ok(CACHED_SCREENS.get().unwrap())
-        }
+    fn screen_cache() -> Option<ScreenCache> {
+        CACHED_SCREENS.read().ok()?.clone()
+    }
+
+    fn get_cached_screens() -> PlatformResult<Vec<CachedScreen>> {
+        Self::screen_cache()
+            .map(|cache| cache.screens)
+            .ok_or_else(|| "Screens have not been initialized".into())
+    }
+
+    fn cached_max_screen_top() -> Option<i32> {
+        Self::screen_cache().map(|cache| cache.max_screen_top)
     }
 
     fn get_screen_bounds_for_position(position: &Position) -> Option<Bounds> {
@@ -170,42 +187,33 @@ impl MacOSPlatform {
         screens.first().map(|screen| screen.bounds.clone())
     }
 
-    pub fn get_cgevent_y_offset() -> i32 {
-        CGEVENT_Y_OFFSET.get().copied().unwrap_or(0)
-    }
+    /// Lists the manageable windows of one app. Only makes AX calls, which are safe to make off
+    /// the main thread, so this can run on the enumeration workers. Anything that needs AppKit
+    /// (screens, running applications) has to stay out of here.
+    fn list_windows_for_pid(pid: u32) -> PlatformResult<Vec<MacOSPlatformWindow>> {
+        let app = AXUIElementExt::from(
+            AXUIElement::create_application(pid as pid_t)
+                .map_err(|_| format!("Could not create AXUIElement for pid {}", pid))?,
+        );
 
-    pub fn get_max_screen_top() -> i32 {
-        MAX_SCREEN_TOP.get().copied().unwrap_or(1080)
-    }
-}
+        match app_is_manageable(&app) {
+            Ok(_) => {}
+            Err(NotManageable(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        }
 
-impl PlatformImpl for MacOSPlatform {
-    fn list_visible_windows() -> PlatformResult<Vec<MacOSPlatformWindow>> {
         let mut windows = Vec::new();
-        for pid in MacOSPlatform::find_pids_with_windows()? {
-            let app = AXUIElementExt::from(
-                AXUIElement::create_application(pid as pid_t)
-                    .map_err(|_| format!("Could not create AXUIElement for pid {}", pid))?,
-            );
-
-            match app_is_manageable(&app) {
-                Ok(_) => {}
-                Err(NotManageable(_)) => continue,
-                Err(e) => return Err(e.into()),
-            }
+        if let Ok(app_windows) = app.windows() {
+            for window in app_windows {
+                match window_is_manageable(&window) {
+                    Ok(_) => {}
+                    Err(NotManageable(_)) => continue,
+                    Err(e) => return Err(e.into()),
+                }
 
-            if let Ok(app_windows) = app.windows() {
-                for window in app_windows {
-                    match window_is_manageable(&window) {
-                        Ok(_) => {}
-                        Err(NotManageable(_)) => continue,
-                        Err(e) => return Err(e.into()),
-                    }
-
-                    let window = MacOSPlatformWindow::new(window);
-                    if let Ok(window) = window {
-                        windows.push(window);
-                    }
+                let window = MacOSPlatformWindow::new(window);
+                if let Ok(window) = window {
+                    windows.push(window);
                 }
             }
         }
@@ -213,6 +221,26 @@ impl PlatformImpl for MacOSPlatform {
         Ok(windows)
     }
 
+    pub fn get_cgevent_y_offset() -> i32 {
+        Self::screen_cache()
+            .map(|cache| cache.cgevent_y_offset)
+            .unwrap_or(0)
+    }
+
+    pub fn get_max_screen_top() -> i32 {
+        Self::cached_max_screen_top().unwrap_or(1080)
+    }
+}
+
+impl PlatformImpl for MacOSPlatform {
+    fn list_visible_windows() -> PlatformResult<Vec<MacOSPlatformWindow>> {
+        let mut pids: Vec<u32> = MacOSPlatform::find_pids_with_windows()?
+            .into_iter()
+            .collect();
+        pids.sort();
+        enumerate_concurrently(pids, MacOSPlatform::list_windows_for_pid)
+    }
+
     fn list_all_displays() -> PlatformResult<Vec<Display>> {
         let screens = Self::get_cached_screens()?;
         Ok(screens
@@ -222,13 +250,16 @@ impl PlatformImpl for MacOSPlatform {
                 name: screen.name.clone(),
                 bounds: screen.bounds.clone(),
                 work_area: screen.work_area.clone(),
+                // AppKit works in points, which are already the same size on every screen
+                // whatever its backingScaleFactor is
+                scale_factor: 1.0,
             })
             .collect())
     }
 
     fn get_mouse_position() -> PlatformResult<Position> {
         let pos = NSEvent::mouseLocation();
-        let max_screen_top = MAX_SCREEN_TOP.get().copied().unwrap_or(1080);
+        let max_screen_top = Self::get_max_screen_top();
         Ok(Position::new(pos.x as i32, max_screen_top - pos.y as i32))
     }
 
@@ -242,6 +273,18 @@ impl PlatformImpl for MacOSPlatform {
         Ok(())
     }
 
+    fn warp_mouse(position: Position) -> PlatformResult<()> {
+        let y_offset = Self::get_cgevent_y_offset();
+        let screen_pos = core_graphics::geometry::CGPoint::new(
+            position.x as f64,
+            (position.y - y_offset) as f64,
+        );
+
+        CGDisplay::warp_mouse_cursor_position(screen_pos)
+            .map_err(|e| format!("Failed to warp mouse: {e:?}"))?;
+        Ok(())
+    }
+
     fn start_window_bounds_batch(_window_count: u32) -> PlatformResult<()> {
         // Not supported on macOS for now
         Ok(())
@@ -348,7 +391,7 @@ impl From<Bounds> for CGRect {
     fn from(value: Bounds) -> Self {
         // Use the cached max screen top for coordinate conversion
         // If not available, calculate from the screen bounds (fallback)
-        let max_screen_top = MAX_SCREEN_TOP.get().copied().unwrap_or_else(|| {
+        let max_screen_top = MacOSPlatform::cached_max_screen_top().unwrap_or_else(|| {
             let screen = MacOSPlatform::get_screen_bounds_for_position(&value.position)
                 .or_else(|| MacOSPlatform::get_default_screen_bounds())
                 .unwrap_or_else(|| Bounds::new(0, 0, 1920, 1080));
@@ -369,7 +412,7 @@ impl From<CGRect> for Bounds {
     fn from(value: NSRect) -> Self {
         // Use the cached max screen top for coordinate conversion
         // If not available, calculate from the screen bounds (fallback)
-        let max_screen_top = MAX_SCREEN_TOP.get().copied().unwrap_or_else(|| {
+        let max_screen_top = MacOSPlatform::cached_max_screen_top().unwrap_or_else(|| {
             let screen = MacOSPlatform::get_screen_bounds_for_position(&Position::new(
                 value.origin.x as i32,
                 value.origin.y as i32,
@@ -1,5 +1,7 @@
 use crate::event_loop_main::run_on_main_thread_blocking;
-use crate::platform::macos::ffi::{get_window_id, AXUIElementExt};
+use crate::platform::macos::ffi::{
+    _CGSDefaultConnection, get_window_id, AXUIElementExt, CGSSetWindowAlpha,
+};
 use crate::platform::macos::platform::MacOSPlatform;
 use crate::platform::traits::PlatformWindowImpl;
 use crate::platform::{Bounds, PlatformError, PlatformResult, Position, ProcessId, Size, WindowId};
@@ -81,6 +83,13 @@ impl PlatformWindowImpl for MacOSPlatformWindow {
             .to_string()
     }
 
+    fn app_name(&self) -> String {
+        self.app_element()
+            .ok()
+            .and_then(|app| app.title().ok())
+            .unwrap_or_default()
+    }
+
     fn position(&self) -> Position {
         let position = self
             .element
@@ -108,6 +117,19 @@ impl PlatformWindowImpl for MacOSPlatformWindow {
         !self.element.minimized().unwrap_or(false)
     }
 
+    fn min_size(&self) -> Option<Size> {
+        // Most apps don't report this attribute, those that do are usually the ones that care
+        let size = self.element.minimum_size().ok()?;
+        Some(Size {
+            width: size.width as u32,
+            height: size.height as u32,
+        })
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.element.full_screen().unwrap_or(false)
+    }
+
     fn set_bounds(&self, bounds: &Bounds) -> PlatformResult<()> {
         // Set size BEFORE position to avoid intermediate states where the window
         // temporarily exceeds screen bounds. This is important when shrinking a window
@@ -131,27 +153,10 @@ impl PlatformWindowImpl for MacOSPlatformWindow {
     /// AppleScript. This may not work if you have multiple windows of one
     /// application open, though.
     fn focus(&self) -> PlatformResult<()> {
-        let process_name = {
-            let app_element = unsafe {
-                let mut app_ref: AXUIElementRef = std::ptr::null();
-                let result = application_services::AXUIElementCopyAttributeValue(
-                    self.element.element.as_concrete_TypeRef(),
-                    CFString::new("AXParent").as_concrete_TypeRef(),
-                    &mut app_ref as *mut _ as *mut *const std::ffi::c_void,
-                );
-
-                if result == application_services::kAXErrorSuccess {
-                    AXUIElement::wrap_under_create_rule(app_ref)
-                } else {
-                    return Err("Could not get application element".into());
-                }
-            };
-
-            // Get the application name
-            let app_ext = AXUIElementExt::from(app_element);
-            app_ext.title().unwrap_or_else(|_| "unknown".to_string())
-        };
-
+        let process_name = self
+            .app_element()?
+            .title()
+            .unwrap_or_else(|_| "unknown".to_string());
         self.osa_focus(process_name)?;
         Ok(())
     }
@@ -162,6 +167,19 @@ impl PlatformWindowImpl for MacOSPlatformWindow {
         Ok(())
     }
 
+    /// Only works on windows owned by our own connection unless SIP is disabled, the same
+    /// limitation as `set_always_on_top`.
+    fn set_opacity(&self, opacity: f32) -> PlatformResult<()> {
+        unsafe {
+            CGSSetWindowAlpha(
+                _CGSDefaultConnection(),
+                self.id,
+                opacity.clamp(0.0, 1.0) as f64,
+            );
+        }
+        Ok(())
+    }
+
     fn close(&self) -> PlatformResult<()> {
         let close_button = self
             .element
@@ -183,12 +201,45 @@ impl PlatformWindowImpl for MacOSPlatformWindow {
         Ok(())
     }
 
+    fn unminimize(&self) -> PlatformResult<()> {
+        self.element
+            .set_minimized(false)
+            .map_err(|e| format!("Failed to restore window: {:?}", e))?;
+        Ok(())
+    }
+
+    fn set_fullscreen(&self, fullscreen: bool) -> PlatformResult<()> {
+        self.element
+            .set_full_screen(fullscreen)
+            .map_err(|e| format!("Failed to set full screen: {:?}", e))?;
+        Ok(())
+    }
+
     fn valid(&self) -> bool {
         self.element.position().is_ok() && self.element.size().is_ok()
     }
 }
 
 impl MacOSPlatformWindow {
+    fn app_element(&self) -> PlatformResult<AXUIElementExt> {
+        let app_element = unsafe {
+            let mut app_ref: AXUIElementRef = std::ptr::null();
+            let result = application_services::AXUIElementCopyAttributeValue(
+                self.element.element.as_concrete_TypeRef(),
+                CFString::new("AXParent").as_concrete_TypeRef(),
+                &mut app_ref as *mut _ as *mut *const std::ffi::c_void,
+            );
+
+            if result == application_services::kAXErrorSuccess {
+                AXUIElement::wrap_under_create_rule(app_ref)
+            } else {
+                return Err("Could not get application element".into());
+            }
+        };
+
+        Ok(AXUIElementExt::from(app_element))
+    }
+
     fn osa_focus(&self, process_name: String) -> PlatformResult<()> {
         run_on_main_thread_blocking(|| {
             OSA_SCRIPT.with(|script| {
@@ -1,10 +1,11 @@
 use crate::overlay::OverlayWindowConfig;
 use crate::platform::PlatformOverlayImpl;
 use crate::platform::{
-    Bounds, Display, EventDispatcher, PlatformEventsImpl, PlatformImpl, PlatformResult,
-    PlatformWindow, PlatformWindowImpl, Position, ProcessId, Size, WindowId,
+    Bounds, CursorType, Display, EventDispatcher, PlatformEventsImpl, PlatformImpl, PlatformResult,
+    PlatformWindow, PlatformWindowImpl, Position, ProcessId, Size, WMEvent, WindowId,
 };
 use skia_safe::Image;
+use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
 use winit::window::Window;
 
@@ -20,10 +21,64 @@ unsafe impl PlatformEventsImpl for MockPlatformEvents {
 
 pub struct MockPlatform;
 
+thread_local! {
+    static MOCK_DISPLAYS: RefCell<Vec<Display>> = const { RefCell::new(Vec::new()) };
+    static MOCK_MOUSE_WARPS: RefCell<Vec<Position>> = const { RefCell::new(Vec::new()) };
+    static MOCK_BOUNDS_CALLS: RefCell<Vec<MockBoundsCall>> = const { RefCell::new(Vec::new()) };
+    static MOCK_CURSOR_CALLS: RefCell<Vec<Option<CursorType>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A platform call that moves windows, in the order they were made on the current thread
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockBoundsCall {
+    StartBatch(u32),
+    SetBounds(WindowId),
+    EndBatch,
+}
+
 impl MockPlatform {
     pub fn get_max_screen_top() -> i32 {
         1080
     }
+
+    /// Sets the displays `list_all_displays` returns on the current thread
+    pub fn set_displays(displays: Vec<Display>) {
+        MOCK_DISPLAYS.with(|d| *d.borrow_mut() = displays);
+    }
+
+    /// Returns and clears the positions `warp_mouse` was called with on the current thread
+    pub fn take_mouse_warps() -> Vec<Position> {
+        MOCK_MOUSE_WARPS.with(|w| w.take())
+    }
+
+    /// A few made up windows for a process, the same every time for the same pid
+    pub fn list_windows_for_pid(pid: ProcessId) -> PlatformResult<Vec<MockPlatformWindow>> {
+        Ok((0..pid % 4)
+            .map(|i| {
+                let mut window = MockPlatformWindow::new(
+                    Position { x: 0, y: 0 },
+                    Size {
+                        width: 800,
+                        height: 600,
+                    },
+                    format!("Window {i} of {pid}"),
+                );
+                window.id = pid as WindowId * 10 + i as WindowId;
+                window.pid = pid;
+                window
+            })
+            .collect())
+    }
+
+    /// Returns and clears the cursors set on the current thread, `None` for each `reset_cursor`
+    pub fn take_cursor_calls() -> Vec<Option<CursorType>> {
+        MOCK_CURSOR_CALLS.with(|c| c.take())
+    }
+
+    /// Returns and clears the bounds batch and `set_bounds` calls made on the current thread
+    pub fn take_bounds_calls() -> Vec<MockBoundsCall> {
+        MOCK_BOUNDS_CALLS.with(|c| c.take())
+    }
 }
 
 impl PlatformImpl for MockPlatform {
@@ -32,26 +87,38 @@ impl PlatformImpl for MockPlatform {
     }
 
     fn list_all_displays() -> PlatformResult<Vec<Display>> {
-        Ok(vec![])
+        Ok(MOCK_DISPLAYS.with(|d| d.borrow().clone()))
     }
 
     fn get_mouse_position() -> PlatformResult<Position> {
         Ok(Position { x: 0, y: 0 })
     }
 
-    fn set_cursor(_cursor_type: crate::platform::CursorType) -> PlatformResult<()> {
+    fn set_cursor(cursor_type: CursorType) -> PlatformResult<()> {
+        MOCK_CURSOR_CALLS.with(|c| c.borrow_mut().push(Some(cursor_type)));
         Ok(())
     }
 
     fn reset_cursor() -> PlatformResult<()> {
+        MOCK_CURSOR_CALLS.with(|c| c.borrow_mut().push(None));
         Ok(())
     }
 
-    fn start_window_bounds_batch(_window_count: u32) -> PlatformResult<()> {
+    fn warp_mouse(position: Position) -> PlatformResult<()> {
+        MOCK_MOUSE_WARPS.with(|w| w.borrow_mut().push(position));
+        Ok(())
+    }
+
+    fn start_window_bounds_batch(window_count: u32) -> PlatformResult<()> {
+        MOCK_BOUNDS_CALLS.with(|c| {
+            c.borrow_mut()
+                .push(MockBoundsCall::StartBatch(window_count))
+        });
         Ok(())
     }
 
     fn end_window_bounds_batch() -> PlatformResult<()> {
+        MOCK_BOUNDS_CALLS.with(|c| c.borrow_mut().push(MockBoundsCall::EndBatch));
         Ok(())
     }
 
@@ -91,10 +158,27 @@ pub struct MockPlatformWindow {
     pub id: WindowId,
     pub pid: ProcessId,
     pub title: String,
+    pub app_name: String,
     pub position: Position,
     pub size: Size,
     pub visible: bool,
+    pub min_size: Option<Size>,
+    /// Makes `close` fail, like an app that refuses to quit
+    pub close_fails: bool,
     set_bounds_calls: Arc<Mutex<Vec<Bounds>>>,
+    /// Where `set_bounds` last moved the window, shared between clones like a real window
+    moved_to: Arc<Mutex<Option<Bounds>>>,
+    /// What `change_title` renamed the window to, shared between clones like a real window
+    retitled_to: Arc<Mutex<Option<String>>>,
+    focus_calls: Arc<Mutex<usize>>,
+    close_calls: Arc<Mutex<usize>>,
+    fullscreen: Arc<Mutex<bool>>,
+    set_fullscreen_calls: Arc<Mutex<Vec<bool>>>,
+    set_always_on_top_calls: Arc<Mutex<Vec<bool>>>,
+    title_calls: Arc<Mutex<usize>>,
+    minimize_calls: Arc<Mutex<usize>>,
+    unminimize_calls: Arc<Mutex<usize>>,
+    set_opacity_calls: Arc<Mutex<Vec<f32>>>,
 }
 impl MockPlatformWindow {
     pub fn new(position: Position, size: Size, title: String) -> Self {
@@ -102,10 +186,24 @@ impl MockPlatformWindow {
             id: 0,
             pid: 0,
             title,
+            app_name: String::new(),
             position,
             size,
             visible: false,
+            min_size: None,
+            close_fails: false,
             set_bounds_calls: Arc::new(Mutex::new(Vec::new())),
+            moved_to: Arc::new(Mutex::new(None)),
+            retitled_to: Arc::new(Mutex::new(None)),
+            focus_calls: Arc::new(Mutex::new(0)),
+            close_calls: Arc::new(Mutex::new(0)),
+            fullscreen: Arc::new(Mutex::new(false)),
+            set_fullscreen_calls: Arc::new(Mutex::new(Vec::new())),
+            set_always_on_top_calls: Arc::new(Mutex::new(Vec::new())),
+            title_calls: Arc::new(Mutex::new(0)),
+            minimize_calls: Arc::new(Mutex::new(0)),
+            unminimize_calls: Arc::new(Mutex::new(0)),
+            set_opacity_calls: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -116,6 +214,51 @@ impl MockPlatformWindow {
     pub fn clear_set_bounds_calls(&self) {
         self.set_bounds_calls.lock().unwrap().clear();
     }
+
+    pub fn get_focus_calls(&self) -> usize {
+        *self.focus_calls.lock().unwrap()
+    }
+
+    /// Puts the window in or out of full screen without being asked to, like the user clicking
+    /// its full screen button. Shared between clones like a real window.
+    pub fn user_set_fullscreen(&self, fullscreen: bool) {
+        *self.fullscreen.lock().unwrap() = fullscreen;
+    }
+
+    pub fn get_set_fullscreen_calls(&self) -> Vec<bool> {
+        self.set_fullscreen_calls.lock().unwrap().clone()
+    }
+
+    pub fn get_set_always_on_top_calls(&self) -> Vec<bool> {
+        self.set_always_on_top_calls.lock().unwrap().clone()
+    }
+
+    pub fn get_close_calls(&self) -> usize {
+        *self.close_calls.lock().unwrap()
+    }
+
+    pub fn get_title_calls(&self) -> usize {
+        *self.title_calls.lock().unwrap()
+    }
+
+    pub fn get_minimize_calls(&self) -> usize {
+        *self.minimize_calls.lock().unwrap()
+    }
+
+    pub fn get_unminimize_calls(&self) -> usize {
+        *self.unminimize_calls.lock().unwrap()
+    }
+
+    pub fn get_set_opacity_calls(&self) -> Vec<f32> {
+        self.set_opacity_calls.lock().unwrap().clone()
+    }
+
+    /// Renames the window, returning the event a real platform would send for it
+    pub fn change_title(&mut self, title: &str) -> WMEvent {
+        self.title = title.to_string();
+        self.retitled_to.lock().unwrap().replace(self.title.clone());
+        WMEvent::WindowTitleChanged(self.id, self.title.clone())
+    }
 }
 impl PlatformWindowImpl for MockPlatformWindow {
     fn id(&self) -> WindowId {
@@ -125,31 +268,75 @@ impl PlatformWindowImpl for MockPlatformWindow {
         self.pid
     }
     fn title(&self) -> String {
-        self.title.clone()
+        *self.title_calls.lock().unwrap() += 1;
+        match &*self.retitled_to.lock().unwrap() {
+            Some(title) => title.clone(),
+            None => self.title.clone(),
+        }
+    }
+    fn app_name(&self) -> String {
+        self.app_name.clone()
     }
     fn position(&self) -> Position {
-        self.position.clone()
+        match &*self.moved_to.lock().unwrap() {
+            Some(bounds) => bounds.position.clone(),
+            None => self.position.clone(),
+        }
     }
     fn size(&self) -> Size {
-        self.size.clone()
+        match &*self.moved_to.lock().unwrap() {
+            Some(bounds) => bounds.size.clone(),
+            None => self.size.clone(),
+        }
     }
     fn visible(&self) -> bool {
         self.visible
     }
+    fn min_size(&self) -> Option<Size> {
+        self.min_size.clone()
+    }
+    fn is_fullscreen(&self) -> bool {
+        *self.fullscreen.lock().unwrap()
+    }
     fn set_bounds(&self, bounds: &Bounds) -> PlatformResult<()> {
         self.set_bounds_calls.lock().unwrap().push(bounds.clone());
+        self.moved_to.lock().unwrap().replace(bounds.clone());
+        MOCK_BOUNDS_CALLS.with(|c| c.borrow_mut().push(MockBoundsCall::SetBounds(self.id)));
         Ok(())
     }
     fn focus(&self) -> PlatformResult<()> {
+        *self.focus_calls.lock().unwrap() += 1;
+        Ok(())
+    }
+    fn set_always_on_top(&self, always_on_top: bool) -> PlatformResult<()> {
+        self.set_always_on_top_calls
+            .lock()
+            .unwrap()
+            .push(always_on_top);
         Ok(())
     }
-    fn set_always_on_top(&self, _always_on_top: bool) -> PlatformResult<()> {
+    fn set_opacity(&self, opacity: f32) -> PlatformResult<()> {
+        self.set_opacity_calls.lock().unwrap().push(opacity);
         Ok(())
     }
     fn close(&self) -> PlatformResult<()> {
+        *self.close_calls.lock().unwrap() += 1;
+        if self.close_fails {
+            return Err("Window refused to close".into());
+        }
         Ok(())
     }
     fn minimize(&self) -> PlatformResult<()> {
+        *self.minimize_calls.lock().unwrap() += 1;
+        Ok(())
+    }
+    fn unminimize(&self) -> PlatformResult<()> {
+        *self.unminimize_calls.lock().unwrap() += 1;
+        Ok(())
+    }
+    fn set_fullscreen(&self, fullscreen: bool) -> PlatformResult<()> {
+        self.set_fullscreen_calls.lock().unwrap().push(fullscreen);
+        self.user_set_fullscreen(fullscreen);
         Ok(())
     }
     fn valid(&self) -> bool {
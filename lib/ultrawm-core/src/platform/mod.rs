@@ -3,6 +3,8 @@ pub use event_bridge::*;
 pub use traits::*;
 
 mod common;
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) mod enumerate;
 mod event_bridge;
 pub mod input_state;
 pub mod inteceptor;
@@ -39,6 +39,9 @@ where
     /// Resets the cursor to the system default.
     fn reset_cursor() -> PlatformResult<()>;
 
+    /// Moves the mouse cursor to the specified position.
+    fn warp_mouse(position: Position) -> PlatformResult<()>;
+
     fn start_window_bounds_batch(window_count: u32) -> PlatformResult<()>;
     fn end_window_bounds_batch() -> PlatformResult<()>;
 
@@ -85,17 +88,63 @@ where
     fn id(&self) -> WindowId;
     fn pid(&self) -> ProcessId;
     fn title(&self) -> String;
+    /// Name of the application that owns the window
+    fn app_name(&self) -> String;
     fn position(&self) -> Position;
     fn size(&self) -> Size;
     fn visible(&self) -> bool;
+    /// Smallest size the app allows the window to be resized to, if it reports one
+    fn min_size(&self) -> Option<Size>;
+    /// Whether the window is full screen, like macOS native full screen in its own space
+    fn is_fullscreen(&self) -> bool;
 
     fn set_bounds(&self, bounds: &Bounds) -> PlatformResult<()>;
     fn focus(&self) -> PlatformResult<()>;
     fn set_always_on_top(&self, always_on_top: bool) -> PlatformResult<()>;
+    /// Sets the opacity of the window, from 0.0 (transparent) to 1.0 (opaque)
+    fn set_opacity(&self, opacity: f32) -> PlatformResult<()>;
     fn close(&self) -> PlatformResult<()>;
     fn minimize(&self) -> PlatformResult<()>;
+    /// Restores a minimized window, does nothing if it isn't minimized
+    fn unminimize(&self) -> PlatformResult<()>;
+    /// Puts the window in or out of native full screen
+    fn set_fullscreen(&self, fullscreen: bool) -> PlatformResult<()>;
 
     /// Checks if the window is still valid and can be queried/manipulated.
     /// Returns false if the window has been closed, invalidated, or is otherwise inaccessible.
     fn valid(&self) -> bool;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatformWindow;
+
+    /// What the window manager relies on from a platform window: bounds that were set are read
+    /// back, and clones are the same window. Only the mock is checked, the real platforms aren't
+    /// built for tests.
+    fn assert_window_conforms<W: PlatformWindowImpl>(window: &W) {
+        let bounds = Bounds::new(-100, 50, 640, 480);
+        window.set_bounds(&bounds).unwrap();
+        assert_eq!(window.position(), bounds.position);
+        assert_eq!(window.size(), bounds.size);
+
+        let clone = window.clone();
+        assert_eq!(clone.id(), window.id());
+        assert_eq!(clone.pid(), window.pid());
+        assert_eq!(clone.position(), bounds.position);
+        assert!(clone.valid());
+    }
+
+    #[test]
+    fn test_mock_window_conforms() {
+        let mut window = MockPlatformWindow::new(
+            Position::new(0, 0),
+            Size::new(800, 600),
+            "Mock Window".to_string(),
+        );
+        window.id = 1;
+        window.pid = 2;
+        assert_window_conforms(&window);
+    }
+}
@@ -7,24 +7,34 @@ use crate::platform::{
 use log::warn;
 use serde::{Deserialize, Serialize};
 use std::sync::{Mutex, OnceLock};
+use windows::core::w;
 use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
 use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
 use windows::Win32::UI::HiDpi::{
     SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
-    CallNextHookEx, GetCursorPos, SetWindowsHookExW, UnhookWindowsHookEx, EVENT_OBJECT_DESTROY,
-    EVENT_OBJECT_FOCUS, EVENT_OBJECT_SHOW, EVENT_SYSTEM_MINIMIZEEND, EVENT_SYSTEM_MINIMIZESTART,
-    EVENT_SYSTEM_MOVESIZESTART, HHOOK, KBDLLHOOKSTRUCT, MSLLHOOKSTRUCT, WH_KEYBOARD_LL,
-    WH_MOUSE_LL, WINEVENT_OUTOFCONTEXT, WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN, WM_LBUTTONUP,
-    WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SYSKEYDOWN,
-    WM_SYSKEYUP, WM_XBUTTONDOWN, WM_XBUTTONUP, XBUTTON1, XBUTTON2,
+    CallNextHookEx, CreateWindowExW, DefWindowProcW, DestroyWindow, GetAncestor, GetCursorPos,
+    RegisterClassW, SetWindowsHookExW, UnhookWindowsHookEx, EVENT_OBJECT_DESTROY,
+    EVENT_OBJECT_FOCUS, EVENT_OBJECT_LOCATIONCHANGE, EVENT_OBJECT_NAMECHANGE, EVENT_OBJECT_SHOW,
+    EVENT_SYSTEM_MINIMIZEEND, EVENT_SYSTEM_MINIMIZESTART, EVENT_SYSTEM_MOVESIZESTART, GA_ROOT,
+    HHOOK, KBDLLHOOKSTRUCT, MSLLHOOKSTRUCT, OBJID_WINDOW, WH_KEYBOARD_LL, WH_MOUSE_LL,
+    WINDOW_EX_STYLE, WINDOW_STYLE, WINEVENT_OUTOFCONTEXT, WM_DISPLAYCHANGE, WM_KEYDOWN, WM_KEYUP,
+    WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_RBUTTONDOWN,
+    WM_RBUTTONUP, WM_SYSKEYDOWN, WM_SYSKEYUP, WM_XBUTTONDOWN, WM_XBUTTONUP, WNDCLASSW, XBUTTON1,
+    XBUTTON2,
 };
 use winit::keyboard::KeyCode;
 
 static EVENT_DISPATCHER: OnceLock<EventDispatcher> = OnceLock::new();
 static WIN_EVENT_HOOKS: Mutex<Vec<isize>> = Mutex::new(Vec::new());
 static LOW_LEVEL_HOOKS: Mutex<Vec<isize>> = Mutex::new(Vec::new());
+/// Windows last seen full screen, so only entering and leaving it are sent
+static FULLSCREEN_WINDOWS: Mutex<Vec<WindowId>> = Mutex::new(Vec::new());
+/// Hidden window that gets `WM_DISPLAYCHANGE`. It has to be a top level window, message only
+/// windows don't get broadcasts.
+static DISPLAY_WINDOW: Mutex<Option<isize>> = Mutex::new(None);
 
 pub struct WindowsPlatformEvents;
 
@@ -54,6 +64,8 @@ unsafe impl PlatformEventsImpl for WindowsPlatformEvents {
             EVENT_OBJECT_SHOW,
             EVENT_OBJECT_FOCUS,
             EVENT_OBJECT_DESTROY,
+            EVENT_OBJECT_NAMECHANGE,
+            EVENT_OBJECT_LOCATIONCHANGE,
         ];
 
         for event in events {
@@ -85,6 +97,12 @@ unsafe impl PlatformEventsImpl for WindowsPlatformEvents {
             .unwrap()
             .push(keyboard_hook.0 as isize);
 
+        let display_window = create_display_window()?;
+        DISPLAY_WINDOW
+            .lock()
+            .unwrap()
+            .replace(display_window.0 as isize);
+
         Ok(())
     }
 
@@ -107,6 +125,12 @@ unsafe impl PlatformEventsImpl for WindowsPlatformEvents {
         }
         low_level_hooks.clear();
 
+        if let Some(window) = DISPLAY_WINDOW.lock().unwrap().take() {
+            if DestroyWindow(HWND(window as *mut _)).is_err() {
+                errors.push(format!("Failed to destroy display window {:?}", window));
+            }
+        }
+
         if !errors.is_empty() {
             return Err(format!(
                 "Failed to cleanup {} hooks: {}",
@@ -120,15 +144,76 @@ unsafe impl PlatformEventsImpl for WindowsPlatformEvents {
     }
 }
 
+unsafe fn create_display_window() -> PlatformResult<HWND> {
+    let instance =
+        GetModuleHandleW(None).map_err(|e| format!("Could not get module handle: {:?}", e))?;
+    let class_name = w!("UltraWMDisplayListener");
+    let class = WNDCLASSW {
+        lpfnWndProc: Some(display_window_proc),
+        hInstance: instance.into(),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    if RegisterClassW(&class) == 0 {
+        return Err("Could not register the display window class".into());
+    }
+
+    CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        class_name,
+        w!("UltraWM Display Listener"),
+        WINDOW_STYLE(0),
+        0,
+        0,
+        0,
+        0,
+        None,
+        None,
+        Some(instance.into()),
+        None,
+    )
+    .map_err(|e| format!("Could not create display window: {:?}", e).into())
+}
+
+unsafe extern "system" fn display_window_proc(
+    hwnd: HWND,
+    message: u32,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> LRESULT {
+    if message == WM_DISPLAYCHANGE {
+        if let Some(dispatcher) = EVENT_DISPATCHER.get() {
+            dispatcher.send(WMEvent::DisplaysChanged);
+        }
+        return LRESULT(0);
+    }
+    DefWindowProcW(hwnd, message, w_param, l_param)
+}
+
 unsafe extern "system" fn win_event_hook_proc(
     _h_win_event_hook: HWINEVENTHOOK,
     event: u32,
     hwnd: HWND,
-    _id_object: i32,
+    id_object: i32,
     _id_child: i32,
     _id_event_thread: u32,
     _dwms_event_time: u32,
 ) {
+    // Name and location changes are also sent for every object inside a window and for the
+    // cursor, only the window's own matter
+    if matches!(event, EVENT_OBJECT_NAMECHANGE | EVENT_OBJECT_LOCATIONCHANGE)
+        && id_object != OBJID_WINDOW.0
+    {
+        return;
+    }
+
+    // Child windows move with their parent, only top level windows can be full screen
+    if event == EVENT_OBJECT_LOCATIONCHANGE
+        && (hwnd.0.is_null() || GetAncestor(hwnd, GA_ROOT) != hwnd)
+    {
+        return;
+    }
+
     let window = WindowsPlatformWindow::new(hwnd).unwrap();
 
     let event = match event {
@@ -138,6 +223,20 @@ unsafe extern "system" fn win_event_hook_proc(
         EVENT_OBJECT_SHOW => WMEvent::WindowOpened(window.clone()),
         EVENT_OBJECT_FOCUS => WMEvent::WindowFocused(window.id()),
         EVENT_OBJECT_DESTROY => WMEvent::WindowClosed(window.id()),
+        EVENT_OBJECT_NAMECHANGE => WMEvent::WindowTitleChanged(window.id(), window.title()),
+        EVENT_OBJECT_LOCATIONCHANGE => {
+            let fullscreen = window.is_fullscreen();
+            let mut fullscreen_windows = FULLSCREEN_WINDOWS.lock().unwrap();
+            if fullscreen_windows.contains(&window.id()) == fullscreen {
+                return;
+            }
+            if fullscreen {
+                fullscreen_windows.push(window.id());
+            } else {
+                fullscreen_windows.retain(|id| *id != window.id());
+            }
+            WMEvent::WindowFullscreenChanged(window.id(), fullscreen)
+        }
         _ => return,
     };
 
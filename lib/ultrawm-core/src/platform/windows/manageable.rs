@@ -4,7 +4,7 @@ use std::mem::size_of;
 use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
 use windows::Win32::UI::WindowsAndMessaging::{
     GetClassNameW, GetWindowLongW, IsWindowVisible, GWL_EXSTYLE, GWL_STYLE, WS_CAPTION, WS_CHILD,
-    WS_DISABLED, WS_EX_TOOLWINDOW, WS_OVERLAPPEDWINDOW,
+    WS_DISABLED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_OVERLAPPEDWINDOW,
 };
 
 pub fn window_is_manageable(window: &WindowsPlatformWindow) -> ObserveResult {
@@ -43,6 +43,11 @@ pub fn window_is_manageable(window: &WindowsPlatformWindow) -> ObserveResult {
             Err("Window is a tool window")?
         }
 
+        // Notifications and on-screen keyboards, they can't be focused
+        if ex_style & WS_EX_NOACTIVATE.0 != 0 {
+            Err("Window can't be activated")?
+        }
+
         let mut class_name: [u16; 256] = [0; 256];
         let len = GetClassNameW(hwnd, &mut class_name);
         let class_name = String::from_utf16_lossy(&class_name[..len as usize]);
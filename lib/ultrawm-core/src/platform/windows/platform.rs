@@ -11,6 +11,7 @@ use windows::Win32::Foundation::{HWND, LPARAM, POINT, RECT};
 use windows::Win32::Graphics::Gdi::{
     EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFOEXW,
 };
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
 use windows::Win32::UI::Input::KeyboardAndMouse::{
     SendInput, INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_LEFTDOWN,
     MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE,
@@ -18,10 +19,10 @@ use windows::Win32::UI::Input::KeyboardAndMouse::{
 };
 use windows::Win32::UI::WindowsAndMessaging::{
     BeginDeferWindowPos, CopyIcon, EndDeferWindowPos, EnumWindows, GetCursorPos, LoadCursorW,
-    SetSystemCursor, SystemParametersInfoW, HCURSOR, HDWP, HICON, IDC_ARROW, IDC_IBEAM, IDC_NO,
-    IDC_SIZEALL, IDC_SIZENESW, IDC_SIZENS, IDC_SIZENWSE, IDC_SIZEWE, IDC_WAIT, OCR_IBEAM, OCR_NO,
-    OCR_NORMAL, OCR_SIZEALL, OCR_SIZENESW, OCR_SIZENS, OCR_SIZENWSE, OCR_SIZEWE, OCR_WAIT,
-    SPIF_SENDCHANGE, SPI_SETCURSORS,
+    SetCursorPos, SetSystemCursor, SystemParametersInfoW, HCURSOR, HDWP, HICON, IDC_ARROW,
+    IDC_IBEAM, IDC_NO, IDC_SIZEALL, IDC_SIZENESW, IDC_SIZENS, IDC_SIZENWSE, IDC_SIZEWE, IDC_WAIT,
+    OCR_IBEAM, OCR_NO, OCR_NORMAL, OCR_SIZEALL, OCR_SIZENESW, OCR_SIZENS, OCR_SIZENWSE, OCR_SIZEWE,
+    OCR_WAIT, SPIF_SENDCHANGE, SPI_SETCURSORS,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
     GetForegroundWindow, GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN,
@@ -37,7 +38,8 @@ impl PlatformImpl for WindowsPlatform {
         let mut windows = Vec::new();
 
         unsafe {
-            EnumWindows(Some(enum_window), LPARAM(&mut windows as *mut _ as isize)).unwrap();
+            EnumWindows(Some(enum_window), LPARAM(&mut windows as *mut _ as isize))
+                .map_err(|e| format!("Failed to list windows: {e}"))?;
         }
 
         Ok(windows)
@@ -53,7 +55,8 @@ impl PlatformImpl for WindowsPlatform {
                 Some(enum_display),
                 LPARAM(&mut displays as *mut _ as isize),
             )
-            .unwrap()
+            .ok()
+            .map_err(|e| format!("Failed to list displays: {e}"))?;
         }
 
         Ok(displays)
@@ -127,6 +130,13 @@ impl PlatformImpl for WindowsPlatform {
         }
     }
 
+    fn warp_mouse(position: Position) -> PlatformResult<()> {
+        unsafe {
+            SetCursorPos(position.x, position.y).map_err(|err| err.to_string())?;
+        }
+        Ok(())
+    }
+
     fn start_window_bounds_batch(window_count: u32) -> PlatformResult<()> {
         let hdswp = unsafe { BeginDeferWindowPos(window_count as i32) }
             .map_err(|e| format!("Failed to begin window batch: {}", e))?;
@@ -280,6 +290,10 @@ extern "system" fn enum_display(
             .collect::<Vec<u16>>();
         let device_name = String::from_utf16_lossy(&device_name_utf16);
 
+        // With per-monitor DPI awareness coordinates are in physical pixels, 96 DPI is 1.0
+        let (mut dpi_x, mut dpi_y) = (96, 96);
+        let _ = GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+
         let info = exinfo.monitorInfo;
         let display = Display {
             id: monitor.0 as DisplayId,
@@ -296,6 +310,7 @@ extern "system" fn enum_display(
                 (info.rcWork.right - info.rcWork.left) as u32,
                 (info.rcWork.bottom - info.rcWork.top) as u32,
             ),
+            scale_factor: dpi_x as f32 / 96.0,
         };
 
         displays.push(display);
@@ -4,14 +4,23 @@ use crate::platform::{
 };
 use std::mem;
 use std::sync::atomic::Ordering;
-use windows::Win32::Foundation::{HWND, LPARAM, RECT, WPARAM};
+use windows::core::PWSTR;
+use windows::Win32::Foundation::{CloseHandle, COLORREF, HWND, LPARAM, RECT, WPARAM};
 use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
-use windows::Win32::System::Threading::{AttachThreadInput, GetCurrentThreadId};
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::System::Threading::{
+    AttachThreadInput, GetCurrentThreadId, OpenProcess, QueryFullProcessImageNameW,
+    PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
 use windows::Win32::UI::WindowsAndMessaging::{
-    BringWindowToTop, DeferWindowPos, GetForegroundWindow, GetWindowRect, GetWindowTextW,
-    GetWindowThreadProcessId, IsIconic, IsWindow, PostMessageW, SetForegroundWindow, SetWindowPos,
-    ShowWindow, HDWP, HWND_NOTOPMOST, HWND_TOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE,
-    SWP_NOZORDER, SW_MINIMIZE, SW_RESTORE, WM_CLOSE,
+    BringWindowToTop, DeferWindowPos, GetForegroundWindow, GetWindowLongW, GetWindowRect,
+    GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsWindow, PostMessageW,
+    SendMessageTimeoutW, SetForegroundWindow, SetLayeredWindowAttributes, SetWindowLongW,
+    SetWindowPos, ShowWindow, GWL_EXSTYLE, GWL_STYLE, HDWP, HWND_NOTOPMOST, HWND_TOPMOST,
+    LWA_ALPHA, MINMAXINFO, SMTO_ABORTIFHUNG, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER,
+    SW_MINIMIZE, SW_RESTORE, WM_CLOSE, WM_GETMINMAXINFO, WS_CAPTION, WS_EX_LAYERED, WS_THICKFRAME,
 };
 
 #[derive(Debug)]
@@ -44,6 +53,20 @@ impl WindowsPlatformWindow {
         self.hwnd
     }
 
+    /// The whole area of the monitor the window is mostly on, taskbar included
+    fn monitor_rect(&self) -> Option<RECT> {
+        unsafe {
+            let monitor = MonitorFromWindow(self.hwnd, MONITOR_DEFAULTTONEAREST);
+            let mut info = MONITORINFO {
+                cbSize: mem::size_of::<MONITORINFO>() as u32,
+                ..Default::default()
+            };
+            GetMonitorInfoW(monitor, &mut info)
+                .as_bool()
+                .then_some(info.rcMonitor)
+        }
+    }
+
     /// Gets the visible window bounds, excluding invisible resize borders
     fn get_visible_bounds(&self) -> PlatformResult<RECT> {
         let mut rect = RECT::default();
@@ -161,6 +184,38 @@ impl PlatformWindowImpl for WindowsPlatformWindow {
         String::from_utf16_lossy(&text[..len as usize])
     }
 
+    /// Uses the executable name (without extension) of the owning process
+    fn app_name(&self) -> String {
+        let mut buffer: [u16; 1024] = [0; 1024];
+        let mut len = buffer.len() as u32;
+
+        let path = unsafe {
+            let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, self.pid())
+            else {
+                return String::new();
+            };
+
+            let result = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buffer.as_mut_ptr()),
+                &mut len,
+            );
+            let _ = CloseHandle(process);
+
+            if result.is_err() {
+                return String::new();
+            }
+
+            String::from_utf16_lossy(&buffer[..len as usize])
+        };
+
+        std::path::Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
     fn position(&self) -> Position {
         let rect = self.get_visible_bounds().unwrap_or(RECT {
             left: 0,
@@ -191,6 +246,51 @@ impl PlatformWindowImpl for WindowsPlatformWindow {
         unsafe { !IsIconic(self.hwnd).as_bool() }
     }
 
+    fn min_size(&self) -> Option<Size> {
+        let mut info = MINMAXINFO::default();
+        unsafe {
+            // Don't wait long on windows that are hung
+            let result = SendMessageTimeoutW(
+                self.hwnd,
+                WM_GETMINMAXINFO,
+                WPARAM(0),
+                LPARAM(&mut info as *mut MINMAXINFO as isize),
+                SMTO_ABORTIFHUNG,
+                100,
+                None,
+            );
+            if result.0 == 0 {
+                return None;
+            }
+        }
+
+        let (width, height) = (info.ptMinTrackSize.x, info.ptMinTrackSize.y);
+        if width <= 0 && height <= 0 {
+            return None;
+        }
+        Some(Size {
+            width: width.max(0) as u32,
+            height: height.max(0) as u32,
+        })
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        // Full screen windows drop their title bar and border, a tiled window that happens to
+        // fill its monitor keeps them
+        let style = unsafe { GetWindowLongW(self.hwnd, GWL_STYLE) } as u32;
+        if style & (WS_CAPTION.0 | WS_THICKFRAME.0) != 0 {
+            return false;
+        }
+
+        let mut rect = RECT::default();
+        if unsafe { GetWindowRect(self.hwnd, &mut rect) }.is_err() {
+            return false;
+        }
+
+        // Maximized windows stop at the taskbar, full screen ones cover the whole monitor
+        self.monitor_rect() == Some(rect)
+    }
+
     fn set_bounds(&self, bounds: &Bounds) -> PlatformResult<()> {
         // Skip if bounds haven't changed to avoid unnecessary operations
         if self.bounds_match(bounds) {
@@ -311,6 +411,25 @@ impl PlatformWindowImpl for WindowsPlatformWindow {
         Ok(())
     }
 
+    fn set_opacity(&self, opacity: f32) -> PlatformResult<()> {
+        unsafe {
+            // Layered windows are required for per-window alpha
+            let ex_style = GetWindowLongW(self.hwnd, GWL_EXSTYLE);
+            if ex_style & WS_EX_LAYERED.0 as i32 == 0 {
+                SetWindowLongW(self.hwnd, GWL_EXSTYLE, ex_style | WS_EX_LAYERED.0 as i32);
+            }
+
+            SetLayeredWindowAttributes(
+                self.hwnd,
+                COLORREF(0),
+                (opacity.clamp(0.0, 1.0) * 255.0).round() as u8,
+                LWA_ALPHA,
+            )
+            .map_err(|e| format!("Failed to set window opacity: {}", e))?;
+        }
+        Ok(())
+    }
+
     fn close(&self) -> PlatformResult<()> {
         unsafe {
             PostMessageW(Some(self.hwnd), WM_CLOSE, WPARAM(0), LPARAM(0))
@@ -328,6 +447,43 @@ impl PlatformWindowImpl for WindowsPlatformWindow {
         Ok(())
     }
 
+    fn unminimize(&self) -> PlatformResult<()> {
+        unsafe {
+            if IsIconic(self.hwnd).as_bool() {
+                ShowWindow(self.hwnd, SW_RESTORE)
+                    .ok()
+                    .map_err(|e| format!("Failed to restore window: {}", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// There's no native full screen on Windows, the window is just stretched over its whole
+    /// monitor. Nothing is done when leaving it, the window manager moves it back to its tile
+    /// or floating bounds.
+    fn set_fullscreen(&self, fullscreen: bool) -> PlatformResult<()> {
+        if !fullscreen {
+            return Ok(());
+        }
+
+        let rect = self
+            .monitor_rect()
+            .ok_or("Failed to get the window's monitor")?;
+        unsafe {
+            SetWindowPos(
+                self.hwnd,
+                None,
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                SWP_NOZORDER | SWP_NOACTIVATE,
+            )
+            .map_err(|e| format!("Failed to set full screen: {}", e))?;
+        }
+        Ok(())
+    }
+
     fn valid(&self) -> bool {
         unsafe { IsWindow(Some(self.hwnd)).as_bool() }
     }
@@ -1,4 +1,4 @@
-use crate::platform::{Bounds, Position};
+use crate::platform::{Bounds, CursorType, Position};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HandleOrientation {
@@ -8,12 +8,24 @@ pub enum HandleOrientation {
     Horizontal,
 }
 
+impl HandleOrientation {
+    /// The cursor shown while hovering a handle, pointing the way it moves
+    pub fn cursor(&self) -> CursorType {
+        match self {
+            HandleOrientation::Vertical => CursorType::ResizeEast,
+            HandleOrientation::Horizontal => CursorType::ResizeNorth,
+        }
+    }
+}
+
 pub enum ResizeMode {
     Evenly,
     Before,
     After,
     BeforeSymmetric,
     AfterSymmetric,
+    /// Resize both sides equally, rounding the split to the nearest multiple of the increment
+    Snap(f32),
 }
 
 #[derive(Debug, Clone)]
@@ -1,5 +1,4 @@
-use crate::layouts::ContainerTree;
-use crate::layouts::WindowLayout;
+use crate::layouts::{self, LayoutType, WindowLayout};
 use crate::partition::{Partition, PartitionId};
 use crate::paths;
 use crate::platform::{Bounds, WindowId};
@@ -7,14 +6,36 @@ use crate::window::WindowRef;
 use crate::wm::WindowManager;
 use crate::workspace::{Workspace, WorkspaceId};
 use crate::Config;
-use log::debug;
+use log::{debug, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Layout file used for this session instead of the default one, set from the CLI.
+static LAYOUT_FILE_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Version of the layout file format this build writes. Bump it and add a step to
+/// `migrate_layout` whenever the format changes.
+pub const LAYOUT_VERSION: u32 = 1;
 
 #[derive(Serialize, Deserialize)]
 pub struct SerializedWindowManager {
+    /// Format version the file was written with, files from before versioning are version 0
+    #[serde(default)]
+    pub version: u32,
     pub partitions: Vec<SerializedPartition>,
+    /// Focus order from the bottom up, the last window was focused most recently
+    #[serde(default)]
+    pub window_order: Vec<WindowId>,
+    /// The window gap set with the gap commands, used over the configured one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_gap: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -25,22 +46,28 @@ pub struct SerializedPartition {
     pub workspaces: Vec<SerializedWorkspace>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct SerializedWorkspace {
     pub id: WorkspaceId,
     pub name: String,
+    /// Which layout `layout` was saved from, older files without it are container trees
+    #[serde(rename = "type", default)]
+    pub layout_type: LayoutType,
     pub layout: serde_yaml::Value,
     pub floating: Vec<SerializedWindow>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct SerializedWindow {
     pub id: WindowId,
     pub bounds: Bounds,
+    #[serde(default)]
+    pub sticky: bool,
 }
 
 fn serialize_wm(wm: &WindowManager) -> serde_yaml::Value {
     let serialized = SerializedWindowManager {
+        version: LAYOUT_VERSION,
         partitions: wm
             .partitions()
             .iter()
@@ -51,31 +78,40 @@ fn serialize_wm(wm: &WindowManager) -> serde_yaml::Value {
                 workspaces: partition
                     .assigned_workspaces()
                     .iter()
-                    .map(|id| {
-                        let workspace = wm.workspaces().get(id).unwrap();
-                        SerializedWorkspace {
-                            id: workspace.id(),
-                            name: workspace.name().to_string(),
-                            layout: workspace.serialize(),
-                            floating: workspace
-                                .windows()
-                                .iter()
-                                .filter(|(_, window)| window.floating())
-                                .map(|(id, window)| SerializedWindow {
-                                    id: id.clone(),
-                                    bounds: window.bounds().clone(),
-                                })
-                                .collect(),
-                        }
-                    })
+                    .map(|id| serialize_workspace(wm.workspaces().get(id).unwrap()))
                     .collect(),
             })
             .collect(),
+        window_order: wm.window_order().iter().copied().collect(),
+        window_gap: wm.window_gap_override(),
     };
 
     serde_yaml::to_value(serialized).unwrap()
 }
 
+pub(crate) fn serialize_workspace(workspace: &Workspace) -> SerializedWorkspace {
+    let mut floating: Vec<SerializedWindow> = workspace
+        .windows()
+        .iter()
+        .filter(|(_, window)| window.floating())
+        .map(|(id, window)| SerializedWindow {
+            id: id.clone(),
+            bounds: window.bounds().clone(),
+            sticky: window.sticky(),
+        })
+        .collect();
+    // Sorted so the same workspace always serializes the same way
+    floating.sort_by_key(|w| w.id);
+
+    SerializedWorkspace {
+        id: workspace.id(),
+        name: workspace.name().to_string(),
+        layout_type: workspace.layout_type(),
+        layout: workspace.serialize(),
+        floating,
+    }
+}
+
 pub fn deserialize_partition(
     serialized: &SerializedPartition,
     available_windows: &Vec<WindowRef>,
@@ -98,30 +134,90 @@ pub fn deserialize_workspace(
     partition: &Partition,
     available_windows: &Vec<WindowRef>,
 ) -> Workspace {
-    let layout = Box::new(ContainerTree::deserialize(
+    let (layout, report) = restore_layout(
+        serialized.layout_type,
         partition.bounds().clone(),
         available_windows,
         &serialized.layout,
-    ));
+    );
+    debug!("Restored workspace {}: {report:?}", serialized.name);
 
     let mut floating = HashMap::new();
     for window in available_windows.iter() {
-        if serialized.floating.iter().any(|w| w.id == window.id()) {
+        if let Some(saved) = serialized.floating.iter().find(|w| w.id == window.id()) {
+            window.set_sticky(saved.sticky);
             floating.insert(window.id(), window.clone());
         }
     }
 
-    let workspace = Workspace::new_with_id::<ContainerTree>(
+    let workspace = Workspace::new_with_layout(
         serialized.id,
-        partition.bounds().clone(),
         serialized.name.clone(),
-        Some(layout),
+        layout,
         Some(floating),
     );
 
     workspace
 }
 
+/// What restoring a saved layout did with its windows
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    /// Windows put back where the layout had them
+    pub placed: Vec<WindowId>,
+    /// Available windows the layout had no place for, they still need to be placed
+    pub unplaced: Vec<WindowId>,
+    /// Windows the layout had that aren't open anymore, their slots are collapsed
+    pub missing: Vec<WindowId>,
+}
+
+impl RestoreReport {
+    pub fn new(
+        saved: &[WindowId],
+        available_windows: &[WindowRef],
+        placed: impl IntoIterator<Item = WindowId>,
+    ) -> Self {
+        let mut placed: Vec<WindowId> = placed.into_iter().collect();
+        placed.sort();
+        let mut unplaced: Vec<WindowId> = available_windows
+            .iter()
+            .map(|w| w.id())
+            .filter(|id| !placed.contains(id))
+            .collect();
+        unplaced.sort();
+        let mut missing: Vec<WindowId> = saved
+            .iter()
+            .copied()
+            .filter(|id| !available_windows.iter().any(|w| w.id() == *id))
+            .collect();
+        missing.sort();
+        missing.dedup();
+
+        Self {
+            placed,
+            unplaced,
+            missing,
+        }
+    }
+}
+
+/// Rebuilds a saved layout from the windows that are still open, along with a report of
+/// which windows it placed
+pub fn restore_layout(
+    layout_type: LayoutType,
+    bounds: Bounds,
+    available_windows: &Vec<WindowRef>,
+    saved_layout: &serde_yaml::Value,
+) -> (Box<dyn WindowLayout>, RestoreReport) {
+    let layout = layouts::deserialize_layout(layout_type, bounds, available_windows, saved_layout);
+    let report = RestoreReport::new(
+        &extract_window_ids(saved_layout),
+        available_windows,
+        layout.windows().iter().map(|w| w.id()),
+    );
+    (layout, report)
+}
+
 /// Extract window IDs from saved layout for matching
 pub fn extract_window_ids(layout: &serde_yaml::Value) -> Vec<WindowId> {
     let mut window_ids = Vec::new();
@@ -158,6 +254,21 @@ fn extract_window_ids_recursive(value: &serde_yaml::Value, window_ids: &mut Vec<
     }
 }
 
+/// Use a different layout file for loading and saving during this session.
+pub fn set_layout_file_path(path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    LAYOUT_FILE_OVERRIDE
+        .set(path)
+        .map_err(|_| "Layout file path already set".into())
+}
+
+/// The layout file for this session, either the override or the default path.
+pub fn layout_file_path() -> Option<PathBuf> {
+    LAYOUT_FILE_OVERRIDE
+        .get()
+        .cloned()
+        .or_else(paths::layout_file_path)
+}
+
 /// Save the current window manager layout to file
 pub fn save_layout(wm: &WindowManager) -> Result<(), Box<dyn std::error::Error>> {
     if !Config::persistence() {
@@ -167,49 +278,293 @@ pub fn save_layout(wm: &WindowManager) -> Result<(), Box<dyn std::error::Error>>
     let layout_data = serialize_wm(wm);
     let layout_yaml = serde_yaml::to_string(&layout_data)?;
 
-    if let Some(path) = paths::layout_file_path() {
-        // Create directory if it doesn't exist
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
+    let path = layout_file_path().ok_or("Could not determine layout file path")?;
+    debug!("Saving layout...");
+    write_layout_file(&path, &layout_yaml)
+}
+
+/// The current layout as it would be written to the layout file
+pub fn layout_contents(wm: &WindowManager) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(serde_yaml::to_string(&serialize_wm(wm))?)
+}
+
+/// Write already serialized layout contents to the layout file
+pub fn write_layout(contents: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let path = layout_file_path().ok_or("Could not determine layout file path")?;
+    debug!("Saving layout...");
+    write_layout_file(&path, contents)
+}
+
+/// The copy of the previous layout kept next to `path`
+fn layout_backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Writes `contents` to a temp file next to `path` and renames it over `path`, so a crash
+/// mid-write leaves the old file intact. The old file is copied to the backup first.
+pub(crate) fn write_layout_file(
+    path: &Path,
+    contents: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
 
-        debug!("Saving layout...");
-        fs::write(&path, layout_yaml)?;
-    } else {
-        return Err("Could not determine layout file path".into());
+    if path.exists() {
+        fs::copy(path, layout_backup_path(path))?;
     }
 
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, path)?;
     Ok(())
 }
 
+/// Reads the layout at `path`, falling back to its backup if it can't be read
+pub(crate) fn read_layout_file(path: &Path) -> Option<SerializedWindowManager> {
+    if !path.exists() {
+        return None;
+    }
+
+    let read = |path: &Path| -> Result<SerializedWindowManager, Box<dyn std::error::Error>> {
+        deserialize_layout(&fs::read_to_string(path)?)
+    };
+    match read(path) {
+        Ok(layout) => Some(layout),
+        Err(e) => {
+            warn!("Could not load saved layout, trying the backup: {e}");
+            match read(&layout_backup_path(path)) {
+                Ok(layout) => Some(layout),
+                // A layout that can't be read is dropped, the windows are arranged from scratch
+                Err(e) => {
+                    warn!("Could not load the layout backup, starting fresh: {e}");
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Writes layout saves on a background thread. Saves that come in while one is waiting
+/// replace it, so at most one write happens per debounce window and it has the newest layout.
+pub struct LayoutSaveDebouncer {
+    sender: Option<mpsc::Sender<String>>,
+    thread: Option<JoinHandle<()>>,
+    /// How long to wait for newer saves in milliseconds, read at the start of each wait
+    debounce_ms: Arc<AtomicU64>,
+}
+
+impl LayoutSaveDebouncer {
+    pub fn new(debounce: Duration) -> Self {
+        Self::with_writer(debounce, |contents| {
+            if let Err(e) = write_layout(contents) {
+                warn!("Failed to save layout: {e}");
+            }
+        })
+    }
+
+    pub fn with_writer(debounce: Duration, write: impl Fn(&str) + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel::<String>();
+        let debounce_ms = Arc::new(AtomicU64::new(debounce.as_millis() as u64));
+        let thread_debounce_ms = debounce_ms.clone();
+        let thread = thread::spawn(move || {
+            while let Ok(mut contents) = receiver.recv() {
+                let debounce = Duration::from_millis(thread_debounce_ms.load(Ordering::Relaxed));
+                let deadline = Instant::now() + debounce;
+                loop {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    match receiver.recv_timeout(timeout) {
+                        Ok(newer) => contents = newer,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        // Shutting down, the final save is written synchronously instead
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                write(&contents);
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+            thread: Some(thread),
+            debounce_ms,
+        }
+    }
+
+    /// Changes how long later saves wait, a save already waiting keeps its deadline
+    pub fn set_debounce(&self, debounce: Duration) {
+        self.debounce_ms
+            .store(debounce.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Queue a save, replacing any that hasn't been written yet
+    pub fn request(&self, contents: String) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(contents);
+        }
+    }
+
+    /// Stops the background thread, dropping any save that hasn't been written yet
+    pub fn shutdown(&mut self) {
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for LayoutSaveDebouncer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
 /// Load layout from file if it exists
 pub fn load_layout() -> Result<Option<SerializedWindowManager>, Box<dyn std::error::Error>> {
     if !Config::persistence() {
         return Ok(None);
     }
 
-    if let Some(path) = paths::layout_file_path() {
-        if path.exists() {
-            let contents = fs::read_to_string(&path)?;
-            let layout: SerializedWindowManager = serde_yaml::from_str(&contents)?;
-            return Ok(Some(layout));
+    Ok(layout_file_path().and_then(|path| read_layout_file(&path)))
+}
+
+/// Reads a saved layout, upgrading it first if it was written by an older version
+pub fn deserialize_layout(
+    contents: &str,
+) -> Result<SerializedWindowManager, Box<dyn std::error::Error>> {
+    let value: serde_yaml::Value = serde_yaml::from_str(contents)?;
+    let layout = serde_yaml::from_value(migrate_layout(value)?)?;
+    Ok(layout)
+}
+
+/// Upgrades a saved layout one format version at a time until it's at `LAYOUT_VERSION`
+fn migrate_layout(
+    mut value: serde_yaml::Value,
+) -> Result<serde_yaml::Value, Box<dyn std::error::Error>> {
+    let mut version = match value.get("version") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| format!("Invalid layout version: {v:?}"))?,
+    };
+
+    if version > LAYOUT_VERSION {
+        return Err(format!(
+            "Layout version {version} is newer than the supported version {LAYOUT_VERSION}"
+        )
+        .into());
+    }
+
+    while version < LAYOUT_VERSION {
+        match version {
+            // Version 0 is the same format, it just predates the version field
+            0 => {}
+            _ => return Err(format!("No migration from layout version {version}").into()),
         }
+        version += 1;
+        debug!("Migrated layout to version {version}");
     }
-    Ok(None)
+
+    let mapping = value
+        .as_mapping_mut()
+        .ok_or("Layout file is not a mapping")?;
+    mapping.insert("version".into(), version.into());
+    Ok(value)
 }
 
 pub fn reset_layout() -> Result<(), Box<dyn std::error::Error>> {
-    if let Some(path) = paths::layout_file_path() {
+    if let Some(path) = layout_file_path() {
         if path.exists() {
             fs::remove_file(&path)?;
         }
+        let backup = layout_backup_path(&path);
+        if backup.exists() {
+            fs::remove_file(backup)?;
+        }
     }
     Ok(())
 }
 
+const PRESET_EXTENSION: &str = "yaml";
+
+fn presets_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    paths::presets_dir().ok_or_else(|| "Could not determine presets directory".into())
+}
+
+fn preset_path(dir: &Path, name: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("Invalid preset name: {name:?}").into());
+    }
+    Ok(dir.join(format!("{name}.{PRESET_EXTENSION}")))
+}
+
+/// Saves a workspace's layout as a named preset
+pub fn save_layout_preset(
+    workspace: &Workspace,
+    name: &str,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    write_layout_preset(&presets_dir()?, name, &serialize_workspace(workspace))
+}
+
+/// Loads a named preset saved with `save_layout_preset`
+pub fn load_layout_preset(name: &str) -> Result<SerializedWorkspace, Box<dyn std::error::Error>> {
+    read_layout_preset(&presets_dir()?, name)
+}
+
+/// The names of all saved presets, sorted
+pub fn list_layout_presets() -> Vec<String> {
+    presets_dir()
+        .map(|dir| list_layout_presets_in(&dir))
+        .unwrap_or_default()
+}
+
+pub(crate) fn write_layout_preset(
+    dir: &Path,
+    name: &str,
+    preset: &SerializedWorkspace,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = preset_path(dir, name)?;
+    fs::create_dir_all(dir)?;
+    fs::write(&path, serde_yaml::to_string(preset)?)?;
+    Ok(path)
+}
+
+pub(crate) fn read_layout_preset(
+    dir: &Path,
+    name: &str,
+) -> Result<SerializedWorkspace, Box<dyn std::error::Error>> {
+    let path = preset_path(dir, name)?;
+    if !path.exists() {
+        return Err(format!("No preset named {:?}", name.trim()).into());
+    }
+    let contents = fs::read_to_string(&path)?;
+    Ok(serde_yaml::from_str(&contents)?)
+}
+
+fn list_layout_presets_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == PRESET_EXTENSION))
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+        .collect();
+    names.sort();
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::layouts::{ContainerTree, MasterStack};
     use serde_yaml::{Mapping, Number, Value};
 
     fn create_test_yaml() -> Value {
@@ -484,12 +839,51 @@ mod tests {
         assert!(ids.is_empty()); // Because only "id" key is checked, not "valid_id"
     }
 
+    #[test]
+    fn test_renamed_workspace_round_trip() {
+        let bounds = Bounds::new(0, 0, 1920, 1080);
+        let partition = Partition::new("Main".to_string(), bounds.clone());
+        let mut workspace =
+            Workspace::new::<ContainerTree>(bounds, "Default".to_string(), None, None);
+        workspace.set_name("Code".to_string());
+
+        let yaml = serde_yaml::to_string(&serialize_workspace(&workspace)).unwrap();
+        let serialized: SerializedWorkspace = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(serialized.name, "Code");
+
+        let restored = deserialize_workspace(&serialized, &partition, &vec![]);
+        assert_eq!(restored.name(), "Code");
+        assert_eq!(restored.id(), workspace.id());
+    }
+
+    #[test]
+    fn test_layout_type_round_trip() {
+        let bounds = Bounds::new(0, 0, 1920, 1080);
+        let partition = Partition::new("Main".to_string(), bounds.clone());
+        let workspace = Workspace::new::<MasterStack>(bounds, "Default".to_string(), None, None);
+
+        let yaml = serde_yaml::to_string(&serialize_workspace(&workspace)).unwrap();
+        assert!(yaml.contains("type: master-stack"));
+
+        let serialized: SerializedWorkspace = serde_yaml::from_str(&yaml).unwrap();
+        let restored = deserialize_workspace(&serialized, &partition, &vec![]);
+        assert_eq!(restored.layout_type(), LayoutType::MasterStack);
+    }
+
+    #[test]
+    fn test_missing_layout_type_is_container_tree() {
+        let yaml = "id: 0\nname: Old\nlayout: null\nfloating: []\n";
+        let serialized: SerializedWorkspace = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(serialized.layout_type, LayoutType::ContainerTree);
+    }
+
     #[test]
     fn test_serialized_structures_serde() {
         // Test that our serialized structures can be serialized and deserialized
         let workspace = SerializedWorkspace {
             id: 0,
             name: "Test Workspace".to_string(),
+            layout_type: LayoutType::ContainerTree,
             layout: Value::String("test layout".to_string()),
             floating: vec![],
         };
@@ -502,7 +896,10 @@ mod tests {
         };
 
         let wm = SerializedWindowManager {
+            version: LAYOUT_VERSION,
             partitions: vec![partition],
+            window_order: vec![3, 1, 2],
+            window_gap: None,
         };
 
         // Should serialize without error
@@ -519,5 +916,144 @@ mod tests {
             deserialized.partitions[0].workspaces[0].name,
             "Test Workspace"
         );
+        assert_eq!(deserialized.window_order, vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn test_unversioned_layout_is_migrated() {
+        let yaml = "partitions:\n- id: 0\n  name: Main\n  bounds:\n    position: {x: 0, y: 0}\n    size: {width: 1920, height: 1080}\n  workspaces:\n  - id: 0\n    name: Default\n    layout: null\n    floating: []\n";
+
+        let layout = deserialize_layout(yaml).unwrap();
+        assert_eq!(layout.version, LAYOUT_VERSION);
+        assert_eq!(layout.partitions.len(), 1);
+        assert_eq!(layout.partitions[0].name, "Main");
+        assert_eq!(layout.partitions[0].workspaces[0].name, "Default");
+    }
+
+    #[test]
+    fn test_versioned_layout_round_trip() {
+        let yaml = "version: 1\npartitions:\n- id: 0\n  name: Main\n  bounds:\n    position: {x: 0, y: 0}\n    size: {width: 1920, height: 1080}\n  workspaces:\n  - id: 0\n    name: Code\n    type: master-stack\n    layout: null\n    floating: []\n";
+
+        let layout = deserialize_layout(yaml).unwrap();
+        assert_eq!(layout.version, 1);
+        let workspace = &layout.partitions[0].workspaces[0];
+        assert_eq!(workspace.name, "Code");
+        assert_eq!(workspace.layout_type, LayoutType::MasterStack);
+
+        let newer = yaml.replace("version: 1", &format!("version: {}", LAYOUT_VERSION + 1));
+        assert!(deserialize_layout(&newer).is_err());
+    }
+
+    #[test]
+    fn test_layout_presets_are_listed_by_name() {
+        let dir = std::env::temp_dir().join(format!("ultrawm-preset-list-{}", std::process::id()));
+        let preset = SerializedWorkspace {
+            id: 1,
+            name: "Default".to_string(),
+            layout_type: LayoutType::ContainerTree,
+            layout: Value::Null,
+            floating: Vec::new(),
+        };
+
+        write_layout_preset(&dir, "writing", &preset).unwrap();
+        write_layout_preset(&dir, "coding", &preset).unwrap();
+        assert!(write_layout_preset(&dir, "../escape", &preset).is_err());
+        assert!(read_layout_preset(&dir, "missing").is_err());
+
+        assert_eq!(list_layout_presets_in(&dir), vec!["coding", "writing"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rapid_saves_are_written_once() {
+        use std::sync::{Arc, Mutex};
+
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let written = writes.clone();
+        let mut debouncer =
+            LayoutSaveDebouncer::with_writer(Duration::from_millis(100), move |contents| {
+                written.lock().unwrap().push(contents.to_string());
+            });
+
+        for i in 0..10 {
+            debouncer.request(format!("layout {i}"));
+        }
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(*writes.lock().unwrap(), vec!["layout 9".to_string()]);
+
+        // A shorter debounce from a config reload applies to the next save
+        debouncer.set_debounce(Duration::from_millis(10));
+        debouncer.request("layout 10".to_string());
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(writes.lock().unwrap().len(), 2);
+
+        // A save still waiting at shutdown is dropped for the synchronous one
+        debouncer.set_debounce(Duration::from_millis(100));
+        debouncer.request("layout 11".to_string());
+        debouncer.shutdown();
+        assert_eq!(writes.lock().unwrap().len(), 2);
+    }
+
+    fn layout_named(name: &str) -> String {
+        serde_yaml::to_string(&SerializedWindowManager {
+            version: LAYOUT_VERSION,
+            partitions: vec![SerializedPartition {
+                id: 1,
+                name: name.to_string(),
+                bounds: Bounds::new(0, 0, 1920, 1080),
+                workspaces: Vec::new(),
+            }],
+            window_order: Vec::new(),
+            window_gap: None,
+        })
+        .unwrap()
+    }
+
+    fn layout_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ultrawm-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_partial_write_keeps_old_layout() {
+        let dir = layout_test_dir("partial-write");
+        let path = dir.join("layout.yaml");
+        write_layout_file(&path, &layout_named("Good")).unwrap();
+
+        // A crash before the rename leaves only the temp file half written
+        fs::write(
+            dir.join("layout.yaml.tmp"),
+            "partitions:\n  - id: 1\n    na",
+        )
+        .unwrap();
+        let layout = read_layout_file(&path).unwrap();
+        assert_eq!(layout.partitions[0].name, "Good");
+
+        // A corrupt primary falls back to the backup taken before the last write
+        write_layout_file(&path, &layout_named("Newer")).unwrap();
+        fs::write(&path, "partitions: [").unwrap();
+        let layout = read_layout_file(&path).unwrap();
+        assert_eq!(layout.partitions[0].name, "Good");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_save_replaces_layout_file() {
+        let dir = layout_test_dir("save-replace");
+        let path = dir.join("layout.yaml");
+        write_layout_file(&path, &layout_named("First")).unwrap();
+        write_layout_file(&path, &layout_named("Second")).unwrap();
+
+        assert_eq!(
+            read_layout_file(&path).unwrap().partitions[0].name,
+            "Second"
+        );
+        assert!(!dir.join("layout.yaml.tmp").exists());
+        let backup = fs::read_to_string(layout_backup_path(&path)).unwrap();
+        assert_eq!(backup, layout_named("First"));
+
+        let _ = fs::remove_dir_all(&dir);
     }
 }
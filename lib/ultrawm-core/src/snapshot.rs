@@ -0,0 +1,66 @@
+use crate::partition::PartitionId;
+use crate::platform::{Bounds, WindowId};
+use crate::workspace::WorkspaceId;
+use crate::workspace_animator::AnimationStats;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+/// A read-only copy of the window manager state, published by the WM thread so external
+/// readers (status bars, scripts) can query it without waiting on the event loop.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayoutSnapshot {
+    pub partitions: Vec<PartitionSnapshot>,
+    pub workspaces: Vec<WorkspaceSnapshot>,
+    pub focused_window: Option<WindowId>,
+    /// Frame rates of the window animations finished when the snapshot was taken
+    #[serde(default)]
+    pub animation_stats: AnimationStats,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionSnapshot {
+    pub id: PartitionId,
+    pub name: String,
+    pub bounds: Bounds,
+    pub current_workspace: Option<WorkspaceId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceSnapshot {
+    pub id: WorkspaceId,
+    pub name: String,
+    pub partition: Option<PartitionId>,
+    pub windows: Vec<WindowSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowSnapshot {
+    pub id: WindowId,
+    pub title: String,
+    pub app_name: String,
+    pub bounds: Bounds,
+    pub floating: bool,
+    pub focused: bool,
+    /// The window has changes that haven't been sent to the platform yet
+    pub dirty: bool,
+}
+
+static CURRENT_SNAPSHOT: Lazy<RwLock<Arc<LayoutSnapshot>>> =
+    Lazy::new(|| RwLock::new(Arc::new(LayoutSnapshot::default())));
+
+/// Replaces the shared snapshot. Readers holding the previous one keep their copy.
+pub fn publish(snapshot: LayoutSnapshot) {
+    if let Ok(mut current) = CURRENT_SNAPSHOT.write() {
+        *current = Arc::new(snapshot);
+    }
+}
+
+/// Returns the most recently published snapshot. The lock is only held long enough to
+/// clone the `Arc`, so this never waits on the WM thread.
+pub fn current() -> Arc<LayoutSnapshot> {
+    CURRENT_SNAPSHOT
+        .read()
+        .map(|s| s.clone())
+        .unwrap_or_default()
+}
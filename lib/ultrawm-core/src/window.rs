@@ -1,5 +1,9 @@
 use crate::config::Config;
-use crate::platform::{Bounds, PlatformResult, PlatformWindow, PlatformWindowImpl, WindowId};
+use crate::layouts::{RatioLimits, ResizeDirection};
+use crate::platform::{
+    Bounds, Platform, PlatformImpl, PlatformResult, PlatformWindow, PlatformWindowImpl, ProcessId,
+    Size, WindowId,
+};
 use std::cell::{Ref, RefCell};
 use std::rc::Rc;
 
@@ -10,8 +14,36 @@ pub struct Window {
     bounds_dirty: RefCell<bool>,
     always_on_top: RefCell<bool>,
     always_on_top_dirty: RefCell<bool>,
+    /// Set by the user to keep a floating window on top or not, instead of the default
+    always_on_top_override: RefCell<Option<bool>>,
+    opacity: RefCell<f32>,
+    opacity_dirty: RefCell<bool>,
     platform_window: RefCell<PlatformWindow>,
+    /// Cached since asking the platform can be slow, kept up to date by title change events
+    title: RefCell<String>,
     floating: RefCell<bool>,
+    sticky: RefCell<bool>,
+    sticky_dirty: RefCell<bool>,
+    /// Whether this is the focused window. Runtime state only, it isn't saved with the layout.
+    focused: RefCell<bool>,
+    /// Full screen windows are left alone until they leave it
+    fullscreen: RefCell<bool>,
+    /// The bounds last sent to the platform, forgotten when something else may have moved it
+    sent_bounds: RefCell<Option<Bounds>>,
+    /// Where the window was before it was tiled, so floating it again can put it back
+    float_bounds: RefCell<Option<Bounds>>,
+    /// Width / height ratio kept while resizing the window when it's floating
+    aspect_lock: RefCell<Option<f32>>,
+    /// Queried once when the window is first seen, apps rarely change it
+    min_size: Option<Size>,
+    /// Queried once when the window is first seen, a window never changes app
+    app_name: String,
+    /// Scale factor of the display the window is tiled on, gaps are scaled by it
+    scale_factor: RefCell<f32>,
+    /// Gap of the workspace the window is on, when it doesn't use the global one
+    window_gap: RefCell<Option<u32>>,
+    /// How far the window can be resized within its container by dragging dividers
+    ratio_limits: RefCell<RatioLimits>,
 }
 
 impl std::fmt::Debug for Window {
@@ -22,15 +54,27 @@ impl std::fmt::Debug for Window {
             .field("visible", &self.visible())
             .field("bounds", &*self.bounds.borrow())
             .field("floating", &self.floating())
+            .field("sticky", &self.sticky())
+            .field("focused", &self.focused())
+            .field("fullscreen", &self.fullscreen())
+            .field("aspect_lock", &self.aspect_lock())
             .field("always_on_top", &*self.always_on_top.borrow())
             .field("bounds_dirty", &*self.bounds_dirty.borrow())
+            .field("sent_bounds", &*self.sent_bounds.borrow())
             .field("always_on_top_dirty", &*self.always_on_top_dirty.borrow())
+            .field("opacity", &*self.opacity.borrow())
+            .field("opacity_dirty", &*self.opacity_dirty.borrow())
+            .field("sticky_dirty", &*self.sticky_dirty.borrow())
             .finish()
     }
 }
 
 impl Window {
     pub fn new(platform_window: PlatformWindow) -> Self {
+        let min_size = platform_window.min_size();
+        let app_name = platform_window.app_name();
+        let title = platform_window.title();
+        let fullscreen = platform_window.is_fullscreen();
         Self {
             bounds: RefCell::new(Bounds {
                 position: platform_window.position(),
@@ -39,8 +83,24 @@ impl Window {
             bounds_dirty: RefCell::new(false),
             always_on_top: RefCell::new(false),
             always_on_top_dirty: RefCell::new(false),
+            always_on_top_override: RefCell::new(None),
+            opacity: RefCell::new(1.0),
+            opacity_dirty: RefCell::new(false),
             platform_window: RefCell::new(platform_window),
+            title: RefCell::new(title),
             floating: RefCell::new(false),
+            sticky: RefCell::new(false),
+            sticky_dirty: RefCell::new(false),
+            focused: RefCell::new(false),
+            fullscreen: RefCell::new(fullscreen),
+            sent_bounds: RefCell::new(None),
+            float_bounds: RefCell::new(None),
+            aspect_lock: RefCell::new(None),
+            min_size,
+            app_name,
+            scale_factor: RefCell::new(1.0),
+            window_gap: RefCell::new(None),
+            ratio_limits: RefCell::new(RatioLimits::default()),
         }
     }
 
@@ -49,7 +109,17 @@ impl Window {
     }
 
     pub fn set_floating(&self, floating: bool) {
-        self.always_on_top.replace(floating);
+        // Windows that are already tiled keep the bounds from before they were first tiled
+        if !floating && (self.floating() || self.float_bounds.borrow().is_none()) {
+            self.float_bounds.replace(Some(self.platform_bounds()));
+        }
+
+        // Tiled windows are never on top
+        if !floating {
+            self.always_on_top_override.replace(None);
+        }
+        let on_top = self.always_on_top_override.borrow().unwrap_or(floating);
+        self.always_on_top.replace(on_top);
         self.always_on_top_dirty.replace(true);
         self.floating.replace(floating);
         if floating {
@@ -65,14 +135,102 @@ impl Window {
         !self.floating()
     }
 
+    /// The bounds the window had before it was last tiled
+    pub fn float_bounds(&self) -> Option<Bounds> {
+        self.float_bounds.borrow().clone()
+    }
+
+    /// Sticky windows stay on screen when switching workspaces
+    pub fn sticky(&self) -> bool {
+        self.sticky.borrow().clone()
+    }
+
+    pub fn set_sticky(&self, sticky: bool) {
+        let old = self.sticky.replace(sticky);
+        if old != sticky {
+            self.sticky_dirty.replace(true);
+        }
+    }
+
+    /// Whether the window is kept above other windows. Floating and sticky windows are unless
+    /// the user says otherwise.
+    pub fn always_on_top(&self) -> bool {
+        *self.always_on_top.borrow()
+    }
+
+    pub fn set_always_on_top(&self, always_on_top: bool) {
+        self.always_on_top_override.replace(Some(always_on_top));
+        self.always_on_top.replace(always_on_top);
+        self.always_on_top_dirty.replace(true);
+    }
+
+    pub fn focused(&self) -> bool {
+        *self.focused.borrow()
+    }
+
+    pub fn set_focused(&self, focused: bool) {
+        self.focused.replace(focused);
+    }
+
+    pub fn fullscreen(&self) -> bool {
+        *self.fullscreen.borrow()
+    }
+
+    pub fn set_fullscreen(&self, fullscreen: bool) {
+        self.fullscreen.replace(fullscreen);
+    }
+
+    pub fn aspect_lock(&self) -> Option<f32> {
+        *self.aspect_lock.borrow()
+    }
+
+    pub fn set_aspect_lock(&self, ratio: Option<f32>) {
+        self.aspect_lock
+            .replace(ratio.filter(|r| r.is_finite() && *r > 0.0));
+    }
+
+    pub fn opacity(&self) -> f32 {
+        self.opacity.borrow().clone()
+    }
+
+    pub fn set_opacity(&self, opacity: f32) {
+        let opacity = opacity.clamp(0.0, 1.0);
+        let old = self.opacity.replace(opacity);
+        if old != opacity {
+            self.opacity_dirty.replace(true);
+        }
+    }
+
     pub fn title(&self) -> String {
-        self.platform_window.borrow().title()
+        self.title.borrow().clone()
+    }
+
+    pub fn set_title(&self, title: String) {
+        self.title.replace(title);
+    }
+
+    /// Asks the platform for the title again, for when a title change may have been missed
+    pub fn refresh_title(&self) {
+        let title = self.platform_window.borrow().title();
+        self.set_title(title);
+    }
+
+    pub fn pid(&self) -> ProcessId {
+        self.platform_window.borrow().pid()
+    }
+
+    pub fn app_name(&self) -> String {
+        self.app_name.clone()
     }
 
     pub fn visible(&self) -> bool {
         self.platform_window.borrow().visible()
     }
 
+    pub fn min_size(&self) -> Option<Size> {
+        self.min_size.clone()
+    }
+
     pub fn bounds(&self) -> Bounds {
         self.bounds.borrow().clone()
     }
@@ -90,11 +248,13 @@ impl Window {
             position: self.platform_window().position(),
         };
         self.bounds.replace(bounds);
+        self.forget_sent_bounds();
     }
 
     /// Set the position of the raw window, without updating it's managed/tiled position.
     /// Useful for previewing a window location before it's finalized.
     pub fn set_preview_bounds(&self, bounds: Bounds) -> PlatformResult<()> {
+        self.forget_sent_bounds();
         self.set_platform_bounds(bounds)?;
         Ok(())
     }
@@ -103,21 +263,62 @@ impl Window {
         self.platform_window.borrow()
     }
 
+    /// Forces the window to be moved to its managed bounds on the next flush.
+    pub fn mark_dirty(&self) {
+        self.forget_sent_bounds();
+        self.bounds_dirty.replace(true);
+    }
+
+    /// Call when the window was sent somewhere other than its managed bounds, so flushes leave
+    /// it there until it's marked dirty again.
+    pub fn mark_clean(&self) {
+        self.forget_sent_bounds();
+        self.bounds_dirty.replace(false);
+    }
+
+    /// Call when the platform window was moved without going through `flush`, so the next
+    /// flush doesn't assume it's still where it was last sent.
+    pub fn forget_sent_bounds(&self) {
+        self.sent_bounds.replace(None);
+    }
+
+    /// Whether the window's bounds have changed since they were last sent to the platform
+    pub fn bounds_dirty(&self) -> bool {
+        *self.bounds_dirty.borrow()
+            && self.sent_bounds.borrow().as_ref() != Some(&self.window_bounds())
+    }
+
     pub fn dirty(&self) -> bool {
-        self.bounds_dirty.borrow().clone() || self.always_on_top_dirty.borrow().clone()
+        self.bounds_dirty.borrow().clone()
+            || self.always_on_top_dirty.borrow().clone()
+            || self.opacity_dirty.borrow().clone()
+            || self.sticky_dirty.borrow().clone()
     }
 
     pub fn flush(&self) -> PlatformResult<()> {
-        if self.bounds_dirty.borrow().clone() {
+        // Bounds that changed and settled back to what the platform already has are skipped
+        if self.bounds_dirty() {
             self.bounds_dirty.replace(false);
 
-            self.set_platform_bounds(self.window_bounds())?;
+            let bounds = self.window_bounds();
+            self.set_platform_bounds(bounds.clone())?;
+            self.sent_bounds.replace(Some(bounds));
         }
+        self.bounds_dirty.replace(false);
 
         self.flush_always_on_top()?;
+        self.flush_opacity()?;
+        self.flush_sticky()?;
         Ok(())
     }
 
+    /// Sets always on top again even if it didn't change, which puts the window above the
+    /// other windows that are on top
+    pub fn reapply_always_on_top(&self) -> PlatformResult<()> {
+        self.always_on_top_dirty.replace(true);
+        self.flush_always_on_top()
+    }
+
     pub fn flush_always_on_top(&self) -> PlatformResult<()> {
         if self.always_on_top_dirty.borrow().clone() {
             let on_top = self.always_on_top.borrow().clone();
@@ -127,23 +328,69 @@ impl Window {
         Ok(())
     }
 
+    pub fn flush_opacity(&self) -> PlatformResult<()> {
+        if self.opacity_dirty.borrow().clone() {
+            let opacity = self.opacity.borrow().clone();
+            self.opacity_dirty.replace(false);
+            self.platform_window.borrow().set_opacity(opacity)?;
+        }
+        Ok(())
+    }
+
+    /// Sticky windows are kept above the windows of whichever workspace they're shown on
+    pub fn flush_sticky(&self) -> PlatformResult<()> {
+        if self.sticky_dirty.borrow().clone() {
+            self.sticky_dirty.replace(false);
+            let on_top = self
+                .always_on_top_override
+                .borrow()
+                .unwrap_or(self.sticky() || self.floating());
+            self.always_on_top.replace(on_top);
+            self.platform_window.borrow().set_always_on_top(on_top)?;
+        }
+        Ok(())
+    }
+
     /// The bounds of the window, with tiling gaps applied
     pub fn window_bounds(&self) -> Bounds {
-        let config = Config::current();
-        let mut bounds = self.bounds.borrow().clone();
-
-        if !self.floating() {
-            bounds.position.x += config.window_gap as i32 / 2;
-            bounds.position.y += config.window_gap as i32 / 2;
-            bounds.size.width = bounds.size.width.saturating_sub(config.window_gap).max(100);
-            bounds.size.height = bounds
-                .size
-                .height
-                .saturating_sub(config.window_gap)
-                .max(100);
+        let bounds = self.bounds.borrow().clone();
+        if self.floating() {
+            return bounds;
+        }
+        gapped_bounds(&bounds, self.window_gap(), self.scale_factor())
+    }
+
+    pub fn window_gap(&self) -> u32 {
+        self.window_gap.borrow().unwrap_or_else(Config::window_gap)
+    }
+
+    pub fn ratio_limits(&self) -> RatioLimits {
+        *self.ratio_limits.borrow()
+    }
+
+    pub fn set_ratio_limits(&self, limits: RatioLimits) {
+        self.ratio_limits.replace(limits);
+    }
+
+    /// Moves the window to a workspace with its own gap, or the global one when `None`
+    pub fn set_window_gap(&self, window_gap: Option<u32>) {
+        let old = self.window_gap.replace(window_gap);
+        if old != window_gap {
+            self.bounds_dirty.replace(true);
         }
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        *self.scale_factor.borrow()
+    }
 
-        bounds
+    /// Moves the window to a display with a different scale factor, so its gaps are redrawn at
+    /// the new scale on the next flush
+    pub fn set_scale_factor(&self, scale_factor: f32) {
+        let old = self.scale_factor.replace(scale_factor);
+        if old != scale_factor {
+            self.bounds_dirty.replace(true);
+        }
     }
 
     pub fn platform_bounds(&self) -> Bounds {
@@ -165,6 +412,15 @@ impl Window {
         self.platform_window.borrow().minimize()
     }
 
+    pub fn unminimize(&self) -> PlatformResult<()> {
+        self.platform_window.borrow().unminimize()
+    }
+
+    /// Asks the platform to put the window in or out of full screen
+    pub fn request_fullscreen(&self, fullscreen: bool) -> PlatformResult<()> {
+        self.platform_window.borrow().set_fullscreen(fullscreen)
+    }
+
     pub fn valid(&self) -> bool {
         self.platform_window.borrow().valid()
     }
@@ -174,6 +430,79 @@ impl Window {
     }
 }
 
+/// Shrinks tiled bounds by the window gap, scaled so gaps look the same on every display
+pub fn gapped_bounds(bounds: &Bounds, gap: u32, scale_factor: f32) -> Bounds {
+    let gap = (gap as f32 * scale_factor).round() as u32;
+    let min_size = (100.0 * scale_factor).round() as u32;
+
+    let mut bounds = bounds.clone();
+    bounds.position.x += gap as i32 / 2;
+    bounds.position.y += gap as i32 / 2;
+    bounds.size.width = bounds.size.width.saturating_sub(gap).max(min_size);
+    bounds.size.height = bounds.size.height.saturating_sub(gap).max(min_size);
+    bounds
+}
+
+/// Flushes windows, sending the ones with new bounds to the platform as one batch so platforms
+/// that support it can move them all at once. The batch is ended even if a flush fails.
+pub fn flush_windows_batched<'a>(
+    windows: impl IntoIterator<Item = &'a WindowRef>,
+) -> PlatformResult<()> {
+    let windows: Vec<&WindowRef> = windows.into_iter().collect();
+    let moving = windows.iter().filter(|w| w.bounds_dirty()).count() as u32;
+    if moving == 0 {
+        return windows.iter().try_for_each(|w| w.flush());
+    }
+
+    Platform::start_window_bounds_batch(moving)?;
+    let result = windows.iter().try_for_each(|w| w.flush());
+    Platform::end_window_bounds_batch()?;
+    result
+}
+
+/// Adjusts bounds from a resize so width / height stays at `ratio`. Edge resizes keep the
+/// dragged axis and fit the other one, corner resizes follow whichever axis moved further.
+/// The edges opposite the ones being dragged stay where they are.
+pub fn aspect_locked_bounds(
+    old: &Bounds,
+    new: &Bounds,
+    ratio: f32,
+    direction: ResizeDirection,
+) -> Bounds {
+    let width_leads = match direction {
+        ResizeDirection::Left | ResizeDirection::Right => true,
+        ResizeDirection::Top | ResizeDirection::Bottom => false,
+        _ => {
+            let width_delta = new.size.width.abs_diff(old.size.width);
+            let height_delta = new.size.height.abs_diff(old.size.height);
+            width_delta >= height_delta
+        }
+    };
+
+    let (width, height) = if width_leads {
+        let height = (new.size.width as f32 / ratio).round() as u32;
+        (new.size.width.max(1), height.max(1))
+    } else {
+        let width = (new.size.height as f32 * ratio).round() as u32;
+        (width.max(1), new.size.height.max(1))
+    };
+
+    let right = new.position.x + new.size.width as i32;
+    let bottom = new.position.y + new.size.height as i32;
+    let x = if direction.has_left() {
+        right - width as i32
+    } else {
+        new.position.x
+    };
+    let y = if direction.has_top() {
+        bottom - height as i32
+    } else {
+        new.position.y
+    };
+
+    Bounds::new(x, y, width, height)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +521,21 @@ mod tests {
         (window, platform_window)
     }
 
+    #[test]
+    fn test_title_is_cached() {
+        let (window, platform_window) = new_tracking_window();
+        assert_eq!(platform_window.get_title_calls(), 1);
+
+        for _ in 0..3 {
+            assert_eq!(window.title(), "Test Window");
+        }
+        assert_eq!(platform_window.get_title_calls(), 1);
+
+        window.refresh_title();
+        assert_eq!(window.title(), "Test Window");
+        assert_eq!(platform_window.get_title_calls(), 2);
+    }
+
     #[test]
     fn test_set_bounds_marks_dirty_but_no_platform_call() {
         let (window, platform_window) = new_tracking_window();
@@ -337,6 +681,41 @@ mod tests {
         assert_eq!(platform_window.get_set_bounds_calls().len(), 1);
     }
 
+    #[test]
+    fn test_set_same_bounds_does_not_mark_dirty() {
+        let (window, platform_window) = new_tracking_window();
+
+        window.set_bounds(window.bounds());
+        assert!(!window.dirty());
+
+        window.flush().unwrap();
+        assert!(platform_window.get_set_bounds_calls().is_empty());
+    }
+
+    #[test]
+    fn test_flush_skips_bounds_that_settled_back() {
+        let (window, platform_window) = new_tracking_window();
+        let first = Bounds::new(10, 20, 200, 300);
+
+        window.set_bounds(first.clone());
+        window.flush().unwrap();
+        assert_eq!(platform_window.get_set_bounds_calls().len(), 1);
+
+        // Moves away and back before the next flush, so the platform already has these bounds
+        window.set_bounds(Bounds::new(50, 20, 200, 300));
+        window.set_bounds(first);
+        assert!(window.dirty());
+
+        window.flush().unwrap();
+        assert!(!window.dirty());
+        assert_eq!(platform_window.get_set_bounds_calls().len(), 1);
+
+        // Marking dirty forces the bounds to be sent again
+        window.mark_dirty();
+        window.flush().unwrap();
+        assert_eq!(platform_window.get_set_bounds_calls().len(), 2);
+    }
+
     #[test]
     fn test_gap_calculation_in_flush() {
         let (window, platform_window) = new_tracking_window();
@@ -462,4 +841,131 @@ mod tests {
         window.flush().unwrap();
         assert!(!window.dirty());
     }
+
+    #[test]
+    fn test_set_opacity_flushes_once() {
+        let (window, platform_window) = new_tracking_window();
+
+        window.set_opacity(0.8);
+        window.set_opacity(0.6);
+        assert!(window.dirty());
+        assert!(platform_window.get_set_opacity_calls().is_empty());
+
+        window.flush().unwrap();
+        assert!(!window.dirty());
+        assert_eq!(platform_window.get_set_opacity_calls(), vec![0.6]);
+        assert!(platform_window.get_set_bounds_calls().is_empty());
+    }
+
+    #[test]
+    fn test_set_same_opacity_does_not_flush() {
+        let (window, platform_window) = new_tracking_window();
+
+        window.set_opacity(1.0);
+        assert!(!window.dirty());
+
+        window.set_opacity(1.5);
+        window.flush().unwrap();
+        assert_eq!(window.opacity(), 1.0);
+        assert!(platform_window.get_set_opacity_calls().is_empty());
+    }
+
+    #[test]
+    fn test_set_sticky_marks_dirty_until_flush() {
+        let (window, _platform_window) = new_tracking_window();
+
+        window.set_sticky(false);
+        assert!(!window.dirty());
+
+        window.set_sticky(true);
+        assert!(window.sticky());
+        assert!(window.dirty());
+
+        window.flush().unwrap();
+        assert!(!window.dirty());
+        assert!(window.sticky());
+    }
+
+    #[test]
+    fn test_aspect_lock_right_edge_fits_height() {
+        let old = Bounds::new(100, 100, 400, 300);
+        let new = Bounds::new(100, 100, 800, 300);
+
+        let locked = aspect_locked_bounds(&old, &new, 4.0 / 3.0, ResizeDirection::Right);
+        assert_eq!(locked, Bounds::new(100, 100, 800, 600));
+    }
+
+    #[test]
+    fn test_aspect_lock_top_edge_keeps_bottom() {
+        let old = Bounds::new(100, 100, 400, 300);
+        let new = Bounds::new(100, 40, 400, 360);
+
+        let locked = aspect_locked_bounds(&old, &new, 4.0 / 3.0, ResizeDirection::Top);
+        assert_eq!(locked, Bounds::new(100, 40, 480, 360));
+    }
+
+    #[test]
+    fn test_aspect_lock_top_left_follows_larger_delta() {
+        let old = Bounds::new(100, 100, 400, 300);
+        // Width grew by 200, height by 20, so width leads
+        let new = Bounds::new(-100, 80, 600, 320);
+
+        let locked = aspect_locked_bounds(&old, &new, 4.0 / 3.0, ResizeDirection::TopLeft);
+        assert_eq!(locked.size.width, 600);
+        assert_eq!(locked.size.height, 450);
+        // The bottom right corner stays put
+        assert_eq!(locked.position.x + locked.size.width as i32, 500);
+        assert_eq!(locked.position.y + locked.size.height as i32, 400);
+    }
+
+    #[test]
+    fn test_aspect_lock_bottom_right_height_leads() {
+        let old = Bounds::new(100, 100, 400, 300);
+        // Height grew by 300, width by 10, so height leads
+        let new = Bounds::new(100, 100, 410, 600);
+
+        let locked = aspect_locked_bounds(&old, &new, 4.0 / 3.0, ResizeDirection::BottomRight);
+        assert_eq!(locked, Bounds::new(100, 100, 800, 600));
+    }
+
+    #[test]
+    fn test_aspect_lock_bottom_left_keeps_top_right() {
+        let old = Bounds::new(100, 100, 400, 300);
+        let new = Bounds::new(200, 100, 300, 290);
+
+        let locked = aspect_locked_bounds(&old, &new, 4.0 / 3.0, ResizeDirection::BottomLeft);
+        assert_eq!(locked.size.width, 300);
+        assert_eq!(locked.size.height, 225);
+        assert_eq!(locked.position.x + locked.size.width as i32, 500);
+        assert_eq!(locked.position.y, 100);
+    }
+
+    #[test]
+    fn test_gapped_bounds_scale_with_display() {
+        let bounds = Bounds::new(0, 0, 1000, 800);
+        assert_eq!(gapped_bounds(&bounds, 10, 1.0), Bounds::new(5, 5, 990, 790));
+
+        // The same area on a 2x display is twice as many coordinate units, and so is the gap
+        let scaled = Bounds::new(0, 0, 2000, 1600);
+        assert_eq!(
+            gapped_bounds(&scaled, 10, 2.0),
+            Bounds::new(10, 10, 1980, 1580)
+        );
+
+        // Windows are never shrunk below 100 logical pixels
+        let tiny = Bounds::new(0, 0, 50, 50);
+        assert_eq!(gapped_bounds(&tiny, 10, 1.0).size.width, 100);
+        assert_eq!(gapped_bounds(&tiny, 10, 2.0).size.width, 200);
+    }
+
+    #[test]
+    fn test_scale_factor_change_marks_bounds_dirty() {
+        let (window, _) = new_tracking_window();
+        window.set_scale_factor(1.0);
+        assert!(!window.dirty());
+
+        window.set_scale_factor(2.0);
+        assert!(window.dirty());
+        assert_eq!(window.scale_factor(), 2.0);
+    }
 }
@@ -0,0 +1,259 @@
+use crate::config::{Config, WindowRule};
+use crate::layouts::RatioLimits;
+use crate::platform::Size;
+use crate::window::Window;
+use log::warn;
+use regex::Regex;
+
+/// The combined actions of every rule that matches a window. Rules are applied in order,
+/// so a later rule overrides any action an earlier one set.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowRuleActions {
+    pub float: Option<bool>,
+    pub ignore: bool,
+    pub workspace: Option<String>,
+    pub opacity: Option<f32>,
+    pub ratio_limits: RatioLimits,
+}
+
+/// Evaluates the configured window rules against a window.
+pub fn evaluate_window_rules(window: &Window) -> WindowRuleActions {
+    let rules = &Config::current().window_rules;
+    evaluate_rules(rules, &window.app_name(), &window.title())
+}
+
+pub fn evaluate_rules(rules: &[WindowRule], app_name: &str, title: &str) -> WindowRuleActions {
+    let mut actions = WindowRuleActions::default();
+
+    for rule in rules.iter().filter(|r| rule_matches(r, app_name, title)) {
+        if rule.float.is_some() {
+            actions.float = rule.float;
+        }
+        if rule.ignore {
+            actions.ignore = true;
+        }
+        if rule.workspace.is_some() {
+            actions.workspace = rule.workspace.clone();
+        }
+        if rule.opacity.is_some() {
+            actions.opacity = rule.opacity.map(|o| o.clamp(0.0, 1.0));
+        }
+        if rule.min_ratio.is_some() {
+            actions.ratio_limits.min_ratio = rule.min_ratio.map(|r| r.clamp(0.0, 1.0));
+        }
+        if rule.max_ratio.is_some() {
+            actions.ratio_limits.max_ratio = rule.max_ratio.map(|r| r.clamp(0.0, 1.0));
+        }
+    }
+
+    actions
+}
+
+/// Checks whether a window looks transient (a popup, splash screen, etc.) and should be floated
+/// rather than disrupting the tiled layout.
+pub fn is_transient_window(window: &Window) -> bool {
+    let config = Config::current();
+    is_transient(
+        &window.title(),
+        &window.bounds().size,
+        config.auto_float_min_tile_size,
+        config.require_title_to_tile,
+    )
+}
+
+pub fn is_transient(title: &str, size: &Size, min_size: (u32, u32), require_title: bool) -> bool {
+    let (min_width, min_height) = min_size;
+    if size.width < min_width || size.height < min_height {
+        return true;
+    }
+
+    require_title && title.trim().is_empty()
+}
+
+/// A rule matches when every pattern it specifies matches. Rules without any pattern never match.
+pub fn rule_matches(rule: &WindowRule, app_name: &str, title: &str) -> bool {
+    if rule.app_pattern.is_none() && rule.title_pattern.is_none() {
+        return false;
+    }
+
+    let app_matches =
+        rule.app_pattern.is_none() || rule.app_regex().is_some_and(|r| r.is_match(app_name));
+    let title_matches =
+        rule.title_pattern.is_none() || rule.title_regex().is_some_and(|r| r.is_match(title));
+
+    app_matches && title_matches
+}
+
+impl WindowRule {
+    /// The patterns of this rule that aren't valid regexes
+    pub fn invalid_patterns(&self) -> Vec<&str> {
+        [self.app_pattern.as_deref(), self.title_pattern.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|pattern| Regex::new(pattern).is_err())
+            .collect()
+    }
+
+    fn app_regex(&self) -> Option<&Regex> {
+        self.app_regex
+            .get_or_init(|| self.app_pattern.as_deref().and_then(compile_pattern))
+            .as_ref()
+    }
+
+    fn title_regex(&self) -> Option<&Regex> {
+        self.title_regex
+            .get_or_init(|| self.title_pattern.as_deref().and_then(compile_pattern))
+            .as_ref()
+    }
+}
+
+fn compile_pattern(pattern: &str) -> Option<Regex> {
+    Regex::new(pattern)
+        .inspect_err(|e| warn!("Invalid window rule pattern {pattern:?}: {e}"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatformWindow;
+    use crate::platform::{Position, Size};
+
+    fn new_window(app_name: &str, title: &str) -> Window {
+        let mut platform_window = MockPlatformWindow::new(
+            Position { x: 0, y: 0 },
+            Size {
+                width: 800,
+                height: 600,
+            },
+            title.to_string(),
+        );
+        platform_window.app_name = app_name.to_string();
+        Window::new(platform_window)
+    }
+
+    fn evaluate(rules: &[WindowRule], window: &Window) -> WindowRuleActions {
+        evaluate_rules(rules, &window.app_name(), &window.title())
+    }
+
+    fn float_rule(app_pattern: Option<&str>, title_pattern: Option<&str>) -> WindowRule {
+        WindowRule {
+            app_pattern: app_pattern.map(str::to_string),
+            title_pattern: title_pattern.map(str::to_string),
+            float: Some(true),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_small_window_is_transient() {
+        let size = |width, height| Size { width, height };
+
+        assert!(is_transient("Popup", &size(150, 400), (200, 150), false));
+        assert!(is_transient("Popup", &size(400, 100), (200, 150), false));
+        assert!(!is_transient("Editor", &size(200, 150), (200, 150), false));
+        assert!(!is_transient("Editor", &size(800, 600), (200, 150), false));
+    }
+
+    #[test]
+    fn test_untitled_window_is_transient_when_title_required() {
+        let window = new_window("Finder", "");
+        let size = window.bounds().size;
+
+        assert!(is_transient(&window.title(), &size, (0, 0), true));
+        assert!(is_transient("   ", &size, (0, 0), true));
+        assert!(!is_transient(&window.title(), &size, (0, 0), false));
+    }
+
+    #[test]
+    fn test_title_pattern_floats_matching_window() {
+        let rules = vec![float_rule(None, Some("^Preferences$"))];
+
+        let dialog = new_window("Finder", "Preferences");
+        let other = new_window("Finder", "Documents");
+
+        assert_eq!(evaluate(&rules, &dialog).float, Some(true));
+        assert_eq!(evaluate(&rules, &other).float, None);
+    }
+
+    #[test]
+    fn test_app_and_title_patterns_must_both_match() {
+        let rules = vec![float_rule(Some("(?i)^code$"), Some("Settings"))];
+
+        assert_eq!(
+            evaluate(&rules, &new_window("Code", "Settings - Code")).float,
+            Some(true)
+        );
+        assert_eq!(evaluate(&rules, &new_window("Code", "main.rs")).float, None);
+        assert_eq!(
+            evaluate(&rules, &new_window("Safari", "Settings")).float,
+            None
+        );
+    }
+
+    #[test]
+    fn test_ignore_rule() {
+        let rules = vec![WindowRule {
+            app_pattern: Some("Picker".to_string()),
+            ignore: true,
+            ..Default::default()
+        }];
+
+        assert!(evaluate(&rules, &new_window("Color Picker", "Colors")).ignore);
+        assert!(!evaluate(&rules, &new_window("Terminal", "Colors")).ignore);
+    }
+
+    #[test]
+    fn test_later_rules_override_earlier_rules() {
+        let rules = vec![
+            float_rule(Some("Terminal"), None),
+            WindowRule {
+                title_pattern: Some("tiled".to_string()),
+                float: Some(false),
+                workspace: Some("Dev".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let actions = evaluate(&rules, &new_window("Terminal", "tiled shell"));
+        assert_eq!(actions.float, Some(false));
+        assert_eq!(actions.workspace.as_deref(), Some("Dev"));
+
+        let actions = evaluate(&rules, &new_window("Terminal", "shell"));
+        assert_eq!(actions.float, Some(true));
+        assert_eq!(actions.workspace, None);
+    }
+
+    #[test]
+    fn test_rule_without_patterns_never_matches() {
+        let rules = vec![WindowRule {
+            ignore: true,
+            ..Default::default()
+        }];
+
+        assert_eq!(
+            evaluate(&rules, &new_window("Terminal", "shell")),
+            WindowRuleActions::default()
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern_does_not_match() {
+        let rules = vec![float_rule(None, Some("("))];
+        assert_eq!(evaluate(&rules, &new_window("Terminal", "(")).float, None);
+    }
+
+    #[test]
+    fn test_opacity_is_clamped() {
+        let rules = vec![WindowRule {
+            app_pattern: Some("Slack".to_string()),
+            opacity: Some(1.5),
+            ..Default::default()
+        }];
+
+        assert_eq!(
+            evaluate(&rules, &new_window("Slack", "general")).opacity,
+            Some(1.0)
+        );
+    }
+}
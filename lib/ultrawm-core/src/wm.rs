@@ -1,23 +1,44 @@
-use crate::config::Config;
-use crate::layouts::{ContainerTree, LayoutError, PlacementTarget, WindowLayout};
+use crate::ai::{OrganizePlan, OrganizePlanId, OrganizePreview};
+use crate::config::{Config, HiddenWindowParking, NewWindowFocus};
+use crate::layouts::{
+    self, Direction, LayoutError, LayoutType, PlacementTarget, ResizeDirection, Side,
+};
 use crate::partition::{Partition, PartitionId};
-use crate::platform::{Bounds, Platform, PlatformImpl, PlatformResult, Position, WindowId};
+use crate::platform::{
+    Bounds, Display, Platform, PlatformImpl, PlatformResult, Position, WindowId,
+};
 use crate::resize_handle::{ResizeHandle, ResizeMode};
-use crate::serialization::{extract_window_ids, load_layout, save_layout};
+use crate::serialization::{
+    self, extract_window_ids, load_layout, save_layout, LayoutSaveDebouncer, RestoreReport,
+    SerializedWindowManager, SerializedWorkspace,
+};
+use crate::snapshot::{self, LayoutSnapshot, PartitionSnapshot, WindowSnapshot, WorkspaceSnapshot};
 use crate::tile_result::InsertResult;
-use crate::window::{Window, WindowRef};
+use crate::window::{aspect_locked_bounds, flush_windows_batched, Window, WindowRef};
+use crate::window_rules::{evaluate_window_rules, is_transient_window};
 use crate::workspace::{Workspace, WorkspaceId};
-use crate::workspace_animator::{WorkspaceAnimationConfig, WorkspaceAnimationThread};
+use crate::workspace_animator::{
+    AnimationKind, AnimationStats, WorkspaceAnimationConfig, WorkspaceAnimationThread,
+};
 use crate::PlatformError;
 use indexmap::IndexSet;
 use log::{debug, error, trace, warn};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
-// Number of partitions to create per display
-// Temporary
-const PARTITIONS_PER_DISPLAY: u32 = 1;
+// Upper limit for the window gap when adjusting it at runtime
+const MAX_WINDOW_GAP: u32 = 200;
+
+// How far windows may overlap and still count as neighbors when searching in a direction
+const NEIGHBOR_OVERLAP_TOLERANCE: i32 = 10;
+
+// How long a split waits for the window it was meant for before it is dropped
+const PENDING_SPLIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// How long closing a workspace's windows waits to be confirmed before it is dropped
+const PENDING_CLOSE_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Error)]
 pub enum WMError {
@@ -30,11 +51,26 @@ pub enum WMError {
     #[error("No workspace found at position: {0:?}")]
     NoWorkspaceAtPosition(Position),
 
+    #[error("Workspace not found: {0}")]
+    UnknownWorkspace(WorkspaceId),
+
+    #[error("No workspace named {0:?}")]
+    UnknownWorkspaceName(String),
+
+    #[error("No layout named {0:?}, expected container-tree or master-stack")]
+    UnknownLayout(String),
+
+    #[error("Partition not found: {0}")]
+    PartitionNotFound(PartitionId),
+
     #[error(transparent)]
     LayoutError(#[from] LayoutError),
 
     #[error(transparent)]
     Platform(#[from] PlatformError),
+
+    #[error("Layout preset error: {0}")]
+    Preset(String),
 }
 
 pub type WMResult<T> = Result<T, WMError>;
@@ -45,8 +81,44 @@ pub struct WindowManager {
     window_order: IndexSet<WindowId>,
     animation_thread: WorkspaceAnimationThread,
     all_windows: HashMap<WindowId, WindowRef>,
+    /// Windows that were floated because they looked transient when first tracked
+    transient_windows: HashSet<WindowId>,
+    /// Floating windows that follow the active workspace of their partition
+    sticky_windows: HashSet<WindowId>,
+    /// Windows that belong to the scratchpad, in the order they were stashed. While stashed
+    /// they are hidden and in no workspace.
+    scratchpad_windows: IndexSet<WindowId>,
+    /// Windows `park_window` minimized, restored when they're shown again
+    minimized_windows: HashSet<WindowId>,
+    /// Where tiled windows were before going full screen, so they can go back there: the
+    /// workspace, and a window they were beside with the side of it they were on
+    fullscreen_slots: HashMap<WindowId, (WorkspaceId, Option<(WindowId, Side)>)>,
+    /// Where floating windows were before `toggle_fullscreen`, the platform doesn't always
+    /// put them back itself
+    fullscreen_bounds: HashMap<WindowId, Bounds>,
+    /// The layout from before the resize handle drag in progress, recorded for undo once the
+    /// handle is released
+    resize_drag_before: Option<Vec<SerializedWorkspace>>,
     /// Set when deferred resize methods are called, cleared on flush
     needs_flush: bool,
+    /// While paused, layout changes are tracked but window bounds aren't sent to the platform
+    paused: bool,
+    /// The shown workspaces as they were before each layout change, newest last
+    undo_stack: VecDeque<Vec<SerializedWorkspace>>,
+    /// Layouts stepped back over by undo, newest last. Any new change clears them.
+    redo_stack: Vec<Vec<SerializedWorkspace>>,
+    /// An arrangement waiting to be confirmed, with the overlays previewing it
+    pending_organize: Option<(OrganizePlan, Option<OrganizePreview>)>,
+    /// Where the next new window is tiled: beside this window, on this side. Set by the split
+    /// commands and used by the next new window, or dropped once it times out.
+    pending_split: Option<(WindowId, Side, Instant)>,
+    /// The workspace whose windows are closed once confirmed, and the window to keep open
+    pending_close: Option<(WorkspaceId, Option<WindowId>, Instant)>,
+    /// Writes `try_save_layout` saves in the background
+    layout_saver: LayoutSaveDebouncer,
+    /// The window gap set with the gap commands and the configured gap it replaced. Kept over
+    /// config reloads until the config file changes the gap itself.
+    window_gap_override: Option<(u32, u32)>,
 }
 
 impl WindowManager {
@@ -61,24 +133,8 @@ impl WindowManager {
         }
 
         let mut partitions: HashMap<PartitionId, Partition> = HashMap::new();
-        for display in displays {
-            let partition_width = display.work_area.size.width / PARTITIONS_PER_DISPLAY;
-
-            for i in 0..PARTITIONS_PER_DISPLAY {
-                let partition_bounds = Bounds::new(
-                    display.work_area.position.x + (i as i32 * partition_width as i32),
-                    display.work_area.position.y,
-                    partition_width,
-                    display.work_area.size.height,
-                );
-
-                let partition_name = if PARTITIONS_PER_DISPLAY == 1 {
-                    display.name.clone()
-                } else {
-                    format!("{}_partition_{}", display.name, i + 1)
-                };
-
-                let partition = Partition::new(partition_name, partition_bounds);
+        for display in &displays {
+            for partition in Self::partitions_for_display(display) {
                 partitions.insert(partition.id(), partition);
             }
         }
@@ -87,6 +143,7 @@ impl WindowManager {
         let mut windows = Platform::list_visible_windows()?
             .iter()
             .map(|w| Rc::new(Window::new(w.clone())))
+            .filter(|w| !Self::is_ignored(w))
             .collect::<Vec<_>>();
         windows.sort_by_key(|w| w.id());
 
@@ -104,50 +161,43 @@ impl WindowManager {
                 animation_fps: Config::window_tile_fps(),
             }),
             all_windows,
+            transient_windows: HashSet::new(),
+            sticky_windows: HashSet::new(),
+            scratchpad_windows: IndexSet::new(),
+            minimized_windows: HashSet::new(),
+            fullscreen_slots: HashMap::new(),
+            fullscreen_bounds: HashMap::new(),
+            resize_drag_before: None,
             needs_flush: false,
+            paused: false,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            pending_organize: None,
+            pending_split: None,
+            pending_close: None,
+            layout_saver: LayoutSaveDebouncer::new(Duration::from_millis(
+                Config::layout_save_debounce_ms(),
+            )),
+            window_gap_override: None,
         };
 
-        // Try to load saved layout
+        // Try to load saved layout, whatever it doesn't place is placed like a new window
+        let mut unplaced = windows;
         if let Ok(Some(saved_layout)) = load_layout() {
-            for serialized_partition in saved_layout.partitions {
-                // Find partition by name
-                let partition_id = match wm
-                    .partitions
-                    .values()
-                    .find(|p| p.name() == &serialized_partition.name)
-                    .map(|p| p.id())
-                {
-                    Some(id) => id,
-                    None => {
-                        warn!(
-                            "Saved layout references unknown partition: {}",
-                            serialized_partition.name
-                        );
-                        continue;
-                    }
-                };
-
-                // Load each workspace using the reusable function
-                for serialized_workspace in &serialized_partition.workspaces {
-                    if let Err(e) = wm.load_serialized_workspace(serialized_workspace, partition_id)
-                    {
-                        warn!(
-                            "Failed to load workspace {}: {}",
-                            serialized_workspace.id, e
-                        );
-                    }
-                }
+            let report = wm.restore_saved_layout(saved_layout);
+            if !report.missing.is_empty() {
+                debug!("Saved layout windows that are gone: {:?}", report.missing);
             }
+            unplaced.retain(|w| report.unplaced.contains(&w.id()));
         }
 
         // Ensure all partitions have a workspace assigned
         for partition in wm.partitions.values_mut() {
             if partition.current_workspace().is_none() {
-                let workspace = Workspace::new::<ContainerTree>(
+                let workspace = Workspace::with_layout_type(
                     partition.bounds().clone(),
                     "Default".to_string(),
-                    None,
-                    None,
+                    Config::layout_for_workspace("Default"),
                 );
                 let workspace_id = workspace.id();
                 wm.workspaces.insert(workspace_id, workspace);
@@ -155,7 +205,10 @@ impl WindowManager {
             }
         }
 
+        wm.create_pinned_workspaces(&displays);
+
         // Flush all windows
+        wm.apply_window_settings();
         for workspace in wm.workspaces.values_mut() {
             workspace.flush_windows()?;
         }
@@ -165,17 +218,311 @@ impl WindowManager {
             debug!("  {:?} bounds={:?}", p.name(), p.bounds());
         }
 
+        wm.track_unplaced_windows(unplaced);
+
+        wm.publish_snapshot();
+        Ok(wm)
+    }
+
+    /// Loads each saved workspace into the partition with the same name. Saved windows that
+    /// aren't open anymore are left out. The report covers every known window, so a window one
+    /// workspace had no slot for but another one placed isn't reported as unplaced.
+    fn restore_saved_layout(&mut self, saved_layout: SerializedWindowManager) -> RestoreReport {
+        let mut report = RestoreReport::default();
+        if let Some(gap) = saved_layout.window_gap {
+            self.override_window_gap(gap);
+        }
+        let window_order = saved_layout.window_order;
+        for serialized_partition in saved_layout.partitions {
+            // Find partition by name
+            let partition_id = match self
+                .partitions
+                .values()
+                .find(|p| p.name() == &serialized_partition.name)
+                .map(|p| p.id())
+            {
+                Some(id) => id,
+                None => {
+                    warn!(
+                        "Saved layout references unknown partition: {}",
+                        serialized_partition.name
+                    );
+                    continue;
+                }
+            };
+
+            // Load each workspace using the reusable function
+            for serialized_workspace in &serialized_partition.workspaces {
+                match self.load_serialized_workspace(serialized_workspace, partition_id) {
+                    Ok(restored) => {
+                        report.placed.extend(restored.placed);
+                        report.missing.extend(restored.missing);
+                    }
+                    Err(e) => warn!(
+                        "Failed to load workspace {}: {}",
+                        serialized_workspace.id, e
+                    ),
+                }
+            }
+        }
+        self.restore_window_order(&window_order);
+
+        report.placed.sort();
+        report.placed.dedup();
+        report.missing.sort();
+        report.missing.dedup();
+
+        // The rules may have changed since the save, where they set limits they win over the
+        // ones kept in the layout
+        for id in &report.placed {
+            if let Some(window) = self.all_windows.get(id) {
+                let limits = evaluate_window_rules(window).ratio_limits;
+                if !limits.is_empty() {
+                    window.set_ratio_limits(limits);
+                }
+            }
+        }
+
+        report.unplaced = self
+            .all_windows
+            .values()
+            .filter(|w| self.get_workspace_with_window(w).is_none())
+            .map(|w| w.id())
+            .collect();
+        report.unplaced.sort();
+        report
+    }
+
+    /// Puts back the saved focus order. There's no way to set the stacking order directly, so
+    /// floating windows are put on top again from the bottom up and the last focused window is
+    /// focused. This is best effort, where windows can't be kept on top (macOS) only the focused
+    /// window ends up in the right place.
+    fn restore_window_order(&mut self, order: &[WindowId]) {
+        let windows = order
+            .iter()
+            .filter_map(|id| self.all_windows.get(id).cloned())
+            .collect::<Vec<_>>();
+
+        for window in &windows {
+            self.move_to_top(window.id());
+            if window.floating() {
+                window
+                    .reapply_always_on_top()
+                    .unwrap_or_else(|e| warn!("Could not restack window {}: {e}", window.id()));
+            }
+        }
+
+        if let Some(focused) = windows.last() {
+            self.focus_window_internal(focused.id(), false)
+                .unwrap_or_else(|e| warn!("Could not focus window {}: {e}", focused.id()));
+        }
+    }
+
+    /// Places the windows the saved layout didn't put anywhere as if they had just opened
+    fn track_unplaced_windows(&mut self, windows: Vec<WindowRef>) {
         debug!("Tracking {} windows at startup...", windows.len());
         for window in windows {
-            if wm.get_workspace_with_window(&window).is_none() {
-                debug!("  Tracking window: id={}", window.id());
-                wm.track_window(window.clone()).unwrap_or_else(|e| {
+            debug!("  Tracking window: id={}", window.id());
+            self.place_new_window(window.clone())
+                .map(|_| ())
+                .unwrap_or_else(|e| {
                     error!("Failed to track window: {e}");
                 })
+        }
+    }
+
+    /// Splits a display's work area into `partitions_per_display` columns. With a single
+    /// partition it's named after the display, otherwise `{display}_partition_{i}`. Saved
+    /// workspaces are matched by partition name, so changing the count drops the old ones and
+    /// their windows are placed again like new windows.
+    pub(crate) fn partitions_for_display(display: &Display) -> Vec<Partition> {
+        let fractions = Config::partition_fractions();
+        let count = fractions.len();
+        let area = &display.work_area;
+
+        let mut partitions = Vec::with_capacity(count);
+        let mut x = area.position.x;
+        for (i, fraction) in fractions.iter().enumerate() {
+            // The last partition takes whatever is left so rounding never leaves a gap
+            let right = if i + 1 == count {
+                area.position.x + area.size.width as i32
+            } else {
+                x + (area.size.width as f32 * fraction).round() as i32
+            };
+            let bounds = Bounds::new(
+                x,
+                area.position.y,
+                (right - x).max(0) as u32,
+                area.size.height,
+            );
+
+            let name = if count == 1 {
+                display.name.clone()
+            } else {
+                format!("{}_partition_{}", display.name, i + 1)
+            };
+
+            let mut partition = Partition::new(name, bounds);
+            partition.set_scale_factor(display.scale_factor);
+            partitions.push(partition);
+            x = right;
+        }
+        partitions
+    }
+
+    /// Creates the workspaces the config pins to a display on that display's first partition,
+    /// unless they were restored from the saved layout. Workspaces pinned to a display that
+    /// isn't connected go on the primary display, the one at the origin.
+    fn create_pinned_workspaces(&mut self, displays: &[Display]) {
+        if let Err(e) = Config::current().validate_displays(displays) {
+            warn!("{e}, using the primary display for them");
+        }
+
+        let mut pinned: Vec<(String, String)> = Config::current()
+            .workspaces
+            .iter()
+            .filter_map(|(name, workspace)| Some((name.clone(), workspace.display.clone()?)))
+            .collect();
+        pinned.sort();
+
+        let primary = displays
+            .iter()
+            .find(|d| d.bounds.contains(&Position { x: 0, y: 0 }))
+            .or(displays.first());
+        for (name, display) in pinned {
+            if self.find_workspace_by_name(&name).is_some() {
+                continue;
             }
+            let Some(display) = displays.iter().find(|d| d.name == display).or(primary) else {
+                continue;
+            };
+            let Some(partition_name) = Self::partitions_for_display(display)
+                .first()
+                .map(|p| p.name().clone())
+            else {
+                continue;
+            };
+            let Some(partition) = self
+                .partitions
+                .values_mut()
+                .find(|p| *p.name() == partition_name)
+            else {
+                continue;
+            };
+
+            debug!("Creating workspace {name:?} on {}", partition.name());
+            let workspace = Workspace::with_layout_type(
+                partition.bounds().clone(),
+                name.clone(),
+                Config::layout_for_workspace(&name),
+            );
+            partition.assign_workspace(workspace.id());
+            self.workspaces.insert(workspace.id(), workspace);
+        }
+    }
+
+    /// Rebuilds the partitions after displays were plugged in, unplugged or rearranged. They're
+    /// matched to displays by name like saved layouts are, so partitions that are still around
+    /// keep their workspaces. Windows from a display that went away move to the leftmost
+    /// remaining partition.
+    pub fn displays_changed(&mut self) -> WMResult<()> {
+        let displays = Platform::list_all_displays()?;
+        if displays.is_empty() {
+            warn!("No displays found, keeping the current partitions");
+            return Ok(());
         }
 
-        Ok(wm)
+        let wanted: Vec<Partition> = displays
+            .iter()
+            .flat_map(Self::partitions_for_display)
+            .collect();
+        let removed: Vec<PartitionId> = self
+            .partitions
+            .values()
+            .filter(|p| !wanted.iter().any(|w| w.name() == p.name()))
+            .map(|p| p.id())
+            .collect();
+
+        for mut new in wanted {
+            match self
+                .partitions
+                .values_mut()
+                .find(|p| p.name() == new.name())
+            {
+                Some(partition) => {
+                    partition.set_scale_factor(new.scale_factor());
+                    if partition.bounds() == new.bounds() {
+                        continue;
+                    }
+                    debug!("Partition {} moved to {:?}", partition.name(), new.bounds());
+                    partition.set_bounds(new.bounds().clone());
+                    for workspace_id in partition.assigned_workspaces() {
+                        if let Some(workspace) = self.workspaces.get_mut(workspace_id) {
+                            workspace.set_bounds(new.bounds().clone());
+                        }
+                    }
+                }
+                None => {
+                    debug!("Display added, creating partition {}", new.name());
+                    let workspace = Workspace::with_layout_type(
+                        new.bounds().clone(),
+                        "Default".to_string(),
+                        Config::layout_for_workspace("Default"),
+                    );
+                    new.assign_workspace(workspace.id());
+                    self.workspaces.insert(workspace.id(), workspace);
+                    self.partitions.insert(new.id(), new);
+                }
+            }
+        }
+
+        let mut orphans = Vec::new();
+        for id in removed {
+            let Some(partition) = self.partitions.remove(&id) else {
+                continue;
+            };
+            debug!("Display removed, dropping partition {}", partition.name());
+            for workspace_id in partition.assigned_workspaces() {
+                if let Some(workspace) = self.workspaces.remove(workspace_id) {
+                    orphans.extend(workspace.windows().values().cloned());
+                }
+            }
+        }
+
+        let target = self
+            .partitions
+            .values()
+            .min_by_key(|p| (p.bounds().position.x, p.bounds().position.y))
+            .and_then(|p| Some((p.bounds().clone(), p.current_workspace()?)));
+        if let Some((area, workspace_id)) = target {
+            let workspace = self
+                .workspaces
+                .get_mut(&workspace_id)
+                .ok_or(WMError::UnknownWorkspace(workspace_id))?;
+            for window in &orphans {
+                debug!("Moving window {} off the removed display", window.id());
+                let result = if window.floating() {
+                    workspace.float_window_centered(
+                        window,
+                        &area,
+                        Config::float_window_size_ratio(),
+                    )
+                } else {
+                    workspace.tile_window(window, &area.center()).map(|_| ())
+                };
+                if let Err(e) = result {
+                    warn!(
+                        "Could not move window {} off the removed display: {e}",
+                        window.id()
+                    );
+                }
+            }
+        }
+
+        self.animated_flush()?;
+        self.try_save_layout();
+        self.publish_snapshot();
+        Ok(())
     }
 
     pub fn partitions(&self) -> &HashMap<PartitionId, Partition> {
@@ -186,7 +533,81 @@ impl WindowManager {
         &self.workspaces
     }
 
+    /// Returns the workspace of the most recently focused window, falling back to the
+    /// workspace under the mouse cursor.
+    pub fn focused_workspace_id(&self) -> Option<WorkspaceId> {
+        let focused = self
+            .window_order
+            .iter()
+            .rev()
+            .filter_map(|id| self.all_windows.get(id))
+            .find_map(|window| self.get_workspace_with_window(window));
+
+        if let Some(workspace) = focused {
+            return Some(workspace.id());
+        }
+
+        let position = Platform::get_mouse_position().ok()?;
+        self.get_workspace_at_position(&position)
+            .ok()
+            .map(|w| w.id())
+    }
+
+    /// Renames a workspace. If another workspace in the same partition already uses the
+    /// name, a numeric suffix is appended. Returns the name that was applied.
+    pub fn rename_workspace(&mut self, workspace_id: WorkspaceId, name: &str) -> WMResult<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(WMError::LayoutError(LayoutError::Error(
+                "Workspace name cannot be empty".to_string(),
+            )));
+        }
+
+        if !self.workspaces.contains_key(&workspace_id) {
+            return Err(WMError::WorkspaceNotFound(workspace_id));
+        }
+
+        let taken: Vec<String> = self
+            .partitions
+            .values()
+            .find(|p| p.assigned_workspaces().contains(&workspace_id))
+            .map(|p| {
+                p.assigned_workspaces()
+                    .iter()
+                    .filter(|id| **id != workspace_id)
+                    .filter_map(|id| self.workspaces.get(id))
+                    .map(|w| w.name().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut unique_name = name.to_string();
+        let mut suffix = 2;
+        while taken.contains(&unique_name) {
+            unique_name = format!("{name} ({suffix})");
+            suffix += 1;
+        }
+
+        let workspace = self.workspaces.get_mut(&workspace_id).unwrap();
+        workspace.set_name(unique_name.clone());
+        // The new name may come with its own gaps
+        self.animated_flush()?;
+        self.try_save_layout();
+        Ok(unique_name)
+    }
+
     pub fn track_window(&mut self, window: WindowRef) -> WMResult<()> {
+        let previous = self.window_order.last().copied();
+        if self.place_new_window(window.clone())? {
+            self.apply_new_window_focus(&window, previous)?;
+            self.publish_snapshot();
+        }
+        Ok(())
+    }
+
+    /// Places a window into a workspace without touching focus. Returns whether the window
+    /// was newly placed.
+    fn place_new_window(&mut self, window: WindowRef) -> WMResult<bool> {
         trace!(
             "track_window: id={} visible={} title={:?}",
             window.id(),
@@ -194,39 +615,307 @@ impl WindowManager {
             window.title()
         );
 
+        if Self::is_ignored(&window) {
+            trace!("  -> ignored by window rule");
+            return Ok(false);
+        }
+        let rules = evaluate_window_rules(&window);
+
         // Always add to all_windows if not already present
-        if !self.all_windows.contains_key(&window.id()) {
-            self.all_windows.insert(window.id(), window.clone());
+        match self.all_windows.get(&window.id()) {
+            // The title may have changed while the window was hidden
+            Some(existing) => existing.refresh_title(),
+            None => {
+                self.all_windows.insert(window.id(), window.clone());
+            }
         }
 
         // Check if already in a workspace
         if self.get_workspace_with_window(&window).is_some() {
             trace!("  -> already tracked in workspace");
-            return Ok(());
+            return Ok(false);
+        }
+
+        if self.is_stashed(window.id()) {
+            trace!("  -> stashed in the scratchpad");
+            return Ok(false);
         }
 
         if !window.visible() {
             trace!("  -> not visible, stored in all_windows");
-            return Ok(());
+            return Ok(false);
+        }
+
+        if window.fullscreen() {
+            trace!("  -> full screen, stored in all_windows");
+            return Ok(false);
+        }
+
+        // A split is for whichever window opens next, even one that would otherwise float
+        let split = self.take_pending_split();
+
+        // Keep new windows in the focus order so they can be cycled to, even if they never
+        // receive focus. They start at the bottom, behind the focused window.
+        if !self.window_order.contains(&window.id()) {
+            self.window_order.shift_insert(0, window.id());
+        }
+
+        let transient = rules.float.is_none() && is_transient_window(&window);
+        if transient {
+            trace!("  -> looks transient, floating");
+            self.transient_windows.insert(window.id());
+        }
+        // The workspace the window lands on decides whether it floats, it may override the
+        // global float_new_windows
+        let float_new_windows = rules
+            .workspace
+            .as_deref()
+            .and_then(|name| self.find_workspace_by_name(name))
+            .and_then(|id| self.workspaces.get(&id))
+            .or_else(|| {
+                self.partition_at_bounds(&window.bounds())
+                    .and_then(|p| p.current_workspace())
+                    .and_then(|id| self.workspaces.get(&id))
+            })
+            .map_or_else(Config::float_new_windows, |w| w.float_new_windows());
+        let float = rules
+            .float
+            .unwrap_or_else(|| transient || float_new_windows);
+
+        if let Some(opacity) = rules.opacity {
+            window.set_opacity(opacity);
+        }
+        if !rules.ratio_limits.is_empty() {
+            window.set_ratio_limits(rules.ratio_limits);
         }
 
-        if Config::float_new_windows() {
+        if let Some((beside, side)) = split {
+            if self.tile_beside(&window, beside, side) {
+                trace!("  -> tiled {side:?} of window {beside}, as split");
+                return Ok(true);
+            }
+        }
+
+        if let Some(name) = rules.workspace.as_deref() {
+            match self.find_workspace_by_name(name) {
+                Some(workspace_id) => {
+                    trace!("  -> placing window in workspace {name:?}");
+                    self.place_window_in_workspace(&window, workspace_id, float)?;
+                    return Ok(true);
+                }
+                None => warn!("Window rule references unknown workspace: {name}"),
+            }
+        }
+
+        if float {
             trace!("  -> floating window");
             let workspace = self.get_workspace_at_bounds_mut(&window.bounds())?;
             workspace.float_window(&window)?;
             self.float_window(window.id())?;
+        } else if let Some(side) =
+            Config::new_window_side().filter(|side| self.tile_beside_focused(&window, *side))
+        {
+            trace!("  -> tiled {side:?} of the focused window");
+        } else {
+            // Windows are tiled where their top left corner is, but a window straddling two
+            // partitions belongs to the one holding its center
+            let bounds = window.bounds();
+            let mut position = bounds.position.clone();
+            if let Some(partition) = self.partition_at_bounds(&bounds) {
+                let area = partition.bounds();
+                position.x = position.x.clamp(
+                    area.position.x,
+                    area.position.x + area.size.width as i32 - 1,
+                );
+                position.y = position.y.clamp(
+                    area.position.y,
+                    area.position.y + area.size.height as i32 - 1,
+                );
+            }
+            trace!("  -> tiling window at {:?}", position);
+            self.tile_window_at(window.id(), &position)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Tiles a new window on the given side of the focused window. Returns false when there is
+    /// no focused window to place it next to, or the layout can't insert it there.
+    fn tile_beside_focused(&mut self, window: &WindowRef, side: Side) -> bool {
+        let Some(focused) = self
+            .all_windows
+            .values()
+            .find(|w| w.focused() && w.id() != window.id())
+            .map(|w| w.id())
+        else {
+            return false;
+        };
+        self.tile_beside(window, focused, side)
+    }
+
+    /// Tiles a new window on the given side of a tiled window. Returns false when that window
+    /// isn't tiled, or the layout can't insert the new one there.
+    fn tile_beside(&mut self, window: &WindowRef, beside: WindowId, side: Side) -> bool {
+        let Some(workspace_id) = self
+            .all_windows
+            .get(&beside)
+            .filter(|w| !w.floating())
+            .and_then(|w| self.get_workspace_with_window(w))
+            .map(|w| w.id())
+        else {
+            return false;
+        };
+
+        let mut target = serde_yaml::Mapping::new();
+        target.insert("relative_to".into(), beside.into());
+        target.insert(
+            "side".into(),
+            serde_yaml::to_value(side).unwrap_or(serde_yaml::Value::Null),
+        );
+        match self.insert_window_relative(window.id(), target.into(), workspace_id) {
+            Ok(()) => true,
+            Err(e) => {
+                trace!("  -> can't tile beside window {beside}: {e}");
+                false
+            }
+        }
+    }
+
+    /// Tiles the next new window on the given side of a window, for launching an app into a
+    /// split. Only the next window uses it, tiled even if it would float, and only if it opens
+    /// within `PENDING_SPLIT_TIMEOUT`.
+    pub fn split_next_window(&mut self, beside: WindowId, side: Side) -> WMResult<()> {
+        let window = self.get_window(beside)?;
+        if window.floating() {
+            return Err(WMError::LayoutError(LayoutError::Error(
+                "Only tiled windows can be split".to_string(),
+            )));
+        }
+        self.pending_split = Some((beside, side, Instant::now()));
+        Ok(())
+    }
+
+    fn take_pending_split(&mut self) -> Option<(WindowId, Side)> {
+        self.pending_split
+            .take()
+            .filter(|(_, _, at)| at.elapsed() < PENDING_SPLIT_TIMEOUT)
+            .map(|(beside, side, _)| (beside, side))
+    }
+
+    /// Gives focus back to the previously focused window when `new_window_focus` says the new
+    /// window shouldn't keep it. Otherwise focus is left to the OS, which already focused it.
+    fn apply_new_window_focus(
+        &mut self,
+        window: &WindowRef,
+        previous: Option<WindowId>,
+    ) -> WMResult<()> {
+        let previous = previous
+            .filter(|id| *id != window.id())
+            .and_then(|id| self.all_windows.get(&id).cloned());
+
+        let focus_new = match Config::new_window_focus() {
+            NewWindowFocus::Focus => true,
+            NewWindowFocus::NoFocus => false,
+            NewWindowFocus::FocusIfSameApp => previous
+                .as_ref()
+                .is_none_or(|p| p.app_name() == window.app_name()),
+        };
+
+        if focus_new {
+            Ok(())
+        } else if let Some(previous) = previous {
+            trace!("  -> keeping focus on window {}", previous.id());
+            self.focus_window(previous.id())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Ignored windows are never managed, they match a window rule with `ignore` set.
+    pub fn is_ignored(window: &WindowRef) -> bool {
+        evaluate_window_rules(window).ignore
+    }
+
+    /// Stops managing any tracked window that is now ignored, e.g. after a rule was added.
+    pub fn reevaluate_ignored(&mut self) -> WMResult<()> {
+        let ignored: Vec<WindowRef> = self
+            .all_windows
+            .values()
+            .filter(|w| Self::is_ignored(w))
+            .cloned()
+            .collect();
+
+        if ignored.is_empty() {
+            return Ok(());
+        }
+
+        for window in &ignored {
+            debug!(
+                "Untracking ignored window: id={} title={:?}",
+                window.id(),
+                window.title()
+            );
+            if let Ok(workspace) = self.get_workspace_for_window_mut(&window.id()) {
+                workspace.remove_window(window)?;
+            }
+            self.all_windows.remove(&window.id());
+            self.window_order.shift_remove(&window.id());
+            self.transient_windows.remove(&window.id());
+            self.scratchpad_windows.shift_remove(&window.id());
+
+            // Managed floating windows are kept on top, release them
+            if window.floating() {
+                window.set_floating(false);
+                window.flush_always_on_top()?;
+            }
+        }
+
+        self.animated_flush()?;
+        self.try_save_layout();
+        Ok(())
+    }
+
+    fn place_window_in_workspace(
+        &mut self,
+        window: &WindowRef,
+        workspace_id: WorkspaceId,
+        float: bool,
+    ) -> WMResult<()> {
+        let center = self
+            .partitions
+            .values()
+            .find(|p| p.assigned_workspaces().contains(&workspace_id))
+            .map(|p| p.bounds().center())
+            .ok_or(WMError::WorkspaceNotFound(window.id()))?;
+
+        let workspace = self
+            .workspaces
+            .get_mut(&workspace_id)
+            .ok_or(WMError::WorkspaceNotFound(window.id()))?;
+
+        if float {
+            workspace.float_window(window)?;
         } else {
-            trace!("  -> tiling window at {:?}", window.bounds().position);
-            self.tile_window(window.id(), &window.bounds().position)?;
+            workspace.tile_window(window, &center)?;
         }
 
+        self.animated_flush()?;
+        self.move_to_top(window.id());
+        self.try_save_layout();
         Ok(())
     }
 
     pub fn tile_window(&mut self, id: WindowId, position: &Position) -> WMResult<()> {
+        let before = self.undo_snapshot();
+        self.tile_window_at(id, position)?;
+        self.push_undo(before);
+        Ok(())
+    }
+
+    /// Tiles a window without recording it for undo, for windows the user didn't place
+    fn tile_window_at(&mut self, id: WindowId, position: &Position) -> WMResult<()> {
         let window = self.get_window(id)?;
         let was_floating = window.floating();
-        let old_bounds = window.bounds().clone();
 
         if was_floating {
             window.set_floating(false);
@@ -245,8 +934,31 @@ impl WindowManager {
             // Handle the swap case where we need to float a window
             if let InsertResult::Swap(new_window) = &result {
                 if was_floating {
-                    self.float_window(new_window.id())?;
-                    new_window.set_bounds(old_bounds);
+                    // The displaced window floats centered in the partition it was tiled in
+                    let area = self
+                        .partitions
+                        .values()
+                        .find(|p| p.current_workspace() == Some(new_workspace_id))
+                        .map(|p| p.bounds().clone());
+                    let new_workspace = self.workspaces.get_mut(&new_workspace_id).unwrap();
+                    new_workspace.remove_window(new_window)?;
+                    match area {
+                        Some(area) => new_workspace.float_window_centered(
+                            new_window,
+                            &area,
+                            Config::float_window_size_ratio(),
+                        )?,
+                        None => new_workspace.float_window(new_window)?,
+                    }
+                    self.move_to_top(new_window.id());
+                    // The window was dropped on another partition, so it no longer floats in
+                    // the workspace it came from
+                    if id != new_workspace_id {
+                        self.workspaces
+                            .get_mut(&id)
+                            .unwrap()
+                            .remove_window(&window)?;
+                    }
                 } else {
                     let old_workspace = self.workspaces.get_mut(&id).unwrap();
                     old_workspace.replace_window(&window, &new_window)?;
@@ -289,23 +1001,72 @@ impl WindowManager {
         Ok(())
     }
 
-    /// Animated flush that sends dirty windows to the animation thread
-    pub fn animated_flush(&mut self) -> PlatformResult<()> {
-        self.validate_workspaces();
+    /// Moves a window to the given bounds. A floating window is put right there, a tiled one
+    /// is tiled at the center of the bounds like a window dropped there.
+    pub fn place_window_at(
+        &mut self,
+        id: WindowId,
+        bounds: Bounds,
+        floating: bool,
+    ) -> WMResult<()> {
+        let window = self.get_window(id)?;
+        if self.partition_at_bounds(&bounds).is_none() {
+            return Err(WMError::NoWorkspaceAtPosition(bounds.position));
+        }
 
-        for workspace in self.workspaces.values_mut() {
-            for window in workspace.windows().values() {
-                window.flush_always_on_top()?;
+        if !floating {
+            let center = bounds.center();
+            self.get_workspace_at_position(&center)?;
+            return self.tile_window(id, &center);
+        }
 
-                if !window.dirty() {
-                    continue;
-                }
+        let before = self.undo_snapshot();
+        if !window.floating() || self.get_workspace_with_window(&window).is_none() {
+            self.float_window(id)?;
+        }
+        window.set_bounds(bounds);
+        self.update_floating_window(id)?;
 
-                if Config::window_tile_animate() {
+        self.animated_flush()?;
+        self.push_undo(before);
+        self.try_save_layout();
+        Ok(())
+    }
+
+    /// Animated flush that sends dirty windows to the animation thread
+    pub fn animated_flush(&mut self) -> PlatformResult<()> {
+        self.animated_flush_for(AnimationKind::Tile)
+    }
+
+    /// Animated flush using the duration configured for the kind of move
+    fn animated_flush_for(&mut self, kind: AnimationKind) -> PlatformResult<()> {
+        let duration_ms = kind.duration_ms(&Config::current());
+        self.flush_with_duration(kind, duration_ms)
+    }
+
+    /// Animates dirty windows over `duration_ms`, or moves them straight away when it's `None`
+    fn flush_with_duration(
+        &mut self,
+        kind: AnimationKind,
+        duration_ms: Option<u32>,
+    ) -> PlatformResult<()> {
+        self.validate_workspaces();
+        self.apply_window_settings();
+
+        let mut immediate = Vec::new();
+        for workspace in self.workspaces.values_mut() {
+            for window in workspace.windows().values() {
+                window.flush_always_on_top()?;
+                window.flush_opacity()?;
+
+                if self.paused || !window.dirty() || window.fullscreen() {
+                    continue;
+                }
+
+                if let Some(duration_ms) = duration_ms {
                     let platform_window = window.platform_window().clone();
                     let start_bounds = window.platform_bounds();
                     let target_bounds = window.window_bounds().clone();
-                    let duration_ms = Config::window_tile_animation_ms();
 
                     self.animation_thread.animate_window(
                         window.id(),
@@ -313,22 +1074,328 @@ impl WindowManager {
                         start_bounds,
                         target_bounds,
                         duration_ms,
+                        kind,
                     );
+                    window.forget_sent_bounds();
                 } else {
-                    window.flush()?;
+                    immediate.push(window.clone());
                 }
             }
         }
+        flush_windows_batched(&immediate)?;
+
+        self.publish_snapshot();
+        Ok(())
+    }
+
+    /// Tiles a window that was floated for looking transient once it has grown into a
+    /// regular window.
+    pub fn reevaluate_transient_window(&mut self, id: WindowId) -> WMResult<()> {
+        if !self.transient_windows.contains(&id) {
+            return Ok(());
+        }
+
+        let window = self.get_window(id)?;
+        if !window.floating() {
+            self.transient_windows.remove(&id);
+            return Ok(());
+        }
+
+        window.update_bounds();
+        if is_transient_window(&window) {
+            return Ok(());
+        }
 
+        debug!("Transient window grew into a regular window, tiling: id={id}");
+        self.transient_windows.remove(&id);
+        let float_new_windows = self
+            .get_workspace_with_window(&window)
+            .map_or_else(Config::float_new_windows, |w| w.float_new_windows());
+        if !float_new_windows {
+            self.tile_window_at(id, &window.bounds().center())?;
+        }
         Ok(())
     }
 
+    /// Focuses a window. With `mouse_follows_focus` the cursor is moved to the window's center
+    /// unless it's already over the window.
     pub fn focus_window(&mut self, id: WindowId) -> WMResult<()> {
+        self.focus_window_internal(id, true)
+    }
+
+    /// Focuses a window because the mouse is over it. Never moves the cursor, which would fight
+    /// the user for the mouse.
+    pub fn focus_window_from_mouse(&mut self, id: WindowId) -> WMResult<()> {
+        self.focus_window_internal(id, false)
+    }
+
+    fn focus_window_internal(&mut self, id: WindowId, warp_mouse: bool) -> WMResult<()> {
         let window = self.get_window(id)?;
         window
             .focus()
             .unwrap_or_else(|e| error!("Could not focus window: {e}"));
+
+        if warp_mouse && Config::mouse_follows_focus() {
+            let bounds = window.bounds();
+            let over_window = Platform::get_mouse_position().is_ok_and(|p| bounds.contains(&p));
+            if !over_window {
+                Platform::warp_mouse(bounds.center())
+                    .unwrap_or_else(|e| warn!("Could not move mouse to window: {e}"));
+            }
+        }
+
+        self.window_focused(id);
+        Ok(())
+    }
+
+    /// Focuses the next tiled window in the focus order, wrapping around.
+    pub fn focus_next(&mut self) -> WMResult<()> {
+        self.cycle_focus(true)
+    }
+
+    /// Focuses the previous tiled window in the focus order, wrapping around.
+    pub fn focus_prev(&mut self) -> WMResult<()> {
+        self.cycle_focus(false)
+    }
+
+    /// Focuses the next window of the focused window's app, wrapping around. Floating windows
+    /// are included.
+    pub fn focus_next_in_app(&mut self) -> WMResult<()> {
+        self.cycle_app_focus(true)
+    }
+
+    /// Focuses the previous window of the focused window's app, wrapping around.
+    pub fn focus_prev_in_app(&mut self) -> WMResult<()> {
+        self.cycle_app_focus(false)
+    }
+
+    fn cycle_focus(&mut self, forward: bool) -> WMResult<()> {
+        let candidates: Vec<WindowId> = self
+            .window_order
+            .iter()
+            .filter(|id| {
+                self.all_windows
+                    .get(id)
+                    .is_some_and(|w| w.tiled() && w.visible())
+            })
+            .copied()
+            .collect();
+
+        self.cycle_focus_among(candidates, forward)
+    }
+
+    fn cycle_app_focus(&mut self, forward: bool) -> WMResult<()> {
+        let Some(pid) = self
+            .window_order
+            .last()
+            .and_then(|id| self.all_windows.get(id))
+            .map(|w| w.pid())
+        else {
+            return Ok(());
+        };
+
+        let candidates: Vec<WindowId> = self
+            .window_order
+            .iter()
+            .filter(|id| {
+                self.all_windows
+                    .get(id)
+                    .is_some_and(|w| w.pid() == pid && w.visible() && !self.is_stashed(w.id()))
+            })
+            .copied()
+            .collect();
+
+        self.cycle_focus_among(candidates, forward)
+    }
+
+    /// Treats `window_order` as a ring, with the focused window at the end. Going forward
+    /// focuses the oldest candidate, which moves it to the end. Going backward moves the
+    /// focused window to the start and focuses the candidate before it. Both keep the ring in
+    /// the same order, so repeated cycling visits every candidate. `candidates` must be in
+    /// focus order and end with the focused window.
+    fn cycle_focus_among(&mut self, candidates: Vec<WindowId>, forward: bool) -> WMResult<()> {
+        if candidates.len() < 2 {
+            return Ok(());
+        }
+
+        if forward {
+            self.focus_window(candidates[0])
+        } else {
+            let current = candidates[candidates.len() - 1];
+            self.window_order.shift_remove(&current);
+            self.window_order.shift_insert(0, current);
+            self.focus_window(candidates[candidates.len() - 2])
+        }
+    }
+
+    /// Records a focus change, moving the window to the top of the focus order and dimming
+    /// the previously focused window to `inactive_window_opacity`.
+    pub fn window_focused(&mut self, id: WindowId) {
+        let previous = self.window_order.last().copied();
         self.move_to_top(id);
+
+        // Only one window is focused across every workspace
+        for window in self.all_windows.values() {
+            window.set_focused(window.id() == id);
+        }
+
+        if let Some(previous) = previous.filter(|p| *p != id) {
+            if let Some(window) = self.all_windows.get(&previous) {
+                let opacity = Self::active_opacity(window).min(Config::inactive_window_opacity());
+                window.set_opacity(opacity);
+                window
+                    .flush_opacity()
+                    .unwrap_or_else(|e| error!("Could not set window opacity: {e}"));
+            }
+        }
+
+        if let Some(window) = self.all_windows.get(&id) {
+            window.set_opacity(Self::active_opacity(window));
+            window
+                .flush_opacity()
+                .unwrap_or_else(|e| error!("Could not set window opacity: {e}"));
+        }
+
+        self.publish_snapshot();
+    }
+
+    /// Takes a window out of its workspace while it's full screen, like an ignored window, and
+    /// puts it back once it leaves full screen
+    pub fn window_fullscreen_changed(&mut self, id: WindowId, fullscreen: bool) -> WMResult<()> {
+        let window = self.get_window(id)?;
+        if window.fullscreen() == fullscreen {
+            return Ok(());
+        }
+
+        if fullscreen {
+            self.remove_fullscreen_window(&window)?;
+        } else {
+            self.restore_fullscreen_window(&window)?;
+        }
+
+        self.animated_flush()?;
+        Ok(())
+    }
+
+    /// Toggles native full screen for a window. It's taken out of the layout first so it
+    /// doesn't leave a gap, and goes back to the same spot or floating bounds when it leaves
+    /// full screen.
+    pub fn toggle_fullscreen(&mut self, id: WindowId) -> WMResult<()> {
+        let window = self.get_window(id)?;
+        if window.fullscreen() {
+            window.request_fullscreen(false)?;
+            self.restore_fullscreen_window(&window)?;
+            if let Some(bounds) = self.fullscreen_bounds.remove(&id) {
+                if window.floating() {
+                    window.set_bounds(bounds);
+                    self.update_floating_window(id)?;
+                }
+            }
+            self.animated_flush()?;
+        } else {
+            if window.floating() {
+                self.fullscreen_bounds.insert(id, window.bounds());
+            }
+            self.remove_fullscreen_window(&window)?;
+            self.animated_flush()?;
+            if let Err(e) = window.request_fullscreen(true) {
+                self.restore_fullscreen_window(&window)?;
+                self.animated_flush()?;
+                return Err(e.into());
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_fullscreen_window(&mut self, window: &WindowRef) -> WMResult<()> {
+        window.set_fullscreen(true);
+        let Some(workspace_id) = self.get_workspace_with_window(window).map(|w| w.id()) else {
+            return Ok(());
+        };
+
+        if window.tiled() {
+            let neighbour = [Side::Right, Side::Left, Side::Bottom, Side::Top]
+                .into_iter()
+                .find_map(|side| {
+                    let neighbour = self.find_window_in_direction(window, side)?;
+                    Some((neighbour.id(), side.opposite()))
+                });
+            self.fullscreen_slots
+                .insert(window.id(), (workspace_id, neighbour));
+        }
+
+        self.get_workspace_for_window_mut(&window.id())?
+            .remove_window(window)?;
+        Ok(())
+    }
+
+    /// Tiles a window back beside the window it was next to before it went full screen, or
+    /// places it like a new window if it wasn't tiled or that spot is gone
+    fn restore_fullscreen_window(&mut self, window: &WindowRef) -> WMResult<()> {
+        window.set_fullscreen(false);
+
+        let slot = self.fullscreen_slots.remove(&window.id());
+        let target = slot.and_then(|(workspace_id, neighbour)| {
+            let workspace = self.workspaces.get(&workspace_id)?;
+            let position = match neighbour {
+                Some((id, side)) => {
+                    Self::point_inside_edge(&workspace.get_window(&id)?.bounds(), side)
+                }
+                // It was the only tiled window
+                None => window.bounds().center(),
+            };
+            Some((workspace_id, position))
+        });
+
+        window.update_bounds();
+        if let Some((workspace_id, position)) = target {
+            let workspace = self.workspaces.get_mut(&workspace_id).unwrap();
+            match workspace.tile_window(window, &position) {
+                Ok(_) => return Ok(()),
+                Err(e) => debug!("Could not tile window where it was: {e}"),
+            }
+        }
+
+        self.place_new_window(window.clone())?;
+        Ok(())
+    }
+
+    pub fn window_title_changed(&mut self, id: WindowId, title: String) {
+        let Some(window) = self.all_windows.get(&id) else {
+            return;
+        };
+        if window.title() == title {
+            return;
+        }
+
+        window.set_title(title);
+        // A window floated for having no title may have just gotten one
+        self.reevaluate_transient_window(id)
+            .unwrap_or_else(|e| warn!("Could not re-evaluate transient window: {e}"));
+        self.publish_snapshot();
+    }
+
+    /// The opacity of a focused window, set by window rules and otherwise fully opaque.
+    fn active_opacity(window: &WindowRef) -> f32 {
+        evaluate_window_rules(window).opacity.unwrap_or(1.0)
+    }
+
+    /// Briefly dims a window to show which window a command acted on. The dip is relative to
+    /// the window's own opacity, so dimmed windows still visibly pulse and end up dimmed again.
+    pub fn pulse_window(&mut self, id: WindowId) -> WMResult<()> {
+        if !Config::window_pulse() {
+            return Ok(());
+        }
+
+        let window = self.get_window(id)?;
+        let base_opacity = window.opacity();
+        self.animation_thread.pulse_window(
+            id,
+            window.platform_window().clone(),
+            base_opacity,
+            base_opacity * Config::window_pulse_opacity().clamp(0.0, 1.0),
+            Config::window_pulse_ms(),
+        );
         Ok(())
     }
 
@@ -361,6 +1428,14 @@ impl WindowManager {
 
     pub fn float_window(&mut self, id: WindowId) -> WMResult<()> {
         let window = self.get_window(id)?;
+        let was_tiled = window.tiled() && self.get_workspace_with_window(&window).is_some();
+        let center = window.bounds().center();
+        let center_in = self
+            .partitions
+            .values()
+            .find(|p| p.bounds().contains(&center))
+            .map(|p| p.bounds().clone())
+            .filter(|_| Config::float_window_centered());
         let workspace = if let Some(workspace) = self.get_workspace_with_window_mut(&window) {
             workspace.remove_window(&window)?;
             workspace
@@ -368,124 +1443,542 @@ impl WindowManager {
             self.get_workspace_at_position_mut(&window.bounds().position)?
         };
 
-        workspace.float_window(&window)?;
-        self.animated_flush()?;
+        if let Some(area) = &center_in {
+            workspace.float_window_centered(&window, area, Config::float_window_size_ratio())?;
+        } else {
+            workspace.float_window(&window)?;
+        }
+        if was_tiled && center_in.is_none() && Config::remember_float_bounds() {
+            if let Some(bounds) = window.float_bounds() {
+                window.set_bounds(bounds);
+            }
+        }
+        self.animated_flush_for(AnimationKind::FloatSnap)?;
         self.move_to_top(window.id());
         self.try_save_layout();
         Ok(())
     }
 
-    pub fn hide_window(&mut self, id: WindowId) -> WMResult<()> {
+    /// Toggles whether a window stays on screen across workspace switches. Tiled windows are
+    /// floated when they become sticky. Returns the new state.
+    pub fn toggle_sticky(&mut self, id: WindowId) -> WMResult<bool> {
         let window = self.get_window(id)?;
+        let sticky = !window.sticky();
+        if sticky && window.tiled() {
+            self.float_window(id)?;
+        }
 
-        if let Ok(workspace) = self.get_workspace_for_window_mut(&id) {
-            workspace.remove_window(&window)?;
-            self.animated_flush()?;
-            self.try_save_layout();
+        window.set_sticky(sticky);
+        if sticky {
+            self.sticky_windows.insert(id);
+        } else {
+            self.sticky_windows.remove(&id);
         }
-        Ok(())
+
+        self.animated_flush()?;
+        self.try_save_layout();
+        Ok(sticky)
     }
 
-    pub fn remove_window(&mut self, id: WindowId) -> WMResult<()> {
+    /// Toggles whether a window is kept above other windows. Tiled windows are floated when
+    /// they're put on top. Returns the new state.
+    pub fn toggle_always_on_top(&mut self, id: WindowId) -> WMResult<bool> {
         let window = self.get_window(id)?;
-        self.all_windows.remove(&id);
+        let on_top = !window.always_on_top();
+        if on_top && window.tiled() {
+            self.float_window(id)?;
+        }
 
-        let workspace = self.get_workspace_for_window_mut(&id)?;
-        workspace.remove_window(&window)?;
+        window.set_always_on_top(on_top);
         self.animated_flush()?;
-        self.try_save_layout();
-        Ok(())
+        Ok(on_top)
     }
 
-    /// Validates all windows across all workspaces and removes invalid ones.
-    /// Returns the number of invalid windows that were removed.
-    pub fn validate_workspaces(&mut self) -> usize {
-        let mut invalid_windows = Vec::new();
+    /// Hides a window in the scratchpad. It's taken out of its workspace and parked like the
+    /// windows of hidden workspaces until summoned with `scratchpad_toggle`.
+    pub fn scratchpad_stash(&mut self, id: WindowId) -> WMResult<()> {
+        let window = self.get_window(id)?;
 
-        for window in self.all_windows.values() {
-            if !window.valid() && !invalid_windows.contains(&window.id()) {
-                invalid_windows.push(window.id());
-            }
+        if let Some(workspace) = self.get_workspace_with_window_mut(&window) {
+            workspace.remove_window(&window)?;
         }
+        self.scratchpad_windows.shift_remove(&id);
+        self.scratchpad_windows.insert(id);
 
-        // Remove invalid windows
-        let removed_count = invalid_windows.len();
-        for id in &invalid_windows {
-            debug!("Removing invalid window: id={} title={:?}", id, {
-                if let Some(w) = self.all_windows.get(id) {
-                    w.title()
-                } else {
-                    "<unknown>".to_string()
-                }
-            });
-
-            // Try to remove from workspace (may fail if not in workspace, that's ok)
-            if let Ok(window) = self.get_window(*id) {
-                if let Ok(workspace) = self.get_workspace_for_window_mut(id) {
-                    let _ = workspace.remove_window(&window);
-                }
-            }
+        self.park_window(&window)?;
+        self.animated_flush()?;
+        self.try_save_layout();
+        Ok(())
+    }
 
-            // Remove from all_windows
-            self.all_windows.remove(id);
-            self.window_order.shift_remove(id);
+    /// Summons a stashed window, floating and centered on the active partition, or stashes
+    /// it again if it's showing. Returns whether the window is now showing.
+    pub fn scratchpad_toggle(&mut self, id: WindowId) -> WMResult<bool> {
+        if !self.is_stashed(id) {
+            self.scratchpad_stash(id)?;
+            return Ok(false);
         }
 
-        if removed_count > 0 {
-            // Flush and save layout after removing invalid windows
-            let _ = self.animated_flush();
-            self.try_save_layout();
-        }
+        let window = self.get_window(id)?;
+        let workspace_id = self
+            .focused_workspace_id()
+            .or_else(|| self.workspaces.keys().min().copied())
+            .ok_or(WMError::WorkspaceNotFound(0))?;
+        let area = self
+            .partitions
+            .values()
+            .find(|p| p.assigned_workspaces().contains(&workspace_id))
+            .map(|p| p.bounds().clone())
+            .ok_or(WMError::WorkspaceNotFound(workspace_id))?;
 
-        removed_count
-    }
+        let size = window.bounds().size;
+        let width = size.width.min(area.size.width);
+        let height = size.height.min(area.size.height);
+        let center = area.center();
+        window.set_bounds(Bounds::new(
+            center.x - width as i32 / 2,
+            center.y - height as i32 / 2,
+            width,
+            height,
+        ));
 
-    pub fn resize_window(&mut self, id: WindowId, bounds: &Bounds) -> WMResult<()> {
-        let window = self.get_window(id)?;
-        let workspace = self.get_workspace_for_window_mut(&id)?;
+        let workspace = self
+            .workspaces
+            .get_mut(&workspace_id)
+            .ok_or(WMError::WorkspaceNotFound(workspace_id))?;
+        workspace.float_window(&window)?;
+        self.unpark_window(&window)?;
+        window.mark_dirty();
 
-        workspace.resize_window(&window, bounds)?;
-        workspace.flush_windows()?;
-        self.needs_flush = false;
+        self.animated_flush()?;
+        self.focus_window(id)?;
         self.try_save_layout();
-        Ok(())
+        Ok(true)
     }
 
-    /// Set resize bounds without flushing - for use during live drag.
-    /// Call flush() to apply pending changes.
-    pub fn resize_window_deferred(&mut self, id: WindowId, bounds: &Bounds) -> WMResult<()> {
-        let window = self.get_window(id)?;
-        let workspace = self.get_workspace_for_window_mut(&id)?;
-        workspace.resize_window(&window, bounds)?;
-        self.needs_flush = true;
-        Ok(())
+    /// Whether a window is hidden in the scratchpad
+    pub fn is_stashed(&self, id: WindowId) -> bool {
+        self.scratchpad_windows.contains(&id)
+            && self
+                .all_windows
+                .get(&id)
+                .is_some_and(|w| self.get_workspace_with_window(w).is_none())
     }
 
-    pub fn get_window(&self, id: WindowId) -> WMResult<WindowRef> {
-        self.all_windows
-            .get(&id)
-            .cloned()
-            .ok_or_else(|| WMError::WindowNotFound(id))
+    /// Whether a window belongs to the scratchpad, stashed or summoned
+    pub fn in_scratchpad(&self, id: WindowId) -> bool {
+        self.scratchpad_windows.contains(&id)
     }
 
-    pub fn get_all_windows(&self) -> Vec<WindowRef> {
-        let mut all_windows: Vec<WindowRef> = self.all_windows.values().cloned().collect();
-
-        all_windows.sort_by_key(|w| {
-            let order_index = self.window_order.get_index_of(&w.id()).unwrap_or(0);
-            (w.floating(), order_index)
-        });
-
-        all_windows
+    /// The most recently stashed window that is still hidden
+    pub fn last_stashed_window(&self) -> Option<WindowId> {
+        self.scratchpad_windows
+            .iter()
+            .rev()
+            .copied()
+            .find(|id| self.is_stashed(*id))
     }
 
-    pub fn get_tile_bounds(&self, id: WindowId, position: &Position) -> Option<Bounds> {
-        let workspace = self.get_workspace_at_position(position).ok()?;
-        let window = self.get_window(id).ok()?;
-        workspace.get_tile_bounds(&window, position)
-    }
+    /// Makes a workspace the active one on its partition. Sticky floating windows on the
+    /// previous workspace are moved onto the new one so they stay visible.
+    pub fn switch_workspace(&mut self, workspace_id: WorkspaceId) -> WMResult<()> {
+        if !self.workspaces.contains_key(&workspace_id) {
+            return Err(WMError::UnknownWorkspace(workspace_id));
+        }
 
-    pub fn get_partition_with_window(&self, window: &WindowRef) -> Option<&Partition> {
+        let partition = self
+            .partitions
+            .values_mut()
+            .find(|p| p.assigned_workspaces().contains(&workspace_id))
+            .ok_or(WMError::UnknownWorkspace(workspace_id))?;
+        let previous = partition.current_workspace();
+        if previous == Some(workspace_id) {
+            return Ok(());
+        }
+        partition.set_current_workspace(workspace_id);
+
+        if let Some(previous) = previous {
+            let sticky: Vec<WindowRef> = self
+                .sticky_windows
+                .iter()
+                .filter_map(|id| self.all_windows.get(id))
+                .filter(|w| w.floating())
+                .cloned()
+                .collect();
+
+            for window in sticky {
+                let old_workspace = self.workspaces.get_mut(&previous).unwrap();
+                if !old_workspace.has_window(&window.id()) {
+                    continue;
+                }
+                old_workspace.remove_window(&window)?;
+
+                let new_workspace = self.workspaces.get_mut(&workspace_id).unwrap();
+                new_workspace.float_window(&window)?;
+                window.mark_dirty();
+            }
+        }
+
+        let duration_ms = AnimationKind::WorkspaceSwitch.duration_ms(&Config::current());
+        if let Some(previous) = previous {
+            self.slide_workspaces(previous, workspace_id, duration_ms)?;
+        }
+        self.flush_with_duration(AnimationKind::WorkspaceSwitch, duration_ms)?;
+        self.try_save_layout();
+        Ok(())
+    }
+
+    /// Slides the windows of the previous workspace out to where hidden windows are parked, past
+    /// the outermost display, and brings the new workspace's windows back in from there. Sticky
+    /// windows stay where they are. Without an animation, or when hidden windows are minimized,
+    /// the previous workspace's windows are parked instead.
+    fn slide_workspaces(
+        &mut self,
+        from: WorkspaceId,
+        to: WorkspaceId,
+        duration_ms: Option<u32>,
+    ) -> PlatformResult<()> {
+        if self.paused {
+            return Ok(());
+        }
+
+        let sliding = |id: &WorkspaceId| -> Vec<WindowRef> {
+            self.workspaces
+                .get(id)
+                .map(|w| {
+                    w.windows()
+                        .values()
+                        .filter(|w| !w.sticky())
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let (outgoing, incoming) = (sliding(&from), sliding(&to));
+
+        for window in outgoing {
+            match duration_ms.zip(self.parked_bounds(&window)) {
+                Some((duration_ms, parked)) => {
+                    self.animation_thread.animate_window(
+                        window.id(),
+                        window.platform_window().clone(),
+                        window.platform_bounds(),
+                        parked,
+                        duration_ms,
+                        AnimationKind::WorkspaceSwitch,
+                    );
+                    // Left parked until the workspace is switched back to
+                    window.mark_clean();
+                }
+                None => self.park_window(&window)?,
+            }
+        }
+
+        for window in incoming {
+            self.unpark_window(&window)?;
+            match duration_ms {
+                Some(duration_ms) => {
+                    self.animation_thread.animate_window(
+                        window.id(),
+                        window.platform_window().clone(),
+                        window.platform_bounds(),
+                        window.window_bounds(),
+                        duration_ms,
+                        AnimationKind::WorkspaceSwitch,
+                    );
+                    window.forget_sent_bounds();
+                }
+                None => window.mark_dirty(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Where `park_window` moves a window, past the outermost display. `None` when hidden
+    /// windows are minimized instead.
+    fn parked_bounds(&self, window: &WindowRef) -> Option<Bounds> {
+        let screens = self.partitions.values().map(|p| p.bounds());
+        let bounds = window.window_bounds();
+        match Config::hidden_window_parking() {
+            HiddenWindowParking::OffscreenRight => {
+                let right = screens
+                    .map(|b| b.position.x + b.size.width as i32)
+                    .max()
+                    .unwrap_or_default();
+                Some(bounds.translated(right - bounds.position.x, 0))
+            }
+            HiddenWindowParking::OffscreenBottom => {
+                let bottom = screens
+                    .map(|b| b.position.y + b.size.height as i32)
+                    .max()
+                    .unwrap_or_default();
+                Some(bounds.translated(0, bottom - bounds.position.y))
+            }
+            HiddenWindowParking::Minimize => None,
+        }
+    }
+
+    /// Moves a hidden window out of the way, where to depends on `hidden_window_parking`. It's
+    /// left there until it's marked dirty again.
+    pub fn park_window(&mut self, window: &WindowRef) -> PlatformResult<()> {
+        match self.parked_bounds(window) {
+            Some(parked) => window.set_preview_bounds(parked)?,
+            None => {
+                window.minimize()?;
+                self.minimized_windows.insert(window.id());
+            }
+        }
+        window.mark_clean();
+        Ok(())
+    }
+
+    /// Restores a window `park_window` minimized. Parked windows that were only moved come
+    /// back when they're flushed.
+    fn unpark_window(&mut self, window: &WindowRef) -> PlatformResult<()> {
+        if self.minimized_windows.remove(&window.id()) {
+            window.unminimize()?;
+        }
+        Ok(())
+    }
+
+    /// Takes a window out of its workspace and parks it
+    pub fn hide_window(&mut self, id: WindowId) -> WMResult<()> {
+        let window = self.get_window(id)?;
+
+        if let Ok(workspace) = self.get_workspace_for_window_mut(&id) {
+            workspace.remove_window(&window)?;
+            self.park_window(&window)?;
+            self.animated_flush()?;
+            self.try_save_layout();
+        }
+        Ok(())
+    }
+
+    pub fn remove_window(&mut self, id: WindowId) -> WMResult<()> {
+        let window = self.get_window(id)?;
+
+        // Hiding a stashed window can report it as closed, keep it until it's really gone
+        if self.is_stashed(id) && window.valid() {
+            return Ok(());
+        }
+
+        let before = self.undo_snapshot();
+        self.scratchpad_windows.shift_remove(&id);
+        self.minimized_windows.remove(&id);
+        self.fullscreen_slots.remove(&id);
+        self.fullscreen_bounds.remove(&id);
+        self.all_windows.remove(&id);
+        self.transient_windows.remove(&id);
+        self.sticky_windows.remove(&id);
+        self.window_order.shift_remove(&id);
+
+        let workspace = self.get_workspace_for_window_mut(&id)?;
+        workspace.remove_window(&window)?;
+        self.animated_flush()?;
+        self.push_undo(before);
+        self.try_save_layout();
+        Ok(())
+    }
+
+    /// Validates all windows across all workspaces and removes invalid ones.
+    /// Returns the number of invalid windows that were removed.
+    pub fn validate_workspaces(&mut self) -> usize {
+        let mut invalid_windows = Vec::new();
+
+        for window in self.all_windows.values() {
+            if !window.valid() && !invalid_windows.contains(&window.id()) {
+                invalid_windows.push(window.id());
+            }
+        }
+
+        // Remove invalid windows
+        let removed_count = invalid_windows.len();
+        for id in &invalid_windows {
+            debug!("Removing invalid window: id={} title={:?}", id, {
+                if let Some(w) = self.all_windows.get(id) {
+                    w.title()
+                } else {
+                    "<unknown>".to_string()
+                }
+            });
+
+            // Try to remove from workspace (may fail if not in workspace, that's ok)
+            if let Ok(window) = self.get_window(*id) {
+                if let Ok(workspace) = self.get_workspace_for_window_mut(id) {
+                    let _ = workspace.remove_window(&window);
+                }
+            }
+
+            // Remove from all_windows
+            self.all_windows.remove(id);
+            self.window_order.shift_remove(id);
+            self.transient_windows.remove(id);
+            self.sticky_windows.remove(id);
+            self.minimized_windows.remove(id);
+        }
+
+        if removed_count > 0 {
+            // Flush and save layout after removing invalid windows
+            let _ = self.animated_flush();
+            self.try_save_layout();
+        }
+
+        removed_count
+    }
+
+    pub fn resize_window(&mut self, id: WindowId, bounds: &Bounds) -> WMResult<()> {
+        let window = self.get_window(id)?;
+        let bounds = Self::apply_aspect_lock(&window, bounds);
+        let before = self.undo_snapshot();
+        let paused = self.paused;
+        let workspace = self.get_workspace_for_window_mut(&id)?;
+
+        workspace.resize_window(&window, &bounds)?;
+        if paused {
+            self.needs_flush = true;
+        } else {
+            workspace.flush_windows()?;
+            self.needs_flush = false;
+        }
+        self.push_undo(before);
+        self.try_save_layout();
+        Ok(())
+    }
+
+    /// Moves a tiled window out of its container, next to that container in the parent.
+    pub fn promote_window(&mut self, id: WindowId) -> WMResult<()> {
+        let window = self.get_window(id)?;
+        if window.floating() {
+            return Ok(());
+        }
+
+        let before = self.undo_snapshot();
+        let workspace = self
+            .get_workspace_with_window_mut(&window)
+            .ok_or(WMError::WorkspaceNotFound(id))?;
+        workspace.promote_window(&window)?;
+
+        self.animated_flush()?;
+        self.push_undo(before);
+        self.try_save_layout();
+        Ok(())
+    }
+
+    /// Switches a workspace to a different layout, keeping its tiled windows
+    pub fn set_workspace_layout(
+        &mut self,
+        workspace_id: WorkspaceId,
+        layout_type: LayoutType,
+    ) -> WMResult<()> {
+        let bounds = self
+            .partitions
+            .values()
+            .find(|p| p.assigned_workspaces().contains(&workspace_id))
+            .map(|p| p.bounds().clone())
+            .ok_or(WMError::UnknownWorkspace(workspace_id))?;
+
+        let workspace = self
+            .workspaces
+            .get_mut(&workspace_id)
+            .ok_or(WMError::UnknownWorkspace(workspace_id))?;
+        if workspace.layout_type() == layout_type {
+            return Ok(());
+        }
+        workspace.set_layout(layouts::new_layout(layout_type, bounds));
+
+        self.animated_flush()?;
+        self.try_save_layout();
+        Ok(())
+    }
+
+    /// Moves the edge of a tiled window on the given side by `delta_px`. Positive values grow
+    /// the window, negative values shrink it.
+    pub fn resize_window_step(&mut self, id: WindowId, side: Side, delta_px: i32) -> WMResult<()> {
+        let window = self.get_window(id)?;
+        if window.floating() {
+            return Ok(());
+        }
+
+        let before = self.undo_snapshot();
+        let workspace = self
+            .get_workspace_with_window_mut(&window)
+            .ok_or(WMError::WorkspaceNotFound(id))?;
+        workspace.resize_window_step(&window, side, delta_px)?;
+
+        self.animated_flush()?;
+        self.push_undo(before);
+        self.try_save_layout();
+        Ok(())
+    }
+
+    /// Set resize bounds without flushing - for use during live drag.
+    /// Call flush() to apply pending changes.
+    pub fn resize_window_deferred(&mut self, id: WindowId, bounds: &Bounds) -> WMResult<()> {
+        let window = self.get_window(id)?;
+        let bounds = Self::apply_aspect_lock(&window, bounds);
+        let workspace = self.get_workspace_for_window_mut(&id)?;
+        workspace.resize_window(&window, &bounds)?;
+        self.needs_flush = true;
+        Ok(())
+    }
+
+    /// Keeps a floating window's aspect ratio when it has one locked
+    fn apply_aspect_lock(window: &WindowRef, bounds: &Bounds) -> Bounds {
+        match window.aspect_lock() {
+            Some(ratio) if window.floating() => {
+                let old = window.bounds();
+                let direction = ResizeDirection::from_bounds_change(&old, bounds);
+                aspect_locked_bounds(&old, bounds, ratio, direction)
+            }
+            _ => bounds.clone(),
+        }
+    }
+
+    /// Locks a window's aspect ratio to its current size, or unlocks it. Returns whether the
+    /// ratio is now locked. The lock only applies while the window is floating.
+    pub fn toggle_aspect_lock(&mut self, id: WindowId) -> WMResult<bool> {
+        let window = self.get_window(id)?;
+        if window.aspect_lock().is_some() {
+            window.set_aspect_lock(None);
+            return Ok(false);
+        }
+
+        let size = window.bounds().size;
+        if size.width == 0 || size.height == 0 {
+            return Ok(false);
+        }
+        window.set_aspect_lock(Some(size.width as f32 / size.height as f32));
+        Ok(true)
+    }
+
+    pub fn get_window(&self, id: WindowId) -> WMResult<WindowRef> {
+        self.all_windows
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| WMError::WindowNotFound(id))
+    }
+
+    pub fn get_all_windows(&self) -> Vec<WindowRef> {
+        let mut all_windows: Vec<WindowRef> = self.all_windows.values().cloned().collect();
+
+        all_windows.sort_by_key(|w| {
+            let order_index = self.window_order.get_index_of(&w.id()).unwrap_or(0);
+            (w.floating(), order_index)
+        });
+
+        all_windows
+    }
+
+    /// The bounds a window would get if dropped at `position`. The workspace is looked up from
+    /// the position rather than the window, so dragging into another partition previews the
+    /// spot in that partition.
+    pub fn get_tile_bounds(&self, id: WindowId, position: &Position) -> Option<Bounds> {
+        let workspace = self.get_workspace_at_position(position).ok()?;
+        let window = self.get_window(id).ok()?;
+        workspace.get_tile_bounds(&window, position)
+    }
+
+    pub fn get_partition_with_window(&self, window: &WindowRef) -> Option<&Partition> {
         for partition in self.partitions.values() {
             if partition
                 .current_workspace()
@@ -519,6 +2012,13 @@ impl WindowManager {
         None
     }
 
+    pub fn find_workspace_by_name(&self, name: &str) -> Option<WorkspaceId> {
+        self.workspaces
+            .values()
+            .find(|w| w.name() == name)
+            .map(|w| w.id())
+    }
+
     pub fn get_workspace_with_window(&self, window: &WindowRef) -> Option<&Workspace> {
         for workspace in self.workspaces.values() {
             if workspace.has_window(&window.id()) {
@@ -536,7 +2036,8 @@ impl WindowManager {
         }
         None
     }
-    fn get_workspace_at_position(&self, position: &Position) -> WMResult<&Workspace> {
+
+    pub fn get_workspace_at_position(&self, position: &Position) -> WMResult<&Workspace> {
         let partition = self
             .partitions
             .values()
@@ -561,17 +2062,30 @@ impl WindowManager {
             .unwrap())
     }
 
-    fn get_workspace_at_bounds_mut(&mut self, bounds: &Bounds) -> WMResult<&mut Workspace> {
-        let partition = self
-            .partitions
+    /// The partition a window with the given bounds belongs to, the one containing its center
+    /// or else the one it overlaps the most
+    fn partition_at_bounds(&self, bounds: &Bounds) -> Option<&Partition> {
+        let center = bounds.center();
+        self.partitions
             .values()
-            .find(|p| p.bounds().intersects(&bounds))
+            .find(|p| p.bounds().contains(&center))
+            .or_else(|| {
+                self.partitions
+                    .values()
+                    .map(|p| (p.bounds().intersection_area(bounds), p))
+                    .filter(|(area, _)| *area > 0)
+                    .max_by_key(|(area, _)| *area)
+                    .map(|(_, p)| p)
+            })
+    }
+
+    fn get_workspace_at_bounds_mut(&mut self, bounds: &Bounds) -> WMResult<&mut Workspace> {
+        let workspace_id = self
+            .partition_at_bounds(bounds)
+            .and_then(|p| p.current_workspace())
             .ok_or(WMError::NoWorkspaceAtPosition(bounds.position.clone()))?;
 
-        Ok(self
-            .workspaces
-            .get_mut(&partition.current_workspace().unwrap())
-            .unwrap())
+        Ok(self.workspaces.get_mut(&workspace_id).unwrap())
     }
 
     fn get_workspace_for_window_mut(&mut self, window_id: &WindowId) -> WMResult<&mut Workspace> {
@@ -584,22 +2098,6 @@ impl WindowManager {
         Err(WMError::WorkspaceNotFound(*window_id))
     }
 
-    fn get_windows_for_partition(windows: &mut Vec<WindowRef>, bounds: &Bounds) -> Vec<WindowRef> {
-        let mut windows_in_partition = Vec::new();
-        let mut i = 0;
-        while i < windows.len() {
-            let window = windows.get(i).unwrap();
-            let center = window.bounds().center();
-            if bounds.contains(&center) {
-                windows_in_partition.push(windows.remove(i));
-            } else {
-                i += 1;
-            }
-        }
-
-        windows_in_partition
-    }
-
     /// Finds a window at the given position. This will return the top-most window/the floating window first.
     pub fn find_window_at_position(&self, position: &Position) -> Option<WindowRef> {
         let all_windows = self.get_all_windows();
@@ -608,7 +2106,7 @@ impl WindowManager {
         let found = all_windows
             .into_iter()
             .rev()
-            .filter(|w| w.visible())
+            .filter(|w| w.visible() && !self.is_stashed(w.id()))
             .find(|w| w.bounds().contains(position))?;
 
         if found.tiled() {
@@ -620,16 +2118,89 @@ impl WindowManager {
         Some(found)
     }
 
-    /// If the position is on the edge a window, that window is returned.
-    pub fn find_window_at_resize_edge(&self, position: &Position) -> Option<WindowRef> {
-        let thickness = 15;
-        let workspace = self.get_workspace_at_position(position).ok()?;
-        for window in workspace.windows().values() {
-            let bounds = window.window_bounds();
+    /// Finds the closest tiled window on the given side of a window in the same workspace.
+    /// Windows that overlap more along the edge win ties.
+    pub fn find_window_in_direction(&self, window: &WindowRef, side: Side) -> Option<WindowRef> {
+        let workspace = self.get_workspace_with_window(window)?;
+        let from = window.bounds();
 
-            let on_left_edge = (position.x - bounds.position.x).abs() <= thickness;
-            let on_right_edge =
-                (position.x - (bounds.position.x + bounds.size.width as i32)).abs() <= thickness;
+        workspace
+            .windows()
+            .values()
+            .filter(|w| w.tiled() && w.id() != window.id())
+            .filter_map(|w| {
+                let (distance, overlap) = Self::directional_distance(&from, &w.bounds(), side)?;
+                Some(((distance, -overlap), w.clone()))
+            })
+            .min_by_key(|(key, _)| *key)
+            .map(|(_, w)| w)
+    }
+
+    /// Distance from `from` to `to` on the given side, and how much they overlap along that
+    /// edge. Returns None when `to` isn't on that side.
+    fn directional_distance(from: &Bounds, to: &Bounds, side: Side) -> Option<(i32, i32)> {
+        // Where `to` would overlap `from` if it were slid along the side's axis
+        let overlap = match side.direction() {
+            Direction::Horizontal => {
+                let band = Bounds::new(
+                    to.position.x,
+                    from.position.y,
+                    to.size.width,
+                    from.size.height,
+                );
+                band.intersection(to)?.size.height
+            }
+            Direction::Vertical => {
+                let band = Bounds::new(
+                    from.position.x,
+                    to.position.y,
+                    from.size.width,
+                    to.size.height,
+                );
+                band.intersection(to)?.size.width
+            }
+        } as i32;
+
+        let distance = match side {
+            Side::Left => from.position.x - (to.position.x + to.size.width as i32),
+            Side::Right => to.position.x - (from.position.x + from.size.width as i32),
+            Side::Top => from.position.y - (to.position.y + to.size.height as i32),
+            Side::Bottom => to.position.y - (from.position.y + from.size.height as i32),
+        };
+
+        if distance < -NEIGHBOR_OVERLAP_TOLERANCE {
+            return None;
+        }
+
+        Some((distance.max(0), overlap))
+    }
+
+    /// A point just inside the middle of one edge of the bounds, where dropping a window puts
+    /// it on that side
+    fn point_inside_edge(bounds: &Bounds, side: Side) -> Position {
+        let center = bounds.center();
+        match side {
+            Side::Left => Position::new(bounds.position.x + 1, center.y),
+            Side::Right => {
+                Position::new(bounds.position.x + bounds.size.width as i32 - 2, center.y)
+            }
+            Side::Top => Position::new(center.x, bounds.position.y + 1),
+            Side::Bottom => {
+                Position::new(center.x, bounds.position.y + bounds.size.height as i32 - 2)
+            }
+        }
+    }
+
+    /// If the position is on the edge a window, that window is returned.
+    pub fn find_window_at_resize_edge(&self, position: &Position) -> Option<WindowRef> {
+        let thickness = 15;
+        let workspace = self.get_workspace_at_position(position).ok()?;
+        for window in workspace.windows().values() {
+            let bounds = window.window_bounds();
+
+            let on_left_edge = (position.x - bounds.position.x).abs() <= thickness;
+            let on_right_edge =
+                (position.x - (bounds.position.x + bounds.size.width as i32)).abs() <= thickness;
             let on_top_edge = (position.y - bounds.position.y).abs() <= thickness;
             let on_bottom_edge =
                 (position.y - (bounds.position.y + bounds.size.height as i32)).abs() <= thickness;
@@ -670,21 +2241,11 @@ impl WindowManager {
     }
 
     fn resize_handle_at_position_internal(&self, position: &Position) -> Option<ResizeHandle> {
-        let thickness = Config::resize_handle_width() as i32;
+        // Hit testing uses its own width so the grab area can be wider than the drawn handle
+        let thickness = Config::resize_handle_hit_width() as i32;
         self.resize_handles(position)
             .iter()
-            .find(|h| match h.orientation {
-                crate::resize_handle::HandleOrientation::Vertical => {
-                    let dx = (position.x - h.center.x).abs();
-                    let dy = (position.y - h.center.y).abs();
-                    dx <= thickness / 2 && dy <= h.length as i32 / 2
-                }
-                crate::resize_handle::HandleOrientation::Horizontal => {
-                    let dx = (position.x - h.center.x).abs();
-                    let dy = (position.y - h.center.y).abs();
-                    dy <= thickness / 2 && dx <= h.length as i32 / 2
-                }
-            })
+            .find(|h| h.contains(position, thickness))
             .cloned()
     }
 
@@ -694,6 +2255,9 @@ impl WindowManager {
         position: &Position,
         mode: &ResizeMode,
     ) -> WMResult<()> {
+        if self.resize_drag_before.is_none() {
+            self.resize_drag_before = Some(self.undo_snapshot());
+        }
         if let Ok(workspace) = self.get_workspace_at_position_mut(position) {
             workspace.resize_handle_moved(handle, position, mode);
             self.needs_flush = true;
@@ -701,20 +2265,64 @@ impl WindowManager {
         Ok(())
     }
 
+    /// Ends a resize handle drag, recording the whole drag as one undo step
+    pub fn resize_handle_released(&mut self) {
+        if let Some(before) = self.resize_drag_before.take() {
+            self.push_undo(before);
+        }
+        self.try_save_layout();
+        self.publish_snapshot();
+    }
+
+    /// Resets the split under a resize handle back to its default sizes
+    pub fn reset_resize_handle(&mut self, handle: &ResizeHandle) -> WMResult<()> {
+        let before = self.undo_snapshot();
+        let workspace = self.get_workspace_at_position_mut(&handle.center)?;
+        if workspace.reset_resize_handle(handle) {
+            self.animated_flush()?;
+            self.push_undo(before);
+            self.try_save_layout();
+        }
+        Ok(())
+    }
+
     /// Flush all pending window changes across all workspaces.
-    /// Called periodically by the event loop during live resize operations.
+    /// Called periodically by the event loop during live resize operations. The snapshot isn't
+    /// published for every frame, only once the drag ends.
     pub fn flush(&mut self) -> WMResult<()> {
-        if !self.needs_flush {
+        if !self.needs_flush || self.paused {
             return Ok(());
         }
         self.needs_flush = false;
         self.validate_workspaces();
+        self.apply_window_settings();
         for workspace in self.workspaces.values_mut() {
             workspace.flush_windows()?;
         }
         Ok(())
     }
 
+    /// Gives every window the scale factor of the display its workspace is on and the gap its
+    /// workspace tiles with
+    fn apply_window_settings(&self) {
+        for partition in self.partitions.values() {
+            for workspace_id in partition.assigned_workspaces() {
+                let Some(workspace) = self.workspaces.get(workspace_id) else {
+                    continue;
+                };
+                for window in workspace.windows().values() {
+                    window.set_scale_factor(partition.scale_factor());
+                    window.set_window_gap(workspace.window_gap());
+                }
+            }
+        }
+    }
+
+    /// Windows from the least to the most recently focused
+    pub fn window_order(&self) -> &IndexSet<WindowId> {
+        &self.window_order
+    }
+
     pub fn move_to_top(&mut self, id: WindowId) {
         if self.all_windows.contains_key(&id) {
             self.window_order.shift_remove(&id);
@@ -729,102 +2337,2563 @@ impl WindowManager {
         Ok(())
     }
 
+    /// Builds a read-only copy of the current partitions, workspaces and windows for live
+    /// querying. Unlike the saved layout, this includes runtime state like focus.
+    pub fn snapshot(&self) -> LayoutSnapshot {
+        let focused_window = self
+            .all_windows
+            .values()
+            .find(|w| w.focused())
+            .map(|w| w.id());
+
+        let mut partitions: Vec<_> = self
+            .partitions
+            .values()
+            .map(|p| PartitionSnapshot {
+                id: p.id(),
+                name: p.name().clone(),
+                bounds: p.bounds().clone(),
+                current_workspace: p.current_workspace(),
+            })
+            .collect();
+        partitions.sort_by_key(|p| p.id);
+
+        let mut workspaces: Vec<_> = self
+            .workspaces
+            .values()
+            .map(|w| {
+                let mut windows: Vec<_> = w
+                    .windows()
+                    .values()
+                    .map(|window| WindowSnapshot {
+                        id: window.id(),
+                        title: window.title(),
+                        app_name: window.app_name(),
+                        bounds: window.bounds(),
+                        floating: window.floating(),
+                        focused: window.focused(),
+                        dirty: window.dirty(),
+                    })
+                    .collect();
+                windows.sort_by_key(|window| window.id);
+
+                WorkspaceSnapshot {
+                    id: w.id(),
+                    name: w.name().to_string(),
+                    partition: self
+                        .partitions
+                        .values()
+                        .find(|p| p.assigned_workspaces().contains(&w.id()))
+                        .map(|p| p.id()),
+                    windows,
+                }
+            })
+            .collect();
+        workspaces.sort_by_key(|w| w.id);
+
+        LayoutSnapshot {
+            partitions,
+            workspaces,
+            focused_window,
+            animation_stats: self.animation_stats(),
+        }
+    }
+
+    /// Frame rates of the window animations finished so far, by what moved the windows
+    pub fn animation_stats(&self) -> AnimationStats {
+        self.animation_thread.stats()
+    }
+
+    /// Publishes the current state to the shared snapshot read by `get_layout_snapshot`.
+    pub fn publish_snapshot(&self) {
+        snapshot::publish(self.snapshot());
+    }
+
+    /// Saves the layout once the changes settle, see `Config::layout_save_debounce_ms`
     pub fn try_save_layout(&self) {
+        if !Config::persistence() {
+            return;
+        }
+        if Config::layout_save_debounce_ms() == 0 {
+            self.save_layout_now();
+            return;
+        }
+
+        match serialization::layout_contents(self) {
+            Ok(contents) => self.layout_saver.request(contents),
+            Err(e) => warn!("Failed to save layout: {e}"),
+        }
+    }
+
+    /// Drops any save still waiting in the background and writes the layout right away, so
+    /// nothing is lost when shutting down
+    pub fn final_save_layout(&mut self) {
+        self.layout_saver.shutdown();
+        self.save_layout_now();
+    }
+
+    fn save_layout_now(&self) {
         if let Err(e) = save_layout(self) {
             warn!("Failed to save layout: {e}");
         }
     }
 
-    pub fn config_changed(&mut self) -> PlatformResult<()> {
+    /// Changes the window gap by `delta` pixels and animates the layout to the new gap. The
+    /// config file is left alone, the gap is kept as an override that is saved with the layout
+    /// when persistence is on. Returns the new gap.
+    pub fn adjust_window_gap(&mut self, delta: i32) -> WMResult<u32> {
+        let gap = (Config::window_gap() as i32 + delta).clamp(0, MAX_WINDOW_GAP as i32) as u32;
+        self.override_window_gap(gap);
+
         for workspace in self.workspaces.values_mut() {
-            workspace.config_changed()?;
+            workspace.relayout();
+        }
+        self.animated_flush()?;
+        self.try_save_layout();
+        Ok(gap)
+    }
+
+    /// The window gap set with the gap commands, if it is still used over the configured one
+    pub fn window_gap_override(&self) -> Option<u32> {
+        self.window_gap_override.map(|(gap, _)| gap)
+    }
+
+    fn override_window_gap(&mut self, gap: u32) {
+        let configured = self
+            .window_gap_override
+            .map_or_else(Config::window_gap, |(_, configured)| configured);
+        self.window_gap_override = Some((gap, configured));
+        Config::update(|c| c.window_gap = gap);
+    }
+
+    /// Swaps a tiled window with its closest tiled neighbor on the given side.
+    pub fn swap_direction(&mut self, id: WindowId, side: Side) -> WMResult<()> {
+        let window = self.get_window(id)?;
+        if window.floating() {
+            return Ok(());
         }
+
+        let Some(neighbor) = self.find_window_in_direction(&window, side) else {
+            trace!("No window to the {side:?} of window {id}");
+            return Ok(());
+        };
+
+        let before = self.undo_snapshot();
+        let workspace = self
+            .get_workspace_with_window_mut(&window)
+            .ok_or(WMError::WorkspaceNotFound(id))?;
+        workspace.swap_windows(&window, &neighbor)?;
+
+        self.animated_flush()?;
+        self.push_undo(before);
+        self.try_save_layout();
         Ok(())
     }
 
-    pub fn load_layout_to_workspace(
+    /// Swaps a tiled window with the largest tiled window on its workspace
+    pub fn swap_with_largest(&mut self, id: WindowId) -> WMResult<()> {
+        self.swap_with_extreme(id, true)
+    }
+
+    /// Swaps a tiled window with the smallest tiled window on its workspace
+    pub fn swap_with_smallest(&mut self, id: WindowId) -> WMResult<()> {
+        self.swap_with_extreme(id, false)
+    }
+
+    fn swap_with_extreme(&mut self, id: WindowId, largest: bool) -> WMResult<()> {
+        let window = self.get_window(id)?;
+        if window.floating() {
+            return Ok(());
+        }
+
+        let before = self.undo_snapshot();
+        let workspace = self
+            .get_workspace_with_window_mut(&window)
+            .ok_or(WMError::WorkspaceNotFound(id))?;
+        let swapped = if largest {
+            workspace.swap_with_largest(&window)?
+        } else {
+            workspace.swap_with_smallest(&window)?
+        };
+        if swapped.is_none() {
+            trace!(
+                "Window {id} is already the {}",
+                if largest { "largest" } else { "smallest" }
+            );
+            return Ok(());
+        }
+
+        self.animated_flush()?;
+        self.push_undo(before);
+        self.try_save_layout();
+        Ok(())
+    }
+
+    /// The partition after (or before) the given one, ordered left to right then top to
+    /// bottom and wrapping around
+    pub fn adjacent_partition(&self, partition_id: PartitionId, next: bool) -> Option<PartitionId> {
+        let mut partitions = self.partitions.values().collect::<Vec<_>>();
+        partitions.sort_by_key(|p| (p.bounds().position.x, p.bounds().position.y, p.id()));
+
+        let index = partitions.iter().position(|p| p.id() == partition_id)?;
+        let count = partitions.len();
+        let adjacent = if next {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        };
+        Some(partitions[adjacent].id())
+    }
+
+    /// Moves a window to the current workspace of another partition and focuses it. Tiled
+    /// windows are split off the largest window there, floating windows keep their place
+    /// relative to the partition.
+    pub fn move_window_to_partition(
         &mut self,
-        workspace_id: WorkspaceId,
-        layout: &serde_yaml::Value,
+        id: WindowId,
+        partition_id: PartitionId,
     ) -> WMResult<()> {
-        let workspace = self
-            .workspaces
-            .get_mut(&workspace_id)
-            .ok_or(WMError::WorkspaceNotFound(0))?;
+        let window = self.get_window(id)?;
+        let partition = self
+            .partitions
+            .get(&partition_id)
+            .ok_or(WMError::PartitionNotFound(partition_id))?;
+        let target_bounds = partition.bounds().clone();
+        let target_id = partition
+            .current_workspace()
+            .ok_or(WMError::PartitionNotFound(partition_id))?;
 
-        let partition_bounds = self
+        let Some(source) = self.get_workspace_with_window(&window) else {
+            return Err(WMError::WorkspaceNotFound(id));
+        };
+        let source_id = source.id();
+        if source_id == target_id {
+            return Ok(());
+        }
+        let source_bounds = self
             .partitions
             .values()
-            .find(|p| p.current_workspace() == Some(workspace_id))
+            .find(|p| p.assigned_workspaces().contains(&source_id))
             .map(|p| p.bounds().clone())
-            .ok_or(WMError::LayoutError(LayoutError::Error(format!(
-                "No partition found for workspace {}",
-                workspace_id
-            ))))?;
+            .unwrap_or_else(|| target_bounds.clone());
 
-        let layout_window_ids = extract_window_ids(layout);
-        let layout_windows: Vec<WindowRef> = layout_window_ids
-            .iter()
-            .filter_map(|id| self.all_windows.get(id).cloned())
-            .collect();
+        let before = self.undo_snapshot();
+        self.workspaces
+            .get_mut(&source_id)
+            .unwrap()
+            .remove_window(&window)?;
 
-        for window in &layout_windows {
-            window.set_floating(false);
+        let target = self.workspaces.get_mut(&target_id).unwrap();
+        if window.floating() {
+            window.set_bounds(window.bounds().translated(
+                target_bounds.position.x - source_bounds.position.x,
+                target_bounds.position.y - source_bounds.position.y,
+            ));
+            target.float_window(&window)?;
+        } else {
+            // Next to a window's edge splits it, the middle of the partition fills it when
+            // it's empty
+            let position = target
+                .windows()
+                .values()
+                .filter(|w| w.tiled())
+                .max_by_key(|w| (w.bounds().area(), w.id()))
+                .map(|w| Self::point_inside_edge(&w.bounds(), Side::Right))
+                .unwrap_or_else(|| target_bounds.center());
+            target.tile_window(&window, &position)?;
         }
 
-        let new_layout = Box::new(ContainerTree::deserialize(
-            partition_bounds.clone(),
-            &layout_windows,
-            layout,
-        ));
+        self.animated_flush()?;
+        self.focus_window(id)?;
+        self.push_undo(before);
+        self.try_save_layout();
+        Ok(())
+    }
 
-        let workspace_name = workspace.name().to_string();
-        let new_workspace = Workspace::new_with_id::<ContainerTree>(
-            workspace_id,
-            partition_bounds,
-            workspace_name,
-            Some(new_layout),
-            None,
-        );
+    /// Floats every tiled window on a workspace where it is. Returns how many were floated.
+    pub fn float_all(&mut self, workspace_id: WorkspaceId) -> WMResult<usize> {
+        let workspace = self
+            .workspaces
+            .get_mut(&workspace_id)
+            .ok_or(WMError::WorkspaceNotFound(workspace_id))?;
+        let floated = workspace.float_all()?;
 
-        *self.workspaces.get_mut(&workspace_id).unwrap() = new_workspace;
+        self.animated_flush_for(AnimationKind::FloatSnap)?;
+        self.try_save_layout();
+        Ok(floated)
+    }
+
+    /// Asks to close every window on a workspace except `keep`, which only happens once
+    /// `confirm_close_windows` is called within `PENDING_CLOSE_TIMEOUT`. Replaces any close
+    /// already waiting. Returns how many windows would be closed.
+    pub fn request_close_windows(
+        &mut self,
+        workspace_id: WorkspaceId,
+        keep: Option<WindowId>,
+    ) -> WMResult<usize> {
+        let workspace = self
+            .workspaces
+            .get(&workspace_id)
+            .ok_or(WMError::UnknownWorkspace(workspace_id))?;
+        let count = workspace
+            .windows()
+            .keys()
+            .filter(|id| Some(**id) != keep)
+            .count();
+
+        self.pending_close = Some((workspace_id, keep, Instant::now()));
+        Ok(count)
+    }
+
+    /// Closes the windows of the waiting close request. Returns the windows asked to close, or
+    /// `None` if nothing was waiting.
+    pub fn confirm_close_windows(&mut self) -> WMResult<Option<Vec<WindowId>>> {
+        let Some((workspace_id, keep, _)) = self
+            .pending_close
+            .take()
+            .filter(|(_, _, at)| at.elapsed() < PENDING_CLOSE_TIMEOUT)
+        else {
+            return Ok(None);
+        };
+        self.close_workspace_windows(workspace_id, keep).map(Some)
+    }
+
+    /// Throws away the waiting close request. Returns false if there wasn't one.
+    pub fn cancel_close_windows(&mut self) -> bool {
+        self.pending_close.take().is_some()
+    }
+
+    /// Asks every window on a workspace except `keep` to close. They stay managed until the
+    /// platform reports them closed, so one that asks to save first isn't lost. A window that
+    /// fails to close is logged without stopping the rest. Returns the windows asked to close.
+    fn close_workspace_windows(
+        &mut self,
+        workspace_id: WorkspaceId,
+        keep: Option<WindowId>,
+    ) -> WMResult<Vec<WindowId>> {
+        let workspace = self
+            .workspaces
+            .get(&workspace_id)
+            .ok_or(WMError::UnknownWorkspace(workspace_id))?;
+        let mut windows: Vec<WindowRef> = workspace
+            .windows()
+            .values()
+            .filter(|w| Some(w.id()) != keep)
+            .cloned()
+            .collect();
+        windows.sort_by_key(|w| w.id());
+
+        let mut closed = Vec::new();
+        for window in windows {
+            match window.close() {
+                Ok(()) => closed.push(window.id()),
+                Err(e) => warn!("Could not close window {}: {e}", window.id()),
+            }
+        }
+        Ok(closed)
+    }
+
+    /// Tiles every floating window on a workspace where it is. Returns how many were tiled.
+    pub fn tile_all(&mut self, workspace_id: WorkspaceId) -> WMResult<usize> {
+        let workspace = self
+            .workspaces
+            .get_mut(&workspace_id)
+            .ok_or(WMError::WorkspaceNotFound(workspace_id))?;
+        let tiled = workspace.tile_all()?;
 
         self.animated_flush()?;
         self.try_save_layout();
+        Ok(tiled)
+    }
+
+    /// Sizes the tiled windows of a workspace relative to each other using `app_size_weights`.
+    pub fn balance_weighted(&mut self, workspace_id: WorkspaceId) -> WMResult<()> {
+        let weights = Config::app_size_weights();
+        let workspace = self
+            .workspaces
+            .get_mut(&workspace_id)
+            .ok_or(WMError::WorkspaceNotFound(0))?;
+
+        workspace
+            .balance_weighted(&|window| weights.get(&window.app_name()).copied().unwrap_or(1.0));
 
+        self.animated_flush()?;
+        self.try_save_layout();
         Ok(())
     }
 
-    fn load_serialized_workspace(
-        &mut self,
-        serialized_workspace: &crate::serialization::SerializedWorkspace,
-        partition_id: PartitionId,
-    ) -> WMResult<()> {
-        if !self.workspaces.contains_key(&serialized_workspace.id) {
-            let partition = self.partitions.get(&partition_id).unwrap();
-            let workspace = Workspace::new_with_id::<ContainerTree>(
-                serialized_workspace.id,
-                partition.bounds().clone(),
-                serialized_workspace.name.clone(),
-                None,
-                None,
-            );
-            self.workspaces.insert(serialized_workspace.id, workspace);
-            self.partitions
-                .get_mut(&partition_id)
-                .unwrap()
-                .assign_workspace(serialized_workspace.id);
+    /// Sizes every split of a workspace's layout by the golden ratio
+    pub fn apply_golden_ratio(&mut self, workspace_id: WorkspaceId) -> WMResult<()> {
+        if !self.workspaces.contains_key(&workspace_id) {
+            return Err(WMError::UnknownWorkspace(workspace_id));
         }
 
-        self.load_layout_to_workspace(serialized_workspace.id, &serialized_workspace.layout)?;
+        let before = self.undo_snapshot();
+        self.workspaces
+            .get_mut(&workspace_id)
+            .unwrap()
+            .apply_golden_ratio();
+        self.push_undo(before);
 
-        let workspace = self.workspaces.get_mut(&serialized_workspace.id).unwrap();
-        for serialized_floating in &serialized_workspace.floating {
-            if let Some(window) = self.all_windows.get(&serialized_floating.id) {
-                let _ = workspace.float_window(window);
+        self.animated_flush()?;
+        self.try_save_layout();
+        Ok(())
+    }
+
+    /// Recalculates every workspace and flushes all tiled windows, even ones whose managed
+    /// bounds did not change. Uses the tiling animation when it is enabled.
+    pub fn relayout_all(&mut self) -> WMResult<()> {
+        for workspace in self.workspaces.values_mut() {
+            workspace.relayout();
+            for window in workspace.windows().values().filter(|w| w.tiled()) {
+                window.mark_dirty();
             }
         }
+        self.animated_flush()?;
+        Ok(())
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stops or resumes moving windows. Layout changes made while paused are kept, and
+    /// resuming snaps every tiled window back to its place.
+    pub fn set_paused(&mut self, paused: bool) -> WMResult<()> {
+        if self.paused == paused {
+            return Ok(());
+        }
 
+        self.paused = paused;
+        if !paused {
+            self.relayout_all()?;
+        }
         Ok(())
     }
+
+    /// Pauses tiling if it's running, or resumes it. Returns whether tiling is now paused.
+    pub fn toggle_paused(&mut self) -> WMResult<bool> {
+        self.set_paused(!self.paused)?;
+        Ok(self.paused)
+    }
+
+    pub fn config_changed(&mut self) -> WMResult<()> {
+        self.layout_saver
+            .set_debounce(Duration::from_millis(Config::layout_save_debounce_ms()));
+        // Editing the gap in the config file drops the gap set with the gap commands
+        if let Some((gap, configured)) = self.window_gap_override {
+            if Config::window_gap() == configured {
+                Config::update(|c| c.window_gap = gap);
+            } else {
+                self.window_gap_override = None;
+            }
+        }
+        for workspace in self.workspaces.values_mut() {
+            workspace.config_changed()?;
+        }
+        self.reevaluate_ignored()
+    }
+
+    pub fn load_layout_to_workspace(
+        &mut self,
+        workspace_id: WorkspaceId,
+        layout: &serde_yaml::Value,
+    ) -> WMResult<()> {
+        let layout_type = self
+            .workspaces
+            .get(&workspace_id)
+            .ok_or(WMError::WorkspaceNotFound(0))?
+            .layout_type();
+        self.load_typed_layout_to_workspace(workspace_id, layout_type, layout)
+            .map(|_| ())
+    }
+
+    /// The slots a plan would give its windows, for previewing it. Nothing is changed until the
+    /// plan is confirmed.
+    pub fn organize_preview_slots(&self, plan: &OrganizePlan) -> Vec<Bounds> {
+        let mut slots = Vec::new();
+        for (workspace_id, layout) in plan.layouts() {
+            let Some(workspace) = self.workspaces.get(workspace_id) else {
+                continue;
+            };
+            let Some(partition) = self
+                .partitions
+                .values()
+                .find(|p| p.current_workspace() == Some(*workspace_id))
+            else {
+                continue;
+            };
+
+            slots.extend(
+                layouts::preview_layout(workspace.layout_type(), partition.bounds(), layout)
+                    .into_iter()
+                    .filter(|(id, _)| self.all_windows.contains_key(id))
+                    .map(|(_, bounds)| bounds),
+            );
+        }
+        slots
+    }
+
+    /// Holds a plan until it's confirmed or cancelled, replacing any plan already waiting
+    pub fn set_organize_plan(&mut self, plan: OrganizePlan, preview: Option<OrganizePreview>) {
+        self.pending_organize = Some((plan, preview));
+    }
+
+    /// Applies the waiting plan. Returns false if there wasn't one.
+    pub fn confirm_organize_plan(&mut self) -> WMResult<bool> {
+        let Some((plan, _preview)) = self.pending_organize.take() else {
+            return Ok(false);
+        };
+
+        let before = self.undo_snapshot();
+        for (workspace_id, layout) in plan.layouts() {
+            self.load_layout_to_workspace(*workspace_id, layout)?;
+        }
+        self.push_undo(before);
+        Ok(true)
+    }
+
+    /// Throws away the waiting plan. Returns false if there wasn't one.
+    pub fn cancel_organize_plan(&mut self) -> bool {
+        self.pending_organize.take().is_some()
+    }
+
+    /// Throws away the waiting plan if it's still the one that expired
+    pub fn expire_organize_plan(&mut self, plan_id: OrganizePlanId) {
+        if self
+            .pending_organize
+            .as_ref()
+            .is_some_and(|(plan, _)| plan.id() == plan_id)
+        {
+            self.pending_organize = None;
+        }
+    }
+
+    /// Saves the focused workspace's layout as a named preset
+    pub fn save_layout_preset(&self, name: &str) -> WMResult<()> {
+        let workspace_id = self
+            .focused_workspace_id()
+            .ok_or(WMError::WorkspaceNotFound(0))?;
+        let workspace = self
+            .workspaces
+            .get(&workspace_id)
+            .ok_or(WMError::WorkspaceNotFound(0))?;
+
+        let path = serialization::save_layout_preset(workspace, name)
+            .map_err(|e| WMError::Preset(e.to_string()))?;
+        debug!("Saved layout preset {name:?} to {}", path.display());
+        Ok(())
+    }
+
+    /// Applies a named preset to the focused workspace
+    pub fn load_layout_preset(&mut self, name: &str) -> WMResult<()> {
+        let preset =
+            serialization::load_layout_preset(name).map_err(|e| WMError::Preset(e.to_string()))?;
+        let workspace_id = self
+            .focused_workspace_id()
+            .ok_or(WMError::WorkspaceNotFound(0))?;
+        self.apply_layout_preset(workspace_id, &preset)
+    }
+
+    /// Rebuilds a workspace's layout from a preset. Open windows are matched to the preset by
+    /// id and pulled over from other workspaces if needed. Windows on the workspace that the
+    /// preset doesn't tile are floated, at their saved bounds when the preset has them.
+    pub fn apply_layout_preset(
+        &mut self,
+        workspace_id: WorkspaceId,
+        preset: &SerializedWorkspace,
+    ) -> WMResult<()> {
+        let previous: Vec<WindowRef> = self
+            .workspaces
+            .get(&workspace_id)
+            .ok_or(WMError::WorkspaceNotFound(0))?
+            .windows()
+            .values()
+            .cloned()
+            .collect();
+
+        for id in extract_window_ids(&preset.layout) {
+            let Some(window) = self.all_windows.get(&id).cloned() else {
+                continue;
+            };
+            if let Some(workspace) = self.get_workspace_with_window_mut(&window) {
+                if workspace.id() != workspace_id {
+                    workspace.remove_window(&window)?;
+                }
+            }
+        }
+
+        self.load_typed_layout_to_workspace(workspace_id, preset.layout_type, &preset.layout)?;
+
+        let workspace = self.workspaces.get_mut(&workspace_id).unwrap();
+        for window in previous {
+            if workspace.has_window(&window.id()) {
+                continue;
+            }
+            workspace.float_window(&window)?;
+            if let Some(saved) = preset.floating.iter().find(|w| w.id == window.id()) {
+                window.set_bounds(saved.bounds.clone());
+            }
+        }
+
+        self.animated_flush()?;
+        self.try_save_layout();
+        Ok(())
+    }
+
+    /// The shown workspaces as they are now, for undo to step back to
+    fn undo_snapshot(&self) -> Vec<SerializedWorkspace> {
+        let mut ids: Vec<WorkspaceId> = self
+            .partitions
+            .values()
+            .filter_map(|p| p.current_workspace())
+            .collect();
+        ids.sort();
+        ids.iter()
+            .filter_map(|id| self.workspaces.get(id))
+            .map(serialization::serialize_workspace)
+            .collect()
+    }
+
+    /// Records the layout from before a change so it can be undone. Changes that left the
+    /// layout as it was aren't recorded.
+    fn push_undo(&mut self, before: Vec<SerializedWorkspace>) {
+        let limit = Config::undo_history_limit() as usize;
+        if limit == 0 || before == self.undo_snapshot() {
+            return;
+        }
+
+        self.undo_stack.push_back(before);
+        while self.undo_stack.len() > limit {
+            self.undo_stack.pop_front();
+        }
+        self.redo_stack.clear();
+    }
+
+    /// Steps back to the layout from before the last change. Windows are matched by id, so a
+    /// window that has been closed since can't come back and is skipped. Returns false when
+    /// there is nothing to undo.
+    pub fn undo(&mut self) -> WMResult<bool> {
+        let Some(layout) = self.undo_stack.pop_back() else {
+            return Ok(false);
+        };
+
+        let current = self.undo_snapshot();
+        self.restore_undo_snapshot(&layout)?;
+        self.redo_stack.push(current);
+        Ok(true)
+    }
+
+    /// Reapplies the last change stepped back over by `undo`. Returns false when there is
+    /// nothing to redo.
+    pub fn redo(&mut self) -> WMResult<bool> {
+        let Some(layout) = self.redo_stack.pop() else {
+            return Ok(false);
+        };
+
+        let current = self.undo_snapshot();
+        self.restore_undo_snapshot(&layout)?;
+        self.undo_stack.push_back(current);
+        Ok(true)
+    }
+
+    fn restore_undo_snapshot(&mut self, layout: &[SerializedWorkspace]) -> WMResult<()> {
+        let current = self.undo_snapshot();
+        for saved in layout {
+            if current.contains(saved) {
+                continue;
+            }
+
+            // A workspace that was switched away from since has no partition to lay out on
+            let shown = self
+                .partitions
+                .values()
+                .any(|p| p.current_workspace() == Some(saved.id));
+            if !shown {
+                continue;
+            }
+
+            self.apply_layout_preset(saved.id, saved)?;
+        }
+        Ok(())
+    }
+
+    fn load_typed_layout_to_workspace(
+        &mut self,
+        workspace_id: WorkspaceId,
+        layout_type: LayoutType,
+        layout: &serde_yaml::Value,
+    ) -> WMResult<RestoreReport> {
+        let workspace = self
+            .workspaces
+            .get_mut(&workspace_id)
+            .ok_or(WMError::WorkspaceNotFound(0))?;
+
+        let partition_bounds = self
+            .partitions
+            .values()
+            .find(|p| p.current_workspace() == Some(workspace_id))
+            .map(|p| p.bounds().clone())
+            .ok_or(WMError::LayoutError(LayoutError::Error(format!(
+                "No partition found for workspace {}",
+                workspace_id
+            ))))?;
+
+        let layout_window_ids = extract_window_ids(layout);
+        let layout_windows: Vec<WindowRef> = layout_window_ids
+            .iter()
+            .filter_map(|id| self.all_windows.get(id).cloned())
+            .collect();
+
+        for window in &layout_windows {
+            window.set_floating(false);
+        }
+
+        let (new_layout, report) =
+            serialization::restore_layout(layout_type, partition_bounds, &layout_windows, layout);
+
+        let workspace_name = workspace.name().to_string();
+        let new_workspace =
+            Workspace::new_with_layout(workspace_id, workspace_name, new_layout, None);
+
+        *self.workspaces.get_mut(&workspace_id).unwrap() = new_workspace;
+
+        self.animated_flush()?;
+        self.try_save_layout();
+
+        Ok(report)
+    }
+
+    fn load_serialized_workspace(
+        &mut self,
+        serialized_workspace: &crate::serialization::SerializedWorkspace,
+        partition_id: PartitionId,
+    ) -> WMResult<RestoreReport> {
+        if !self.workspaces.contains_key(&serialized_workspace.id) {
+            let partition = self.partitions.get(&partition_id).unwrap();
+            let workspace = Workspace::new_with_layout(
+                serialized_workspace.id,
+                serialized_workspace.name.clone(),
+                layouts::new_layout(serialized_workspace.layout_type, partition.bounds().clone()),
+                None,
+            );
+            self.workspaces.insert(serialized_workspace.id, workspace);
+            self.partitions
+                .get_mut(&partition_id)
+                .unwrap()
+                .assign_workspace(serialized_workspace.id);
+        }
+
+        let mut report = self.load_typed_layout_to_workspace(
+            serialized_workspace.id,
+            serialized_workspace.layout_type,
+            &serialized_workspace.layout,
+        )?;
+
+        let workspace = self.workspaces.get_mut(&serialized_workspace.id).unwrap();
+        for serialized_floating in &serialized_workspace.floating {
+            if let Some(window) = self.all_windows.get(&serialized_floating.id) {
+                let _ = workspace.float_window(window);
+                report.placed.push(window.id());
+                if serialized_floating.sticky {
+                    window.set_sticky(true);
+                    self.sticky_windows.insert(window.id());
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{CommandContext, SET_LAYOUT, SWITCH_WORKSPACE};
+    use crate::config::{ConfigError, WorkspaceConfig};
+    use crate::event_loop_wm::WMOperationError;
+    use crate::layouts::ContainerTree;
+    use crate::platform::mock::{MockPlatform, MockPlatformWindow};
+    use crate::platform::{PlatformWindowImpl, Size, WMEvent};
+
+    #[test]
+    fn test_adjusting_the_gap_leaves_the_config_file_alone() {
+        let dir = std::env::temp_dir().join(format!("ultrawm-gap-{}", std::process::id()));
+        let path = dir.join("config.yaml");
+        Config::default().save_to_file(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.config_path = Some(path.clone());
+        });
+
+        let mut wm = new_test_wm();
+        wm.track_window(Rc::new(Window::new(new_mock_window(56, "Editor"))))
+            .unwrap();
+        let window = wm.get_window(56).unwrap();
+        let before = window.window_bounds();
+
+        let gap = wm.adjust_window_gap(10).unwrap();
+        let after = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(gap, Config::default().window_gap + 10);
+        assert_eq!(Config::window_gap(), gap);
+        assert_eq!(after, contents);
+        assert_ne!(window.window_bounds(), before);
+
+        // A reload that doesn't touch the gap keeps the adjusted one
+        Config::update(|c| c.window_gap = Config::default().window_gap);
+        wm.config_changed().unwrap();
+        assert_eq!(Config::window_gap(), gap);
+        assert_eq!(wm.window_gap_override(), Some(gap));
+
+        // Changing it in the file wins
+        Config::update(|c| c.window_gap = 4);
+        wm.config_changed().unwrap();
+        assert_eq!(Config::window_gap(), 4);
+        assert_eq!(wm.window_gap_override(), None);
+    }
+
+    #[test]
+    fn test_adjusted_gap_is_restored_with_the_layout() {
+        let _config = Config::scoped(|c| c.persistence = false);
+
+        let mut wm = new_test_wm();
+        let gap = wm.adjust_window_gap(-5).unwrap();
+        let saved: SerializedWindowManager =
+            serde_yaml::from_str(&serialization::layout_contents(&wm).unwrap()).unwrap();
+        assert_eq!(saved.window_gap, Some(gap));
+
+        Config::update(|c| c.window_gap = Config::default().window_gap);
+        let mut restored = new_test_wm();
+        restored.restore_saved_layout(saved);
+        assert_eq!(Config::window_gap(), gap);
+        assert_eq!(restored.window_gap_override(), Some(gap));
+    }
+
+    fn new_test_wm() -> WindowManager {
+        let bounds = Bounds::new(0, 0, 1920, 1080);
+        let mut partition = Partition::new("Test".to_string(), bounds.clone());
+        let workspace = Workspace::new::<ContainerTree>(bounds, "Default".to_string(), None, None);
+        partition.assign_workspace(workspace.id());
+
+        WindowManager {
+            partitions: HashMap::from([(partition.id(), partition)]),
+            workspaces: HashMap::from([(workspace.id(), workspace)]),
+            window_order: IndexSet::new(),
+            animation_thread: WorkspaceAnimationThread::new(WorkspaceAnimationConfig {
+                animation_fps: 60,
+            }),
+            all_windows: HashMap::new(),
+            transient_windows: HashSet::new(),
+            sticky_windows: HashSet::new(),
+            scratchpad_windows: IndexSet::new(),
+            minimized_windows: HashSet::new(),
+            fullscreen_slots: HashMap::new(),
+            fullscreen_bounds: HashMap::new(),
+            resize_drag_before: None,
+            needs_flush: false,
+            paused: false,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            pending_organize: None,
+            pending_split: None,
+            pending_close: None,
+            layout_saver: LayoutSaveDebouncer::new(Duration::from_millis(0)),
+            window_gap_override: None,
+        }
+    }
+
+    fn new_mock_window(id: WindowId, app_name: &str) -> MockPlatformWindow {
+        let mut platform_window = MockPlatformWindow::new(
+            Position { x: 100, y: 100 },
+            Size {
+                width: 800,
+                height: 600,
+            },
+            format!("Window {id}"),
+        );
+        platform_window.id = id;
+        platform_window.app_name = app_name.to_string();
+        platform_window.visible = true;
+        platform_window
+    }
+
+    #[test]
+    fn test_new_window_does_not_take_focus_with_no_focus() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.new_window_focus = NewWindowFocus::NoFocus;
+        });
+
+        let mut wm = new_test_wm();
+        let first = new_mock_window(1, "Terminal");
+        wm.track_window(Rc::new(Window::new(first.clone())))
+            .unwrap();
+        wm.focus_window(1).unwrap();
+
+        let second = new_mock_window(2, "Browser");
+        wm.track_window(Rc::new(Window::new(second.clone())))
+            .unwrap();
+
+        assert_eq!(second.get_focus_calls(), 0);
+        assert_eq!(wm.window_order.last(), Some(&1));
+        assert!(wm
+            .get_workspace_with_window(&wm.get_window(2).unwrap())
+            .is_some());
+    }
+
+    #[test]
+    fn test_new_window_focus_is_left_to_the_os_by_default() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.mouse_follows_focus = true;
+        });
+
+        let mut wm = new_test_wm();
+        wm.track_window(Rc::new(Window::new(new_mock_window(1, "Terminal"))))
+            .unwrap();
+        wm.focus_window(1).unwrap();
+        MockPlatform::take_mouse_warps();
+
+        let second = new_mock_window(2, "Browser");
+        wm.track_window(Rc::new(Window::new(second.clone())))
+            .unwrap();
+
+        assert_eq!(second.get_focus_calls(), 0);
+        assert!(MockPlatform::take_mouse_warps().is_empty());
+    }
+
+    #[test]
+    fn test_relayout_all_flushes_tiled_windows() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        let platform_window = new_mock_window(3, "Terminal");
+        wm.track_window(Rc::new(Window::new(platform_window.clone())))
+            .unwrap();
+        platform_window.clear_set_bounds_calls();
+
+        wm.relayout_all().unwrap();
+
+        let window = wm.get_window(3).unwrap();
+        assert_eq!(
+            platform_window.get_set_bounds_calls(),
+            vec![window.window_bounds()]
+        );
+        assert!(!window.dirty());
+    }
+
+    #[test]
+    fn test_focus_window_dims_previously_focused_window() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.inactive_window_opacity = 0.8;
+        });
+
+        let mut wm = new_test_wm();
+        let first = new_mock_window(4, "Terminal");
+        let second = new_mock_window(5, "Browser");
+        wm.track_window(Rc::new(Window::new(first.clone())))
+            .unwrap();
+        wm.track_window(Rc::new(Window::new(second.clone())))
+            .unwrap();
+
+        wm.focus_window(4).unwrap();
+        wm.focus_window(5).unwrap();
+
+        assert_eq!(first.get_set_opacity_calls().last(), Some(&0.8));
+        assert_eq!(second.get_set_opacity_calls().last(), Some(&1.0));
+        assert_eq!(wm.get_window(4).unwrap().opacity(), 0.8);
+        assert_eq!(wm.get_window(5).unwrap().opacity(), 1.0);
+    }
+
+    #[test]
+    fn test_snapshot_lists_workspace_windows() {
+        let _config = Config::scoped(|c| c.persistence = false);
+
+        let mut wm = new_test_wm();
+        for id in [7, 6] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+        wm.focus_window(6).unwrap();
+
+        let snapshot = wm.snapshot();
+        assert_eq!(snapshot.partitions.len(), 1);
+        assert_eq!(snapshot.workspaces.len(), 1);
+        assert_eq!(snapshot.focused_window, Some(6));
+
+        let workspace = &snapshot.workspaces[0];
+        assert_eq!(workspace.name, "Default");
+        assert_eq!(workspace.partition, Some(snapshot.partitions[0].id));
+        let ids: Vec<_> = workspace.windows.iter().map(|w| w.id).collect();
+        assert_eq!(ids, vec![6, 7]);
+        assert!(workspace.windows[0].focused);
+        assert!(!workspace.windows[1].focused);
+    }
+
+    #[test]
+    fn test_only_one_window_is_focused() {
+        let _config = Config::scoped(|c| c.persistence = false);
+
+        let mut wm = new_test_wm();
+        for id in [43, 44, 45] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+
+        for id in [44, 43, 45, 44] {
+            wm.focus_window(id).unwrap();
+
+            let focused: Vec<_> = [43, 44, 45]
+                .into_iter()
+                .filter(|id| wm.get_window(*id).unwrap().focused())
+                .collect();
+            assert_eq!(focused, vec![id]);
+
+            let snapshot = wm.snapshot();
+            assert_eq!(snapshot.focused_window, Some(id));
+            let snapshot_focused: Vec<_> = snapshot.workspaces[0]
+                .windows
+                .iter()
+                .filter(|w| w.focused)
+                .map(|w| w.id)
+                .collect();
+            assert_eq!(snapshot_focused, vec![id]);
+        }
+    }
+
+    #[test]
+    fn test_title_change_updates_snapshot() {
+        let _config = Config::scoped(|c| c.persistence = false);
+
+        let mut wm = new_test_wm();
+        let mut platform_window = new_mock_window(46, "Browser");
+        platform_window.title = "Inbox".to_string();
+        wm.track_window(Rc::new(Window::new(platform_window.clone())))
+            .unwrap();
+
+        let WMEvent::WindowTitleChanged(id, title) = platform_window.change_title("Calendar")
+        else {
+            panic!("Expected a title change event");
+        };
+        wm.window_title_changed(id, title);
+
+        assert_eq!(wm.get_window(46).unwrap().title(), "Calendar");
+        let window = wm.snapshot().workspaces[0]
+            .windows
+            .iter()
+            .find(|w| w.id == 46)
+            .cloned()
+            .unwrap();
+        assert_eq!(window.title, "Calendar");
+    }
+
+    #[test]
+    fn test_untitled_window_is_tiled_once_it_gets_a_title() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.require_title_to_tile = true;
+        });
+
+        let mut wm = new_test_wm();
+        let mut platform_window = new_mock_window(82, "Editor");
+        platform_window.title = String::new();
+        wm.track_window(Rc::new(Window::new(platform_window.clone())))
+            .unwrap();
+        assert!(wm.get_window(82).unwrap().floating());
+
+        let WMEvent::WindowTitleChanged(id, title) = platform_window.change_title("main.rs") else {
+            panic!("Expected a title change event");
+        };
+        wm.window_title_changed(id, title);
+
+        assert!(!wm.get_window(82).unwrap().floating());
+    }
+
+    #[test]
+    fn test_close_other_and_all_workspace_windows() {
+        let _config = Config::scoped(|c| c.persistence = false);
+
+        let mut wm = new_test_wm();
+        let mut platform_windows = Vec::new();
+        for id in [47, 48, 49, 50] {
+            let mut platform_window = new_mock_window(id, "Editor");
+            platform_window.close_fails = id == 48;
+            wm.track_window(Rc::new(Window::new(platform_window.clone())))
+                .unwrap();
+            platform_windows.push(platform_window);
+        }
+        let close_calls = |platform_windows: &[MockPlatformWindow]| -> Vec<usize> {
+            platform_windows
+                .iter()
+                .map(|w| w.get_close_calls())
+                .collect()
+        };
+        let workspace_id = wm.focused_workspace_id().unwrap();
+
+        // Nothing closes until it's confirmed
+        assert_eq!(wm.request_close_windows(workspace_id, Some(47)).unwrap(), 3);
+        assert_eq!(close_calls(&platform_windows), vec![0, 0, 0, 0]);
+        assert!(wm.cancel_close_windows());
+        assert_eq!(wm.confirm_close_windows().unwrap(), None);
+        assert_eq!(close_calls(&platform_windows), vec![0, 0, 0, 0]);
+
+        // The window that refuses to close stays, the rest are still asked to
+        wm.request_close_windows(workspace_id, Some(47)).unwrap();
+        let closed = wm.confirm_close_windows().unwrap();
+        assert_eq!(closed, Some(vec![49, 50]));
+        assert_eq!(close_calls(&platform_windows), vec![0, 1, 1, 1]);
+
+        // Windows are only dropped once the platform says they closed, one still asking to
+        // save its changes stays managed
+        wm.remove_window(49).unwrap();
+        assert!(wm.get_window(49).is_err());
+        assert!(wm.workspaces[&workspace_id].has_window(&50));
+
+        wm.request_close_windows(workspace_id, None).unwrap();
+        let closed = wm.confirm_close_windows().unwrap();
+        assert_eq!(closed, Some(vec![47, 50]));
+        assert_eq!(close_calls(&platform_windows), vec![1, 2, 1, 2]);
+
+        // A close nobody confirmed in time is dropped
+        wm.pending_close = Some((workspace_id, None, Instant::now() - PENDING_CLOSE_TIMEOUT));
+        assert_eq!(wm.confirm_close_windows().unwrap(), None);
+    }
+
+    #[test]
+    fn test_fullscreen_windows_are_not_tiled() {
+        let _config = Config::scoped(|c| c.persistence = false);
+
+        let mut wm = new_test_wm();
+        let tiled = new_mock_window(51, "Editor");
+        let fullscreen = new_mock_window(52, "Video");
+        fullscreen.user_set_fullscreen(true);
+        for platform_window in [&tiled, &fullscreen] {
+            wm.track_window(Rc::new(Window::new(platform_window.clone())))
+                .unwrap();
+        }
+        let in_workspace = |wm: &WindowManager, id| {
+            let window = wm.get_window(id).unwrap();
+            wm.get_workspace_with_window(&window).is_some()
+        };
+
+        // Known, but neither tiled nor floated
+        let window = wm.get_window(52).unwrap();
+        assert!(!in_workspace(&wm, 52));
+        assert!(!window.floating());
+
+        fullscreen.user_set_fullscreen(false);
+        wm.window_fullscreen_changed(52, false).unwrap();
+        assert!(in_workspace(&wm, 52));
+        assert!(window.tiled());
+
+        tiled.user_set_fullscreen(true);
+        wm.window_fullscreen_changed(51, true).unwrap();
+        assert!(!in_workspace(&wm, 51));
+        assert!(wm.get_window(51).is_ok());
+    }
+
+    #[test]
+    fn test_toggle_fullscreen_returns_to_its_slot() {
+        let _config = Config::scoped(|c| c.persistence = false);
+
+        let mut wm = new_test_wm();
+        let platform_window = new_mock_window(53, "Editor");
+        for window in [platform_window.clone(), new_mock_window(54, "Terminal")] {
+            wm.track_window(Rc::new(Window::new(window))).unwrap();
+        }
+        let window = wm.get_window(53).unwrap();
+        let tiled_bounds = window.bounds();
+
+        wm.toggle_fullscreen(53).unwrap();
+        assert_eq!(platform_window.get_set_fullscreen_calls(), vec![true]);
+        assert!(window.fullscreen());
+        assert!(wm.get_workspace_with_window(&window).is_none());
+
+        // The platform reporting what was asked for changes nothing
+        wm.window_fullscreen_changed(53, true).unwrap();
+        assert!(wm.get_workspace_with_window(&window).is_none());
+
+        wm.toggle_fullscreen(53).unwrap();
+        assert_eq!(
+            platform_window.get_set_fullscreen_calls(),
+            vec![true, false]
+        );
+        assert!(!window.fullscreen());
+        assert!(wm.get_workspace_with_window(&window).is_some());
+        assert_eq!(window.bounds(), tiled_bounds);
+    }
+
+    #[test]
+    fn test_toggle_fullscreen_restores_floating_bounds() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = true;
+        });
+
+        let mut wm = new_test_wm();
+        let platform_window = new_mock_window(55, "Video");
+        wm.track_window(Rc::new(Window::new(platform_window.clone())))
+            .unwrap();
+        let floating_bounds = Bounds::new(200, 150, 640, 360);
+        wm.place_window_at(55, floating_bounds.clone(), true)
+            .unwrap();
+        let window = wm.get_window(55).unwrap();
+
+        // Windows only stretches the window over its monitor and leaves it there
+        wm.toggle_fullscreen(55).unwrap();
+        platform_window
+            .set_bounds(&Bounds::new(0, 0, 1920, 1080))
+            .unwrap();
+        window.update_bounds();
+
+        wm.toggle_fullscreen(55).unwrap();
+        assert!(window.floating());
+        assert_eq!(window.bounds(), floating_bounds);
+    }
+
+    #[test]
+    fn test_window_order_survives_a_restart() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+        });
+
+        let mut wm = new_test_wm();
+        let platform_windows = [60, 61, 62].map(|id| new_mock_window(id, "Editor"));
+        for window in &platform_windows {
+            wm.track_window(Rc::new(Window::new(window.clone())))
+                .unwrap();
+        }
+        wm.float_window(61).unwrap();
+        wm.float_window(62).unwrap();
+        for id in [62, 60, 61] {
+            wm.focus_window(id).unwrap();
+        }
+        let order = wm.window_order.iter().copied().collect::<Vec<_>>();
+        assert_eq!(order, vec![62, 60, 61]);
+
+        let contents = serialization::layout_contents(&wm).unwrap();
+        let saved = serialization::deserialize_layout(&contents).unwrap();
+        assert_eq!(saved.window_order, order);
+
+        let mut restored = new_test_wm();
+        for window in &platform_windows {
+            let window = Rc::new(Window::new(window.clone()));
+            restored.all_windows.insert(window.id(), window);
+        }
+        let focus_calls = platform_windows[1].get_focus_calls();
+        restored.restore_saved_layout(saved);
+
+        assert_eq!(
+            restored.window_order.iter().copied().collect::<Vec<_>>(),
+            order
+        );
+        assert!(restored.get_window(61).unwrap().floating());
+        assert_eq!(platform_windows[1].get_focus_calls(), focus_calls + 1);
+    }
+
+    #[test]
+    fn test_restore_places_windows_the_saved_layout_has_no_slot_for() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+        });
+
+        let mut wm = new_test_wm();
+        for id in [1, 2, 3] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Editor"))))
+                .unwrap();
+        }
+        let contents = serialization::layout_contents(&wm).unwrap();
+        let saved = serialization::deserialize_layout(&contents).unwrap();
+
+        let mut restored = new_test_wm();
+        let windows = [2, 3, 4]
+            .map(|id| Rc::new(Window::new(new_mock_window(id, "Editor"))))
+            .to_vec();
+        for window in &windows {
+            restored.all_windows.insert(window.id(), window.clone());
+        }
+        let report = restored.restore_saved_layout(saved);
+
+        assert_eq!(report.placed, vec![2, 3]);
+        assert_eq!(report.unplaced, vec![4]);
+        assert_eq!(report.missing, vec![1]);
+
+        let workspace_id = restored
+            .get_workspace_with_window(&windows[0])
+            .unwrap()
+            .id();
+        assert!(!restored.workspaces[&workspace_id].has_window(&4));
+
+        restored.track_unplaced_windows(
+            windows
+                .into_iter()
+                .filter(|w| report.unplaced.contains(&w.id()))
+                .collect(),
+        );
+        assert!(restored.workspaces[&workspace_id].has_window(&4));
+        assert!(!restored.all_windows.contains_key(&1));
+    }
+
+    #[test]
+    fn test_snapshot_json_round_trip() {
+        let _config = Config::scoped(|c| c.persistence = false);
+
+        let mut wm = new_test_wm();
+        for id in [11, 12] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Editor"))))
+                .unwrap();
+        }
+
+        let snapshot = wm.snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(!json.contains('\n'));
+
+        let parsed: LayoutSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, snapshot);
+    }
+
+    #[test]
+    fn test_focus_next_and_prev_cycle_tiled_windows() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+        });
+
+        let mut wm = new_test_wm();
+        for id in [8, 9, 10] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+        for id in [8, 9, 10] {
+            wm.focus_window(id).unwrap();
+        }
+
+        let mut focused = Vec::new();
+        for _ in 0..4 {
+            wm.focus_next().unwrap();
+            focused.push(*wm.window_order.last().unwrap());
+        }
+        assert_eq!(focused, vec![8, 9, 10, 8]);
+
+        focused.clear();
+        for _ in 0..4 {
+            wm.focus_prev().unwrap();
+            focused.push(*wm.window_order.last().unwrap());
+        }
+        assert_eq!(focused, vec![10, 9, 8, 10]);
+    }
+
+    #[test]
+    fn test_focus_cycles_within_the_focused_app() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+        });
+
+        let mut wm = new_test_wm();
+        for (id, pid) in [(64, 100), (65, 100), (66, 200)] {
+            let mut window = new_mock_window(id, "Editor");
+            window.pid = pid;
+            wm.track_window(Rc::new(Window::new(window))).unwrap();
+        }
+        wm.float_window(65).unwrap();
+        for id in [64, 66, 65] {
+            wm.focus_window(id).unwrap();
+        }
+
+        let mut focused = Vec::new();
+        for _ in 0..3 {
+            wm.focus_next_in_app().unwrap();
+            focused.push(*wm.window_order.last().unwrap());
+        }
+        assert_eq!(focused, vec![64, 65, 64]);
+
+        wm.focus_prev_in_app().unwrap();
+        assert_eq!(wm.window_order.last(), Some(&65));
+
+        // Focusing the other app cycles through its only window, which changes nothing
+        wm.focus_window(66).unwrap();
+        wm.focus_next_in_app().unwrap();
+        assert_eq!(wm.window_order.last(), Some(&66));
+    }
+
+    #[test]
+    fn test_toggle_always_on_top_floats_tiled_window() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+        });
+
+        let mut wm = new_test_wm();
+        let platform_window = new_mock_window(67, "Notes");
+        for window in [platform_window.clone(), new_mock_window(68, "Editor")] {
+            wm.track_window(Rc::new(Window::new(window))).unwrap();
+        }
+        let window = wm.get_window(67).unwrap();
+        assert!(window.tiled());
+
+        assert!(wm.toggle_always_on_top(67).unwrap());
+        assert!(window.floating());
+        assert_eq!(
+            platform_window.get_set_always_on_top_calls().last(),
+            Some(&true)
+        );
+
+        // Taking it off the top leaves it floating
+        assert!(!wm.toggle_always_on_top(67).unwrap());
+        assert!(window.floating());
+        assert!(!window.always_on_top());
+        assert_eq!(
+            platform_window.get_set_always_on_top_calls().last(),
+            Some(&false)
+        );
+    }
+
+    #[test]
+    fn test_new_window_tiles_beside_focused_window() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.new_window_side = Some(Side::Right);
+        });
+
+        let mut wm = new_test_wm();
+        wm.track_window(Rc::new(Window::new(new_mock_window(69, "Editor"))))
+            .unwrap();
+        // Each new window goes right of the focused one, not of the last one opened
+        for id in [75, 70] {
+            wm.focus_window(69).unwrap();
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+
+        let focused = wm.get_window(69).unwrap().bounds();
+        let right = wm.get_window(70).unwrap().bounds();
+        let other = wm.get_window(75).unwrap().bounds();
+        assert!(focused.position.x < right.position.x);
+        assert!(right.position.x < other.position.x);
+    }
+
+    #[test]
+    fn test_split_places_next_window_once() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = true;
+        });
+
+        let mut wm = new_test_wm();
+        wm.track_window(Rc::new(Window::new(new_mock_window(72, "Editor"))))
+            .unwrap();
+        wm.tile_window(72, &Position::new(100, 100)).unwrap();
+        wm.split_next_window(72, Side::Right).unwrap();
+
+        // Opens at the left edge and would float, the split still tiles it on the right
+        let mut platform_window = new_mock_window(73, "Terminal");
+        platform_window.position = Position { x: 0, y: 0 };
+        wm.track_window(Rc::new(Window::new(platform_window)))
+            .unwrap();
+        let editor = wm.get_window(72).unwrap().bounds();
+        let terminal = wm.get_window(73).unwrap();
+        assert!(terminal.tiled());
+        assert!(terminal.bounds().position.x > editor.position.x);
+        assert!(wm.pending_split.is_none());
+
+        // Only the very next window
+        wm.track_window(Rc::new(Window::new(new_mock_window(74, "Browser"))))
+            .unwrap();
+        assert!(wm.get_window(74).unwrap().floating());
+
+        // A split nobody used is dropped after the timeout
+        wm.pending_split = Some((72, Side::Left, Instant::now() - PENDING_SPLIT_TIMEOUT));
+        assert_eq!(wm.take_pending_split(), None);
+    }
+
+    #[test]
+    fn test_sticky_window_follows_workspace_switch() {
+        let _config = Config::scoped(|c| c.persistence = false);
+
+        let mut wm = new_test_wm();
+        let bounds = Bounds::new(0, 0, 1920, 1080);
+        let first = *wm.workspaces.keys().next().unwrap();
+        let second = Workspace::new::<ContainerTree>(bounds, "Second".to_string(), None, None);
+        let second_id = second.id();
+        wm.workspaces.insert(second_id, second);
+        wm.partitions
+            .values_mut()
+            .next()
+            .unwrap()
+            .assign_workspace(second_id);
+
+        wm.track_window(Rc::new(Window::new(new_mock_window(13, "Notes"))))
+            .unwrap();
+        assert!(wm.toggle_sticky(13).unwrap());
+        assert!(wm.workspaces[&first].has_window(&13));
+
+        // Through the command, the way keybinds and IPC switch workspaces
+        let switch = |wm: &mut WindowManager, name: &str| {
+            (SWITCH_WORKSPACE.handler)(wm, Some(&CommandContext::with_argument(name)))
+        };
+        assert!(switch(&mut wm, "Missing").is_err());
+        switch(&mut wm, "Second").unwrap();
+
+        let partition = wm.partitions.values().next().unwrap();
+        assert_eq!(partition.current_workspace(), Some(second_id));
+        assert!(!wm.workspaces[&first].has_window(&13));
+        assert!(wm.workspaces[&second_id].has_window(&13));
+
+        let window = wm.get_window(13).unwrap();
+        assert!(window.sticky());
+        assert!(window.floating());
+    }
+
+    #[test]
+    fn test_new_splits_each_display_into_partitions() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.partitions_per_display = 2;
+            c.partition_ratios = Vec::new();
+        });
+        MockPlatform::set_displays(vec![
+            Display {
+                id: 1,
+                name: "Left".to_string(),
+                bounds: Bounds::new(0, 0, 1920, 1080),
+                work_area: Bounds::new(0, 25, 1920, 1055),
+                scale_factor: 1.0,
+            },
+            Display {
+                id: 2,
+                name: "Right".to_string(),
+                bounds: Bounds::new(1920, 0, 2560, 1440),
+                work_area: Bounds::new(1920, 0, 2560, 1440),
+                scale_factor: 2.0,
+            },
+        ]);
+
+        let wm = WindowManager::new().unwrap();
+
+        let mut partitions: Vec<(String, Bounds)> = wm
+            .partitions()
+            .values()
+            .map(|p| (p.name().clone(), p.bounds().clone()))
+            .collect();
+        partitions.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            partitions,
+            vec![
+                (
+                    "Left_partition_1".to_string(),
+                    Bounds::new(0, 25, 960, 1055)
+                ),
+                (
+                    "Left_partition_2".to_string(),
+                    Bounds::new(960, 25, 960, 1055)
+                ),
+                (
+                    "Right_partition_1".to_string(),
+                    Bounds::new(1920, 0, 1280, 1440)
+                ),
+                (
+                    "Right_partition_2".to_string(),
+                    Bounds::new(3200, 0, 1280, 1440)
+                ),
+            ]
+        );
+        assert!(wm
+            .partitions()
+            .values()
+            .all(|p| p.current_workspace().is_some()));
+    }
+
+    #[test]
+    fn test_pinned_workspaces_fall_back_to_primary_display() {
+        let pinned = |display: &str| WorkspaceConfig {
+            display: Some(display.to_string()),
+            ..Default::default()
+        };
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.workspaces
+                .insert("Pinned Chat".to_string(), pinned("Right"));
+            c.workspaces
+                .insert("Pinned Code".to_string(), pinned("DisplayPort-2"));
+        });
+        let displays = vec![
+            Display {
+                id: 1,
+                name: "Right".to_string(),
+                bounds: Bounds::new(1920, 0, 1920, 1080),
+                work_area: Bounds::new(1920, 0, 1920, 1080),
+                scale_factor: 1.0,
+            },
+            Display {
+                id: 2,
+                name: "Primary".to_string(),
+                bounds: Bounds::new(0, 0, 1920, 1080),
+                work_area: Bounds::new(0, 25, 1920, 1055),
+                scale_factor: 1.0,
+            },
+        ];
+        MockPlatform::set_displays(displays.clone());
+
+        let validation = Config::current().validate_displays(&displays);
+        let wm = WindowManager::new().unwrap();
+
+        assert!(matches!(
+            validation,
+            Err(ConfigError::UnknownDisplays(unknown))
+                if unknown == vec![r#""Pinned Code" on "DisplayPort-2""#.to_string()]
+        ));
+        let display_x = |name: &str| {
+            let id = wm.find_workspace_by_name(name).unwrap();
+            wm.partitions()
+                .values()
+                .find(|p| p.assigned_workspaces().contains(&id))
+                .map(|p| p.bounds().position.x)
+                .unwrap()
+        };
+        assert!(display_x("Pinned Chat") >= 1920);
+        assert!(display_x("Pinned Code") < 1920);
+    }
+
+    fn new_two_partition_test_wm() -> (WindowManager, WorkspaceId, WorkspaceId) {
+        let (wm, workspace_ids) = new_partitioned_test_wm(&[
+            ("Left", Bounds::new(0, 0, 960, 1080)),
+            ("Right", Bounds::new(960, 0, 960, 1080)),
+        ]);
+        (wm, workspace_ids[0], workspace_ids[1])
+    }
+
+    fn new_partitioned_test_wm(partitions: &[(&str, Bounds)]) -> (WindowManager, Vec<WorkspaceId>) {
+        let mut wm = new_test_wm();
+        wm.partitions.clear();
+        wm.workspaces.clear();
+
+        let mut workspace_ids = Vec::new();
+        for (name, bounds) in partitions.iter().cloned() {
+            let mut partition = Partition::new(name.to_string(), bounds.clone());
+            let workspace =
+                Workspace::new::<ContainerTree>(bounds, "Default".to_string(), None, None);
+            partition.assign_workspace(workspace.id());
+            workspace_ids.push(workspace.id());
+            wm.workspaces.insert(workspace.id(), workspace);
+            wm.partitions.insert(partition.id(), partition);
+        }
+
+        (wm, workspace_ids)
+    }
+
+    fn assert_inside(inner: &Bounds, outer: &Bounds) {
+        assert!(
+            inner.position.x >= outer.position.x
+                && inner.position.y >= outer.position.y
+                && inner.position.x + inner.size.width as i32
+                    <= outer.position.x + outer.size.width as i32
+                && inner.position.y + inner.size.height as i32
+                    <= outer.position.y + outer.size.height as i32,
+            "{inner:?} is not inside {outer:?}"
+        );
+    }
+
+    #[test]
+    fn test_drag_preview_into_empty_partition() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let (mut wm, left, right) = new_two_partition_test_wm();
+        wm.track_window(Rc::new(Window::new(new_mock_window(14, "Terminal"))))
+            .unwrap();
+        assert!(wm.workspaces[&left].has_window(&14));
+
+        let right_bounds = Bounds::new(960, 0, 960, 1080);
+        let drop = Position { x: 1440, y: 540 };
+        let preview = wm.get_tile_bounds(14, &drop).unwrap();
+        assert_inside(&preview, &right_bounds);
+
+        wm.tile_window(14, &drop).unwrap();
+        assert!(!wm.workspaces[&left].has_window(&14));
+        assert!(wm.workspaces[&right].has_window(&14));
+        assert_inside(&wm.get_window(14).unwrap().bounds(), &right_bounds);
+    }
+
+    #[test]
+    fn test_startup_windows_land_in_the_partition_holding_their_center() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let (mut wm, left, right) = new_two_partition_test_wm();
+        let window = |id, x, width| {
+            let mut platform_window = new_mock_window(id, "Terminal");
+            platform_window.position = Position { x, y: 100 };
+            platform_window.size = Size { width, height: 400 };
+            Rc::new(Window::new(platform_window))
+        };
+        // The last one starts over the left partition but is mostly over the right
+        wm.track_unplaced_windows(vec![
+            window(57, 800, 800),
+            window(55, 100, 600),
+            window(56, 1100, 600),
+        ]);
+
+        assert!(wm.workspaces[&left].has_window(&55));
+        assert!(wm.workspaces[&right].has_window(&56));
+        assert!(wm.workspaces[&right].has_window(&57));
+        assert!(!wm.workspaces[&left].has_window(&57));
+    }
+
+    #[test]
+    fn test_move_window_through_partitions_wraps() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let (mut wm, workspaces) = new_partitioned_test_wm(&[
+            ("Middle", Bounds::new(640, 0, 640, 1080)),
+            ("Left", Bounds::new(0, 0, 640, 1080)),
+            ("Right", Bounds::new(1280, 0, 640, 1080)),
+        ]);
+        let (middle, left, right) = (workspaces[0], workspaces[1], workspaces[2]);
+        let window = new_mock_window(58, "Terminal");
+        wm.track_window(Rc::new(Window::new(window.clone())))
+            .unwrap();
+        let mut other = new_mock_window(59, "Browser");
+        other.position = Position { x: 700, y: 100 };
+        wm.track_window(Rc::new(Window::new(other))).unwrap();
+        assert!(wm.workspaces[&left].has_window(&58));
+        assert!(wm.workspaces[&middle].has_window(&59));
+
+        let focus_calls = window.get_focus_calls();
+        let mut visited = Vec::new();
+        for next in [true, true, true, false] {
+            let from = wm
+                .get_partition_with_window(&wm.get_window(58).unwrap())
+                .unwrap()
+                .id();
+            let to = wm.adjacent_partition(from, next).unwrap();
+            wm.move_window_to_partition(58, to).unwrap();
+
+            let workspace = wm.get_workspace_with_window(&wm.get_window(58).unwrap());
+            visited.push(workspace.unwrap().id());
+        }
+
+        assert_eq!(visited, vec![middle, right, left, right]);
+        assert!(wm.workspaces[&middle].has_window(&59));
+        assert!(!wm.workspaces[&middle].has_window(&58));
+        assert_eq!(wm.window_order.last(), Some(&58));
+        assert_eq!(window.get_focus_calls(), focus_calls + 4);
+    }
+
+    #[test]
+    fn test_place_window_at_bounds() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let (mut wm, left, right) = new_two_partition_test_wm();
+        wm.track_window(Rc::new(Window::new(new_mock_window(63, "Terminal"))))
+            .unwrap();
+        assert!(wm.workspaces[&left].has_window(&63));
+
+        wm.place_window_at(63, Bounds::new(1100, 100, 600, 400), false)
+            .unwrap();
+        let window = wm.get_window(63).unwrap();
+        assert!(window.tiled());
+        assert!(!wm.workspaces[&left].has_window(&63));
+        assert!(wm.workspaces[&right].has_window(&63));
+
+        let floating_bounds = Bounds::new(100, 200, 500, 300);
+        wm.place_window_at(63, floating_bounds.clone(), true)
+            .unwrap();
+        assert!(window.floating());
+        assert!(wm.workspaces[&left].has_window(&63));
+        assert_eq!(window.bounds(), floating_bounds);
+
+        let off_screen = Bounds::new(5000, 5000, 100, 100);
+        assert!(matches!(
+            wm.place_window_at(63, off_screen, false),
+            Err(WMError::NoWorkspaceAtPosition(_))
+        ));
+    }
+
+    #[test]
+    fn test_drag_preview_onto_window_in_other_partition() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let (mut wm, left, right) = new_two_partition_test_wm();
+        for id in [15, 16] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+        wm.tile_window(16, &Position { x: 1440, y: 540 }).unwrap();
+        assert!(wm.workspaces[&right].has_window(&16));
+
+        // Near the bottom edge of the window in the right partition, so it splits
+        let right_bounds = Bounds::new(960, 0, 960, 1080);
+        let target = wm.get_window(16).unwrap().bounds();
+        let drop = Position {
+            x: target.center().x,
+            y: target.position.y + target.size.height as i32 - 10,
+        };
+        let preview = wm.get_tile_bounds(15, &drop).unwrap();
+        assert_inside(&preview, &right_bounds);
+
+        wm.tile_window(15, &drop).unwrap();
+        assert!(!wm.workspaces[&left].has_window(&15));
+        assert!(wm.workspaces[&right].has_window(&15));
+        assert_inside(&wm.get_window(15).unwrap().bounds(), &right_bounds);
+    }
+
+    #[test]
+    fn test_resize_handle_hit_width_wider_than_render_width() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+            c.resize_handle_hit_width = 40;
+            c.resize_handle_render_width = 10;
+        });
+
+        let mut wm = new_test_wm();
+        for id in [17, 18] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+        let handle = wm
+            .workspaces
+            .values()
+            .flat_map(|w| w.resize_handles().iter())
+            .find(|h| h.orientation == crate::resize_handle::HandleOrientation::Vertical)
+            .cloned()
+            .unwrap();
+
+        // Outside the drawn handle but inside the hit area
+        let near = Position {
+            x: handle.center.x + 15,
+            y: handle.center.y,
+        };
+        assert!(!handle.preview_bounds(10).contains(&near));
+        let hit = wm.resize_handle_at_position(&near).unwrap();
+        assert_eq!(hit.center, handle.center);
+
+        let far = Position {
+            x: handle.center.x + 25,
+            y: handle.center.y,
+        };
+        assert!(wm.resize_handle_at_position(&far).is_none());
+    }
+
+    #[test]
+    fn test_layout_preset_round_trip() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        for id in [19, 20, 21] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+        let workspace_id = *wm.workspaces.keys().next().unwrap();
+        let saved_bounds: Vec<Bounds> = [19, 20, 21]
+            .iter()
+            .map(|id| wm.get_window(*id).unwrap().bounds())
+            .collect();
+
+        let dir = std::env::temp_dir().join(format!("ultrawm-presets-{}", std::process::id()));
+        let workspace = &wm.workspaces[&workspace_id];
+        serialization::write_layout_preset(
+            &dir,
+            "coding",
+            &serialization::serialize_workspace(workspace),
+        )
+        .unwrap();
+
+        // Rearrange the workspace after saving
+        wm.float_window(20).unwrap();
+        wm.track_window(Rc::new(Window::new(new_mock_window(22, "Browser"))))
+            .unwrap();
+
+        let preset = serialization::read_layout_preset(&dir, "coding").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        wm.apply_layout_preset(workspace_id, &preset).unwrap();
+
+        for (id, bounds) in [19, 20, 21].iter().zip(&saved_bounds) {
+            let window = wm.get_window(*id).unwrap();
+            assert!(window.tiled(), "window {id} should be tiled");
+            assert_eq!(&window.bounds(), bounds);
+        }
+
+        // Not part of the preset, so it floats instead of being dropped
+        let extra = wm.get_window(22).unwrap();
+        assert!(extra.floating());
+        assert!(wm.workspaces[&workspace_id].has_window(&22));
+    }
+
+    #[test]
+    fn test_paused_tiling_holds_bounds_until_resumed() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        let first = new_mock_window(23, "Terminal");
+        let second = new_mock_window(24, "Browser");
+        wm.track_window(Rc::new(Window::new(first.clone())))
+            .unwrap();
+        first.clear_set_bounds_calls();
+
+        assert!(wm.toggle_paused().unwrap());
+        wm.track_window(Rc::new(Window::new(second.clone())))
+            .unwrap();
+        wm.relayout_all().unwrap();
+        wm.flush().unwrap();
+
+        assert!(first.get_set_bounds_calls().is_empty());
+        assert!(second.get_set_bounds_calls().is_empty());
+        assert!(wm.get_window(23).unwrap().dirty());
+
+        assert!(!wm.toggle_paused().unwrap());
+
+        let first_window = wm.get_window(23).unwrap();
+        let second_window = wm.get_window(24).unwrap();
+        assert_eq!(
+            first.get_set_bounds_calls(),
+            vec![first_window.window_bounds()]
+        );
+        assert_eq!(
+            second.get_set_bounds_calls(),
+            vec![second_window.window_bounds()]
+        );
+        assert!(!first_window.dirty());
+    }
+
+    #[test]
+    fn test_scratchpad_stash_and_summon() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        wm.track_window(Rc::new(Window::new(new_mock_window(25, "Terminal"))))
+            .unwrap();
+        wm.track_window(Rc::new(Window::new(new_mock_window(26, "Browser"))))
+            .unwrap();
+        let window = wm.get_window(25).unwrap();
+        let position = window.bounds().center();
+
+        wm.scratchpad_stash(25).unwrap();
+
+        assert!(wm.is_stashed(25));
+        assert!(wm.workspaces.values().all(|w| !w.has_window(&25)));
+        assert!(wm
+            .snapshot()
+            .workspaces
+            .iter()
+            .flat_map(|w| &w.windows)
+            .all(|w| w.id != 25));
+        assert!(wm
+            .find_window_at_position(&position)
+            .is_none_or(|w| w.id() != 25));
+
+        // Stashed windows survive the WM reacting to them being hidden and config reloads
+        wm.remove_window(25).unwrap();
+        wm.config_changed().unwrap();
+        assert_eq!(wm.last_stashed_window(), Some(25));
+
+        assert!(wm.scratchpad_toggle(25).unwrap());
+
+        assert!(!wm.is_stashed(25));
+        assert!(window.floating());
+        assert!(wm.workspaces.values().any(|w| w.has_window(&25)));
+        assert_eq!(
+            window.bounds().center(),
+            Bounds::new(0, 0, 1920, 1080).center()
+        );
+
+        assert!(!wm.scratchpad_toggle(25).unwrap());
+        assert!(wm.is_stashed(25));
+    }
+
+    #[test]
+    fn test_mouse_follows_keyboard_focus() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = true;
+            c.window_tile_animate = false;
+            c.mouse_follows_focus = true;
+        });
+
+        let mut wm = new_test_wm();
+        wm.track_window(Rc::new(Window::new(new_mock_window(27, "Terminal"))))
+            .unwrap();
+        MockPlatform::take_mouse_warps();
+
+        wm.focus_window(27).unwrap();
+        let center = wm.get_window(27).unwrap().bounds().center();
+        assert_eq!(MockPlatform::take_mouse_warps(), vec![center]);
+
+        // Focus that came from the mouse leaves the cursor where it is
+        wm.focus_window_from_mouse(27).unwrap();
+        assert!(MockPlatform::take_mouse_warps().is_empty());
+
+        Config::update(|c| c.mouse_follows_focus = false);
+        wm.focus_window(27).unwrap();
+        assert!(MockPlatform::take_mouse_warps().is_empty());
+    }
+
+    #[test]
+    fn test_animation_duration_per_kind() {
+        let mut config = Config {
+            window_tile_animate: true,
+            window_tile_animation_ms: 150,
+            ..Default::default()
+        };
+        for kind in [
+            AnimationKind::Tile,
+            AnimationKind::WorkspaceSwitch,
+            AnimationKind::FloatSnap,
+        ] {
+            assert_eq!(kind.duration_ms(&config), Some(150));
+        }
+
+        config.tile_animation_ms = Some(100);
+        config.workspace_switch_animation_ms = Some(250);
+        config.float_snap_animation_ms = Some(0);
+        assert_eq!(AnimationKind::Tile.duration_ms(&config), Some(100));
+        assert_eq!(
+            AnimationKind::WorkspaceSwitch.duration_ms(&config),
+            Some(250)
+        );
+        assert_eq!(AnimationKind::FloatSnap.duration_ms(&config), None);
+
+        config.window_tile_animate = false;
+        assert_eq!(AnimationKind::WorkspaceSwitch.duration_ms(&config), None);
+    }
+
+    #[test]
+    fn test_flush_sends_duration_to_animation_thread() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        let platform_window = new_mock_window(28, "Terminal");
+        wm.track_window(Rc::new(Window::new(platform_window.clone())))
+            .unwrap();
+        platform_window.clear_set_bounds_calls();
+        let window = wm.get_window(28).unwrap();
+
+        window.mark_dirty();
+        wm.flush_with_duration(AnimationKind::Tile, Some(250))
+            .unwrap();
+        assert_eq!(
+            wm.animation_thread.sent_animations,
+            vec![(28, window.window_bounds(), 250)]
+        );
+        assert!(platform_window.get_set_bounds_calls().is_empty());
+
+        // Without a duration the window is moved straight away
+        window.mark_dirty();
+        wm.flush_with_duration(AnimationKind::Tile, None).unwrap();
+        assert_eq!(wm.animation_thread.sent_animations.len(), 1);
+        assert_eq!(
+            platform_window.get_set_bounds_calls(),
+            vec![window.window_bounds()]
+        );
+    }
+
+    #[test]
+    fn test_workspace_switch_slides_outgoing_windows_off_screen() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.hidden_window_parking = HiddenWindowParking::OffscreenRight;
+        });
+
+        let (mut wm, left, right) = new_two_partition_test_wm();
+        let second = Workspace::new::<ContainerTree>(
+            Bounds::new(0, 0, 960, 1080),
+            "Second".to_string(),
+            None,
+            None,
+        );
+        let second_id = second.id();
+        wm.workspaces.insert(second_id, second);
+        wm.partitions
+            .values_mut()
+            .find(|p| p.current_workspace() == Some(left))
+            .unwrap()
+            .assign_workspace(second_id);
+
+        wm.track_window(Rc::new(Window::new(new_mock_window(40, "Terminal"))))
+            .unwrap();
+        let mut platform_window = new_mock_window(41, "Browser");
+        platform_window.position = Position::new(1200, 100);
+        wm.track_window(Rc::new(Window::new(platform_window)))
+            .unwrap();
+        assert!(wm.workspaces[&left].has_window(&40));
+        assert!(wm.workspaces[&right].has_window(&41));
+        wm.animation_thread.sent_animations.clear();
+
+        wm.switch_workspace(second_id).unwrap();
+
+        // Past the right display rather than onto it, and left there
+        let (_, target, _) = wm
+            .animation_thread
+            .sent_animations
+            .iter()
+            .find(|(id, _, _)| *id == 40)
+            .cloned()
+            .unwrap();
+        assert!(target.position.x >= 1920);
+        for partition in wm.partitions.values() {
+            assert!(!target.intersects(partition.bounds()));
+        }
+        assert!(!wm.get_window(40).unwrap().dirty());
+        assert!(!wm
+            .animation_thread
+            .sent_animations
+            .iter()
+            .any(|(id, _, _)| *id == 41));
+    }
+
+    #[test]
+    fn test_park_window_per_parking_mode() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+        });
+
+        let mut wm = new_test_wm();
+        let platform_window = new_mock_window(42, "Terminal");
+        wm.track_window(Rc::new(Window::new(platform_window.clone())))
+            .unwrap();
+        let window = wm.get_window(42).unwrap();
+        let bounds = window.window_bounds();
+
+        Config::update(|c| c.hidden_window_parking = HiddenWindowParking::OffscreenRight);
+        platform_window.clear_set_bounds_calls();
+        wm.park_window(&window).unwrap();
+        assert_eq!(
+            platform_window.get_set_bounds_calls(),
+            vec![Bounds::from_position(
+                Position::new(1920, bounds.position.y),
+                bounds.size.clone()
+            )]
+        );
+        assert!(!window.dirty());
+
+        Config::update(|c| c.hidden_window_parking = HiddenWindowParking::OffscreenBottom);
+        platform_window.clear_set_bounds_calls();
+        wm.park_window(&window).unwrap();
+        assert_eq!(
+            platform_window.get_set_bounds_calls(),
+            vec![Bounds::from_position(
+                Position::new(bounds.position.x, 1080),
+                bounds.size.clone()
+            )]
+        );
+
+        Config::update(|c| c.hidden_window_parking = HiddenWindowParking::Minimize);
+        platform_window.clear_set_bounds_calls();
+        wm.park_window(&window).unwrap();
+        assert!(platform_window.get_set_bounds_calls().is_empty());
+        assert_eq!(platform_window.get_minimize_calls(), 1);
+    }
+
+    #[test]
+    fn test_hidden_windows_come_back_per_parking_mode() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+        let screen = Bounds::new(0, 0, 1920, 1080);
+
+        for parking in [
+            HiddenWindowParking::OffscreenRight,
+            HiddenWindowParking::OffscreenBottom,
+            HiddenWindowParking::Minimize,
+        ] {
+            Config::update(|c| c.hidden_window_parking = parking);
+            let minimized = (parking == HiddenWindowParking::Minimize) as usize;
+
+            let mut wm = new_test_wm();
+            let first = *wm.workspaces.keys().next().unwrap();
+            let second =
+                Workspace::new::<ContainerTree>(screen.clone(), "Second".to_string(), None, None);
+            let second_id = second.id();
+            wm.workspaces.insert(second_id, second);
+            wm.partitions
+                .values_mut()
+                .next()
+                .unwrap()
+                .assign_workspace(second_id);
+
+            let platform_window = new_mock_window(74, "Terminal");
+            wm.track_window(Rc::new(Window::new(platform_window.clone())))
+                .unwrap();
+            let shown = wm.get_window(74).unwrap().window_bounds();
+            let platform_bounds =
+                || Bounds::from_position(platform_window.position(), platform_window.size());
+
+            wm.switch_workspace(second_id).unwrap();
+            assert_eq!(platform_window.get_minimize_calls(), minimized);
+            if minimized == 0 {
+                assert!(!platform_bounds().intersects(&screen), "{parking:?}");
+            }
+
+            wm.switch_workspace(first).unwrap();
+            assert_eq!(platform_window.get_unminimize_calls(), minimized);
+            assert_eq!(platform_bounds(), shown, "{parking:?}");
+
+            // The scratchpad parks the same way
+            wm.scratchpad_stash(74).unwrap();
+            assert_eq!(platform_window.get_minimize_calls(), minimized * 2);
+            assert!(wm.scratchpad_toggle(74).unwrap());
+            assert_eq!(platform_window.get_unminimize_calls(), minimized * 2);
+        }
+    }
+
+    #[test]
+    fn test_flush_batches_dirty_window_bounds() {
+        use crate::platform::mock::MockBoundsCall;
+
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        for (id, app) in [(29, "Terminal"), (30, "Browser")] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, app))))
+                .unwrap();
+        }
+        MockPlatform::take_bounds_calls();
+
+        wm.get_window(29).unwrap().mark_dirty();
+        wm.get_window(30).unwrap().mark_dirty();
+        wm.flush_with_duration(AnimationKind::Tile, None).unwrap();
+
+        let calls = MockPlatform::take_bounds_calls();
+        assert_eq!(calls.len(), 4);
+        assert_eq!(calls[0], MockBoundsCall::StartBatch(2));
+        assert!(calls[1..3]
+            .iter()
+            .all(|c| matches!(c, MockBoundsCall::SetBounds(29 | 30))));
+        assert_eq!(calls[3], MockBoundsCall::EndBatch);
+
+        // Nothing moved, so there is nothing to batch
+        wm.flush_with_duration(AnimationKind::Tile, None).unwrap();
+        assert!(MockPlatform::take_bounds_calls().is_empty());
+    }
+
+    #[test]
+    fn test_undo_and_redo_tile() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        for id in [31, 32, 33] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+        let workspace_id = *wm.workspaces.keys().next().unwrap();
+        let serialized = |wm: &WindowManager| {
+            serialization::serialize_workspace(&wm.workspaces[&workspace_id]).layout
+        };
+        let before = serialized(&wm);
+
+        // Drop the last window on the left edge of the first
+        let first = wm.get_window(31).unwrap().bounds();
+        let position = Position {
+            x: first.position.x + 5,
+            y: first.center().y,
+        };
+        wm.tile_window(33, &position).unwrap();
+        let after = serialized(&wm);
+        assert_ne!(after, before);
+
+        assert!(wm.undo().unwrap());
+        assert_eq!(serialized(&wm), before);
+        assert!(!wm.undo().unwrap());
+
+        assert!(wm.redo().unwrap());
+        assert_eq!(serialized(&wm), after);
+        assert!(!wm.redo().unwrap());
+    }
+
+    #[test]
+    fn test_resize_handle_drag_is_one_undo_step() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        for id in [57, 58] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+        let workspace_id = *wm.workspaces.keys().next().unwrap();
+        let serialized = |wm: &WindowManager| {
+            serialization::serialize_workspace(&wm.workspaces[&workspace_id]).layout
+        };
+        let before = serialized(&wm);
+        let handle = wm.workspaces[&workspace_id].resize_handles()[0].clone();
+
+        for dx in [40, 80, 120] {
+            let position = Position {
+                x: handle.center.x + dx,
+                y: handle.center.y,
+            };
+            wm.resize_handle_moved(&handle, &position, &ResizeMode::Evenly)
+                .unwrap();
+        }
+        wm.resize_handle_released();
+        let after = serialized(&wm);
+        assert_ne!(after, before);
+
+        assert!(wm.undo().unwrap());
+        assert_eq!(serialized(&wm), before);
+        assert!(!wm.undo().unwrap());
+        assert!(wm.redo().unwrap());
+        assert_eq!(serialized(&wm), after);
+    }
+
+    #[test]
+    fn test_window_displaced_by_a_floating_drop_floats_centered() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.float_window_centered = false;
+            c.float_window_size_ratio = 0.5;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        for id in [79, 80] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+        wm.float_window(80).unwrap();
+        let tiled = wm.get_window(79).unwrap();
+        assert!(tiled.tiled());
+
+        // Dropping the floating window on the middle of the tiled one swaps them
+        wm.tile_window(80, &tiled.bounds().center()).unwrap();
+
+        assert!(wm.get_window(80).unwrap().tiled());
+        assert!(tiled.floating());
+        assert_eq!(tiled.bounds(), Bounds::new(480, 270, 960, 540));
+    }
+
+    #[test]
+    fn test_set_layout_rejects_unknown_layouts() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        wm.track_window(Rc::new(Window::new(new_mock_window(81, "Terminal"))))
+            .unwrap();
+        let workspace_id = *wm.workspaces.keys().next().unwrap();
+        let set_layout = |wm: &mut WindowManager, name: &str| {
+            (SET_LAYOUT.handler)(wm, Some(&CommandContext::with_argument(name)))
+        };
+
+        let error = set_layout(&mut wm, "grid").unwrap_err();
+        assert!(matches!(
+            &error,
+            WMOperationError::Error(WMError::UnknownLayout(name)) if name == "grid"
+        ));
+        let message = error.to_string();
+        assert!(message.contains("container-tree") && message.contains("master-stack"));
+        assert_eq!(
+            wm.workspaces[&workspace_id].layout_type(),
+            LayoutType::ContainerTree
+        );
+
+        set_layout(&mut wm, "master-stack").unwrap();
+        assert_eq!(
+            wm.workspaces[&workspace_id].layout_type(),
+            LayoutType::MasterStack
+        );
+    }
+
+    #[test]
+    fn test_golden_ratio_can_be_undone() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        for id in [76, 77] {
+            wm.track_window(Rc::new(Window::new(new_mock_window(id, "Terminal"))))
+                .unwrap();
+        }
+        let workspace_id = *wm.workspaces.keys().next().unwrap();
+        let serialized = |wm: &WindowManager| {
+            serialization::serialize_workspace(&wm.workspaces[&workspace_id]).layout
+        };
+        let before = serialized(&wm);
+
+        wm.apply_golden_ratio(workspace_id).unwrap();
+        assert_ne!(serialized(&wm), before);
+        assert!(wm.undo().unwrap());
+        assert_eq!(serialized(&wm), before);
+
+        assert!(matches!(
+            wm.apply_golden_ratio(workspace_id + 1000),
+            Err(WMError::UnknownWorkspace(id)) if id == workspace_id + 1000
+        ));
+    }
+
+    #[test]
+    fn test_float_window_returns_to_bounds_from_before_tiling() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        let mut platform_window = new_mock_window(34, "Terminal");
+        platform_window.position = Position { x: 300, y: 200 };
+        let natural = Bounds::new(300, 200, 800, 600);
+        wm.track_window(Rc::new(Window::new(platform_window)))
+            .unwrap();
+        wm.track_window(Rc::new(Window::new(new_mock_window(35, "Browser"))))
+            .unwrap();
+
+        let window = wm.get_window(34).unwrap();
+        assert!(window.tiled());
+        assert_ne!(window.platform_bounds(), natural);
+
+        wm.float_window(34).unwrap();
+        assert!(window.floating());
+        assert_eq!(window.bounds(), natural);
+    }
+
+    #[test]
+    fn test_organize_plan_waits_for_confirmation() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+        });
+
+        let mut wm = new_test_wm();
+        wm.track_window(Rc::new(Window::new(new_mock_window(36, "Terminal"))))
+            .unwrap();
+        wm.track_window(Rc::new(Window::new(new_mock_window(37, "Browser"))))
+            .unwrap();
+        let workspace_id = *wm.workspaces.keys().next().unwrap();
+        let left = wm.get_window(36).unwrap().bounds();
+        let right = wm.get_window(37).unwrap().bounds();
+        assert!(left.position.x < right.position.x);
+
+        let layout = serde_yaml::from_str(
+            "root: {direction: horizontal, ratios: [0.5, 0.5], children: [{type: window, id: 37}, {type: window, id: 36}]}",
+        )
+        .unwrap();
+        let plan = OrganizePlan::new(vec![(workspace_id, layout)]);
+        let before = wm.undo_snapshot();
+
+        let slots = wm.organize_preview_slots(&plan);
+        wm.set_organize_plan(plan, None);
+
+        assert_eq!(slots, vec![left.clone(), right.clone()]);
+        assert_eq!(wm.undo_snapshot(), before);
+        assert_eq!(wm.get_window(36).unwrap().bounds(), left);
+        assert_eq!(wm.get_window(37).unwrap().bounds(), right);
+
+        assert!(wm.confirm_organize_plan().unwrap());
+        assert_ne!(wm.undo_snapshot(), before);
+        assert_eq!(wm.get_window(37).unwrap().bounds(), left);
+        assert_eq!(wm.get_window(36).unwrap().bounds(), right);
+        assert!(!wm.confirm_organize_plan().unwrap());
+    }
+
+    #[test]
+    fn test_displays_changed_rehomes_windows_from_removed_display() {
+        let _config = Config::scoped(|c| {
+            c.persistence = false;
+            c.float_new_windows = false;
+            c.window_tile_animate = false;
+            c.partitions_per_display = 1;
+            c.partition_ratios = Vec::new();
+        });
+
+        let (mut wm, left, right) = new_two_partition_test_wm();
+        let mut platform_window = new_mock_window(38, "Terminal");
+        platform_window.position = Position { x: 1200, y: 100 };
+        wm.track_window(Rc::new(Window::new(platform_window)))
+            .unwrap();
+        wm.track_window(Rc::new(Window::new(new_mock_window(39, "Browser"))))
+            .unwrap();
+        assert!(wm.workspaces[&right].has_window(&38));
+        assert!(wm.workspaces[&left].has_window(&39));
+
+        // The right display is unplugged and the left one takes up the whole screen
+        MockPlatform::set_displays(vec![Display {
+            id: 1,
+            name: "Left".to_string(),
+            bounds: Bounds::new(0, 0, 1920, 1080),
+            work_area: Bounds::new(0, 0, 1920, 1080),
+            scale_factor: 1.0,
+        }]);
+        wm.displays_changed().unwrap();
+
+        assert_eq!(wm.partitions().len(), 1);
+        let partition = wm.partitions().values().next().unwrap();
+        assert_eq!(partition.name(), "Left");
+        assert_eq!(partition.bounds(), &Bounds::new(0, 0, 1920, 1080));
+        assert_eq!(partition.current_workspace(), Some(left));
+        assert!(!wm.workspaces.contains_key(&right));
+        assert!(wm.workspaces[&left].has_window(&38));
+        assert!(wm.workspaces[&left].has_window(&39));
+    }
 }
@@ -1,10 +1,11 @@
-use crate::layouts::{LayoutError, LayoutResult, WindowLayout};
-use crate::platform::traits::PlatformImpl;
-use crate::platform::{Bounds, Platform, PlatformResult, Position, WindowId};
+use crate::config::{Config, WorkspaceConfig};
+use crate::layouts::{self, Gaps, LayoutError, LayoutResult, LayoutType, Side, WindowLayout};
+use crate::platform::{Bounds, PlatformResult, Position, WindowId};
 use crate::resize_handle::{ResizeHandle, ResizeMode};
 use crate::tile_result::InsertResult;
-use crate::window::WindowRef;
+use crate::window::{flush_windows_batched, WindowRef};
 use log::warn;
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -17,6 +18,10 @@ pub struct Workspace {
     layout: Box<dyn WindowLayout>,
     windows: HashMap<WindowId, WindowRef>,
     cached_handles: Vec<ResizeHandle>,
+    /// Settings from `Config::workspaces` for this workspace's name
+    config: WorkspaceConfig,
+    /// The gaps last handed to the layout
+    layout_gaps: Option<Gaps>,
 }
 
 static ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
@@ -32,6 +37,12 @@ impl Workspace {
         Self::new_with_id::<TLayout>(id, bounds, name, layout, floating)
     }
 
+    /// Creates an empty workspace using a layout of the given type
+    pub fn with_layout_type(bounds: Bounds, name: String, layout_type: LayoutType) -> Self {
+        let id = ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+        Self::new_with_layout(id, name, layouts::new_layout(layout_type, bounds), None)
+    }
+
     pub fn new_with_id<TLayout: WindowLayout + 'static>(
         id: WorkspaceId,
         bounds: Bounds,
@@ -40,6 +51,18 @@ impl Workspace {
         floating: Option<HashMap<WindowId, WindowRef>>,
     ) -> Self {
         let layout = layout.unwrap_or_else(|| Box::new(TLayout::new(bounds)));
+        Self::new_with_layout(id, name, layout, floating)
+    }
+
+    pub fn new_with_layout(
+        id: WorkspaceId,
+        name: String,
+        mut layout: Box<dyn WindowLayout>,
+        floating: Option<HashMap<WindowId, WindowRef>>,
+    ) -> Self {
+        let config = Config::workspace_config(&name);
+        let layout_gaps = gaps(&config);
+        layout.set_gaps(layout_gaps);
 
         let windows = layout
             .windows()
@@ -53,15 +76,18 @@ impl Workspace {
             }
         }
 
-        let cached_handles = layout.resize_handles();
-
-        Self {
+        let mut workspace = Self {
             id,
             name,
             layout,
             windows,
-            cached_handles,
-        }
+            cached_handles: Vec::new(),
+            config,
+            layout_gaps,
+        };
+        workspace.update_gaps();
+        workspace.refresh_resize_handles();
+        workspace
     }
 
     pub fn id(&self) -> WorkspaceId {
@@ -72,10 +98,98 @@ impl Workspace {
         &self.name
     }
 
+    /// Renames the workspace, picking up the settings configured for the new name
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+        self.relayout();
+    }
+
+    /// The window gap configured for this workspace, if it doesn't use the global one
+    pub fn window_gap(&self) -> Option<u32> {
+        if self.solo() {
+            return Some(0);
+        }
+        self.config.window_gap
+    }
+
+    /// Whether smart gaps are dropping the gaps because only one window is tiled
+    fn solo(&self) -> bool {
+        self.config.smart_gaps.unwrap_or_else(Config::smart_gaps)
+            && self.layout.windows().len() == 1
+    }
+
+    /// The gaps the layout should tile with right now, `None` for the global ones
+    fn current_gaps(&self) -> Option<Gaps> {
+        if self.solo() {
+            return Some(Gaps {
+                window: 0,
+                partition: 0,
+                outer: None,
+            });
+        }
+        gaps(&self.config)
+    }
+
+    /// Whether new windows on this workspace start as floating
+    pub fn float_new_windows(&self) -> bool {
+        self.config
+            .float_new_windows
+            .unwrap_or_else(Config::float_new_windows)
+    }
+
     pub fn layout(&self) -> &Box<dyn WindowLayout> {
         &self.layout
     }
 
+    pub fn layout_type(&self) -> LayoutType {
+        self.layout.layout_type()
+    }
+
+    /// Moves the tiled windows into a new layout, inserting each at the center of where it
+    /// is now. Floating windows are left as they are.
+    pub fn set_layout(&mut self, mut layout: Box<dyn WindowLayout>) {
+        self.layout_gaps = gaps(&self.config);
+        layout.set_gaps(self.layout_gaps);
+        let mut tiled: Vec<(WindowRef, Position)> = self
+            .layout
+            .windows()
+            .into_iter()
+            .map(|w| {
+                let center = w.bounds().center();
+                (w, center)
+            })
+            .collect();
+        tiled.sort_by_key(|(_, p)| (p.x, p.y));
+
+        let mut unplaced = Vec::new();
+        for (window, center) in &tiled {
+            match layout.insert_window(window, center) {
+                Ok(InsertResult::None) => {}
+                Ok(InsertResult::Swap(displaced)) => unplaced.push(displaced),
+                Err(_) => unplaced.push(window.clone()),
+            }
+        }
+
+        // Windows whose old spot doesn't map onto the new layout go on the right edge of the
+        // rightmost window
+        for window in unplaced {
+            let position =
+                right_edge_position(layout.as_ref()).unwrap_or_else(|| window.bounds().center());
+
+            if let Err(e) = layout.insert_window(&window, &position) {
+                warn!(
+                    "Could not move window {} to the new layout: {e}",
+                    window.id()
+                );
+                self.windows.remove(&window.id());
+            }
+        }
+
+        self.layout = layout;
+        self.update_gaps();
+        self.refresh_resize_handles();
+    }
+
     pub fn set_bounds(&mut self, bounds: Bounds) {
         self.layout.set_bounds(bounds);
         self.refresh_resize_handles();
@@ -102,6 +216,7 @@ impl Workspace {
         if old.is_some() {
             if self.layout.windows().iter().any(|w| w.id() == window.id()) {
                 self.layout.remove_window(window)?;
+                self.update_gaps();
                 self.refresh_resize_handles();
             }
         }
@@ -120,6 +235,51 @@ impl Workspace {
         Ok(())
     }
 
+    pub fn swap_windows(&mut self, a: &WindowRef, b: &WindowRef) -> LayoutResult<()> {
+        self.layout.swap_windows(a, b)?;
+        self.refresh_resize_handles();
+        Ok(())
+    }
+
+    /// Swaps a tiled window with the tiled window taking up the most area. Returns the window
+    /// it was swapped with, or `None` if it already was the largest.
+    pub fn swap_with_largest(&mut self, window: &WindowRef) -> LayoutResult<Option<WindowRef>> {
+        let target = self
+            .layout
+            .windows()
+            .into_iter()
+            .max_by_key(|w| (window_area(w), w.id() == window.id(), Reverse(w.id())));
+        self.swap_with(window, target)
+    }
+
+    /// Swaps a tiled window with the tiled window taking up the least area. Returns the window
+    /// it was swapped with, or `None` if it already was the smallest.
+    pub fn swap_with_smallest(&mut self, window: &WindowRef) -> LayoutResult<Option<WindowRef>> {
+        let target = self
+            .layout
+            .windows()
+            .into_iter()
+            .min_by_key(|w| (window_area(w), w.id() != window.id(), w.id()));
+        self.swap_with(window, target)
+    }
+
+    fn swap_with(
+        &mut self,
+        window: &WindowRef,
+        target: Option<WindowRef>,
+    ) -> LayoutResult<Option<WindowRef>> {
+        if !self.layout.windows().iter().any(|w| w.id() == window.id()) {
+            return Err(LayoutError::WindowNotFound(window.id()));
+        }
+        match target.filter(|target| target.id() != window.id()) {
+            Some(target) => {
+                self.swap_windows(window, &target)?;
+                Ok(Some(target))
+            }
+            None => Ok(None),
+        }
+    }
+
     pub fn tile_window(
         &mut self,
         window: &WindowRef,
@@ -128,6 +288,7 @@ impl Workspace {
         let action = self.layout.insert_window(window, position)?;
         window.set_floating(false);
         self.windows.insert(window.id(), window.clone());
+        self.update_gaps();
         self.refresh_resize_handles();
         Ok(action)
     }
@@ -140,6 +301,7 @@ impl Workspace {
         let action = self.layout.insert_relative(window, target)?;
         window.set_floating(false);
         self.windows.insert(window.id(), window.clone());
+        self.update_gaps();
         self.refresh_resize_handles();
         Ok(action)
     }
@@ -151,6 +313,70 @@ impl Workspace {
 
         window.set_floating(true);
         self.windows.insert(window.id(), window.clone());
+        self.update_gaps();
+        self.refresh_resize_handles();
+        Ok(())
+    }
+
+    /// Floats every tiled window, leaving each where it is now. Returns how many were floated.
+    pub fn float_all(&mut self) -> LayoutResult<usize> {
+        let tiled = self.layout.windows();
+        for window in &tiled {
+            let bounds = window.window_bounds();
+            self.float_window(window)?;
+            window.set_bounds(bounds);
+        }
+        Ok(tiled.len())
+    }
+
+    /// Tiles every floating window at the center of where it is now, left to right. Returns how
+    /// many were tiled.
+    pub fn tile_all(&mut self) -> LayoutResult<usize> {
+        let mut floating: Vec<(WindowRef, Position)> = self
+            .windows
+            .values()
+            .filter(|w| w.floating())
+            .map(|w| (w.clone(), w.bounds().center()))
+            .collect();
+        floating.sort_by_key(|(_, p)| (p.x, p.y));
+
+        for (window, center) in &floating {
+            // Landing on the middle of a window swaps it out, it goes on the right edge instead
+            let mut result = self.tile_window(window, center)?;
+            while let InsertResult::Swap(displaced) = result {
+                let position = right_edge_position(self.layout.as_ref())
+                    .unwrap_or_else(|| displaced.bounds().center());
+                result = self.tile_window(&displaced, &position)?;
+            }
+        }
+        Ok(floating.len())
+    }
+
+    /// Floats a window and sizes it to `size_ratio` of `area`, centered in it
+    pub fn float_window_centered(
+        &mut self,
+        window: &WindowRef,
+        area: &Bounds,
+        size_ratio: f32,
+    ) -> LayoutResult<()> {
+        self.float_window(window)?;
+        window.set_bounds(centered_bounds(area, size_ratio));
+        Ok(())
+    }
+
+    pub fn promote_window(&mut self, window: &WindowRef) -> LayoutResult<()> {
+        self.layout.promote_window(window)?;
+        self.refresh_resize_handles();
+        Ok(())
+    }
+
+    pub fn resize_window_step(
+        &mut self,
+        window: &WindowRef,
+        side: Side,
+        delta_px: i32,
+    ) -> LayoutResult<()> {
+        self.layout.resize_window_step(window, side, delta_px)?;
         self.refresh_resize_handles();
         Ok(())
     }
@@ -171,13 +397,7 @@ impl Workspace {
     }
 
     pub fn flush_windows(&mut self) -> PlatformResult<()> {
-        let window_count = self.windows.len() as u32;
-        Platform::start_window_bounds_batch(window_count).unwrap();
-        for window in self.windows.values_mut() {
-            window.flush()?;
-        }
-        Platform::end_window_bounds_batch().unwrap();
-        Ok(())
+        flush_windows_batched(self.windows.values())
     }
 
     pub fn serialize(&self) -> serde_yaml::Value {
@@ -188,10 +408,21 @@ impl Workspace {
         &self.cached_handles
     }
 
+    /// Called after every layout change.
     pub fn refresh_resize_handles(&mut self) {
         self.cached_handles = self.layout.resize_handles();
     }
 
+    /// Moves the layout to other gaps when smart gaps turn on or off because the number of
+    /// tiled windows crossed one. Called whenever tiled windows are added or removed.
+    fn update_gaps(&mut self) {
+        let gaps = self.current_gaps();
+        if gaps != self.layout_gaps {
+            self.layout_gaps = gaps;
+            self.layout.set_gaps(gaps);
+        }
+    }
+
     pub fn resize_handle_moved(
         &mut self,
         handle: &ResizeHandle,
@@ -203,9 +434,33 @@ impl Workspace {
         result
     }
 
-    pub fn config_changed(&mut self) -> PlatformResult<()> {
+    pub fn reset_resize_handle(&mut self, handle: &ResizeHandle) -> bool {
+        let result = self.layout.reset_resize_handle(handle);
+        self.refresh_resize_handles();
+        result
+    }
+
+    pub fn balance_weighted(&mut self, weight_of: &dyn Fn(&WindowRef) -> f32) {
+        self.layout.balance_weighted(weight_of);
+        self.refresh_resize_handles();
+    }
+
+    pub fn apply_golden_ratio(&mut self) {
+        self.layout.apply_golden_ratio();
+        self.refresh_resize_handles();
+    }
+
+    /// Recalculates the layout from the current config without flushing the windows.
+    pub fn relayout(&mut self) {
+        self.config = Config::workspace_config(&self.name);
+        self.layout_gaps = self.current_gaps();
+        self.layout.set_gaps(self.layout_gaps);
         self.layout.config_changed();
         self.refresh_resize_handles();
+    }
+
+    pub fn config_changed(&mut self) -> PlatformResult<()> {
+        self.relayout();
         self.flush_windows()
     }
 
@@ -220,3 +475,300 @@ impl Workspace {
         }
     }
 }
+
+/// The gaps a workspace tiles with, or `None` when it doesn't override the global ones
+fn gaps(config: &WorkspaceConfig) -> Option<Gaps> {
+    if config.window_gap.is_none() && config.partition_gap.is_none() {
+        return None;
+    }
+
+    let global = Gaps::from_config();
+    Some(Gaps {
+        window: config.window_gap.unwrap_or(global.window),
+        partition: config.partition_gap.unwrap_or(global.partition),
+        // A workspace's own partition gap wins over the global outer gap
+        outer: global.outer.filter(|_| config.partition_gap.is_none()),
+    })
+}
+
+/// The area a tiled window takes up on screen
+fn window_area(window: &WindowRef) -> u64 {
+    let size = window.window_bounds().size;
+    size.width as u64 * size.height as u64
+}
+
+/// Just inside the right edge of the rightmost tiled window, where a window is added as a new
+/// column
+fn right_edge_position(layout: &dyn WindowLayout) -> Option<Position> {
+    layout
+        .windows()
+        .iter()
+        .map(|w| w.bounds())
+        .max_by_key(|b| b.position.x + b.size.width as i32)
+        .map(|b| Position {
+            x: b.position.x + b.size.width as i32 - 2,
+            y: b.center().y,
+        })
+}
+
+fn centered_bounds(area: &Bounds, size_ratio: f32) -> Bounds {
+    let ratio = size_ratio.clamp(0.1, 1.0);
+    let width = (area.size.width as f32 * ratio).round() as u32;
+    let height = (area.size.height as f32 * ratio).round() as u32;
+    Bounds::new(
+        area.position.x + (area.size.width - width) as i32 / 2,
+        area.position.y + (area.size.height - height) as i32 / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layouts::{tiling_area, tiling_area_with_gaps, ContainerTree, MasterStack};
+    use crate::platform::mock::MockPlatformWindow;
+    use crate::platform::Size;
+    use crate::window::Window;
+    use std::rc::Rc;
+
+    fn create_mock_window(id: WindowId) -> WindowRef {
+        let mut platform_window = MockPlatformWindow::new(
+            Position { x: 0, y: 0 },
+            Size {
+                width: 800,
+                height: 600,
+            },
+            format!("Test Window {}", id),
+        );
+        platform_window.id = id;
+        Rc::new(Window::new(platform_window))
+    }
+
+    #[test]
+    fn test_set_layout_migrates_windows() {
+        let _config = Config::scoped(|_| {});
+
+        let bounds = Bounds::new(0, 0, 1920, 1080);
+        let windows: Vec<_> = (1..=3).map(create_mock_window).collect();
+
+        let mut tree = ContainerTree::new(bounds.clone());
+        for window in &windows {
+            // Each window goes on the right edge of the layout
+            let area = tiling_area(&bounds);
+            let position = Position {
+                x: area.position.x + area.size.width as i32 - 2,
+                y: area.center().y,
+            };
+            tree.insert_window(window, &position).unwrap();
+        }
+        assert_eq!(tree.windows().len(), 3);
+
+        let mut workspace = Workspace::new_with_layout(0, "Test".to_string(), Box::new(tree), None);
+        let floating = create_mock_window(4);
+        workspace.float_window(&floating).unwrap();
+
+        workspace.set_layout(Box::new(MasterStack::new(bounds)));
+
+        assert_eq!(workspace.layout_type(), LayoutType::MasterStack);
+        let mut ids: Vec<WindowId> = workspace
+            .layout()
+            .windows()
+            .iter()
+            .map(|w| w.id())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        assert!(workspace.has_window(&4));
+        assert!(floating.floating());
+    }
+
+    #[test]
+    fn test_smart_gaps_with_one_window() {
+        let _config = Config::scoped(|c| {
+            c.workspaces.insert(
+                "Smart Gaps".to_string(),
+                WorkspaceConfig {
+                    window_gap: Some(10),
+                    partition_gap: Some(20),
+                    smart_gaps: Some(true),
+                    ..Default::default()
+                },
+            );
+        });
+        let bounds = Bounds::new(0, 0, 1920, 1080);
+        let mut workspace =
+            Workspace::new::<ContainerTree>(bounds.clone(), "Smart Gaps".to_string(), None, None);
+
+        let first = create_mock_window(1);
+        workspace.tile_window(&first, &bounds.center()).unwrap();
+        first.set_window_gap(workspace.window_gap());
+        assert_eq!(first.window_bounds(), bounds);
+
+        let second = create_mock_window(2);
+        let right_edge = Position {
+            x: bounds.size.width as i32 - 30,
+            y: bounds.center().y,
+        };
+        workspace.tile_window(&second, &right_edge).unwrap();
+        first.set_window_gap(workspace.window_gap());
+        assert_eq!(workspace.window_gap(), Some(10));
+        assert_eq!(first.window_bounds().position, Position { x: 20, y: 20 });
+
+        // Back to one window, the gaps go away again
+        workspace.remove_window(&second).unwrap();
+        first.set_window_gap(workspace.window_gap());
+        assert_eq!(first.window_bounds(), bounds);
+    }
+
+    #[test]
+    fn test_swap_with_largest_and_smallest() {
+        let _config = Config::scoped(|_| {});
+
+        let bounds = Bounds::new(0, 0, 1920, 1080);
+        let windows: Vec<_> = (1..=3).map(create_mock_window).collect();
+
+        // Three columns, with the first widened at the cost of the second
+        let mut tree = ContainerTree::new(bounds.clone());
+        for window in &windows {
+            let area = tiling_area(&bounds);
+            let position = Position {
+                x: area.position.x + area.size.width as i32 - 2,
+                y: area.center().y,
+            };
+            tree.insert_window(window, &position).unwrap();
+        }
+        tree.resize_window_step(&windows[0], Side::Right, 200)
+            .unwrap();
+        let largest_bounds = windows[0].window_bounds();
+        let smallest_bounds = windows[1].window_bounds();
+
+        let mut workspace = Workspace::new_with_layout(0, "Test".to_string(), Box::new(tree), None);
+
+        let swapped = workspace.swap_with_largest(&windows[2]).unwrap();
+        assert_eq!(swapped.map(|w| w.id()), Some(1));
+        assert_eq!(windows[2].window_bounds(), largest_bounds);
+
+        // Already the largest, nothing moves
+        assert!(workspace.swap_with_largest(&windows[2]).unwrap().is_none());
+        assert_eq!(windows[2].window_bounds(), largest_bounds);
+
+        let swapped = workspace.swap_with_smallest(&windows[2]).unwrap();
+        assert_eq!(swapped.map(|w| w.id()), Some(2));
+        assert_eq!(windows[2].window_bounds(), smallest_bounds);
+        assert!(workspace.swap_with_smallest(&windows[2]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_float_window_centered_in_partition() {
+        let _config = Config::scoped(|_| {});
+
+        let config = crate::config::Config::default();
+        let partition = Bounds::new(1920, 0, 2560, 1440);
+        let window = create_mock_window(1);
+        let mut workspace =
+            Workspace::new::<ContainerTree>(partition.clone(), "Test".to_string(), None, None);
+
+        workspace
+            .float_window_centered(&window, &partition, config.float_window_size_ratio)
+            .unwrap();
+
+        let bounds = window.bounds();
+        let expected = Size::new(
+            (2560.0 * config.float_window_size_ratio).round() as u32,
+            (1440.0 * config.float_window_size_ratio).round() as u32,
+        );
+        assert!(window.floating());
+        assert_eq!(bounds.size, expected);
+        assert_eq!(bounds.center(), partition.center());
+    }
+
+    #[test]
+    fn test_workspace_config_overrides_gaps() {
+        let _config = Config::scoped(|c| {
+            c.workspaces.insert(
+                "Gaps Coding".to_string(),
+                WorkspaceConfig {
+                    window_gap: Some(4),
+                    partition_gap: Some(0),
+                    float_new_windows: Some(true),
+                    ..Default::default()
+                },
+            );
+        });
+        let bounds = Bounds::new(0, 0, 1920, 1080);
+        let coding =
+            Workspace::new::<ContainerTree>(bounds.clone(), "Gaps Coding".to_string(), None, None);
+        let mut other =
+            Workspace::new::<ContainerTree>(bounds.clone(), "Gaps Other".to_string(), None, None);
+
+        assert_eq!(coding.window_gap(), Some(4));
+        assert!(coding.float_new_windows());
+        assert_eq!(other.window_gap(), None);
+
+        let tiled_bounds = |mut workspace: Workspace, id| {
+            let window = create_mock_window(id);
+            workspace.tile_window(&window, &bounds.center()).unwrap();
+            window.bounds()
+        };
+        let coding_bounds = tiled_bounds(coding, 1);
+        assert_eq!(
+            coding_bounds,
+            tiling_area_with_gaps(
+                &bounds,
+                &Gaps {
+                    window: 4,
+                    partition: 0,
+                    outer: None,
+                }
+            )
+        );
+
+        // Renaming a workspace to a configured name picks up its overrides
+        other.set_name("Gaps Coding".to_string());
+        assert_eq!(other.window_gap(), Some(4));
+        let other_bounds = tiled_bounds(other, 2);
+        assert_eq!(other_bounds, coding_bounds);
+        assert_ne!(other_bounds, tiling_area(&bounds));
+    }
+
+    #[test]
+    fn test_float_all_and_tile_all() {
+        let _config = Config::scoped(|_| {});
+
+        let bounds = Bounds::new(0, 0, 1920, 1080);
+        let mut workspace =
+            Workspace::new::<ContainerTree>(bounds.clone(), "Test".to_string(), None, None);
+        let area = tiling_area(&bounds);
+        for window in (1..=3).map(create_mock_window) {
+            let position = Position {
+                x: area.position.x + area.size.width as i32 - 2,
+                y: area.center().y,
+            };
+            workspace.tile_window(&window, &position).unwrap();
+        }
+        let floating = create_mock_window(4);
+        workspace.float_window(&floating).unwrap();
+
+        let count_floating = |workspace: &Workspace| {
+            workspace
+                .windows()
+                .values()
+                .filter(|w| w.floating())
+                .count()
+        };
+
+        let tiled_bounds = workspace.windows()[&2].window_bounds();
+        assert_eq!(workspace.float_all().unwrap(), 3);
+        assert_eq!(workspace.windows().len(), 4);
+        assert_eq!(count_floating(&workspace), 4);
+        assert!(workspace.layout().windows().is_empty());
+        assert_eq!(workspace.windows()[&2].bounds(), tiled_bounds);
+
+        assert_eq!(workspace.tile_all().unwrap(), 4);
+        assert_eq!(workspace.windows().len(), 4);
+        assert_eq!(count_floating(&workspace), 0);
+        assert_eq!(workspace.layout().windows().len(), 4);
+    }
+}
@@ -1,8 +1,11 @@
-use crate::animation::{ease_in_out_cubic, Animator};
+use crate::animation::{ease_pulse, Animator};
 use crate::coalescing_channel::CoalescingAsyncChannel;
+use crate::config::Config;
 use crate::platform::{Bounds, PlatformWindow, PlatformWindowImpl, WindowId};
 use log::{error, warn};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 use tokio::runtime::Runtime;
@@ -16,12 +19,82 @@ pub enum WorkspaceAnimationCommand {
         from_bounds: Bounds,
         to_bounds: Bounds,
         duration_ms: u32,
+        kind: AnimationKind,
+    },
+    PulseWindow {
+        window_id: WindowId,
+        platform_window: PlatformWindow,
+        base_opacity: f32,
+        opacity: f32,
+        duration_ms: u32,
     },
     StopWindow(WindowId),
     RemoveWindow(WindowId),
     Exit,
 }
 
+/// What moved a window, each kind of move animates for its own configured duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnimationKind {
+    Tile,
+    WorkspaceSwitch,
+    FloatSnap,
+}
+
+impl AnimationKind {
+    /// How long this kind of move animates for, or `None` when it shouldn't animate
+    pub fn duration_ms(&self, config: &Config) -> Option<u32> {
+        if !config.window_tile_animate {
+            return None;
+        }
+
+        let duration_ms = match self {
+            AnimationKind::Tile => config.tile_animation_ms,
+            AnimationKind::WorkspaceSwitch => config.workspace_switch_animation_ms,
+            AnimationKind::FloatSnap => config.float_snap_animation_ms,
+        };
+        Some(duration_ms.unwrap_or(config.window_tile_animation_ms)).filter(|ms| *ms > 0)
+    }
+}
+
+/// Frame rates of the window animations that ran to the end, to help spot janky animations
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnimationStats {
+    pub kinds: Vec<AnimationKindStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationKindStats {
+    pub kind: AnimationKind,
+    /// How many animations of this kind finished
+    pub animations: u32,
+    pub average_fps: f64,
+    pub last_fps: f64,
+}
+
+impl AnimationStats {
+    fn record(&mut self, kind: AnimationKind, fps: f64) {
+        let index = match self.kinds.iter().position(|stats| stats.kind == kind) {
+            Some(index) => index,
+            None => {
+                self.kinds.push(AnimationKindStats {
+                    kind,
+                    animations: 0,
+                    average_fps: 0.0,
+                    last_fps: 0.0,
+                });
+                self.kinds.len() - 1
+            }
+        };
+
+        let stats = &mut self.kinds[index];
+        stats.animations += 1;
+        stats.average_fps += (fps - stats.average_fps) / stats.animations as f64;
+        stats.last_fps = fps;
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WorkspaceAnimationConfig {
     pub animation_fps: u32,
@@ -36,18 +109,32 @@ impl Default for WorkspaceAnimationConfig {
 struct AnimatedWindow {
     platform_window: PlatformWindow,
     animator: Animator<Bounds>,
+    kind: AnimationKind,
+}
+
+struct PulsingWindow {
+    platform_window: PlatformWindow,
+    animator: Animator<f32>,
+    /// The opacity the window is restored to once the pulse ends
+    base_opacity: f32,
 }
 
 pub struct WorkspaceAnimationThread {
     config: WorkspaceAnimationConfig,
     command_sender: mpsc::UnboundedSender<WorkspaceAnimationCommand>,
     animator_thread: Option<thread::JoinHandle<()>>,
+    stats: Arc<Mutex<AnimationStats>>,
+    /// The window, target and duration of every animation sent to the thread
+    #[cfg(test)]
+    pub(crate) sent_animations: Vec<(WindowId, Bounds, u32)>,
 }
 
 struct WorkspaceAnimationThreadAnimator {
     config: WorkspaceAnimationConfig,
     animated_windows: HashMap<WindowId, AnimatedWindow>,
+    pulsing_windows: HashMap<WindowId, PulsingWindow>,
     command_channel: CoalescingAsyncChannel<WorkspaceAnimationCommand>,
+    stats: Arc<Mutex<AnimationStats>>,
 }
 
 impl WorkspaceAnimationThread {
@@ -56,11 +143,15 @@ impl WorkspaceAnimationThread {
         let command_sender = command_channel.sender();
 
         let config_clone = config.clone();
+        let stats = Arc::new(Mutex::new(AnimationStats::default()));
+        let thread_stats = stats.clone();
         let animator_thread = thread::spawn(move || {
             let mut animator = WorkspaceAnimationThreadAnimator {
                 config: config_clone,
                 animated_windows: HashMap::new(),
+                pulsing_windows: HashMap::new(),
                 command_channel,
+                stats: thread_stats,
             };
 
             // Create a tokio runtime for the animator thread
@@ -72,6 +163,9 @@ impl WorkspaceAnimationThread {
             config,
             command_sender,
             animator_thread: Some(animator_thread),
+            stats,
+            #[cfg(test)]
+            sent_animations: Vec::new(),
         }
     }
 
@@ -82,7 +176,12 @@ impl WorkspaceAnimationThread {
         from_bounds: Bounds,
         to_bounds: Bounds,
         duration_ms: u32,
+        kind: AnimationKind,
     ) {
+        #[cfg(test)]
+        self.sent_animations
+            .push((window_id, to_bounds.clone(), duration_ms));
+
         if let Err(e) = self
             .command_sender
             .send(WorkspaceAnimationCommand::AnimateWindow {
@@ -91,12 +190,41 @@ impl WorkspaceAnimationThread {
                 from_bounds,
                 to_bounds,
                 duration_ms,
+                kind,
             })
         {
             error!("Failed to send AnimateWindow command to workspace animation thread: {e}");
         }
     }
 
+    /// Frame rates of the animations finished so far
+    pub fn stats(&self) -> AnimationStats {
+        self.stats.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Briefly dip a window's opacity from `base_opacity` and restore it
+    pub fn pulse_window(
+        &mut self,
+        window_id: WindowId,
+        platform_window: PlatformWindow,
+        base_opacity: f32,
+        opacity: f32,
+        duration_ms: u32,
+    ) {
+        if let Err(e) = self
+            .command_sender
+            .send(WorkspaceAnimationCommand::PulseWindow {
+                window_id,
+                platform_window,
+                base_opacity,
+                opacity,
+                duration_ms,
+            })
+        {
+            warn!("Failed to send PulseWindow command to workspace animation thread: {e}");
+        }
+    }
+
     /// Stop animating a specific window
     pub fn stop_window(&mut self, window_id: WindowId) {
         if let Err(e) = self
@@ -142,7 +270,7 @@ impl WorkspaceAnimationThreadAnimator {
                 break;
             }
 
-            if !self.animated_windows.is_empty() {
+            if !self.animated_windows.is_empty() || !self.pulsing_windows.is_empty() {
                 let now = Instant::now();
                 let elapsed = now.duration_since(last_frame_time);
 
@@ -168,30 +296,64 @@ impl WorkspaceAnimationThreadAnimator {
                 from_bounds,
                 to_bounds,
                 duration_ms,
+                kind,
             } => {
-                let mut animator =
-                    Animator::new(from_bounds.clone(), to_bounds.clone(), ease_in_out_cubic);
+                // A window that's still moving carries on from where it is now, rather than the
+                // bounds the platform last reported, so it never jumps back
+                if let Some(animated_window) = self.animated_windows.get_mut(&window_id) {
+                    animated_window.platform_window = platform_window;
+                    animated_window.kind = kind;
+                    if animated_window.animator.to != to_bounds {
+                        animated_window.animator.start(to_bounds, duration_ms);
+                    }
+                    return;
+                }
+
+                let ease_fn = Config::window_tile_animation_curve().ease_fn();
+                let mut animator = Animator::new(from_bounds.clone(), to_bounds.clone(), ease_fn);
                 animator.start_from(from_bounds, to_bounds, duration_ms);
 
                 let animated_window = AnimatedWindow {
                     platform_window,
                     animator,
+                    kind,
                 };
 
                 self.animated_windows.insert(window_id, animated_window);
             }
+            WorkspaceAnimationCommand::PulseWindow {
+                window_id,
+                platform_window,
+                base_opacity,
+                opacity,
+                duration_ms,
+            } => {
+                // The pulse curve returns to the start, so the animator dips to `opacity` and back
+                let mut animator = Animator::new(base_opacity, opacity, ease_pulse);
+                animator.start_from(base_opacity, opacity, duration_ms);
+
+                self.pulsing_windows.insert(
+                    window_id,
+                    PulsingWindow {
+                        platform_window,
+                        animator,
+                        base_opacity,
+                    },
+                );
+            }
             WorkspaceAnimationCommand::StopWindow(window_id) => {
                 if let Some(animated_window) = self.animated_windows.get_mut(&window_id) {
                     // Stop animation by setting it to not animating
                     animated_window.animator = Animator::new(
                         animated_window.animator.current_value().clone(),
                         animated_window.animator.current_value().clone(),
-                        ease_in_out_cubic,
+                        animated_window.animator.ease_fn,
                     );
                 }
             }
             WorkspaceAnimationCommand::RemoveWindow(window_id) => {
                 self.animated_windows.remove(&window_id);
+                self.pulsing_windows.remove(&window_id);
             }
             WorkspaceAnimationCommand::Exit => {
                 *running = false;
@@ -214,6 +376,11 @@ impl WorkspaceAnimationThreadAnimator {
                 if let Err(e) = animated_window.platform_window.set_bounds(&target_bounds) {
                     warn!("Failed to set final bounds for window {}: {}", window_id, e);
                 }
+                if let (Some(fps), Ok(mut stats)) =
+                    (animated_window.animator.last_fps(), self.stats.lock())
+                {
+                    stats.record(animated_window.kind, fps);
+                }
                 completed_windows.push(*window_id);
             }
         }
@@ -221,5 +388,101 @@ impl WorkspaceAnimationThreadAnimator {
         for window_id in completed_windows {
             self.animated_windows.remove(&window_id);
         }
+
+        self.pulsing_windows.retain(|window_id, pulsing_window| {
+            if let Some(opacity) = pulsing_window.animator.update() {
+                if let Err(e) = pulsing_window.platform_window.set_opacity(opacity) {
+                    warn!("Failed to set opacity for window {}: {}", window_id, e);
+                }
+            }
+
+            if pulsing_window.animator.is_animating() {
+                return true;
+            }
+
+            if let Err(e) = pulsing_window
+                .platform_window
+                .set_opacity(pulsing_window.base_opacity)
+            {
+                warn!("Failed to restore opacity for window {}: {}", window_id, e);
+            }
+            false
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatformWindow;
+    use crate::platform::{Position, Size};
+
+    fn new_animator() -> WorkspaceAnimationThreadAnimator {
+        WorkspaceAnimationThreadAnimator {
+            config: WorkspaceAnimationConfig::default(),
+            animated_windows: HashMap::new(),
+            pulsing_windows: HashMap::new(),
+            command_channel: CoalescingAsyncChannel::new(),
+            stats: Arc::new(Mutex::new(AnimationStats::default())),
+        }
+    }
+
+    fn animate(
+        platform_window: &MockPlatformWindow,
+        from_bounds: Bounds,
+        to_bounds: Bounds,
+    ) -> WorkspaceAnimationCommand {
+        WorkspaceAnimationCommand::AnimateWindow {
+            window_id: 1,
+            platform_window: platform_window.clone(),
+            from_bounds,
+            to_bounds,
+            duration_ms: 2000,
+            kind: AnimationKind::Tile,
+        }
+    }
+
+    #[test]
+    fn test_new_target_continues_from_current_position() {
+        let platform_window = MockPlatformWindow::new(
+            Position { x: 0, y: 0 },
+            Size {
+                width: 800,
+                height: 600,
+            },
+            "Window".to_string(),
+        );
+        let start = Bounds::new(0, 0, 800, 600);
+        let first_target = Bounds::new(10000, 0, 800, 600);
+        let second_target = Bounds::new(5000, 2000, 800, 600);
+        let mut animator = new_animator();
+        let mut running = true;
+
+        animator.handle_command(
+            animate(&platform_window, start.clone(), first_target),
+            &mut running,
+        );
+        thread::sleep(Duration::from_millis(200));
+        animator.animate_frame();
+        let current = animator.animated_windows[&1].animator.last_value.clone();
+        assert!(current.position.x > 0);
+
+        // The platform still reports the start bounds, the window should carry on from where
+        // the first animation left it
+        animator.handle_command(
+            animate(&platform_window, start, second_target.clone()),
+            &mut running,
+        );
+
+        let animated_window = &animator.animated_windows[&1];
+        assert_eq!(animated_window.animator.from, current);
+        assert_eq!(animated_window.animator.to, second_target);
+
+        platform_window.clear_set_bounds_calls();
+        animator.animate_frame();
+        assert!(platform_window
+            .get_set_bounds_calls()
+            .iter()
+            .all(|b| b.position.x >= current.position.x.min(second_target.position.x)));
     }
 }